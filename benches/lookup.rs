@@ -0,0 +1,31 @@
+//! Lookups of messages, run as `cargo bench --bench lookup`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use recoyx_localization::*;
+
+fn locale_map() -> LocaleMap {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(tokio::runtime::Runtime::new().unwrap().block_on(locale_map.load(Some(parse_locale("pt-BR").unwrap()))));
+    locale_map
+}
+
+fn lookup(c: &mut Criterion) {
+    let locale_map = locale_map();
+    c.bench_function("get", |b| b.iter(|| locale_map.get("common.message_id")));
+    c.bench_function("get from fallback", |b| b.iter(|| locale_map.get("common.contextual_male")));
+    c.bench_function("get missing", |b| b.iter(|| locale_map.get("common.nope")));
+    c.bench_function("get_formatted", |b| b.iter(|| locale_map.get_formatted("common.parameterized", vec![ &localization_vars!{"x" => "a"} ])));
+    c.bench_function("get_formatted with quantity", |b| b.iter(|| locale_map.get_formatted("common.qty", vec![ &3 ])));
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+//! Copies the locale data into `OUT_DIR`, keeping only the languages listed in the
+//! `RECOYX_LOCALIZATION_LOCALES` environment variable, such as `en,pt-BR,fr`, if it is set.
+//! English is always kept, since it completes the formatting data of every locale.
+//! Country and subdivision data is keyed by region, so the subdivisions keep only the
+//! names in those languages.
+
+use std::{collections::BTreeSet, env, fs, path::Path};
+use serde_json::{Map, Value};
+
+const LOCALES_VAR: &str = "RECOYX_LOCALIZATION_LOCALES";
+const FILTERED_FILES: [&str; 4] = ["basic_data.json", "currency_data.json", "exemplar_characters.json", "formatting_data.json"];
+const REGION_FILES: [&str; 2] = ["country_data.json", "subdivision_data.json"];
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={}", LOCALES_VAR);
+    println!("cargo:rerun-if-changed=locale-data");
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let allowlist: Option<BTreeSet<String>> = env::var(LOCALES_VAR).ok().map(|value| {
+        value.split(',')
+            .map(|tag| language_of(tag.trim()).to_lowercase())
+            .filter(|language| !language.is_empty())
+            .chain(std::iter::once("en".to_string()))
+            .collect()
+    });
+
+    let mut excluded = BTreeSet::new();
+    for file in FILTERED_FILES.iter() {
+        let content = fs::read_to_string(Path::new("locale-data").join(file)).unwrap();
+        let data: Map<String, Value> = serde_json::from_str(&content).unwrap();
+        let data: Map<String, Value> = data.into_iter().filter(|(tag, _)| {
+            let language = language_of(tag);
+            let keep = allowlist.as_ref().map(|a| a.contains(language)).unwrap_or(true);
+            if !keep {
+                excluded.insert(language.to_string());
+            }
+            keep
+        }).collect();
+        fs::write(Path::new(&out_dir).join(file), serde_json::to_string(&data).unwrap()).unwrap();
+    }
+    for file in REGION_FILES.iter() {
+        let content = fs::read_to_string(Path::new("locale-data").join(file)).unwrap();
+        let mut data: Map<String, Value> = serde_json::from_str(&content).unwrap();
+        if let Some(allowlist) = allowlist.as_ref() {
+            for names in data.values_mut().filter_map(|entry| entry.get_mut("names")).filter_map(|names| names.as_object_mut()) {
+                names.retain(|language, _| allowlist.contains(language_of(language)));
+            }
+        }
+        fs::write(Path::new(&out_dir).join(file), serde_json::to_string(&data).unwrap()).unwrap();
+    }
+
+    let excluded = excluded.iter().map(|l| format!("{:?}", l)).collect::<Vec<_>>().join(", ");
+    fs::write(Path::new(&out_dir).join("bundled_languages.rs"), format!(
+        "pub(crate) const EXCLUDED_LANGUAGES: &[&str] = &[{}];\n",
+        excluded,
+    )).unwrap();
+}
+
+fn language_of(tag: &str) -> &str {
+    tag.split(['-', '_']).next().unwrap()
+}
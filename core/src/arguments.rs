@@ -0,0 +1,47 @@
+use alloc::{collections::BTreeMap, string::{String, ToString}, vec::Vec};
+use super::Gender;
+
+/// Arguments a message is formatted with: the values of its variables and, apart
+/// from them, the quantities and genders that select the messages of `${name|plural}`
+/// and `${name|gender}` and the kinds of the values given as numbers or dates.
+///
+/// ```
+/// use recoyx_localization_core::{MessageArguments, ValueKind};
+///
+/// let mut arguments = MessageArguments::new();
+/// arguments.insert_number("items", "3");
+/// assert_eq!(arguments.variables["items"], "3");
+/// assert_eq!(arguments.kinds["items"], ValueKind::Number);
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct MessageArguments {
+    /// Values of the variables, such as `$user`.
+    pub variables: BTreeMap<String, String>,
+    /// Quantity suffixes selected by the count of a variable for `${name|plural}`,
+    /// from the most specific, such as `["_zero", "_empty"]`; see `quantity_suffixes()`.
+    pub plurals: BTreeMap<String, Vec<&'static str>>,
+    /// Genders of variables for `${name|gender}`, which need no value of their own.
+    pub genders: BTreeMap<String, Gender>,
+    /// Kinds of the values of variables given as numbers or dates.
+    pub kinds: BTreeMap<String, ValueKind>,
+}
+
+/// Kind of the value of a variable, other than text.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ValueKind {
+    Number,
+    DateTime,
+}
+
+impl MessageArguments {
+    /// Constructs empty arguments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of a variable given as a number, such as `$number`.
+    pub fn insert_number<S: ToString, V: ToString>(&mut self, name: S, value: V) {
+        self.kinds.insert(name.to_string(), ValueKind::Number);
+        self.variables.insert(name.to_string(), value.to_string());
+    }
+}
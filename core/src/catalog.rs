@@ -0,0 +1,179 @@
+use alloc::{collections::BTreeMap, format, string::{String, ToString}, vec::Vec};
+use super::{format_message, gender_suffix, quantity_suffix, quantity_suffixes, tokenize, Gender, MessageArguments, MessageFormatterCall, MessagePart, MessageResolver};
+
+/// Metadata fields an object may have besides `message` for it to be a message
+/// with metadata rather than an object of nested messages.
+static METADATA_FIELDS: &[&str] = &["description", "max_length", "max_width"];
+
+/// Returns the text of an asset value that is a message, which is either
+/// a string or an object with a string `message` field and at least one metadata
+/// field, so that an object of nested messages may contain a message named `message`.
+pub fn message_of(value: &serde_json::Value) -> Option<&str> {
+    if let Some(r) = value.as_str() {
+        return Some(r);
+    }
+    let object = value.as_object()?;
+    let is_metadata = |k: &String| METADATA_FIELDS.contains(&k.as_str());
+    if !object.keys().all(|k| k == "message" || is_metadata(k)) || !object.keys().any(is_metadata) {
+        return None;
+    }
+    object.get("message")?.as_str()
+}
+
+/// The messages of a locale, indexed by their complete identifiers, such as
+/// `common.save`, read from the same asset files as `LocaleMap` and formatted
+/// with the same message syntax, without the standard library.
+///
+/// Unlike `LocaleMap`, a `Catalog` has no fallbacks, formatters or locale data,
+/// so that `$number` is not transliterated into the digits of the locale.
+///
+/// ```
+/// use recoyx_localization_core::{Argument, Catalog};
+///
+/// let mut catalog = Catalog::new();
+/// catalog.insert_json("common", r#"{ "qty_one": "One item", "qty_multiple": "$number items" }"#).unwrap();
+/// assert_eq!(catalog.format("common.qty", &[Argument::Number("3")]).unwrap(), "3 items");
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct Catalog {
+    _messages: BTreeMap<String, String>,
+}
+
+/// Formatting argument of `Catalog::format()`, corresponding to
+/// the arguments of `LocaleMap::get_formatted()`.
+#[derive(Copy, Clone, Debug)]
+pub enum Argument<'a> {
+    /// Name and value of a variable, such as `$user`.
+    Variable(&'a str, &'a str),
+    /// Number selecting the message followed by `_empty`, `_one` or `_multiple`,
+    /// or by `_zero` or `_negative` if present, given as `$number`.
+    /// Only the first number selects the suffix.
+    Number(&'a str),
+    /// Gender selecting the message followed by `_male`, `_female` or `_other`.
+    Gender(Gender),
+    /// Name and quantity of a variable for `${name|plural}`, as a `Count`.
+    Count(&'a str, &'a str),
+    /// Name and gender of a variable for `${name|gender}`, as a `GenderOf`.
+    GenderOf(&'a str, Gender),
+}
+
+impl Catalog {
+    /// Constructs an empty `Catalog`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the messages of an asset file under its base file name, such as `common`
+    /// for `common.json`. Messages are either strings or objects with metadata;
+    /// values that are neither messages nor objects of nested messages are ignored.
+    pub fn insert_json(&mut self, base_name: &str, json: &str) -> Result<(), serde_json::Error> {
+        let root: serde_json::Value = serde_json::from_str(json)?;
+        self.insert_value(base_name, &root);
+        Ok(())
+    }
+
+    /// Adds the messages of an asset file, using its file stem as base file name.
+    #[cfg(feature = "std")]
+    pub fn load_file<P: AsRef<std::path::Path>>(&mut self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let base_name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        self.insert_json(&base_name, &content).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    fn insert_value(&mut self, path: &str, value: &serde_json::Value) {
+        for (name, value) in value.as_object().into_iter().flatten() {
+            let id = if path.is_empty() { name.clone() } else { format!("{}.{}", path, name) };
+            match message_of(value) {
+                Some(message) => self.insert(id, message),
+                None => self.insert_value(&id, value),
+            }
+        }
+    }
+
+    /// Sets a message.
+    pub fn insert<S: ToString, M: ToString>(&mut self, id: S, message: M) {
+        self._messages.insert(id.to_string(), message.to_string());
+    }
+
+    /// Returns the number of messages.
+    pub fn len(&self) -> usize {
+        self._messages.len()
+    }
+
+    /// Returns `true` if there are no messages.
+    pub fn is_empty(&self) -> bool {
+        self._messages.is_empty()
+    }
+
+    /// Returns `true` if there is a message at the given identifier.
+    pub fn contains(&self, id: &str) -> bool {
+        self._messages.contains_key(id)
+    }
+
+    /// Returns the message at the given identifier, unformatted.
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self._messages.get(id).map(|m| m.as_str())
+    }
+
+    /// Enumerates the message identifiers in byte order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self._messages.keys().map(|k| k.as_str())
+    }
+
+    /// Formats the message at the given identifier, after appending the gender and
+    /// quantity suffixes selected by the arguments, as `LocaleMap::get_formatted()` does.
+    /// Returns `None` if the message does not exist.
+    pub fn format(&self, id: &str, arguments: &[Argument]) -> Option<String> {
+        let mut id = id.to_string();
+        let mut args = MessageArguments::new();
+        let (mut number, mut gender) = (None, None);
+        for argument in arguments.iter() {
+            match *argument {
+                Argument::Variable(name, value) => { args.variables.insert(name.to_string(), value.to_string()); },
+                Argument::Number(n) => { number = number.or(Some(n)); },
+                Argument::Gender(g) => gender = Some(g),
+                Argument::Count(name, n) => {
+                    args.plurals.insert(name.to_string(), quantity_suffixes(n, quantity_suffix(n), false));
+                    args.insert_number(name, n);
+                },
+                Argument::GenderOf(name, g) => { args.genders.insert(name.to_string(), g); },
+            }
+        }
+        if let Some(g) = gender {
+            id.push_str(gender_suffix(g));
+        }
+        if let Some(n) = number {
+            let suffixes = quantity_suffixes(n, quantity_suffix(n), false);
+            let suffix = suffixes.iter().find(|s| self.contains(&format!("{}{}", id, s))).unwrap_or_else(|| suffixes.last().unwrap());
+            id.push_str(suffix);
+            args.insert_number("number", n);
+        }
+        let message = tokenize(self.get(&id)?);
+        let mut r = String::new();
+        format_message(self, &id, &message, &args, &mut r);
+        Some(r)
+    }
+}
+
+/// Formatters other than `plural` and `gender` are ignored,
+/// as unregistered formatters are by `LocaleMap`.
+impl MessageResolver for Catalog {
+    type Message = Vec<MessagePart>;
+
+    fn parts<'m>(&self, message: &'m Self::Message) -> &'m [MessagePart] {
+        message
+    }
+
+    fn contains(&self, _within: &Self::Message, id: &str) -> bool {
+        self.contains(id)
+    }
+
+    fn message(&self, _within: &Self::Message, id: &str) -> Option<Self::Message> {
+        self.get(id).map(tokenize)
+    }
+
+    fn format_value(&self, _message: &Self::Message, _formatter: &MessageFormatterCall, _value: &str) -> Option<String> {
+        None
+    }
+}
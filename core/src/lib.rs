@@ -0,0 +1,28 @@
+//! The parts of `recoyx_localization` that need no operating system: the message
+//! syntax, the selection of messages by quantity and gender, and a `Catalog` of messages
+//! read from the same asset files, so that firmware UIs can share the catalogs
+//! of desktop and web applications.
+//!
+//! The crate is `no_std` and only requires `alloc`. The `std` feature, enabled by default,
+//! adds reading asset files from the file system (`Catalog::load_file()`).
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod message_syntax;
+pub use message_syntax::{parse_message, tokenize, MessageAst, MessagePart, MessageVariable, MessageFormatterCall, SyntaxError, SyntaxErrorKind};
+
+mod selection;
+pub use selection::{Gender, quantity_suffix, quantity_suffixes, gender_suffix};
+
+mod arguments;
+pub use arguments::{MessageArguments, ValueKind};
+
+mod message_format;
+pub use message_format::{format_message, MessageResolver, MessageWriter};
+
+mod catalog;
+pub use catalog::{Catalog, Argument, message_of};
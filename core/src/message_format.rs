@@ -0,0 +1,132 @@
+use alloc::{format, string::{String, ToString}, vec, vec::Vec};
+use super::{gender_suffix, MessageArguments, MessageFormatterCall, MessagePart, ValueKind};
+
+/// Source of the messages formatted by `format_message()`, such as a `Catalog`,
+/// which also applies the formatters of variables other than `plural` and `gender`.
+pub trait MessageResolver {
+    /// Message and whatever the resolver keeps with it, such as the locale that provided it.
+    type Message;
+
+    /// Returns the parts of a message.
+    fn parts<'m>(&self, message: &'m Self::Message) -> &'m [MessagePart];
+
+    /// Returns `true` if there is a message at an identifier for `${name|plural}`
+    /// or `${name|gender}` within another message.
+    fn contains(&self, within: &Self::Message, id: &str) -> bool;
+
+    /// Returns the message at an identifier selected by `${name|plural}`
+    /// or `${name|gender}` within another message.
+    fn message(&self, within: &Self::Message, id: &str) -> Option<Self::Message>;
+
+    /// Applies a formatter to the value of a variable of a message, returning `None`
+    /// if the formatter is unknown, in which case it is ignored.
+    fn format_value(&self, message: &Self::Message, formatter: &MessageFormatterCall, value: &str) -> Option<String>;
+}
+
+/// Output of `format_message()`, such as a `String`, which may keep the parts
+/// of the message apart.
+pub trait MessageWriter<M>: Default {
+    /// Writes literal text of a message.
+    fn write_text(&mut self, message: &M, text: &str);
+
+    /// Writes the value of a variable of a message, given the kind of the value,
+    /// if not text, and the language marked by `${name|lang:fr}`, if any.
+    fn write_value(&mut self, message: &M, name: &str, value: String, kind: Option<ValueKind>, language: Option<&str>);
+
+    /// Writes the output of a message selected by `${name|plural}` or `${name|gender}`.
+    fn append(&mut self, output: Self);
+
+    /// Returns the text written so far.
+    fn text(&self) -> String;
+}
+
+impl<M> MessageWriter<M> for String {
+    fn write_text(&mut self, _message: &M, text: &str) {
+        self.push_str(text);
+    }
+
+    fn write_value(&mut self, _message: &M, _name: &str, value: String, _kind: Option<ValueKind>, _language: Option<&str>) {
+        self.push_str(&value);
+    }
+
+    fn append(&mut self, output: Self) {
+        self.push_str(&output);
+    }
+
+    fn text(&self) -> String {
+        self.clone()
+    }
+}
+
+/// Formats a message at a complete identifier, such as `cart.summary`, with its arguments,
+/// as `Catalog::format()` and `LocaleMap::get_formatted()` do after selecting the
+/// gender and quantity suffixes of the identifier.
+///
+/// A variable is replaced by its value, or by `undefined` if it has none. Within braces,
+/// `${name|plural}` and `${name|gender}` replace it by the message at the identifier
+/// followed by `_`, the name and the suffix selected by the count or gender of the
+/// variable, or at the identifier given as argument followed by the suffix, as in
+/// `${files|plural:cart.files}`, formatted with `$number` as the count.
+/// Other formatters are applied by the resolver; `lang`, unless the resolver applies it,
+/// marks the language of the value, as in `${phrase|lang:fr}`.
+pub fn format_message<R: MessageResolver, W: MessageWriter<R::Message>>(resolver: &R, id: &str, message: &R::Message, arguments: &MessageArguments, output: &mut W) {
+    for part in resolver.parts(message) {
+        let variable = match part {
+            MessagePart::Text(text) => { output.write_text(message, text); continue; },
+            MessagePart::Variable(variable) => variable,
+        };
+        // A variable only given a gender has no value of its own,
+        // so it is undefined unless `${name|gender}` selects a message.
+        let mut value = arguments.variables.get(&variable.name).cloned();
+        // Output of the message last selected by `${name|plural}` or `${name|gender}`,
+        // unless a formatter is applied after it.
+        let mut selected: Option<W> = None;
+        let mut language = None;
+        for f in variable.formatters.iter() {
+            let suffixes = match f.name.as_str() {
+                "plural" => arguments.plurals.get(&variable.name).cloned(),
+                "gender" => arguments.genders.get(&variable.name).map(|g| vec![gender_suffix(*g)]),
+                _ => None,
+            };
+            if let Some(suffixes) = suffixes {
+                if let Some(r) = selected_message::<R, W>(resolver, id, message, &variable.name, f, &suffixes, value.as_deref().unwrap_or_default(), arguments) {
+                    value = Some(r.text());
+                    selected = Some(r);
+                }
+                continue;
+            }
+            if let Some(v) = value.as_deref().and_then(|v| resolver.format_value(message, f, v)) {
+                value = Some(v);
+                selected = None;
+            } else if f.name == "lang" {
+                language = f.argument.as_deref().or(language);
+            }
+        }
+        match (selected, value) {
+            (Some(selected), _) => output.append(selected),
+            (None, Some(value)) => output.write_value(message, &variable.name, value, arguments.kinds.get(&variable.name).copied(), language),
+            (None, None) => output.write_value(message, &variable.name, "undefined".to_string(), None, language),
+        }
+    }
+}
+
+/// Formats the message selected for `${name|plural}` or `${name|gender}`, which is
+/// the first that exists of the identifiers with the given suffixes, or else the last one.
+#[allow(clippy::too_many_arguments)]
+fn selected_message<R: MessageResolver, W: MessageWriter<R::Message>>(resolver: &R, id: &str, message: &R::Message, name: &str, formatter: &MessageFormatterCall, suffixes: &[&str], value: &str, arguments: &MessageArguments) -> Option<W> {
+    let ids: Vec<String> = suffixes.iter().map(|suffix| match formatter.argument.as_ref() {
+        Some(base_id) => format!("{}{}", base_id, suffix),
+        None => format!("{}_{}{}", id, name, suffix),
+    }).collect();
+    let id = ids.iter().find(|id| resolver.contains(message, id)).unwrap_or_else(|| ids.last().unwrap());
+    let selected = resolver.message(message, id)?;
+    let mut output = W::default();
+    if formatter.name == "plural" {
+        let mut arguments = arguments.clone();
+        arguments.insert_number("number", value);
+        format_message(resolver, id, &selected, &arguments, &mut output);
+    } else {
+        format_message(resolver, id, &selected, arguments, &mut output);
+    }
+    Some(output)
+}
@@ -0,0 +1,256 @@
+//! Parser of the message syntax, in which text is literal except for:
+//!
+//! ```text
+//! message      = *( text / escape / continuation / variable )
+//! escape       = "$$" / "\" ( "$" / "\" / "{" / "}" / "|" )
+//! continuation = "\" newline *( " " / "\t" )
+//! variable     = "$" name
+//!              / "${" name *( "|" name [ ":" argument ] ) "}"
+//! name         = 1*( ALPHA / DIGIT / "_" / "-" )
+//! argument     = *( any character other than "|" and "}" )
+//! ```
+//!
+//! An escape produces the escaped character, such as `$` for both `$$` and `\$`,
+//! and a continuation joins two lines of a long message, dropping the line break
+//! and the indentation of the next line. A backslash followed by any other
+//! character, or a `$` that starts no variable, is literal, as is a `${`
+//! without a closing brace. Inside braces, `\}`, `\|` and `\\` are escapes
+//! and whitespace around names and arguments is ignored.
+//!
+//! `parse_message()` reports the constructs that the lenient reading above
+//! treats as literal text by mistake, such as an unclosed `${`, as errors.
+
+use alloc::{string::{String, ToString}, vec, vec::Vec};
+use core::{fmt::{Display, Formatter}, ops::Range};
+
+/// Message parsed by `parse_message()`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MessageAst {
+    pub parts: Vec<MessagePart>,
+}
+
+/// Piece of a message.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MessagePart {
+    /// Literal text, with escapes resolved.
+    Text(String),
+    Variable(MessageVariable),
+}
+
+/// Variable of a message, such as `$name` or `${price|currency:EUR}`.
+/// Only a variable written in braces has formatters.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MessageVariable {
+    pub name: String,
+    pub formatters: Vec<MessageFormatterCall>,
+    /// Whether the variable is written as `${...}`.
+    pub braced: bool,
+    /// Byte range of the variable in the message.
+    pub span: Range<usize>,
+}
+
+/// Formatter applied to a variable, such as `|currency:EUR`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MessageFormatterCall {
+    pub name: String,
+    pub argument: Option<String>,
+    /// Byte range in the message, including the `|`.
+    pub span: Range<usize>,
+}
+
+/// Error returned by `parse_message()`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SyntaxError {
+    pub kind: SyntaxErrorKind,
+    /// Byte offset of the error in the message.
+    pub offset: usize,
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SyntaxErrorKind {
+    /// A `${` has no closing brace.
+    UnclosedPlaceholder,
+    /// A `$` is followed by neither a variable name nor another `$`.
+    ExpectedVariableName,
+    /// The name of a `${...}` variable is empty or has characters other
+    /// than letters, digits, `_` and `-`.
+    InvalidVariableName(String),
+    /// The name of a formatter in `${...}` is empty or has characters other
+    /// than letters, digits, `_` and `-`.
+    InvalidFormatterName(String),
+    /// A `{` or `}` in the text has no matching brace.
+    UnbalancedBrace,
+}
+
+impl Display for SyntaxErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SyntaxErrorKind::UnclosedPlaceholder => write!(f, "Unclosed placeholder"),
+            SyntaxErrorKind::ExpectedVariableName => write!(f, "Expected variable name after $ (write $$ for a literal $)"),
+            SyntaxErrorKind::InvalidVariableName(name) => write!(f, "Invalid variable name \"{}\"", name),
+            SyntaxErrorKind::InvalidFormatterName(name) => write!(f, "Invalid formatter name \"{}\"", name),
+            SyntaxErrorKind::UnbalancedBrace => write!(f, "Unbalanced brace (write \\{{ or \\}} for a literal brace)"),
+        }
+    }
+}
+
+/// Parses a message, reporting malformed placeholders and unbalanced braces,
+/// such as for linting translations before they are shipped.
+///
+/// ```
+/// use recoyx_localization_core::{parse_message, SyntaxErrorKind};
+///
+/// assert!(parse_message("Total: ${price|currency:EUR}").is_ok());
+/// let error = parse_message("Total: ${price").unwrap_err();
+/// assert_eq!(error.kind, SyntaxErrorKind::UnclosedPlaceholder);
+/// assert_eq!(error.offset, 7);
+/// ```
+pub fn parse_message(message: &str) -> Result<MessageAst, SyntaxError> {
+    scan(message, true).map(|parts| MessageAst { parts })
+}
+
+/// Splits a message into literal text and variables, reading
+/// malformed constructs as literal text, as messages are read when formatted.
+pub fn tokenize(message: &str) -> Vec<MessagePart> {
+    scan(message, false).unwrap_or_default()
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_name_char)
+}
+
+fn scan(message: &str, strict: bool) -> Result<Vec<MessagePart>, SyntaxError> {
+    let error = |kind, offset| Err(SyntaxError { kind, offset });
+    let mut r = vec![];
+    let mut text = String::new();
+    let mut open_braces = vec![];
+    let mut i = 0;
+    while let Some(ch) = message[i..].chars().next() {
+        let rest = &message[i..];
+        match ch {
+            '\\' => {
+                let next = rest[1..].chars().next();
+                match next {
+                    Some('$') | Some('\\') | Some('{') | Some('}') | Some('|') => {
+                        text.push(next.unwrap());
+                        i += 2;
+                    },
+                    Some('\n') | Some('\r') => {
+                        i += if rest[1..].starts_with("\r\n") { 3 } else { 2 };
+                        i += message[i..].len() - message[i..].trim_start_matches([' ', '\t']).len();
+                    },
+                    _ => {
+                        text.push('\\');
+                        i += 1;
+                    },
+                }
+                continue;
+            },
+            '{' if strict => open_braces.push(i),
+            '}' if strict && open_braces.pop().is_none() => return error(SyntaxErrorKind::UnbalancedBrace, i),
+            _ => {},
+        }
+        if ch != '$' {
+            text.push(ch);
+            i += ch.len_utf8();
+            continue;
+        }
+        if rest.starts_with("$$") {
+            text.push('$');
+            i += 2;
+            continue;
+        }
+        let braced = rest.starts_with("${");
+        let variable = if braced { braced_variable(message, i) } else { plain_variable(message, i) };
+        match variable {
+            Some(variable) => {
+                if strict && braced {
+                    if !is_name(&variable.name) {
+                        return error(SyntaxErrorKind::InvalidVariableName(variable.name), i);
+                    }
+                    if let Some(f) = variable.formatters.iter().find(|f| !is_name(&f.name)) {
+                        return error(SyntaxErrorKind::InvalidFormatterName(f.name.clone()), f.span.start);
+                    }
+                }
+                if !text.is_empty() {
+                    r.push(MessagePart::Text(core::mem::take(&mut text)));
+                }
+                i = variable.span.end;
+                r.push(MessagePart::Variable(variable));
+            },
+            None if strict => return error(if braced { SyntaxErrorKind::UnclosedPlaceholder } else { SyntaxErrorKind::ExpectedVariableName }, i),
+            None => {
+                text.push('$');
+                i += 1;
+            },
+        }
+    }
+    if let Some(offset) = open_braces.first() {
+        return error(SyntaxErrorKind::UnbalancedBrace, *offset);
+    }
+    if !text.is_empty() {
+        r.push(MessagePart::Text(text));
+    }
+    Ok(r)
+}
+
+/// Returns the length of the longest prefix of `src` whose characters satisfy `predicate`.
+fn prefix_len(src: &str, predicate: fn(char) -> bool) -> usize {
+    src.find(|ch| !predicate(ch)).unwrap_or(src.len())
+}
+
+/// Reads a `$name` variable at the given offset.
+fn plain_variable(message: &str, start: usize) -> Option<MessageVariable> {
+    let name_len = prefix_len(&message[start + 1..], is_name_char);
+    if name_len == 0 {
+        return None;
+    }
+    let end = start + 1 + name_len;
+    Some(MessageVariable {
+        name: message[start + 1..end].to_string(),
+        formatters: vec![],
+        braced: false,
+        span: start..end,
+    })
+}
+
+/// Reads a `${name|formatter:argument}` variable at the given offset.
+fn braced_variable(message: &str, start: usize) -> Option<MessageVariable> {
+    let mut parts = vec![(String::new(), start + 2)];
+    let mut chars = message[start + 2..].char_indices().map(|(i, ch)| (start + 2 + i, ch));
+    let end = loop {
+        let (i, ch) = chars.next()?;
+        match ch {
+            '\\' if matches!(message[i + 1..].chars().next(), Some('}') | Some('|') | Some('\\')) => {
+                let (_, escaped) = chars.next().unwrap();
+                parts.last_mut().unwrap().0.push(escaped);
+            },
+            '|' => parts.push((String::new(), i)),
+            '}' => break i,
+            ch => parts.last_mut().unwrap().0.push(ch),
+        }
+    };
+    let mut spans: Vec<usize> = parts.iter().map(|(_, start)| *start).collect();
+    spans.push(end);
+    let mut parts = parts.into_iter().enumerate();
+    let name = parts.next().unwrap().1.0.trim().to_string();
+    let formatters = parts.map(|(n, (part, pipe_start))| {
+        let mut spec = part.splitn(2, ':');
+        MessageFormatterCall {
+            name: spec.next().unwrap().trim().to_string(),
+            argument: spec.next().map(|a| a.trim().to_string()),
+            span: pipe_start..spans[n + 1],
+        }
+    }).collect();
+    Some(MessageVariable { name, formatters, braced: true, span: start..end + 1 })
+}
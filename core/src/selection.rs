@@ -0,0 +1,57 @@
+use alloc::{vec, vec::Vec};
+
+/// Gender enumeration. This enumeration can be used as a message formatting argument.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Gender {
+    Male,
+    Female,
+    Other,
+}
+
+/// Selects the `_empty`, `_one` or `_multiple` suffix of a message for a number,
+/// such as `_one` for `"1"`. Anything other than zero and one, including text
+/// that is not a number, selects `_multiple`.
+///
+/// ```
+/// use recoyx_localization_core::quantity_suffix;
+///
+/// assert_eq!(quantity_suffix("0"), "_empty");
+/// assert_eq!(quantity_suffix("1.0"), "_one");
+/// assert_eq!(quantity_suffix("3"), "_multiple");
+/// ```
+pub fn quantity_suffix(number: &str) -> &'static str {
+    match number.parse::<f64>() {
+        Ok(0.0) => "_empty",
+        Ok(1.0) => "_one",
+        _ => "_multiple",
+    }
+}
+
+/// Lists the quantity suffixes a number selects, from the most specific, of which
+/// a message uses the first it has, or else the last one, given the suffix the number
+/// otherwise selects, such as by `quantity_suffix()`: `_negative` before `_multiple` for
+/// a negative number, and `_zero` before the suffix for zero or, if `zero_category`,
+/// a number in the `zero` plural category of the locale, such as 10 in Latvian.
+///
+/// ```
+/// use recoyx_localization_core::{quantity_suffix, quantity_suffixes};
+///
+/// assert_eq!(quantity_suffixes("-2", quantity_suffix("-2"), false), ["_negative", "_multiple"]);
+/// assert_eq!(quantity_suffixes("0", quantity_suffix("0"), false), ["_zero", "_empty"]);
+/// assert_eq!(quantity_suffixes("10", quantity_suffix("10"), true), ["_zero", "_multiple"]);
+/// ```
+pub fn quantity_suffixes(number: &str, suffix: &'static str, zero_category: bool) -> Vec<&'static str> {
+    if number.starts_with('-') && suffix != "_empty" {
+        return vec!["_negative", "_multiple"];
+    }
+    if suffix == "_empty" || zero_category { vec!["_zero", suffix] } else { vec![suffix] }
+}
+
+/// Selects the `_male`, `_female` or `_other` suffix of a message for a gender.
+pub fn gender_suffix(gender: Gender) -> &'static str {
+    match gender {
+        Gender::Male => "_male",
+        Gender::Female => "_female",
+        Gender::Other => "_other",
+    }
+}
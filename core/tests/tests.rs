@@ -0,0 +1,63 @@
+use recoyx_localization_core::*;
+
+#[test]
+fn catalog_lookup() {
+    let mut catalog = Catalog::new();
+    catalog.insert_json("common", r#"{
+        "greeting": { "message": "Hello, $user!", "description": "Home screen title" },
+        "menu": { "open": "Open" },
+        "version": 2
+    }"#).unwrap();
+    assert_eq!(catalog.len(), 2);
+    assert_eq!(catalog.keys().collect::<Vec<_>>(), vec!["common.greeting", "common.menu.open"]);
+    assert_eq!(catalog.get("common.menu.open"), Some("Open"));
+    assert_eq!(catalog.format("common.greeting", &[Argument::Variable("user", "Ada")]).unwrap(), "Hello, Ada!");
+    assert_eq!(catalog.format("common.greeting", &[]).unwrap(), "Hello, undefined!");
+    assert_eq!(catalog.format("common.missing", &[]), None);
+}
+
+#[test]
+fn catalog_selection() {
+    let mut catalog = Catalog::new();
+    catalog.insert_json("cart", r#"{
+        "qty_empty": "No items",
+        "qty_one": "One item",
+        "qty_multiple": "$number items",
+        "qty_negative": "$number returned",
+        "welcome_female": "Welcome, madam",
+        "summary": "${items|plural} in ${files|plural}",
+        "summary_items_multiple": "$number items",
+        "summary_files_one": "one file",
+        "gift": "${recipient|gender|unknown} got a gift",
+        "gift_recipient_male": "He"
+    }"#).unwrap();
+    assert_eq!(catalog.format("cart.qty", &[Argument::Number("0")]).unwrap(), "No items");
+    assert_eq!(catalog.format("cart.qty", &[Argument::Number("1"), Argument::Number("5")]).unwrap(), "One item");
+    assert_eq!(catalog.format("cart.qty", &[Argument::Number("5")]).unwrap(), "5 items");
+    assert_eq!(catalog.format("cart.qty", &[Argument::Number("-2")]).unwrap(), "-2 returned");
+    assert_eq!(catalog.format("cart.welcome", &[Argument::Gender(Gender::Female)]).unwrap(), "Welcome, madam");
+    let args = [Argument::Count("items", "3"), Argument::Count("files", "1")];
+    assert_eq!(catalog.format("cart.summary", &args).unwrap(), "3 items in one file");
+    assert_eq!(catalog.format("cart.gift", &[Argument::GenderOf("recipient", Gender::Male)]).unwrap(), "He got a gift");
+    assert_eq!(catalog.format("cart.gift", &[Argument::GenderOf("recipient", Gender::Female)]).unwrap(), "undefined got a gift");
+}
+
+#[test]
+fn message_syntax() {
+    assert_eq!(parse_message("${name").unwrap_err().kind, SyntaxErrorKind::UnclosedPlaceholder);
+    assert_eq!(tokenize("${name").len(), 1);
+    assert_eq!(quantity_suffix("two"), "_multiple");
+}
+
+#[test]
+fn nested_message_namespace() {
+    assert_eq!(message_of(&serde_json::json!({ "message": "Save", "description": "Toolbar button" })), Some("Save"));
+    assert_eq!(message_of(&serde_json::json!({ "message": "Error" })), None);
+
+    let mut catalog = Catalog::new();
+    catalog.insert_json("common", r#"{ "errors": { "message": "Something went wrong", "title": "Error" } }"#).unwrap();
+    catalog.insert_json("other", r#"{ "errors": { "message": "Something went wrong" } }"#).unwrap();
+    assert_eq!(catalog.format("common.errors.message", &[]).unwrap(), "Something went wrong");
+    assert_eq!(catalog.format("common.errors.title", &[]).unwrap(), "Error");
+    assert_eq!(catalog.format("other.errors.message", &[]).unwrap(), "Something went wrong");
+}
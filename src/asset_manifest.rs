@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use super::Locale;
+
+/// Manifest of the assets published at the assets source, read by
+/// `LocaleMap::discover_assets()` from `manifest.json`, such as:
+///
+/// ```json
+/// {
+///     "locales": ["en-US", "pt-BR"],
+///     "namespaces": ["common", "checkout"],
+///     "versions": { "en-US": "12", "pt-BR": "9" }
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
+pub struct AssetManifest {
+    /// The available locales, each the name of a directory of asset files.
+    pub locales: Vec<String>,
+    /// The base file names of the asset files of every locale.
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    /// An opaque version of the asset files of each locale, which changes
+    /// whenever they are republished.
+    #[serde(default)]
+    pub versions: HashMap<String, String>,
+}
+
+/// Changes of the assets found by `LocaleMap::discover_assets()`,
+/// compared to the previous manifest or, at first, the supported locales.
+#[derive(Clone, Default)]
+pub struct ManifestChanges {
+    /// Locales that were not supported.
+    pub added_locales: Vec<Locale>,
+    /// Locales that are no longer supported.
+    pub removed_locales: Vec<Locale>,
+    /// Locales whose version differs from the previous manifest.
+    pub updated_locales: Vec<Locale>,
+}
+
+impl ManifestChanges {
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added_locales.is_empty() && self.removed_locales.is_empty() && self.updated_locales.is_empty()
+    }
+
+    pub(crate) fn between(previous_locales: &HashMap<Locale, String>, previous: Option<&AssetManifest>, locales: &HashMap<Locale, String>, manifest: &AssetManifest) -> Self {
+        let mut r = ManifestChanges {
+            added_locales: locales.keys().filter(|l| !previous_locales.contains_key(*l)).cloned().collect(),
+            removed_locales: previous_locales.keys().filter(|l| !locales.contains_key(*l)).cloned().collect(),
+            updated_locales: vec![],
+        };
+        if let Some(previous) = previous {
+            r.updated_locales = locales.iter()
+                .filter(|(l, tag)| previous_locales.contains_key(*l) && previous.versions.get(*tag) != manifest.versions.get(*tag))
+                .map(|(l, _)| l.clone())
+                .collect();
+        }
+        r.added_locales.sort();
+        r.removed_locales.sort();
+        r.updated_locales.sort();
+        r
+    }
+}
+
+/// Changes to the messages of a locale since a catalog version, read by
+/// `LocaleMap::apply_delta()` from `delta/{version}.json` under the directory
+/// of the locale, such as:
+///
+/// ```json
+/// {
+///     "version": "13",
+///     "changed": { "common.save": "Save", "checkout.pay": "Pay now" },
+///     "removed": ["common.discard"]
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct CatalogDelta {
+    /// The catalog version after the changes.
+    pub version: String,
+    /// Messages added or changed, by identifier.
+    #[serde(default)]
+    pub changed: HashMap<String, String>,
+    /// Identifiers of the messages removed.
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
@@ -0,0 +1,178 @@
+//! Inspects the messages of a `LocaleMap` from the command line,
+//! available through the `cli` feature.
+
+use std::{collections::HashMap, process::exit};
+use recoyx_localization::*;
+use serde::Deserialize;
+
+const USAGE: &str = "\
+Usage: recoyx-localization [--config <file>] <command>
+
+Commands:
+    keys [prefix]                       List message identifiers and the locales missing them
+    get <locale> <id> [name=value...]   Render a message; count=N also selects the quantity
+    diff <locale> <other-locale>        List the messages only one of two locales has
+    missing [locale]                    Validate translations against the default locale
+
+The configuration file, localization.json by default, specifies the assets, such as:
+    {
+        \"src\": \"res/lang\",
+        \"supported_locales\": [\"en-US\", \"pt-BR\"],
+        \"default_locale\": \"en-US\",
+        \"fallbacks\": { \"pt-BR\": [\"en-US\"] },
+        \"base_file_names\": [\"common\"]
+    }
+Optional fields are \"layered\", \"overlays\" and \"format\" (json, android or apple).";
+
+/// Configuration file of the assets, corresponding to `LocaleMapOptions`.
+#[derive(Deserialize)]
+struct Config {
+    src: String,
+    supported_locales: Vec<String>,
+    default_locale: String,
+    #[serde(default)]
+    fallbacks: HashMap<String, Vec<String>>,
+    base_file_names: Vec<String>,
+    #[serde(default)]
+    layered: bool,
+    #[serde(default)]
+    overlays: Vec<String>,
+    format: Option<String>,
+}
+
+impl Config {
+    fn locale_map(&self) -> LocaleMap {
+        let format = match self.format.as_deref().unwrap_or("json") {
+            "json" => LocaleMapAssetFormat::Json,
+            "android" => LocaleMapAssetFormat::AndroidXml,
+            "apple" => LocaleMapAssetFormat::AppleStrings,
+            format => fail(&format!("Unknown format {}.", format), 2),
+        };
+        let is_http = self.src.starts_with("http://") || self.src.starts_with("https://");
+        LocaleMap::new(LocaleMapOptions::new()
+            .supported_locales(self.supported_locales.clone())
+            .default_locale(&self.default_locale)
+            .fallbacks(self.fallbacks.clone())
+            .assets(LocaleMapAssetOptions::new()
+                .src(&self.src)
+                .base_file_names(self.base_file_names.clone())
+                .auto_clean(false)
+                .layered(self.layered)
+                .overlays(self.overlays.clone())
+                .format(format)
+                .loader_type(if is_http { LocaleMapLoaderType::Http } else { LocaleMapLoaderType::FileSystem })))
+    }
+}
+
+fn fail(message: &str, code: i32) -> ! {
+    eprintln!("{}", message);
+    exit(code)
+}
+
+fn parse_locale_arg(tag: Option<&String>) -> Locale {
+    let tag = tag.unwrap_or_else(|| fail(USAGE, 2));
+    parse_locale(tag).unwrap_or_else(|e| fail(&format!("Invalid locale {}: {}", tag, e), 2))
+}
+
+async fn load_all(locale_map: &mut LocaleMap) {
+    let mut locales: Vec<Locale> = locale_map.supported_locales().into_iter().collect();
+    locales.sort();
+    for locale in locales {
+        if !locale_map.load_secondary_locale(&locale).await {
+            fail(&format!("Failed to load {}.", locale.standard_tag()), 1);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut config_path = "localization.json".to_string();
+    if args.first().map(|a| a == "--config").unwrap_or(false) {
+        if args.len() < 2 {
+            fail(USAGE, 2);
+        }
+        config_path = args.remove(1);
+        args.remove(0);
+    }
+    if args.is_empty() || args[0] == "--help" || args[0] == "-h" {
+        println!("{}", USAGE);
+        return;
+    }
+    let config = std::fs::read_to_string(&config_path)
+        .unwrap_or_else(|e| fail(&format!("Failed to read {}: {}", config_path, e), 2));
+    let config: Config = serde_json::from_str(&config)
+        .unwrap_or_else(|e| fail(&format!("Invalid configuration {}: {}", config_path, e), 2));
+    let mut locale_map = config.locale_map();
+
+    match args[0].as_str() {
+        "keys" => {
+            load_all(&mut locale_map).await;
+            let prefix = args.get(1).map(|s| s.as_str()).unwrap_or("");
+            for entry in locale_map.key_page(prefix, None, usize::MAX).entries {
+                let missing: Vec<String> = entry.presence.iter()
+                    .filter(|(_, present)| !present)
+                    .map(|(l, _)| l.standard_tag().to_string())
+                    .collect();
+                if missing.is_empty() {
+                    println!("{}", entry.id);
+                } else {
+                    println!("{}  (missing in {})", entry.id, missing.join(", "));
+                }
+            }
+        },
+        "get" => {
+            let locale = parse_locale_arg(args.get(1));
+            let id = args.get(2).unwrap_or_else(|| fail(USAGE, 2));
+            if !locale_map.load(Some(locale.clone())).await {
+                fail(&format!("Failed to load {}.", locale.standard_tag()), 1);
+            }
+            let mut vars = HashMap::<String, String>::new();
+            for arg in args[3..].iter() {
+                let (name, value) = arg.split_once('=').unwrap_or_else(|| fail(&format!("Expected name=value, found {}.", arg), 2));
+                vars.insert(name.to_string(), value.to_string());
+            }
+            let count = vars.get("count").and_then(|c| c.parse::<i64>().ok());
+            let resolved_id = match count {
+                Some(count) => locale_map.quantity_variant(id, count),
+                None => id.clone(),
+            };
+            if !locale_map.has_key(&resolved_id) {
+                let current = locale_map.current_locale().unwrap();
+                fail(&format!("{} is not found in {} or its fallbacks.", resolved_id, current.standard_tag()), 1);
+            }
+            println!("{}", match count {
+                Some(count) => locale_map.get_formatted(id, vec![&vars, &count]),
+                None => locale_map.get_formatted(id, vec![&vars]),
+            });
+        },
+        "diff" => {
+            let (a, b) = (parse_locale_arg(args.get(1)), parse_locale_arg(args.get(2)));
+            load_all(&mut locale_map).await;
+            let (a_messages, b_messages) = (locale_map.messages_for(&a).unwrap(), locale_map.messages_for(&b).unwrap());
+            for id in a_messages.keys().filter(|id| !b_messages.contains(id)) {
+                println!("- {}", id);
+            }
+            for id in b_messages.keys().filter(|id| !a_messages.contains(id)) {
+                println!("+ {}", id);
+            }
+        },
+        "missing" => {
+            let report = locale_map.validate().await;
+            let locales: Vec<&LocaleValidation> = match args.get(1) {
+                Some(_) => {
+                    let locale = parse_locale_arg(args.get(1));
+                    report.get(&locale).into_iter().collect()
+                },
+                None => report.locales.iter().collect(),
+            };
+            for l in locales.iter() {
+                print!("{}", l);
+            }
+            if !locales.iter().all(|l| l.is_ok()) {
+                exit(1);
+            }
+        },
+        command => fail(&format!("Unknown command {}.\n\n{}", command, USAGE), 2),
+    }
+}
@@ -0,0 +1,313 @@
+//! Compile-time validation and embedding of the asset files, available through
+//! the `build` feature for use as a build dependency.
+//!
+//! Calling `generate()` from `build.rs` loads every supported locale from the asset
+//! files, fails the build if a file cannot be read or the `$` variables of a message
+//! differ from the default locale's, and writes a Rust module with the messages
+//! as static data and a `Key` enumeration of the message identifiers:
+//!
+//! ```no_run
+//! // build.rs
+//! use recoyx_localization::*;
+//!
+//! fn main() {
+//!     let options = LocaleMapOptions::new();
+//!     options
+//!         .supported_locales(vec!["en-US", "pt-BR"])
+//!         .default_locale("en-US")
+//!         .assets(LocaleMapAssetOptions::new()
+//!             .src("res/lang")
+//!             .base_file_names(vec!["common"]));
+//!     let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("localization.rs");
+//!     build::generate(&options, out).unwrap();
+//! }
+//! ```
+//!
+//! The application then includes the module and constructs its `LocaleMap` from it:
+//!
+//! ```ignore
+//! mod localization {
+//!     include!(concat!(env!("OUT_DIR"), "/localization.rs"));
+//! }
+//!
+//! let locale_map = LocaleMap::from_embedded(&options, localization::ASSETS);
+//! locale_map.get(localization::Key::CommonGreeting);
+//! ```
+//!
+//! The module also has a function retrieving each message of the default locale,
+//! nested in modules after the components of its identifier, taking its `$` variables
+//! as arguments, so that renaming a message or its variables breaks the build:
+//!
+//! ```ignore
+//! localization::messages::common::greeting(&locale_map, "Ana");   // common.greeting: "Hello, $name!"
+//! localization::messages::common::files(&locale_map, 3);           // common.files_one, common.files_multiple...
+//! localization::messages::common::welcome(&locale_map, Gender::Female); // common.welcome_male, common.welcome_female...
+//! ```
+//!
+//! Messages selected by quantity or gender, such as `files_one` and `files_multiple`,
+//! are retrieved through a single function taking a count as `i64` or a `Gender`.
+//! Identifiers are converted to snake case, where keywords are written as raw
+//! identifiers, such as `r#type`, except `self`, `super` and `crate`, which are
+//! followed by an underscore, as in `self_`. Messages or variables whose names
+//! convert to the same identifier fail the build.
+//!
+//! Messages missing from or orphaned in a locale and empty messages are reported
+//! as Cargo warnings without failing the build.
+
+use std::{collections::{BTreeMap, BTreeSet}, path::Path};
+use super::{tokenize, validation, LocaleMap, LocaleMapLoaderType, LocaleMapOptions, MessagePart, MessageTree};
+
+/// Suffixes selected by `LocaleMap::get_formatted()` from its arguments,
+/// whose messages are retrieved through the identifier without the suffix.
+const VARIANT_SUFFIXES: [&str; 8] = ["_empty", "_one", "_multiple", "_zero", "_negative", "_male", "_female", "_other"];
+const QUANTITY_SUFFIXES: [&str; 5] = ["_empty", "_one", "_multiple", "_zero", "_negative"];
+const GENDER_SUFFIXES: [&str; 3] = ["_male", "_female", "_other"];
+
+/// Words that cannot be identifiers in the generated functions.
+const KEYWORDS: [&str; 35] = [
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+/// Keywords that cannot be raw identifiers.
+const PATH_KEYWORDS: [&str; 3] = ["crate", "self", "super"];
+
+/// Validates the asset files of every supported locale, read from the file system
+/// regardless of the loader type, and writes the generated module to `output`
+/// unless it is unchanged. Returns the issues that fail the build.
+pub fn generate<P: AsRef<Path>>(options: &LocaleMapOptions, output: P) -> Result<(), String> {
+    let mut locale_map = LocaleMap::try_new(options)?;
+    std::sync::Arc::make_mut(&mut locale_map._config)._assets_loader_type = LocaleMapLoaderType::FileSystem;
+    println!("cargo:rerun-if-changed={}", locale_map._config._assets_src);
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().map_err(|e| e.to_string())?;
+    let mut locales: Vec<_> = locale_map.supported_locales().into_iter().collect();
+    locales.sort();
+    let mut errors = vec![];
+    let mut trees = BTreeMap::new();
+    for locale in locales.iter() {
+        let mut load_errors = vec![];
+        if let Some(loaded) = runtime.block_on(locale_map.load_single_locale(locale, Some(&mut load_errors))) {
+            trees.insert(locale_map._config._locale_path_components[locale].clone(), (locale, loaded.root));
+        }
+        errors.extend(load_errors.iter().map(|e| e.to_string()));
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    let default_tag = &locale_map._config._locale_path_components[&locale_map._config._default_locale];
+    let reference = &trees[default_tag].1;
+    for (locale, tree) in trees.values() {
+        if *locale == &locale_map._config._default_locale {
+            continue;
+        }
+        let validation = validation::validate_locale(locale, Some(reference), Some(tree), &locale_map._config);
+        errors.extend(validation.placeholder_mismatches.iter().map(|m| format!("{} in {}", m, locale.standard_tag())));
+        for (issue, ids) in [
+            ("missing", &validation.missing_keys), ("orphaned", &validation.orphaned_keys), ("empty", &validation.empty_messages),
+            ("incomplete gender variants of", &validation.incomplete_gender_variants), ("incomplete plural variants of", &validation.incomplete_plural_variants),
+        ] {
+            for id in ids.iter() {
+                println!("cargo:warning={} message {} in {}", issue, id, locale.standard_tag());
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    let mut keys: BTreeMap<String, String> = BTreeMap::new();
+    for id in reference.keys() {
+        let mut ids: Vec<String> = vec![id.to_string()];
+        ids.extend(VARIANT_SUFFIXES.iter().filter_map(|suffix| id.strip_suffix(*suffix)).map(|id| id.to_string()));
+        for id in ids {
+            let variant = variant_name(&id);
+            match keys.get(&variant) {
+                Some(other) if *other != id => return Err(format!("Messages {} and {} are both named Key::{}", other, id, variant)),
+                _ => { keys.insert(variant, id); },
+            }
+        }
+    }
+
+    let mut code = String::from("// Generated by recoyx_localization::build::generate().\n\n");
+    code.push_str("/// The asset trees of the supported locales, as JSON, by locale tag;\n/// see `LocaleMap::from_embedded()`.\n");
+    code.push_str("#[allow(dead_code)]\npub const ASSETS: &[(&str, &str)] = &[\n");
+    for (tag, (_, tree)) in trees.iter() {
+        code.push_str(&format!("    ({:?}, {:?}),\n", tag, tree.to_json().to_string()));
+    }
+    code.push_str("];\n\n");
+    code.push_str("/// The message identifiers of the default locale.\n");
+    code.push_str("#[allow(dead_code)]\n#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]\npub enum Key {\n");
+    for variant in keys.keys() {
+        code.push_str(&format!("    {},\n", variant));
+    }
+    code.push_str("}\n\n#[allow(dead_code)]\nimpl Key {\n    /// Every identifier.\n    pub const ALL: &'static [Key] = &[\n");
+    for variant in keys.keys() {
+        code.push_str(&format!("        Key::{},\n", variant));
+    }
+    code.push_str("    ];\n\n    /// Returns the identifier, such as `common.greeting`.\n    pub const fn id(self) -> &'static str {\n        match self {\n");
+    for (variant, id) in keys.iter() {
+        code.push_str(&format!("            Key::{} => {:?},\n", variant, id));
+    }
+    code.push_str("        }\n    }\n}\n\nimpl std::fmt::Display for Key {\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n        f.write_str(self.id())\n    }\n}\n");
+    code.push_str("\n/// Functions retrieving the messages of the default locale.\n#[allow(dead_code, clippy::all)]\npub mod messages {\n");
+    write_accessors(&mut code, &accessor_modules(reference)?, 1);
+    code.push_str("}\n");
+
+    let output = output.as_ref();
+    if std::fs::read_to_string(output).map(|c| c == code).unwrap_or(false) {
+        return Ok(());
+    }
+    std::fs::write(output, code).map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+}
+
+/// Functions retrieving messages, nested in modules after the components of their identifiers.
+#[derive(Default)]
+struct AccessorModule {
+    modules: BTreeMap<String, AccessorModule>,
+    functions: BTreeMap<String, Accessor>,
+}
+
+/// A function retrieving a message or the messages selected by quantity or gender.
+#[derive(Default)]
+struct Accessor {
+    id: String,
+    count: bool,
+    gender: bool,
+    variables: BTreeSet<String>,
+    /// A message retrieved, for the documentation of the function.
+    example: String,
+}
+
+/// Groups the messages of the default locale into functions, returning an error if the
+/// identifiers of two modules, functions or variables of a function are the same.
+fn accessor_modules(reference: &MessageTree) -> Result<AccessorModule, String> {
+    let mut r = AccessorModule::default();
+    // Identifier prefixes by the path of their module, to detect collisions.
+    let mut module_names: BTreeMap<String, String> = BTreeMap::new();
+    for (id, message) in reference.iter().filter(|(id, _)| !id.contains('#')) {
+        let mut base = id;
+        let count = QUANTITY_SUFFIXES.iter().find_map(|s| base.strip_suffix(*s)).map(|b| base = b).is_some();
+        let gender = GENDER_SUFFIXES.iter().find_map(|s| base.strip_suffix(*s)).map(|b| base = b).is_some();
+        let mut components: Vec<&str> = base.split('.').collect();
+        let name = identifier(components.pop().unwrap());
+        let mut path = String::new();
+        for (i, c) in components.iter().enumerate() {
+            path.push_str(&format!("::{}", identifier(c)));
+            let prefix = components[..=i].join(".");
+            match module_names.get(&path) {
+                Some(other) if *other != prefix => return Err(format!("Messages {} and {} are both in the module messages{}", other, prefix, path)),
+                _ => { module_names.insert(path.clone(), prefix); },
+            }
+        }
+        let module = components.iter().fold(&mut r, |module, c| module.modules.entry(identifier(c)).or_default());
+        let accessor = module.functions.entry(name).or_default();
+        if !accessor.id.is_empty() && accessor.id != base {
+            return Err(format!("Messages {} and {} are both retrieved by the same function", accessor.id, base));
+        }
+        accessor.id = base.to_string();
+        accessor.count |= count;
+        accessor.gender |= gender;
+        if accessor.example.is_empty() {
+            accessor.example = message.to_string();
+        }
+        for part in tokenize(message) {
+            if let MessagePart::Variable(variable) = part {
+                if !(count && variable.name == "number") {
+                    accessor.variables.insert(variable.name);
+                }
+            }
+        }
+    }
+    check_variables(&r)?;
+    Ok(r)
+}
+
+/// Returns an error if two variables of a function have the same identifier.
+fn check_variables(module: &AccessorModule) -> Result<(), String> {
+    for accessor in module.functions.values() {
+        let mut names: BTreeMap<String, &str> = BTreeMap::new();
+        for variable in accessor.variables.iter() {
+            if let Some(other) = names.insert(identifier(variable), variable) {
+                return Err(format!("Variables ${} and ${} of {} are both named {}", other, variable, accessor.id, identifier(variable)));
+            }
+        }
+    }
+    module.modules.values().try_for_each(check_variables)
+}
+
+fn write_accessors(code: &mut String, module: &AccessorModule, depth: usize) {
+    let indent = "    ".repeat(depth);
+    for (i, (name, accessor)) in module.functions.iter().enumerate() {
+        if i > 0 {
+            code.push('\n');
+        }
+        // The parameters other than the variables start with two underscores,
+        // which the identifiers of variables never do.
+        let mut params = vec!["__locale_map: &::recoyx_localization::LocaleMap".to_string()];
+        let mut args = vec![];
+        if !accessor.variables.is_empty() {
+            let vars: Vec<String> = accessor.variables.iter().map(|v| format!("({:?}.to_string(), {}.to_string())", v, identifier(v))).collect();
+            args.push(format!("&vec![{}].into_iter().collect::<::std::collections::HashMap<::std::string::String, ::std::string::String>>()", vars.join(", ")));
+            params.extend(accessor.variables.iter().map(|v| format!("{}: &str", identifier(v))));
+        }
+        if accessor.count {
+            params.push("__count: i64".to_string());
+            args.push("&__count".to_string());
+        }
+        if accessor.gender {
+            params.push("__gender: ::recoyx_localization::Gender".to_string());
+            args.push("&__gender".to_string());
+        }
+        code.push_str(&format!("{}/// `{}`: {}\n", indent, accessor.id, accessor.example.replace('\n', " ")));
+        code.push_str(&format!("{}pub fn {}({}) -> ::std::string::String {{\n", indent, name, params.join(", ")));
+        code.push_str(&format!("{}    __locale_map.get_formatted({:?}, vec![{}])\n{}}}\n", indent, accessor.id, args.join(", "), indent));
+    }
+    for (name, module) in module.modules.iter() {
+        code.push_str(&format!("{}pub mod {} {{\n", indent, name));
+        write_accessors(code, module, depth + 1);
+        code.push_str(&format!("{}}}\n", indent));
+    }
+}
+
+/// Converts a component of a message identifier or a variable name into a
+/// snake case identifier, such as `message_id` for `messageId`.
+fn identifier(name: &str) -> String {
+    let mut r = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !r.ends_with('_') {
+                r.push('_');
+            }
+            r.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            r.push(c);
+        } else if !r.ends_with('_') {
+            r.push('_');
+        }
+    }
+    if !r.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') || r == "_" {
+        r.insert_str(0, "m_");
+    }
+    if KEYWORDS.contains(&r.as_str()) {
+        r.insert_str(0, "r#");
+    } else if PATH_KEYWORDS.contains(&r.as_str()) {
+        r.push('_');
+    }
+    r
+}
+
+/// Converts a message identifier into the name of a `Key` variant,
+/// such as `CommonMessageId` for `common.message_id`.
+fn variant_name(id: &str) -> String {
+    let mut r: String = id.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part[..1].to_uppercase() + &part[1..])
+        .collect();
+    if !r.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        r.insert(0, 'K');
+    }
+    r
+}
@@ -0,0 +1,111 @@
+use serde::Serialize;
+use super::{AssetLoadErrorKind, Locale, LocaleMap, MessageTree};
+
+/// The messages added, removed and changed in a locale between two versions of
+/// a catalog, obtained from `diff_catalogs()` or `diff_messages()`, such as for
+/// release notes to translators. Identifiers are ordered component by component.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct CatalogDiff {
+    /// The tag of the locale, such as `pt-BR`.
+    pub locale: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ChangedMessage>,
+}
+
+/// A message whose text differs between two versions of a catalog.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct ChangedMessage {
+    pub id: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl CatalogDiff {
+    /// Returns `true` if no message was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares the messages of every locale supported by either of two `LocaleMap`s,
+/// such as the catalogs of the previous and the next release, returning the
+/// differences of the locales that have any, ordered by locale. Locales that are
+/// not loaded are read from the assets without being retained. A locale that is
+/// not supported by, or has no assets in, one of them has all of its messages added
+/// or removed, and a locale whose assets fail to load in either is not compared.
+/// Only the messages stored for each locale are compared, not the ones resolved
+/// through fallbacks.
+///
+/// ```
+/// use recoyx_localization::{diff_catalogs, LocaleMap, LocaleMapOptions};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let options = LocaleMapOptions::new();
+/// options.supported_locales(vec!["en"]).default_locale("en");
+/// let old = LocaleMap::from_embedded(&options, &[("en", r#"{"save": "Save", "quit": "Quit"}"#)]);
+/// let new = LocaleMap::from_embedded(&options, &[("en", r#"{"save": "Save changes", "exit": "Exit"}"#)]);
+/// let diffs = diff_catalogs(&old, &new).await;
+/// assert_eq!(diffs[0].added, vec!["exit"]);
+/// assert_eq!(diffs[0].removed, vec!["quit"]);
+/// assert_eq!(diffs[0].changed[0].new, "Save changes");
+/// # });
+/// ```
+pub async fn diff_catalogs(old: &LocaleMap, new: &LocaleMap) -> Vec<CatalogDiff> {
+    let mut locales: Vec<Locale> = old.supported_locales().into_iter().collect();
+    locales.extend(new.supported_locales());
+    locales.sort();
+    locales.dedup();
+    let mut r = vec![];
+    for locale in locales.iter() {
+        if let (Some(old_messages), Some(new_messages)) = (stored_messages(old, locale).await, stored_messages(new, locale).await) {
+            let diff = diff_messages(locale, &old_messages, &new_messages);
+            if !diff.is_empty() {
+                r.push(diff);
+            }
+        }
+    }
+    r
+}
+
+/// Returns the messages stored for a locale, reading them from the assets if the locale
+/// is not loaded, or `None` if any of its assets fails to load for a reason other than
+/// not existing.
+async fn stored_messages(locale_map: &LocaleMap, locale: &Locale) -> Option<MessageTree> {
+    if let Some(messages) = locale_map.messages_for(locale) {
+        return Some(messages.clone());
+    }
+    if !locale_map.supports_locale(locale) {
+        return Some(MessageTree::new());
+    }
+    let mut errors = vec![];
+    let loaded = locale_map.load_single_locale(locale, Some(&mut errors)).await?;
+    if errors.iter().any(|e| e.kind != AssetLoadErrorKind::NotFound) {
+        return None;
+    }
+    Some(loaded.root)
+}
+
+/// Compares two versions of the messages of a locale, such as the assets
+/// of a deployment and the ones it replaces.
+pub fn diff_messages(locale: &Locale, old: &MessageTree, new: &MessageTree) -> CatalogDiff {
+    let mut r = CatalogDiff {
+        locale: locale.standard_tag().to_string(),
+        added: vec![],
+        removed: vec![],
+        changed: vec![],
+    };
+    for (id, message) in new.iter() {
+        match old.get(id) {
+            None => r.added.push(id.to_string()),
+            Some(old_message) if old_message != message => r.changed.push(ChangedMessage {
+                id: id.to_string(),
+                old: old_message.to_string(),
+                new: message.to_string(),
+            }),
+            _ => {},
+        }
+    }
+    r.removed = old.keys().filter(|id| !new.contains(id)).map(|id| id.to_string()).collect();
+    r
+}
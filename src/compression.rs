@@ -0,0 +1,39 @@
+use std::io::{Read, Write};
+
+/// Largest size of the decompressed content of a compressed asset file or namespace,
+/// beyond which it is rejected, so that a small malicious file cannot exhaust memory.
+pub(crate) const MAX_DECOMPRESSED_LEN: usize = 64 * 1024 * 1024;
+
+/// Decompresses the content of an asset file compressed with brotli, detected by
+/// the `.br` extension of its path, or with gzip, detected by its magic bytes,
+/// returning the reason it cannot be decompressed.
+/// Content that is not compressed is returned as is.
+pub(crate) fn decompress_asset(path: &str, content: Vec<u8>) -> Result<Vec<u8>, String> {
+    if path.ends_with(".br") {
+        read_limited(brotli_decompressor::Decompressor::new(content.as_slice(), 4096))
+    } else if content.starts_with(&[0x1f, 0x8b]) {
+        gunzip(&content)
+    } else {
+        Ok(content)
+    }
+}
+
+pub(crate) fn gzip(content: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::fast());
+    encoder.write_all(content).unwrap();
+    encoder.finish().unwrap()
+}
+
+pub(crate) fn gunzip(content: &[u8]) -> Result<Vec<u8>, String> {
+    read_limited(flate2::read::GzDecoder::new(content))
+}
+
+/// Reads decompressed content up to `MAX_DECOMPRESSED_LEN`.
+fn read_limited<R: Read>(reader: R) -> Result<Vec<u8>, String> {
+    let mut r = vec![];
+    reader.take(MAX_DECOMPRESSED_LEN as u64 + 1).read_to_end(&mut r).map_err(|e| e.to_string())?;
+    if r.len() > MAX_DECOMPRESSED_LEN {
+        return Err(format!("decompressed content exceeds {} bytes", MAX_DECOMPRESSED_LEN));
+    }
+    Ok(r)
+}
@@ -1,29 +1,114 @@
-use std::fmt::{Display, Formatter};
-
-#[derive(PartialEq, Clone)]
-pub struct Country {
-    pub(crate) _standard_code: isocountry::CountryCode,
-}
-
-impl Country {
-    pub fn standard_code(&self) -> isocountry::CountryCode {
-        self._standard_code.clone()
-    }
-
-    pub fn universal_name(&self) -> &str {
-        self._standard_code.name()
-    }
-}
-
-impl Display for Country {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self._standard_code.to_string())
-    }
-}
-
-pub fn parse_country<S: ToString>(src: S) -> Result<Country, isocountry::CountryCodeParseErr> {
-    let src = src.to_string();
-    let src: &str = src.as_ref();
-    let r = if src.len() == 3 { isocountry::CountryCode::for_alpha3_caseless(src) } else { isocountry::CountryCode::for_alpha2_caseless(src) };
-    if let Ok(r) = r { Ok(Country { _standard_code: r }) } else { Err(r.unwrap_err()) }
+use super::{CountryData, Continent, PhoneFormat, Subdivision, WeekInfo, Weekday, COUNTRY_DATA, SUBDIVISION_DATA};
+use std::fmt::{Display, Formatter};
+
+#[derive(PartialEq, Clone)]
+pub struct Country {
+    pub(crate) _standard_code: isocountry::CountryCode,
+}
+
+impl Country {
+    pub fn standard_code(&self) -> isocountry::CountryCode {
+        self._standard_code
+    }
+
+    pub fn universal_name(&self) -> &str {
+        self._standard_code.name()
+    }
+
+    fn _get_data(&self) -> &CountryData {
+        COUNTRY_DATA.get(self._standard_code.alpha2()).unwrap()
+    }
+
+    /// Returns the international calling code, without the leading `+`,
+    /// such as `"55"` for Brazil. For countries sharing a numbering plan, such as
+    /// the North American Numbering Plan, this includes the area code, as in `"1876"` for Jamaica.
+    pub fn calling_code(&self) -> Option<&str> {
+        self._get_data().calling_codes.first().map(|c| c.as_ref())
+    }
+
+    /// Returns every international calling code of the country,
+    /// such as `["1809", "1829", "1849"]` for the Dominican Republic.
+    pub fn calling_codes(&self) -> Vec<&str> {
+        self._get_data().calling_codes.iter().map(|c| c.as_ref()).collect()
+    }
+
+    /// Returns the prefix of international phone numbers, such as `"+55"` for Brazil
+    /// and `"+1"` for Jamaica, whose calling code includes the area code `876`.
+    pub fn phone_prefix(&self) -> Option<String> {
+        let code = self.calling_code()?;
+        Some(format!("+{}", if code.starts_with('1') { "1" } else { code }))
+    }
+
+    /// Returns format hints of the country's phone numbers, such as for input placeholders,
+    /// or `None` if they are not bundled for the country.
+    pub fn phone_format(&self) -> Option<&PhoneFormat> {
+        self._get_data().phone_format.as_ref()
+    }
+
+    /// Returns the country code top-level domain, such as `".br"`.
+    pub fn tld(&self) -> Option<&str> {
+        self._get_data().tld.as_deref()
+    }
+
+    /// Returns the ISO 4217 codes of the currencies in use, such as `["EUR"]`.
+    pub fn currencies(&self) -> Vec<&str> {
+        self._get_data().currencies.iter().map(|c| c.as_ref()).collect()
+    }
+
+    pub fn continent(&self) -> Continent {
+        self._get_data().continent
+    }
+
+    /// Returns the week conventions of the country, such as weeks starting on Sunday
+    /// in Brazil and ISO 8601 weeks in Germany.
+    pub fn week_info(&self) -> WeekInfo {
+        let data = self._get_data();
+        WeekInfo {
+            first_day: data.first_day.as_ref().and_then(|d| Weekday::from_code(d)).unwrap_or(Weekday::Monday),
+            minimal_days: data.min_days.unwrap_or(1),
+        }
+    }
+
+    /// Returns the days of the weekend in the country, such as Friday and Saturday
+    /// in Saudi Arabia and only Sunday in India.
+    pub fn weekend(&self) -> Vec<Weekday> {
+        let data = self._get_data();
+        let start = data.weekend_start.as_ref().and_then(|d| Weekday::from_code(d)).unwrap_or(Weekday::Saturday);
+        let end = data.weekend_end.as_ref().and_then(|d| Weekday::from_code(d)).unwrap_or(Weekday::Sunday);
+        let mut r = vec![start];
+        while *r.last().unwrap() != end {
+            r.push(r.last().unwrap().succ());
+        }
+        r
+    }
+
+    /// Returns the ISO 3166-2 subdivisions of the country, such as states and provinces,
+    /// ordered by code, leaving out the subdivisions of other subdivisions, such as the
+    /// departments of French regions. The list is empty for countries without subdivisions,
+    /// such as Vatican City.
+    pub fn subdivisions(&self) -> Vec<Subdivision> {
+        let prefix = format!("{}-", self._standard_code.alpha2());
+        SUBDIVISION_DATA.range(prefix.clone()..).take_while(|(code, _)| code.starts_with(&prefix))
+            .map(|(code, data)| Subdivision { _code: code.as_ref(), _data: data })
+            .collect()
+    }
+
+    /// Returns the emoji flag, made of the regional indicator symbols of the
+    /// ISO 3166-1 alpha-2 code, such as `"🇧🇷"`.
+    pub fn emoji_flag(&self) -> String {
+        self._standard_code.alpha2().chars().map(|ch| char::from_u32(0x1F1E6 + (ch as u32 - 'A' as u32)).unwrap()).collect()
+    }
+}
+
+impl Display for Country {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self._standard_code)
+    }
+}
+
+pub fn parse_country<S: ToString>(src: S) -> Result<Country, isocountry::CountryCodeParseErr> {
+    let src = src.to_string();
+    let src: &str = src.as_ref();
+    let r = if src.len() == 3 { isocountry::CountryCode::for_alpha3_caseless(src) } else { isocountry::CountryCode::for_alpha2_caseless(src) };
+    if let Ok(r) = r { Ok(Country { _standard_code: r }) } else { Err(r.unwrap_err()) }
 }
\ No newline at end of file
@@ -0,0 +1,87 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref COUNTRY_DATA: HashMap<String, CountryData> = serde_json::from_str::<HashMap<String, CountryData>>(&String::from_utf8_lossy(include_bytes!(concat!(env!("OUT_DIR"), "/country_data.json")))).unwrap();
+    pub static ref SUBDIVISION_DATA: BTreeMap<String, SubdivisionData> = serde_json::from_str::<BTreeMap<String, SubdivisionData>>(&String::from_utf8_lossy(include_bytes!(concat!(env!("OUT_DIR"), "/subdivision_data.json")))).unwrap();
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CountryData {
+    pub calling_codes: Vec<String>,
+    pub currencies: Vec<String>,
+    pub continent: Continent,
+    pub tld: Option<String>,
+    #[serde(default)]
+    pub phone_format: Option<PhoneFormat>,
+    /// The first day of the week, such as `"sun"`, if not Monday.
+    #[serde(default)]
+    pub first_day: Option<String>,
+    /// The minimal days of the first week of a year, if not 1.
+    #[serde(default)]
+    pub min_days: Option<u32>,
+    /// The first and last days of the weekend, such as `"fri"` and `"sat"`, if not Saturday and Sunday.
+    #[serde(default)]
+    pub weekend_start: Option<String>,
+    #[serde(default)]
+    pub weekend_end: Option<String>,
+}
+
+/// Format hints of a country's phone numbers, which write each digit as `#`,
+/// for showing placeholders in phone number inputs. The patterns are those of
+/// a typical mobile number, such as `(##) #####-####` for Brazil.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct PhoneFormat {
+    /// The pattern of a number dialed within the country, such as `(##) #####-####`.
+    pub national: String,
+    /// The pattern of a number dialed from abroad, such as `+55 ## #####-####`.
+    pub international: String,
+}
+
+impl PhoneFormat {
+    /// Returns the national pattern with each `#` replaced by a digit of an example
+    /// number, such as `(11) 91234-5678`.
+    pub fn national_example(&self) -> String {
+        example_number(&self.national)
+    }
+
+    /// Returns the international pattern with each `#` replaced by a digit of an example
+    /// number, such as `+55 11 91234-5678`.
+    pub fn international_example(&self) -> String {
+        example_number(&self.international)
+    }
+}
+
+fn example_number(pattern: &str) -> String {
+    let mut digits = "1191234567890".chars().cycle();
+    pattern.chars().map(|c| if c == '#' { digits.next().unwrap() } else { c }).collect()
+}
+
+/// ISO 3166-2 subdivision data, keyed by the subdivision code, such as `BR-SP`.
+#[derive(Serialize, Deserialize)]
+pub struct SubdivisionData {
+    pub category: String,
+    /// The ISO 3166-2 name, such as `Bayern` for `DE-BY`.
+    pub iso_name: String,
+    /// Names from CLDR keyed by language, such as `en`.
+    pub names: HashMap<String, String>,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub enum Continent {
+    #[serde(rename = "AF")]
+    Africa,
+    #[serde(rename = "AN")]
+    Antarctica,
+    #[serde(rename = "AS")]
+    Asia,
+    #[serde(rename = "EU")]
+    Europe,
+    #[serde(rename = "NA")]
+    NorthAmerica,
+    #[serde(rename = "OC")]
+    Oceania,
+    #[serde(rename = "SA")]
+    SouthAmerica,
+}
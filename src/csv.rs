@@ -0,0 +1,57 @@
+/// Appends a CSV record to `output` as specified by RFC 4180, quoting
+/// the fields that contain commas, quotes or line breaks.
+pub(crate) fn write_record<S: AsRef<str>>(output: &mut String, fields: &[S]) {
+    for (i, field) in fields.iter().enumerate() {
+        let field = field.as_ref();
+        if i != 0 {
+            output.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+    output.push_str("\r\n");
+}
+
+/// Parses CSV content as specified by RFC 4180, accepting both CRLF and LF line breaks.
+pub(crate) fn parse(src: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::<Vec<String>>::new();
+    let mut record = Vec::<String>::new();
+    let mut field = String::new();
+    let mut chars = src.trim_start_matches('\u{feff}').chars().peekable();
+    let mut line = 1;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if field.is_empty() => {
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => { chars.next(); field.push('"'); },
+                        Some('"') => break,
+                        Some(ch) => { if ch == '\n' { line += 1; } field.push(ch); },
+                        None => return Err(format!("Unterminated quoted field at line {}", line)),
+                    }
+                }
+                if !matches!(chars.peek(), None | Some(',') | Some('\r') | Some('\n')) {
+                    return Err(format!("Unexpected character after quoted field at line {}", line));
+                }
+            },
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {},
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                line += 1;
+            },
+            ch => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
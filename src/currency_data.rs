@@ -0,0 +1,120 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use lazy_static::lazy_static;
+use super::{locale::likely_locale_of_region, Locale, PluralCategory};
+
+lazy_static! {
+    static ref CURRENCY_DATA: HashMap<String, CurrencyData> = serde_json::from_str::<HashMap<String, CurrencyData>>(&String::from_utf8_lossy(include_bytes!(concat!(env!("OUT_DIR"), "/currency_data.json")))).unwrap();
+}
+
+/// Currency patterns, symbols and display names of a locale, from CLDR, where regional
+/// entries only contain the fields that differ from their language.
+#[derive(Deserialize)]
+struct CurrencyData {
+    /// Pattern of amounts, such as `¤#,##0.00`, where `¤` is the symbol, followed by
+    /// the pattern of negative amounts after a `;`, if it is not the positive one after a minus sign.
+    standard_pattern: Option<String>,
+    /// Pattern of amounts in financial statements, such as `¤#,##0.00;(¤#,##0.00)`.
+    accounting_pattern: Option<String>,
+    /// Combines the start (`{0}`) and end (`{1}`) of a range, as in "$10–$20".
+    range_pattern: Option<String>,
+    /// Combines an amount (`{0}`) and a display name (`{1}`) by plural category, as in "2 US dollars".
+    #[serde(default)]
+    unit_pattern: HashMap<String, String>,
+    /// Symbols keyed by ISO 4217 code, such as `USD`.
+    #[serde(default)]
+    symbols: HashMap<String, String>,
+    /// Display names keyed by ISO 4217 code and then by plural category.
+    #[serde(default)]
+    names: HashMap<String, HashMap<String, String>>,
+}
+
+/// Currency patterns of a locale.
+#[derive(Clone)]
+pub(crate) struct CurrencyPatterns {
+    pub standard: String,
+    pub accounting: String,
+    pub range: String,
+}
+
+/// Currencies whose amounts have no fraction digits or three, from CLDR,
+/// where the others have two.
+const ZERO_DIGIT_CURRENCIES: [&str; 16] = ["BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW", "PYG", "RWF", "UGX", "VND", "VUV", "XAF", "XOF", "XPF"];
+const THREE_DIGIT_CURRENCIES: [&str; 6] = ["BHD", "JOD", "KWD", "LYD", "OMR", "TND"];
+
+/// Returns the number of fraction digits of amounts of a currency.
+pub(crate) fn currency_fraction_digits(code: &str) -> usize {
+    if ZERO_DIGIT_CURRENCIES.contains(&code) { 0 } else if THREE_DIGIT_CURRENCIES.contains(&code) { 3 } else { 2 }
+}
+
+/// Lists the entries of a locale, its language and English, in this order.
+fn entries(locale: &Locale) -> Vec<&'static CurrencyData> {
+    let mut r = language_entries(locale);
+    r.push(&CURRENCY_DATA["en"]);
+    r
+}
+
+/// Lists the entries of a locale and its language, in this order.
+fn language_entries(locale: &Locale) -> Vec<&'static CurrencyData> {
+    let mut r: Vec<&CurrencyData> = vec![];
+    if let Some(region) = locale.region() {
+        r.extend(CURRENCY_DATA.get(&format!("{}-{}", locale.language(), region)));
+    }
+    r.extend(CURRENCY_DATA.get(locale.language()));
+    r
+}
+
+/// Returns the currency patterns of a locale, resolving missing fields
+/// from its language and then from English. With the `-u-rg-` extension,
+/// they are those of the most likely locale of the requested region.
+pub(crate) fn currency_patterns(locale: &Locale) -> CurrencyPatterns {
+    let region_locale = locale.region_override().and_then(|region| likely_locale_of_region(&region));
+    let entries = entries(region_locale.as_ref().unwrap_or(locale));
+    macro_rules! field {
+        ($name:ident) => { entries.iter().find_map(|e| e.$name.clone()).unwrap() };
+    }
+    CurrencyPatterns {
+        standard: field!(standard_pattern),
+        accounting: field!(accounting_pattern),
+        range: field!(range_pattern),
+    }
+}
+
+/// Returns the symbol of a currency in a locale, or its code if it has none.
+pub(crate) fn currency_symbol(locale: &Locale, code: &str) -> String {
+    entries(locale).iter().find_map(|e| e.symbols.get(code).cloned()).unwrap_or_else(|| code.to_string())
+}
+
+/// Lists the currency symbols of a locale, its language and English, longest first.
+pub(crate) fn currency_symbols(locale: &Locale) -> Vec<String> {
+    let mut r: Vec<String> = entries(locale).iter().flat_map(|e| e.symbols.values().cloned()).collect();
+    r.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then_with(|| a.cmp(b)));
+    r.dedup();
+    r
+}
+
+/// Formats an amount already formatted as a number with the display name of a currency
+/// for a plural category, such as "2 US dollars", using the names of the locale and then
+/// its language. Currencies without a display name in the language use their code,
+/// rather than a name in another language.
+pub(crate) fn format_currency_long(locale: &Locale, amount: &str, code: &str, category: PluralCategory) -> String {
+    let entries = entries(locale);
+    let names = language_entries(locale);
+    let category = match category {
+        PluralCategory::ZERO => "zero",
+        PluralCategory::ONE => "one",
+        PluralCategory::TWO => "two",
+        PluralCategory::FEW => "few",
+        PluralCategory::MANY => "many",
+        PluralCategory::OTHER => "other",
+    };
+    let select = |forms: &HashMap<String, String>| forms.get(category).or_else(|| forms.get("other")).cloned();
+    let (data, name) = names.iter()
+        .find_map(|e| e.names.get(code).and_then(select).map(|name| (*e, name)))
+        .unwrap_or_else(|| (entries[0], code.to_string()));
+    select(&data.unit_pattern)
+        .or_else(|| entries.iter().find_map(|e| select(&e.unit_pattern)))
+        .unwrap()
+        .replace("{0}", amount)
+        .replace("{1}", &name)
+}
@@ -0,0 +1,176 @@
+use std::cell::{Cell, RefCell};
+use super::{currency_data::{currency_fraction_digits, currency_patterns, currency_symbol, CurrencyPatterns}, FormatOverrides, Locale, NumberFormatter, NumberFormatOptions};
+
+/// Formats amounts of a currency with its symbol, following the currency patterns of a locale.
+///
+/// ```
+/// use recoyx_localization::{CurrencyFormatter, CurrencyFormatOptions, CurrencyStyle, parse_locale};
+///
+/// let en = parse_locale("en").unwrap();
+/// let formatter = CurrencyFormatter::new(&en, "USD", &CurrencyFormatOptions::new());
+/// assert_eq!(formatter.format(-1234.5), "-$1,234.50");
+/// let formatter = CurrencyFormatter::new(&en, "USD", CurrencyFormatOptions::new().style(CurrencyStyle::Accounting));
+/// assert_eq!(formatter.format(-1234.5), "($1,234.50)");
+/// ```
+#[derive(Clone)]
+pub struct CurrencyFormatter {
+    _number: NumberFormatter,
+    _symbol: String,
+    _pattern: String,
+    _patterns: CurrencyPatterns,
+}
+
+/// Style of the amounts of a `CurrencyFormatter`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CurrencyStyle {
+    /// Negative amounts have a minus sign, as in "-$5.00".
+    Standard,
+    /// Negative amounts are in parentheses where customary in financial
+    /// statements, as in "($5.00)" in English.
+    Accounting,
+}
+
+impl CurrencyFormatter {
+    /// Constructs a formatter for a currency given by its ISO 4217 code, such as `EUR`.
+    pub fn new<S: ToString>(locale: &Locale, code: S, options: &CurrencyFormatOptions) -> Self {
+        let code = code.to_string().to_uppercase();
+        let digits = currency_fraction_digits(&code);
+        let number_options = NumberFormatOptions::new();
+        number_options
+            .minimum_fraction_digits(options._minimum_fraction_digits.get().unwrap_or(digits).min(digits))
+            .maximum_fraction_digits(digits)
+            .overrides(&options._overrides.borrow().clone().unwrap_or_default());
+        if let Some(numbering_system) = options._numbering_system.borrow().as_ref() {
+            number_options.numbering_system(numbering_system);
+        }
+        let patterns = currency_patterns(locale);
+        Self {
+            _number: NumberFormatter::new(locale, &number_options),
+            _symbol: currency_symbol(locale, &code),
+            _pattern: match options._style.get() {
+                CurrencyStyle::Standard => patterns.standard.clone(),
+                CurrencyStyle::Accounting => patterns.accounting.clone(),
+            },
+            _patterns: patterns,
+        }
+    }
+
+    /// Formats an amount, rounded to the fraction digits of the currency.
+    pub fn format(&self, amount: f64) -> String {
+        let number = self._number.format(amount.abs());
+        let (positive, negative) = match self._pattern.split_once(';') {
+            Some((positive, negative)) => (positive, Some(negative)),
+            None => (self._pattern.as_str(), None),
+        };
+        // Amounts rounded to zero have no sign.
+        if amount >= 0.0 || self._number.plural_operands(amount).n == 0.0 {
+            return apply_pattern(positive, &number, &self._symbol);
+        }
+        // The `-` of CLDR patterns stands for the minus sign of the numbering system.
+        let minus = self._number.minus_sign();
+        match negative {
+            Some(negative) => apply_pattern(&negative.replace('-', minus), &number, &self._symbol),
+            None => format!("{}{}", minus, apply_pattern(positive, &number, &self._symbol)),
+        }
+    }
+
+    /// Formats a range of amounts, such as "$10–$20" in English and "10–20 €" in German,
+    /// where the symbol is written once if it follows the amounts.
+    pub fn format_range(&self, start: f64, end: f64) -> String {
+        let positive = self._pattern.split(';').next().unwrap();
+        let symbol_follows = positive.find('¤') > positive.find(['#', '0']);
+        let start = if symbol_follows && start >= 0.0 && end >= 0.0 {
+            self._number.format(start)
+        } else {
+            self.format(start)
+        };
+        self._patterns.range.replace("{0}", &start).replace("{1}", &self.format(end))
+    }
+}
+
+/// Replaces the number placeholder of a CLDR currency pattern, such as `#,##0.00`
+/// in `¤#,##0.00`, with a formatted number and `¤` with a symbol, separating
+/// the symbol from the number by a no-break space if it ends in a letter beside it,
+/// as in "CHF 5.00".
+fn apply_pattern(pattern: &str, number: &str, symbol: &str) -> String {
+    let start = pattern.find(['#', '0']).unwrap_or(pattern.len());
+    let end = pattern.rfind(['#', '0']).map(|i| i + 1).unwrap_or(start);
+    let (prefix, suffix) = (&pattern[..start], &pattern[end..]);
+    let prefix = if prefix.ends_with('¤') && symbol.chars().last().map(|c| c.is_alphanumeric()).unwrap_or(false) {
+        prefix.replace('¤', &format!("{}\u{A0}", symbol))
+    } else {
+        prefix.replace('¤', symbol)
+    };
+    let suffix = if suffix.starts_with('¤') && symbol.chars().next().map(|c| c.is_alphanumeric()).unwrap_or(false) {
+        suffix.replace('¤', &format!("\u{A0}{}", symbol))
+    } else {
+        suffix.replace('¤', symbol)
+    };
+    format!("{}{}{}", prefix, number, suffix)
+}
+
+/// Options for constructing a `CurrencyFormatter`.
+#[derive(Clone)]
+pub struct CurrencyFormatOptions {
+    _style: Cell<CurrencyStyle>,
+    _minimum_fraction_digits: Cell<Option<usize>>,
+    _numbering_system: RefCell<Option<String>>,
+    _overrides: RefCell<Option<FormatOverrides>>,
+}
+
+impl CurrencyFormatOptions {
+    pub fn new() -> Self {
+        CurrencyFormatOptions {
+            _style: Cell::new(CurrencyStyle::Standard),
+            _minimum_fraction_digits: Cell::new(None),
+            _numbering_system: RefCell::new(None),
+            _overrides: RefCell::new(None),
+        }
+    }
+
+    /// The style of negative amounts. The default is `CurrencyStyle::Standard`.
+    pub fn style(&self, value: CurrencyStyle) -> &Self {
+        self._style.set(value);
+        self
+    }
+
+    /// Minimum number of fraction digits, such as 0 for "$10" rather than "$10.00".
+    /// The default is the number of fraction digits of the currency.
+    pub fn minimum_fraction_digits(&self, value: usize) -> &Self {
+        self._minimum_fraction_digits.set(Some(value));
+        self
+    }
+
+    /// CLDR numbering system identifier of the digits, such as `"arab"`. The default,
+    /// also used if the identifier is unknown, is the one requested by the locale's
+    /// `-u-nu-` extension, or else the Latin one.
+    pub fn numbering_system<S: ToString>(&self, value: S) -> &Self {
+        self._numbering_system.replace(Some(value.to_string()));
+        self
+    }
+
+    /// Preferences that take precedence over the CLDR data of the locale.
+    pub fn overrides(&self, value: &FormatOverrides) -> &Self {
+        self._overrides.replace(Some(value.clone()));
+        self
+    }
+
+    /// Returns a copy of the options with the unspecified numbering system and overrides
+    /// taken from other options.
+    pub(crate) fn with_defaults(&self, defaults: &CurrencyFormatOptions) -> CurrencyFormatOptions {
+        let r = self.clone();
+        if r._numbering_system.borrow().is_none() {
+            r._numbering_system.replace(defaults._numbering_system.borrow().clone());
+        }
+        if r._overrides.borrow().is_none() {
+            r._overrides.replace(defaults._overrides.borrow().clone());
+        }
+        r
+    }
+}
+
+impl Default for CurrencyFormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::{Datelike, NaiveDate};
+use super::{formatting_data::{formatting_data, pattern_fields, FormattingData}, numbering_system::digit_value, FormatOverrides, Locale};
+
+/// Parses dates typed by users in the conventions of a locale, such as in search filters
+/// and form fields. The parsing is lenient: numeric dates follow the field order of the
+/// short date pattern of the locale, with any separators, while dates with month names,
+/// abbreviated or not, take the day and year in the order of the medium date pattern.
+/// Weekday names and other words, such as "de" in Spanish, are ignored, and a year
+/// of two digits is taken as the closest to the current year.
+///
+/// ```
+/// use recoyx_localization::{DateParser, parse_locale};
+/// use chrono::NaiveDate;
+///
+/// let parser = DateParser::new(&parse_locale("pt-BR").unwrap());
+/// assert_eq!(parser.parse("04/03/2025"), Ok(NaiveDate::from_ymd_opt(2025, 3, 4).unwrap()));
+/// assert_eq!(parser.parse("4 de março de 2025"), Ok(NaiveDate::from_ymd_opt(2025, 3, 4).unwrap()));
+/// assert!(parser.parse("31/02/2025").is_err());
+/// ```
+#[derive(Clone)]
+pub struct DateParser {
+    _data: FormattingData,
+}
+
+/// Token of a date typed by a user.
+enum DateToken {
+    Number(String),
+    Word(String),
+}
+
+impl DateParser {
+    pub fn new(locale: &Locale) -> Self {
+        Self { _data: formatting_data(locale) }
+    }
+
+    /// Applies the numeric date order of format overrides, as in `LocaleMap::date_parser()`.
+    pub(crate) fn with_overrides(mut self, overrides: &FormatOverrides) -> Self {
+        self._data = self._data.with_overrides(overrides);
+        self
+    }
+
+    /// Parses a date, returning an error if the input is not a valid date.
+    pub fn parse(&self, input: &str) -> Result<NaiveDate, String> {
+        let error = || format!("Invalid date: {}.", input);
+        let tokens = tokenize(input);
+        let mut numbers: Vec<&str> = vec![];
+        let mut month: Option<u32> = None;
+        for token in tokens.iter() {
+            match token {
+                DateToken::Number(n) => numbers.push(n),
+                DateToken::Word(w) => if let Some(m) = self.month_of(w) {
+                    if month.is_some() {
+                        return Err(error());
+                    }
+                    month = Some(m);
+                },
+            }
+        }
+
+        let (year, month, day) = match month {
+            Some(month) => {
+                if numbers.len() != 2 {
+                    return Err(error());
+                }
+                // A year of four digits is recognized wherever it is.
+                let year_first = if numbers[0].len() > 2 || numbers[1].len() > 2 {
+                    numbers[0].len() > 2
+                } else {
+                    field_order(&self._data.date_formats.medium).first() == Some(&'y')
+                };
+                let (year, day) = if year_first { (numbers[0], numbers[1]) } else { (numbers[1], numbers[0]) };
+                (year, month, day.parse::<u32>().map_err(|_| error())?)
+            },
+            None => {
+                if numbers.len() != 3 {
+                    return Err(error());
+                }
+                // Dates starting with a year of four digits are read as ISO 8601 dates.
+                let order = if numbers[0].len() == 4 { vec!['y', 'M', 'd'] } else { field_order(&self._data.date_formats.short) };
+                let field = |f: char| order.iter().position(|o| *o == f).map(|i| numbers[i]).ok_or_else(error);
+                (field('y')?, field('M')?.parse::<u32>().map_err(|_| error())?, field('d')?.parse::<u32>().map_err(|_| error())?)
+            },
+        };
+        let year = match year.len() {
+            1 | 2 => closest_year(year.parse::<i32>().map_err(|_| error())?),
+            _ => year.parse::<i32>().map_err(|_| error())?,
+        };
+        NaiveDate::from_ymd_opt(year, month, day).ok_or_else(error)
+    }
+
+    /// Returns the month, from 1, whose name or abbreviation is the given word or starts
+    /// with it, if the word has at least three letters and no other month name starts with it.
+    fn month_of(&self, word: &str) -> Option<u32> {
+        let word = word.to_lowercase();
+        // Weekday abbreviations may start month names, as "mar." for Tuesday in French.
+        let is_weekday = [&self._data.weekdays, &self._data.weekdays_standalone].iter()
+            .flat_map(|widths| widths.wide.iter().chain(widths.abbreviated.iter()))
+            .any(|name| name.to_lowercase().trim_end_matches('.') == word);
+        if is_weekday {
+            return None;
+        }
+        let names: Vec<(u32, String)> = [&self._data.months, &self._data.months_standalone].iter()
+            .flat_map(|widths| widths.wide.iter().enumerate().chain(widths.abbreviated.iter().enumerate()))
+            .map(|(i, name)| (i as u32 + 1, name.to_lowercase().trim_end_matches('.').to_string()))
+            .collect();
+        if let Some((month, _)) = names.iter().find(|(_, name)| *name == word) {
+            return Some(*month);
+        }
+        if word.chars().count() < 3 {
+            return None;
+        }
+        let mut matches = names.iter().filter(|(_, name)| name.starts_with(&word)).map(|(month, _)| *month);
+        let month = matches.next()?;
+        if matches.all(|m| m == month) { Some(month) } else { None }
+    }
+}
+
+/// Splits a date into numbers, whose digits of any numbering system are converted
+/// to ASCII, and words, skipping punctuation and spaces.
+fn tokenize(input: &str) -> Vec<DateToken> {
+    let mut r: Vec<DateToken> = vec![];
+    for ch in input.chars() {
+        if let Some(d) = digit_value(ch) {
+            let d = std::char::from_digit(d, 10).unwrap();
+            match r.last_mut() {
+                Some(DateToken::Number(n)) if !n.is_empty() => n.push(d),
+                _ => r.push(DateToken::Number(d.to_string())),
+            }
+        } else if ch.is_alphabetic() {
+            match r.last_mut() {
+                Some(DateToken::Word(w)) => w.push(ch),
+                _ => r.push(DateToken::Word(ch.to_string())),
+            }
+        } else {
+            // Separates the next token from the last one.
+            r.push(DateToken::Number(String::new()));
+        }
+    }
+    r.retain(|t| !matches!(t, DateToken::Number(n) if n.is_empty()));
+    r
+}
+
+/// Lists the year, month and day fields of a date pattern in their order.
+fn field_order(pattern: &str) -> Vec<char> {
+    let mut r = vec![];
+    for (field, s) in pattern_fields(pattern) {
+        let ch = s.chars().next().unwrap();
+        if field && ['y', 'M', 'L', 'd'].contains(&ch) {
+            let ch = if ch == 'L' { 'M' } else { ch };
+            if !r.contains(&ch) {
+                r.push(ch);
+            }
+        }
+    }
+    r
+}
+
+/// Resolves a year of two digits to the year ending in them that is at most
+/// 80 years before and 20 years after the current year.
+fn closest_year(year: i32) -> i32 {
+    let current = chrono::Utc::now().year();
+    let r = current - current.rem_euclid(100) + year;
+    if r > current + 20 { r - 100 } else if r <= current - 80 { r + 100 } else { r }
+}
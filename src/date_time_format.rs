@@ -0,0 +1,330 @@
+use std::cell::{Cell, RefCell};
+use chrono::{DateTime, Datelike, FixedOffset, Timelike};
+use super::{formatting_data::{formatting_data, is_numeric_date_pattern, pattern_fields, pattern_hour_cycle, with_hour_cycle, FormattingData}, FormatOverrides, HourCycle, Locale, NumberingSystem, parse_numbering_system};
+
+/// Length of the date or time part of a `DateTimeFormatter`'s output.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DateTimeLength {
+    /// Such as "Tuesday, March 4, 2025" and "3:05:09 PM GMT-03:00".
+    Full,
+    /// Such as "March 4, 2025" and "3:05:09 PM GMT-3".
+    Long,
+    /// Such as "Mar 4, 2025" and "3:05:09 PM".
+    Medium,
+    /// Such as "3/4/25" and "3:05 PM".
+    Short,
+}
+
+/// Width of month and weekday names.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum NameWidth {
+    /// Such as "January" and "Sunday".
+    Wide,
+    /// Such as "Jan" and "Sun".
+    Abbreviated,
+    /// Such as "J" and "S".
+    Narrow,
+}
+
+/// Context of month and weekday names, which differ in languages such as
+/// Russian and Polish, where months within dates are declined.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum NameContext {
+    /// Names within dates, as in "4 марта 2025".
+    Format,
+    /// Names on their own, as in a calendar header reading "март".
+    Standalone,
+}
+
+/// Formats dates and times with the month names and patterns of a locale.
+///
+/// ```
+/// use recoyx_localization::{DateTimeFormatter, DateTimeFormatOptions, DateTimeLength, parse_locale};
+/// use chrono::{FixedOffset, TimeZone};
+///
+/// let formatter = DateTimeFormatter::new(&parse_locale("en-US").unwrap(), DateTimeFormatOptions::new().time_length(None));
+/// let date = FixedOffset::east_opt(0).unwrap().with_ymd_and_hms(2025, 3, 4, 15, 5, 9).unwrap();
+/// assert_eq!(formatter.format(&date), "Mar 4, 2025");
+/// ```
+#[derive(Clone)]
+pub struct DateTimeFormatter {
+    _data: FormattingData,
+    _date_length: Option<DateTimeLength>,
+    _time_length: Option<DateTimeLength>,
+    _numbering_system: NumberingSystem,
+}
+
+impl DateTimeFormatter {
+    pub fn new(locale: &Locale, options: &DateTimeFormatOptions) -> Self {
+        let mut data = formatting_data(locale).with_overrides(&options._overrides.borrow());
+        if let Some(hour_cycle) = options._hour_cycle.get().or_else(|| locale.hour_cycle()) {
+            data.time_formats = data.time_formats.map(|p| with_hour_cycle(p, hour_cycle));
+        }
+        Self {
+            _data: data,
+            _date_length: options._date_length.get(),
+            _time_length: options._time_length.get(),
+            _numbering_system: options._numbering_system.borrow().as_ref().and_then(|s| parse_numbering_system(s).ok())
+                .or_else(|| locale.numbering_system())
+                .unwrap_or_else(NumberingSystem::latin),
+        }
+    }
+
+    /// Returns the hour cycle of the formatted times, which is the one specified by
+    /// `DateTimeFormatOptions::hour_cycle()`, if any; otherwise the one requested by
+    /// the locale's `-u-hc-` extension; otherwise the locale's preferred one.
+    pub fn hour_cycle(&self) -> HourCycle {
+        pattern_hour_cycle(&self._data.time_formats.short).unwrap_or(HourCycle::H23)
+    }
+
+    /// Returns the month names, from January to December.
+    pub fn month_names(&self, width: NameWidth, context: NameContext) -> Vec<String> {
+        match context {
+            NameContext::Format => self._data.months.get(width).clone(),
+            NameContext::Standalone => self._data.months_standalone.get(width).clone(),
+        }
+    }
+
+    /// Returns the weekday names, from Sunday to Saturday.
+    pub fn weekday_names(&self, width: NameWidth, context: NameContext) -> Vec<String> {
+        match context {
+            NameContext::Format => self._data.weekdays.get(width).clone(),
+            NameContext::Standalone => self._data.weekdays_standalone.get(width).clone(),
+        }
+    }
+
+    /// Formats a date and time in its own UTC offset.
+    pub fn format(&self, date: &DateTime<FixedOffset>) -> String {
+        let date_part = self._date_length.map(|l| self.format_pattern(date, Self::pattern(&self._data.date_formats, l)));
+        let time_part = self._time_length.map(|l| self.format_pattern(date, Self::pattern(&self._data.time_formats, l)));
+        match (date_part, time_part) {
+            (Some(d), Some(t)) => self._data.date_time_format.replace("{1}", &d).replace("{0}", &t),
+            (Some(d), None) => d,
+            (None, Some(t)) => t,
+            (None, None) => String::new(),
+        }
+    }
+
+    /// Formats the range of dates from `start` to `end` with the date length of the
+    /// formatter, or `DateTimeLength::Medium` if it has none, collapsing the fields
+    /// the dates share, as in "Mar 4 – 9, 2025". Numeric dates are not collapsed,
+    /// as in "3/4/25 – 3/9/25". Times are not included.
+    pub fn format_range(&self, start: &DateTime<FixedOffset>, end: &DateTime<FixedOffset>) -> String {
+        let pattern = Self::pattern(&self._data.date_formats, self._date_length.unwrap_or(DateTimeLength::Medium));
+        let end_in_start_offset = end.with_timezone(start.offset());
+        // Fields that differ between the dates, from the greatest difference down to the day.
+        let differing: &[char] = if start.year() != end_in_start_offset.year() {
+            return self.format_pattern(start, pattern) + &self._data.interval_separator + &self.format_pattern(end, pattern);
+        } else if start.month() != end_in_start_offset.month() {
+            &['M', 'L', 'd', 'E', 'c']
+        } else if start.day() != end_in_start_offset.day() {
+            &['d', 'E', 'c']
+        } else {
+            return self.format_pattern(start, pattern);
+        };
+        let fields = pattern_fields(pattern);
+        let is_differing = |(field, s): &(bool, String)| *field && s.starts_with(differing);
+        let first = fields.iter().position(is_differing).unwrap_or(0);
+        let mut last = fields.iter().rposition(is_differing).unwrap_or(fields.len() - 1);
+        // Numeric dates with unlabeled fields, such as 3/4/25, are not collapsed.
+        let unlabeled = fields.iter().all(|(field, s)| *field || !s.chars().any(char::is_alphabetic));
+        if (is_numeric_date_pattern(pattern) && unlabeled) || (first == 0 && last == fields.len() - 1) {
+            return self.format_pattern(start, pattern) + &self._data.interval_separator + &self.format_pattern(end, pattern);
+        }
+        // Repeats the suffix of a field that belongs to it, as in "5.–9. Januar" and "4日～9日".
+        let mut fields = fields;
+        if let Some((false, literal)) = fields.get(last + 1).cloned() {
+            let len = literal.find(|ch: char| !(ch.is_alphabetic() || ch == '.')).unwrap_or(literal.len());
+            if len != 0 && !literal.starts_with('\'') {
+                fields[last + 1].1 = literal[len..].to_string();
+                fields.insert(last + 1, (false, literal[..len].to_string()));
+                last += 1;
+            }
+        }
+        let join = |fields: &[(bool, String)]| fields.iter().map(|(_, s)| s.as_str()).collect::<String>();
+        let (prefix, repeated, suffix) = (join(&fields[..first]), join(&fields[first..=last]), join(&fields[last + 1..]));
+        self.format_pattern(start, &prefix) + &self.format_pattern(start, &repeated) + &self._data.interval_separator
+            + &self.format_pattern(end, &repeated) + &self.format_pattern(start, &suffix)
+    }
+
+    /// Formats a date with a CLDR date pattern, such as `"d MMM y"` or `"QQQ y"`. The supported
+    /// fields are `G`, `y`, `Q`, `q`, `M`, `L`, `d`, `E`, `c`, `a`, `h`, `H`, `K`, `k`, `m`, `s`,
+    /// `S` and `z`; text between single quotes is literal.
+    pub fn format_pattern(&self, date: &DateTime<FixedOffset>, pattern: &str) -> String {
+        let mut r = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch == '\'' {
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                    r.push('\'');
+                    continue;
+                }
+                while let Some(ch) = chars.next() {
+                    if ch == '\'' {
+                        if chars.peek() != Some(&'\'') {
+                            break;
+                        }
+                        chars.next();
+                    }
+                    r.push(ch);
+                }
+                continue;
+            }
+            if !ch.is_ascii_alphabetic() {
+                r.push(ch);
+                continue;
+            }
+            let mut count = 1;
+            while chars.peek() == Some(&ch) {
+                chars.next();
+                count += 1;
+            }
+            r.push_str(&self.format_field(date, ch, count));
+        }
+        r
+    }
+
+    fn format_field(&self, date: &DateTime<FixedOffset>, field: char, count: usize) -> String {
+        let data = &self._data;
+        let r = match field {
+            'G' => {
+                let (common_era, _) = date.year_ce();
+                let names = match count { 4 => &data.eras.wide, 5 => &data.eras.narrow, _ => &data.eras.abbreviated };
+                names[if common_era { 1 } else { 0 }].clone()
+            },
+            // Years are counted within their era, as in 44 BC.
+            'y' if count == 2 => format!("{:02}", date.year_ce().1 % 100),
+            'y' => format!("{:01$}", date.year_ce().1, count),
+            'Q' | 'q' => {
+                let quarter = date.month0() as usize / 3;
+                match count {
+                    1 | 2 => format!("{:01$}", quarter + 1, count),
+                    3 => data.quarters.abbreviated[quarter].clone(),
+                    4 => data.quarters.wide[quarter].clone(),
+                    _ => data.quarters.narrow[quarter].clone(),
+                }
+            },
+            'M' | 'L' => {
+                let names = if field == 'L' { &data.months_standalone } else { &data.months };
+                match count {
+                    1 | 2 => format!("{:01$}", date.month(), count),
+                    3 => names.abbreviated[date.month0() as usize].clone(),
+                    4 => names.wide[date.month0() as usize].clone(),
+                    _ => names.narrow[date.month0() as usize].clone(),
+                }
+            },
+            'd' => format!("{:01$}", date.day(), count),
+            'E' | 'c' => {
+                let names = if field == 'c' { &data.weekdays_standalone } else { &data.weekdays };
+                let weekday = date.weekday().num_days_from_sunday() as usize;
+                match count {
+                    4 => names.wide[weekday].clone(),
+                    5 => names.narrow[weekday].clone(),
+                    _ => names.abbreviated[weekday].clone(),
+                }
+            },
+            'a' => data.day_periods[if date.hour() < 12 { 0 } else { 1 }].clone(),
+            'h' => format!("{:01$}", (date.hour() + 11) % 12 + 1, count),
+            'H' => format!("{:01$}", date.hour(), count),
+            'K' => format!("{:01$}", date.hour() % 12, count),
+            'k' => format!("{:01$}", if date.hour() == 0 { 24 } else { date.hour() }, count),
+            'm' => format!("{:01$}", date.minute(), count),
+            's' => format!("{:01$}", date.second(), count),
+            'S' => format!("{:09}", date.nanosecond() % 1_000_000_000)[..count.min(9)].to_string(),
+            'z' => Self::format_offset(date.offset().local_minus_utc(), count >= 4),
+            _ => return field.to_string().repeat(count),
+        };
+        let numeric = match field {
+            'M' | 'L' | 'Q' | 'q' => count <= 2,
+            'a' | 'E' | 'c' | 'z' | 'G' => false,
+            _ => true,
+        };
+        if numeric { self._numbering_system.transliterate(r) } else { r }
+    }
+
+    /// Formats a UTC offset as in "GMT-3" or, in the long form, "GMT-03:00".
+    fn format_offset(seconds: i32, long: bool) -> String {
+        if seconds == 0 {
+            return "GMT".to_string();
+        }
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let (hours, minutes) = (seconds.abs() / 3600, seconds.abs() / 60 % 60);
+        if long {
+            format!("GMT{}{:02}:{:02}", sign, hours, minutes)
+        } else if minutes == 0 {
+            format!("GMT{}{}", sign, hours)
+        } else {
+            format!("GMT{}{}:{:02}", sign, hours, minutes)
+        }
+    }
+
+    fn pattern(lengths: &super::formatting_data::Lengths, length: DateTimeLength) -> &str {
+        match length {
+            DateTimeLength::Full => &lengths.full,
+            DateTimeLength::Long => &lengths.long,
+            DateTimeLength::Medium => &lengths.medium,
+            DateTimeLength::Short => &lengths.short,
+        }
+    }
+}
+
+/// Options for constructing a `DateTimeFormatter`.
+pub struct DateTimeFormatOptions {
+    _date_length: Cell<Option<DateTimeLength>>,
+    _time_length: Cell<Option<DateTimeLength>>,
+    _hour_cycle: Cell<Option<HourCycle>>,
+    _numbering_system: RefCell<Option<String>>,
+    _overrides: RefCell<FormatOverrides>,
+}
+
+impl DateTimeFormatOptions {
+    pub fn new() -> Self {
+        DateTimeFormatOptions {
+            _date_length: Cell::new(Some(DateTimeLength::Medium)),
+            _time_length: Cell::new(Some(DateTimeLength::Short)),
+            _hour_cycle: Cell::new(None),
+            _numbering_system: RefCell::new(None),
+            _overrides: RefCell::new(FormatOverrides::new()),
+        }
+    }
+
+    /// Length of the date, or `None` to omit it. The default is `DateTimeLength::Medium`.
+    pub fn date_length(&self, value: Option<DateTimeLength>) -> &Self {
+        self._date_length.set(value);
+        self
+    }
+
+    /// Length of the time, or `None` to omit it. The default is `DateTimeLength::Short`.
+    pub fn time_length(&self, value: Option<DateTimeLength>) -> &Self {
+        self._time_length.set(value);
+        self
+    }
+
+    /// Hour cycle of the times, such as `HourCycle::H23` for 15:00. The default is the
+    /// one requested by the locale's `-u-hc-` extension, or else the locale's preferred one.
+    pub fn hour_cycle(&self, value: HourCycle) -> &Self {
+        self._hour_cycle.set(Some(value));
+        self
+    }
+
+    /// CLDR numbering system identifier of the digits, such as `"arab"`. The default,
+    /// also used if the identifier is unknown, is the one requested by the locale's
+    /// `-u-nu-` extension, or else the Latin one.
+    pub fn numbering_system<S: ToString>(&self, value: S) -> &Self {
+        self._numbering_system.replace(Some(value.to_string()));
+        self
+    }
+
+    /// Preferences that take precedence over the CLDR data of the locale.
+    pub fn overrides(&self, value: &FormatOverrides) -> &Self {
+        self._overrides.replace(value.clone());
+        self
+    }
+}
+
+impl Default for DateTimeFormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
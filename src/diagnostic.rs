@@ -0,0 +1,55 @@
+use std::fmt::{Display, Formatter};
+use super::{AssetLoadErrorKind, Locale, PlaceholderMismatch};
+
+/// Severity of a `Diagnostic`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DiagnosticSeverity {
+    /// The issue is reported and loading proceeds.
+    Warning,
+    /// The issue is reported and `LocaleMap::load()` fails.
+    Error,
+}
+
+impl Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiagnosticSeverity::Warning => write!(f, "warning"),
+            DiagnosticSeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Issue found in the assets of a locale while loading it,
+/// reported through `LocaleMapOptions::on_diagnostic()`.
+#[derive(Clone)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub locale: Locale,
+    pub kind: DiagnosticKind,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in {}: {}", self.severity, self.locale.standard_tag(), self.kind)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DiagnosticKind {
+    /// The `$` variables of a message differ from the default locale's,
+    /// checked if `LocaleMapOptions::check_placeholders()` is specified.
+    PlaceholderMismatch(PlaceholderMismatch),
+    /// An asset file of an overlay failed to load, so the overlay lacks its messages
+    /// while the base messages of the locale are kept. Reported by `LocaleMap::load()`;
+    /// `LocaleMap::load_partial()` lists the file among its errors instead.
+    OverlayAssetFailed { path: String, reason: AssetLoadErrorKind },
+}
+
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiagnosticKind::PlaceholderMismatch(mismatch) => write!(f, "{}", mismatch),
+            DiagnosticKind::OverlayAssetFailed { path, reason } => write!(f, "overlay asset {}: {}", path, reason),
+        }
+    }
+}
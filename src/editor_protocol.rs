@@ -0,0 +1,271 @@
+use std::{io::{Read, Write}, net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs}, time::{Duration, Instant}};
+use serde::{Serialize, Deserialize};
+use super::LocaleMap;
+
+/// Request sent by an in-app translation editor, encoded as JSON
+/// with a `"type"` field, such as `{"type": "list_keys"}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EditorRequest {
+    /// Lists the messages captured by `LocaleMap::start_capture()`.
+    ListKeys,
+    /// Changes a message of the current locale. The change is visible immediately.
+    SetMessage { id: String, message: String },
+    /// Writes the changed messages back to the asset files.
+    Save,
+}
+
+/// Response to an `EditorRequest`, encoded as JSON with a `"type"` field.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EditorResponse {
+    Keys { locale: Option<String>, keys: Vec<EditorKey> },
+    Ok,
+    Error { message: String },
+}
+
+/// A captured message as listed by `EditorRequest::ListKeys`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EditorKey {
+    pub id: String,
+    /// The message stored for the current locale, without consulting fallbacks.
+    pub message: Option<String>,
+}
+
+/// Serves the in-app translation editor protocol over HTTP.
+///
+/// Each request is a `POST` whose body is an `EditorRequest`; `GET` is
+/// equivalent to `EditorRequest::ListKeys`. Since a `LocaleMap` cannot be
+/// shared across threads, the server does not run on its own: the application
+/// calls `poll()` periodically, such as once per frame, to handle pending requests.
+/// Polling never waits for clients: requests are read as their bytes arrive,
+/// over as many polls as needed, and connections idle for 5 seconds are closed.
+///
+/// Since the protocol writes asset files, every request must carry the session
+/// token of the server, given by `token()`, in an `Authorization: Bearer` header.
+/// Requests from web pages are only answered, and given CORS headers, if their
+/// origin is allowed through `allow_origin()`, so that other pages visited by
+/// the translator cannot change the messages.
+pub struct EditorServer {
+    _listener: TcpListener,
+    _token: String,
+    _allowed_origins: Vec<String>,
+    _connections: Vec<EditorConnection>,
+    _pending_edits: Vec<(String, String)>,
+}
+
+/// A connection whose request has not been read completely.
+struct EditorConnection {
+    stream: TcpStream,
+    content: Vec<u8>,
+    accepted_at: Instant,
+}
+
+/// An HTTP request read from a connection.
+struct HttpRequest {
+    method: String,
+    origin: Option<String>,
+    authorization: Option<String>,
+    body: Vec<u8>,
+    /// Whether the `Content-Length` exceeds the maximum size of a request,
+    /// in which case the body is not read and the request is rejected.
+    too_large: bool,
+}
+
+/// Time after which a connection that has not sent a complete request is closed.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Maximum size of a request, including its headers.
+const MAX_REQUEST_SIZE: usize = 1 << 20;
+
+impl EditorServer {
+    /// Binds the server to the given address, such as `"127.0.0.1:7878"`,
+    /// generating a random session token.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let mut token = [0u8; 16];
+        getrandom::getrandom(&mut token)?;
+        Ok(Self {
+            _listener: listener,
+            _token: token.iter().map(|b| format!("{:02x}", b)).collect(),
+            _allowed_origins: vec![],
+            _connections: vec![],
+            _pending_edits: vec![],
+        })
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self._listener.local_addr()
+    }
+
+    /// Returns the session token that requests must carry, to be given to the editor.
+    pub fn token(&self) -> &str {
+        &self._token
+    }
+
+    /// Allows requests from web pages of an origin, such as `"http://localhost:3000"`,
+    /// where the editor runs.
+    pub fn allow_origin<S: ToString>(mut self, origin: S) -> Self {
+        self._allowed_origins.push(origin.to_string());
+        self
+    }
+
+    /// Reads the pending requests and answers the complete ones, returning how
+    /// many were answered. Connections that fail are closed without affecting
+    /// the others; only a failure to accept connections is returned.
+    pub fn poll(&mut self, locale_map: &mut LocaleMap) -> std::io::Result<usize> {
+        loop {
+            match self._listener.accept() {
+                Ok((stream, _)) => {
+                    if stream.set_nonblocking(true).is_ok() {
+                        self._connections.push(EditorConnection { stream, content: vec![], accepted_at: Instant::now() });
+                    }
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        let mut count = 0;
+        for mut connection in std::mem::take(&mut self._connections) {
+            match read_request(&mut connection) {
+                Ok(Some(request)) => {
+                    // The client may have gone away; the response is dropped then.
+                    let _ = self.respond(connection.stream, locale_map, request);
+                    count += 1;
+                },
+                Ok(None) if connection.accepted_at.elapsed() < CONNECTION_TIMEOUT => self._connections.push(connection),
+                _ => {},
+            }
+        }
+        Ok(count)
+    }
+
+    /// Handles a protocol request regardless of the transport it arrived from.
+    pub fn handle(&mut self, locale_map: &mut LocaleMap, request: EditorRequest) -> EditorResponse {
+        let current_locale = locale_map.current_locale();
+        match request {
+            EditorRequest::ListKeys => {
+                let keys = locale_map.captured_ids().into_iter().map(|id| {
+                    let message = current_locale.as_ref().and_then(|l| locale_map.translation_editor(l).get(&id));
+                    EditorKey { id, message }
+                }).collect();
+                EditorResponse::Keys { locale: current_locale.map(|l| l.standard_tag().to_string()), keys }
+            },
+            EditorRequest::SetMessage { id, message } => {
+                if current_locale.is_none() {
+                    return EditorResponse::Error { message: "No locale has been loaded.".to_string() };
+                }
+                match locale_map.translation_editor(&current_locale.unwrap()).set(&id, &message) {
+                    Ok(()) => {
+                        self._pending_edits.push((id, message));
+                        EditorResponse::Ok
+                    },
+                    Err(e) => EditorResponse::Error { message: e.to_string() },
+                }
+            },
+            EditorRequest::Save => {
+                if current_locale.is_none() {
+                    return EditorResponse::Error { message: "No locale has been loaded.".to_string() };
+                }
+                let mut editor = locale_map.translation_editor(&current_locale.unwrap());
+                for (id, message) in self._pending_edits.iter() {
+                    if let Err(e) = editor.set(id, message) {
+                        return EditorResponse::Error { message: e.to_string() };
+                    }
+                }
+                match editor.save() {
+                    Ok(()) => {
+                        self._pending_edits.clear();
+                        EditorResponse::Ok
+                    },
+                    Err(e) => EditorResponse::Error { message: e.to_string() },
+                }
+            },
+        }
+    }
+
+    fn respond(&mut self, mut stream: TcpStream, locale_map: &mut LocaleMap, request: HttpRequest) -> std::io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+
+        let origin_allowed = request.origin.as_ref().is_none_or(|origin| self._allowed_origins.contains(origin));
+        let cors = match request.origin.as_ref().filter(|_| origin_allowed) {
+            Some(origin) => format!("Access-Control-Allow-Origin: {}\r\nVary: Origin\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\nAccess-Control-Allow-Headers: Authorization, Content-Type\r\n", origin),
+            None => String::new(),
+        };
+        let authorized = request.authorization.as_ref()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), self._token.as_bytes()));
+
+        let (status, response) = if request.too_large {
+            ("413 Payload Too Large", Some(EditorResponse::Error { message: format!("Requests are limited to {} bytes.", MAX_REQUEST_SIZE) }))
+        } else if !origin_allowed {
+            ("403 Forbidden", Some(EditorResponse::Error { message: "Origin not allowed.".to_string() }))
+        } else if request.method == "OPTIONS" {
+            ("204 No Content", None)
+        } else if !authorized {
+            ("401 Unauthorized", Some(EditorResponse::Error { message: "Missing or invalid session token.".to_string() }))
+        } else {
+            ("200 OK", Some(match request.method.as_ref() {
+                "GET" => self.handle(locale_map, EditorRequest::ListKeys),
+                "POST" => match serde_json::from_slice::<EditorRequest>(&request.body) {
+                    Ok(request) => self.handle(locale_map, request),
+                    Err(e) => EditorResponse::Error { message: e.to_string() },
+                },
+                method => EditorResponse::Error { message: format!("Unsupported method {}.", method) },
+            }))
+        };
+
+        if let Some(response) = response {
+            let body = serde_json::to_string(&response).unwrap();
+            write!(stream, "HTTP/1.1 {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, cors, body.len(), body)?;
+        } else {
+            write!(stream, "HTTP/1.1 {}\r\n{}Connection: close\r\n\r\n", status, cors)?;
+        }
+        stream.flush()
+    }
+}
+
+/// Reads the bytes available from a connection, returning the request once it is complete.
+fn read_request(connection: &mut EditorConnection) -> std::io::Result<Option<HttpRequest>> {
+    let mut buf = [0u8; 4096];
+    loop {
+        match connection.stream.read(&mut buf) {
+            Ok(0) => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => connection.content.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+        if connection.content.len() > MAX_REQUEST_SIZE {
+            return Err(std::io::ErrorKind::InvalidData.into());
+        }
+    }
+
+    let content = &connection.content;
+    let header_end = match content.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(i) => i + 4,
+        None => return Ok(None),
+    };
+    let head = String::from_utf8_lossy(&content[..header_end]).to_string();
+    let header = |name: &str| head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(n, _)| n.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().to_string());
+    let content_length: usize = header("content-length").and_then(|value| value.parse().ok()).unwrap_or(0);
+    let content_end = header_end.checked_add(content_length).filter(|end| *end <= MAX_REQUEST_SIZE);
+    if content_end.is_some_and(|end| content.len() < end) {
+        return Ok(None);
+    }
+    Ok(Some(HttpRequest {
+        method: head.split(' ').next().unwrap_or("").to_uppercase(),
+        origin: header("origin"),
+        authorization: header("authorization"),
+        body: content_end.map(|end| content[header_end..end].to_vec()).unwrap_or_default(),
+        too_large: content_end.is_none(),
+    }))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0, |r, (x, y)| r | (x ^ y)) == 0
+}
@@ -0,0 +1,74 @@
+use std::fmt::{Display, Formatter};
+use super::Locale;
+
+/// Lookup of a message through the current locale and its fallbacks,
+/// obtained from `LocaleMap::resolve_with_trace()`, such as for finding out
+/// why a message appears in English to users of `pt-BR`.
+#[derive(Clone)]
+pub struct FallbackTrace {
+    /// The message identifier.
+    pub id: String,
+    /// The locales searched, in order, ending with the one that provided
+    /// the message, if any.
+    pub attempts: Vec<FallbackAttempt>,
+    /// The message, unformatted, if any locale provided it.
+    pub message: Option<String>,
+}
+
+impl FallbackTrace {
+    /// Returns the locale that provided the message, if any.
+    pub fn locale(&self) -> Option<&Locale> {
+        self.attempts.last().filter(|a| a.outcome.is_found()).map(|a| &a.locale)
+    }
+}
+
+impl Display for FallbackTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.id)?;
+        if self.attempts.is_empty() {
+            return write!(f, " no current locale");
+        }
+        for (i, attempt) in self.attempts.iter().enumerate() {
+            write!(f, "{} {} {}", if i == 0 { "" } else { "," }, attempt.locale.standard_tag(), attempt.outcome)?;
+        }
+        Ok(())
+    }
+}
+
+/// Locale searched for a message, reported in a `FallbackTrace`.
+#[derive(Clone)]
+pub struct FallbackAttempt {
+    pub locale: Locale,
+    pub outcome: FallbackOutcome,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FallbackOutcome {
+    /// The locale's messages are not loaded, such as when a fallback
+    /// was discarded by loading another locale with `auto_clean` enabled.
+    NotLoaded,
+    /// The locale's messages are loaded but do not include the message.
+    Missing,
+    /// The locale provided the message.
+    Found,
+    /// The selected overlay provided the message for the locale.
+    FoundInOverlay,
+}
+
+impl FallbackOutcome {
+    /// Returns `true` if the locale provided the message.
+    pub fn is_found(&self) -> bool {
+        matches!(self, FallbackOutcome::Found | FallbackOutcome::FoundInOverlay)
+    }
+}
+
+impl Display for FallbackOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FallbackOutcome::NotLoaded => write!(f, "not loaded"),
+            FallbackOutcome::Missing => write!(f, "missing"),
+            FallbackOutcome::Found => write!(f, "found"),
+            FallbackOutcome::FoundInOverlay => write!(f, "found in overlay"),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::RwLock;
+use super::HourCycle;
+
+/// Order of the fields of numeric dates.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DateOrder {
+    /// As in 04/03/2025.
+    DayMonthYear,
+    /// As in 03/04/2025.
+    MonthDayYear,
+    /// As in 2025-03-04, the ISO 8601 form.
+    YearMonthDay,
+}
+
+/// Number and date formatting preferences that take precedence over the CLDR
+/// defaults of a locale, specified through `LocaleMapOptions::format_overrides()`
+/// or the `overrides()` option of the formatters.
+///
+/// ```
+/// use recoyx_localization::{FormatOverrides, DateOrder, HourCycle};
+///
+/// let overrides = FormatOverrides::new();
+/// overrides.date_order(DateOrder::YearMonthDay).hour_cycle(HourCycle::H23);
+/// ```
+#[derive(Default)]
+pub struct FormatOverrides {
+    _decimal_separator: RwLock<Option<String>>,
+    _group_separator: RwLock<Option<String>>,
+    _date_order: RwLock<Option<DateOrder>>,
+    _hour_cycle: RwLock<Option<HourCycle>>,
+}
+
+impl Clone for FormatOverrides {
+    fn clone(&self) -> Self {
+        Self {
+            _decimal_separator: RwLock::new(self.get_decimal_separator()),
+            _group_separator: RwLock::new(self.get_group_separator()),
+            _date_order: RwLock::new(self.get_date_order()),
+            _hour_cycle: RwLock::new(self.get_hour_cycle()),
+        }
+    }
+}
+
+impl FormatOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn decimal_separator<S: ToString>(&self, value: S) -> &Self {
+        *self._decimal_separator.write().unwrap() = Some(value.to_string());
+        self
+    }
+
+    pub fn group_separator<S: ToString>(&self, value: S) -> &Self {
+        *self._group_separator.write().unwrap() = Some(value.to_string());
+        self
+    }
+
+    /// Replaces the patterns of numeric dates, such as the short date, by
+    /// one with the given field order: `dd/MM/y`, `MM/dd/y` or `y-MM-dd`.
+    pub fn date_order(&self, value: DateOrder) -> &Self {
+        *self._date_order.write().unwrap() = Some(value);
+        self
+    }
+
+    /// Uses the given hour cycle in times, adding or removing the AM/PM marker as needed.
+    pub fn hour_cycle(&self, value: HourCycle) -> &Self {
+        *self._hour_cycle.write().unwrap() = Some(value);
+        self
+    }
+
+    pub(crate) fn get_decimal_separator(&self) -> Option<String> {
+        self._decimal_separator.read().unwrap().clone()
+    }
+
+    pub(crate) fn get_group_separator(&self) -> Option<String> {
+        self._group_separator.read().unwrap().clone()
+    }
+
+    pub(crate) fn get_date_order(&self) -> Option<DateOrder> {
+        *self._date_order.read().unwrap()
+    }
+
+    pub(crate) fn get_hour_cycle(&self) -> Option<HourCycle> {
+        *self._hour_cycle.read().unwrap()
+    }
+
+    /// Returns these overrides with the unspecified preferences taken from `other`.
+    pub(crate) fn or(&self, other: &FormatOverrides) -> FormatOverrides {
+        FormatOverrides {
+            _decimal_separator: RwLock::new(self.get_decimal_separator().or_else(|| other.get_decimal_separator())),
+            _group_separator: RwLock::new(self.get_group_separator().or_else(|| other.get_group_separator())),
+            _date_order: RwLock::new(self.get_date_order().or_else(|| other.get_date_order())),
+            _hour_cycle: RwLock::new(self.get_hour_cycle().or_else(|| other.get_hour_cycle())),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, FixedOffset};
+use super::{DateTimeFormatter, NumberFormatter, ValueKind};
+
+/// Value of a message variable, formatted for the current locale when the
+/// message is retrieved with a `HashMap<String, FormatValue>` argument.
+///
+/// ```
+/// use recoyx_localization::FormatValue;
+///
+/// let vars = maplit::hashmap!{
+///     "name".to_string() => FormatValue::from("Ana"),
+///     "count".to_string() => FormatValue::from(1500),
+/// };
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum FormatValue {
+    /// Inserted as is.
+    Str(String),
+    /// Formatted with the locale's digit grouping, such as 1,500.
+    Int(i64),
+    /// Formatted with the locale's decimal and group separators.
+    Float(f64),
+    /// Formatted with the locale's medium date and short time.
+    DateTime(DateTime<FixedOffset>),
+    /// Inserted as `true` or `false`.
+    Bool(bool),
+}
+
+impl FormatValue {
+    pub(crate) fn format(&self, numbers: &NumberFormatter, dates: &DateTimeFormatter) -> String {
+        match self {
+            FormatValue::Str(s) => s.clone(),
+            FormatValue::Int(n) => numbers.format_integer(i128::from(*n)),
+            FormatValue::Float(n) => numbers.format(*n),
+            FormatValue::DateTime(d) => dates.format(d),
+            FormatValue::Bool(b) => b.to_string(),
+        }
+    }
+
+    /// Returns the kind of the value, which selects its kind of `FormattedPart`.
+    pub(crate) fn kind(&self) -> Option<ValueKind> {
+        match self {
+            FormatValue::Int(_) | FormatValue::Float(_) => Some(ValueKind::Number),
+            FormatValue::DateTime(_) => Some(ValueKind::DateTime),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for FormatValue {
+    fn from(value: &str) -> Self { FormatValue::Str(value.to_string()) }
+}
+
+impl From<String> for FormatValue {
+    fn from(value: String) -> Self { FormatValue::Str(value) }
+}
+
+impl From<i32> for FormatValue {
+    fn from(value: i32) -> Self { FormatValue::Int(i64::from(value)) }
+}
+
+impl From<i64> for FormatValue {
+    fn from(value: i64) -> Self { FormatValue::Int(value) }
+}
+
+impl From<u32> for FormatValue {
+    fn from(value: u32) -> Self { FormatValue::Int(i64::from(value)) }
+}
+
+impl From<f32> for FormatValue {
+    fn from(value: f32) -> Self { FormatValue::Float(f64::from(value)) }
+}
+
+impl From<f64> for FormatValue {
+    fn from(value: f64) -> Self { FormatValue::Float(value) }
+}
+
+impl From<DateTime<FixedOffset>> for FormatValue {
+    fn from(value: DateTime<FixedOffset>) -> Self { FormatValue::DateTime(value) }
+}
+
+impl From<bool> for FormatValue {
+    fn from(value: bool) -> Self { FormatValue::Bool(value) }
+}
@@ -0,0 +1,137 @@
+use super::ValueKind;
+
+/// Part of a message formatted by `LocaleMap::get_formatted_parts()`, so that
+/// user interfaces can style the values of variables apart from the text around them,
+/// such as showing the name of a user in bold.
+///
+/// ```
+/// use recoyx_localization::FormattedPart;
+///
+/// let parts = vec![
+///     FormattedPart::Literal("Welcome, ".to_string()),
+///     FormattedPart::Variable { name: "user".to_string(), value: "Ana".to_string() },
+/// ];
+/// assert_eq!(parts.iter().map(|part| part.value()).collect::<String>(), "Welcome, Ana");
+/// assert_eq!(parts[1].name(), Some("user"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FormattedPart {
+    /// Text of the message itself.
+    Literal(String),
+    /// Value of a variable given as a string.
+    Variable { name: String, value: String },
+    /// Value of a variable given as a number, including quantities, formatted for the locale.
+    Number { name: String, value: String },
+    /// Value of a variable given as a date and time, formatted for the locale.
+    DateTime { name: String, value: String },
+}
+
+impl FormattedPart {
+    /// Returns the text of the part.
+    pub fn value(&self) -> &str {
+        match self {
+            FormattedPart::Literal(value) => value,
+            FormattedPart::Variable { value, .. } | FormattedPart::Number { value, .. } | FormattedPart::DateTime { value, .. } => value,
+        }
+    }
+
+    /// Returns the name of the variable of the part, or `None` for literal text.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            FormattedPart::Literal(_) => None,
+            FormattedPart::Variable { name, .. } | FormattedPart::Number { name, .. } | FormattedPart::DateTime { name, .. } => Some(name),
+        }
+    }
+
+    /// Constructs the part of a variable given the kind of its value, if not text.
+    pub(crate) fn of_variable(name: &str, value: String, kind: Option<ValueKind>) -> Self {
+        let name = name.to_string();
+        match kind {
+            Some(ValueKind::Number) => FormattedPart::Number { name, value },
+            Some(ValueKind::DateTime) => FormattedPart::DateTime { name, value },
+            None => FormattedPart::Variable { name, value },
+        }
+    }
+}
+
+/// Appends a part, merging adjacent literal text.
+pub(crate) fn push_part(parts: &mut Vec<FormattedPart>, part: FormattedPart) {
+    match (parts.last_mut(), part) {
+        (Some(FormattedPart::Literal(last)), FormattedPart::Literal(text)) => last.push_str(&text),
+        (_, FormattedPart::Literal(text)) if text.is_empty() => {},
+        (_, part) => parts.push(part),
+    }
+}
+
+/// Message formatted by `LocaleMap::get_attributed()`, whose segments carry the
+/// language they are written in and their role as a `FormattedPart`, so that screen
+/// readers and text-to-speech engines can switch voices within a message, such as for
+/// text from a fallback locale or a phrase in another language marked `${phrase|lang:fr}`.
+///
+/// ```
+/// use recoyx_localization::{AttributedSegment, AttributedString, FormattedPart};
+///
+/// let attributed = AttributedString {
+///     language: "en-US".to_string(),
+///     segments: vec![
+///         AttributedSegment { part: FormattedPart::Literal("Say ".to_string()), language: "en-US".to_string(), machine_translated: false },
+///         AttributedSegment { part: FormattedPart::Variable { name: "phrase".to_string(), value: "c'est la vie".to_string() }, language: "fr".to_string(), machine_translated: false },
+///     ],
+/// };
+/// assert_eq!(attributed.text(), "Say c'est la vie");
+/// assert_eq!(attributed.to_ssml(), r#"<speak xml:lang="en-US">Say <lang xml:lang="fr">c&apos;est la vie</lang></speak>"#);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AttributedString {
+    /// Language tag of the message, such as `en-US`.
+    pub language: String,
+    pub segments: Vec<AttributedSegment>,
+}
+
+/// Segment of an `AttributedString`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AttributedSegment {
+    /// Text and role of the segment.
+    pub part: FormattedPart,
+    /// Language tag of the text, such as `fr`.
+    pub language: String,
+    /// Whether the text is a machine translation from a `MissingTranslationProvider`.
+    pub machine_translated: bool,
+}
+
+impl AttributedString {
+    /// Returns the text of the message.
+    pub fn text(&self) -> String {
+        self.segments.iter().map(|segment| segment.part.value()).collect()
+    }
+
+    /// Returns the message as SSML for text-to-speech engines, wrapping the
+    /// segments in another language than the message in `<lang>` elements.
+    pub fn to_ssml(&self) -> String {
+        let mut r = format!("<speak xml:lang=\"{}\">", escape_xml(&self.language));
+        for segment in self.segments.iter() {
+            let text = escape_xml(segment.part.value());
+            if segment.language == self.language {
+                r.push_str(&text);
+            } else {
+                r.push_str(&format!("<lang xml:lang=\"{}\">{}</lang>", escape_xml(&segment.language), text));
+            }
+        }
+        r.push_str("</speak>");
+        r
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+/// Appends a segment, merging adjacent literal text in the same language.
+pub(crate) fn push_segment(segments: &mut Vec<AttributedSegment>, segment: AttributedSegment) {
+    match (segments.last_mut(), segment) {
+        (Some(AttributedSegment { part: FormattedPart::Literal(last), language, machine_translated }), AttributedSegment { part: FormattedPart::Literal(text), language: l, machine_translated: m })
+            if *language == l && *machine_translated == m => last.push_str(&text),
+        (_, AttributedSegment { part: FormattedPart::Literal(text), .. }) if text.is_empty() => {},
+        (_, segment) => segments.push(segment),
+    }
+}
@@ -0,0 +1,42 @@
+use super::Locale;
+
+/// Indicates which formatters have data for a locale. A formatter without
+/// data for a locale falls back to English.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct FormatterSupport {
+    /// Cardinal plural rules, used by `LocaleMap::select_plural_rule()`.
+    pub cardinal_plural_rules: bool,
+    /// Ordinal plural rules, used by `LocaleMap::select_plural_rule()`.
+    pub ordinal_plural_rules: bool,
+    /// Relative-time formatting, used by `LocaleMap::format_relative_time()`.
+    pub relative_time: bool,
+}
+
+impl FormatterSupport {
+    /// Returns `true` if every formatter has data for the locale.
+    pub fn is_complete(&self) -> bool {
+        self.cardinal_plural_rules && self.ordinal_plural_rules && self.relative_time
+    }
+}
+
+pub(crate) fn formatter_support(locale: &Locale) -> FormatterSupport {
+    let plural_rules_support = |prt| {
+        unic_langid::LanguageIdentifier::from_bytes(locale.language().as_ref()).ok()
+            .map(|langid| intl_pluralrules::PluralRules::create(langid, prt).is_ok())
+            .unwrap_or(false)
+    };
+    FormatterSupport {
+        cardinal_plural_rules: plural_rules_support(intl_pluralrules::PluralRuleType::CARDINAL),
+        ordinal_plural_rules: plural_rules_support(intl_pluralrules::PluralRuleType::ORDINAL),
+        relative_time: relative_time_language(locale).is_some(),
+    }
+}
+
+pub(crate) fn relative_time_language(locale: &Locale) -> Option<timeago::BoxedLanguage> {
+    let lang = if locale.language().len() == 3 {
+        isolang::Language::from_639_3(locale.language())
+    } else {
+        isolang::Language::from_639_1(locale.language())
+    };
+    timeago::from_isolang(lang?)
+}
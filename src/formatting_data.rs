@@ -0,0 +1,259 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use lazy_static::lazy_static;
+use super::{locale::likely_locale_of_region, DateOrder, FormatOverrides, HourCycle, Locale, NameWidth, NumberingSystem};
+
+lazy_static! {
+    static ref FORMATTING_DATA: HashMap<String, PartialFormattingData> = serde_json::from_str::<HashMap<String, PartialFormattingData>>(&String::from_utf8_lossy(include_bytes!(concat!(env!("OUT_DIR"), "/formatting_data.json")))).unwrap();
+}
+
+/// Number and date symbols and patterns of a locale, from CLDR.
+#[derive(Clone)]
+pub(crate) struct FormattingData {
+    pub decimal: String,
+    pub group: String,
+    /// Separates the mantissa and exponent of numbers in scientific notation, as in "1.2E5".
+    pub exponential: String,
+    /// Sizes of the digit groups from the right, where the last size repeats,
+    /// such as `[3]` for 1,234,567 and `[3, 2]` for 12,34,567.
+    pub grouping: Vec<usize>,
+    /// Minimum number of integer digits before the first separator for grouping to apply.
+    pub minimum_grouping_digits: usize,
+    pub months: NameWidths,
+    pub weekdays: NameWidths,
+    /// Month names used on their own, as in calendar headers, rather than within dates.
+    pub months_standalone: NameWidths,
+    pub weekdays_standalone: NameWidths,
+    /// Quarter names, such as "Q3" and "3rd quarter".
+    pub quarters: NameWidths,
+    /// Era names, before and after the epoch of the Gregorian calendar, such as "BC" and "AD".
+    pub eras: NameWidths,
+    pub day_periods: Vec<String>,
+    pub date_formats: Lengths,
+    pub time_formats: Lengths,
+    /// Combines a time (`{0}`) and a date (`{1}`).
+    pub date_time_format: String,
+    /// Places a duration (`{0}`) in the future, as in "in 5 minutes".
+    pub relative_time_future: String,
+    /// Separates the start and end of a range, as in "Mar 4 – 9, 2025".
+    pub interval_separator: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct NameWidths {
+    pub wide: Vec<String>,
+    pub abbreviated: Vec<String>,
+    pub narrow: Vec<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct Lengths {
+    pub full: String,
+    pub long: String,
+    pub medium: String,
+    pub short: String,
+}
+
+/// Entry of the data file, where regional entries only
+/// contain the fields that differ from their language.
+#[derive(Deserialize)]
+struct PartialFormattingData {
+    decimal: Option<String>,
+    group: Option<String>,
+    exponential: Option<String>,
+    grouping: Option<Vec<usize>>,
+    minimum_grouping_digits: Option<usize>,
+    months: Option<NameWidths>,
+    weekdays: Option<NameWidths>,
+    months_standalone: Option<NameWidths>,
+    weekdays_standalone: Option<NameWidths>,
+    quarters: Option<NameWidths>,
+    eras: Option<NameWidths>,
+    day_periods: Option<Vec<String>>,
+    date_formats: Option<Lengths>,
+    time_formats: Option<Lengths>,
+    date_time_format: Option<String>,
+    relative_time_future: Option<String>,
+    interval_separator: Option<String>,
+}
+
+/// Returns the formatting data of a locale, resolving missing fields
+/// from its language and then from English. With the `-u-rg-` extension,
+/// the number symbols and the date and time patterns are those of the most
+/// likely locale of the requested region, while the names stay the locale's.
+pub(crate) fn formatting_data(locale: &Locale) -> FormattingData {
+    let mut r = language_formatting_data(locale);
+    if let Some(region_locale) = locale.region_override().and_then(|region| likely_locale_of_region(&region)) {
+        let formats = language_formatting_data(&region_locale);
+        r.decimal = formats.decimal;
+        r.group = formats.group;
+        r.grouping = formats.grouping;
+        r.minimum_grouping_digits = formats.minimum_grouping_digits;
+        r.date_formats = formats.date_formats;
+        r.time_formats = formats.time_formats;
+        r.date_time_format = formats.date_time_format;
+    }
+    r
+}
+
+fn language_formatting_data(locale: &Locale) -> FormattingData {
+    let mut entries: Vec<&PartialFormattingData> = vec![];
+    if let Some(region) = locale.region() {
+        entries.extend(FORMATTING_DATA.get(&format!("{}-{}", locale.language(), region)));
+    }
+    entries.extend(FORMATTING_DATA.get(locale.language()));
+    entries.push(&FORMATTING_DATA["en"]);
+
+    macro_rules! field {
+        ($name:ident) => { entries.iter().find_map(|e| e.$name.clone()).unwrap() };
+    }
+    FormattingData {
+        decimal: field!(decimal),
+        group: field!(group),
+        exponential: field!(exponential),
+        grouping: field!(grouping),
+        minimum_grouping_digits: field!(minimum_grouping_digits),
+        months: field!(months),
+        weekdays: field!(weekdays),
+        // Standalone names are only specified where they differ from the names within dates.
+        months_standalone: entries.iter().find_map(|e| e.months_standalone.clone().or_else(|| e.months.clone())).unwrap(),
+        weekdays_standalone: entries.iter().find_map(|e| e.weekdays_standalone.clone().or_else(|| e.weekdays.clone())).unwrap(),
+        quarters: field!(quarters),
+        eras: field!(eras),
+        day_periods: field!(day_periods),
+        date_formats: field!(date_formats),
+        time_formats: field!(time_formats),
+        date_time_format: field!(date_time_format),
+        relative_time_future: field!(relative_time_future),
+        interval_separator: field!(interval_separator),
+    }
+}
+
+impl FormattingData {
+    /// Applies preferences that take precedence over the CLDR data.
+    /// Replaces the separators and the exponential symbol by those of a numbering
+    /// system that has its own, such as `٫` as the decimal separator of Arabic-Indic digits.
+    pub(crate) fn with_numbering_system(mut self, numbering_system: &NumberingSystem) -> Self {
+        if let Some(symbols) = numbering_system.symbols() {
+            self.decimal = symbols.decimal.to_string();
+            self.group = symbols.group.to_string();
+            self.exponential = symbols.exponential.to_string();
+        }
+        self
+    }
+
+    pub(crate) fn with_overrides(mut self, overrides: &FormatOverrides) -> Self {
+        if let Some(decimal) = overrides.get_decimal_separator() {
+            self.decimal = decimal;
+        }
+        if let Some(group) = overrides.get_group_separator() {
+            self.group = group;
+        }
+        if let Some(order) = overrides.get_date_order() {
+            let pattern = match order {
+                DateOrder::DayMonthYear => "dd/MM/y",
+                DateOrder::MonthDayYear => "MM/dd/y",
+                DateOrder::YearMonthDay => "y-MM-dd",
+            };
+            for p in self.date_formats.iter_mut() {
+                if is_numeric_date_pattern(p) {
+                    *p = pattern.to_string();
+                }
+            }
+        }
+        if let Some(hour_cycle) = overrides.get_hour_cycle() {
+            for p in self.time_formats.iter_mut() {
+                *p = with_hour_cycle(p, hour_cycle);
+            }
+        }
+        self
+    }
+}
+
+impl NameWidths {
+    pub fn get(&self, width: NameWidth) -> &Vec<String> {
+        match width {
+            NameWidth::Wide => &self.wide,
+            NameWidth::Abbreviated => &self.abbreviated,
+            NameWidth::Narrow => &self.narrow,
+        }
+    }
+}
+
+impl Lengths {
+    pub fn map<F: Fn(&str) -> String>(&self, f: F) -> Lengths {
+        Lengths { full: f(&self.full), long: f(&self.long), medium: f(&self.medium), short: f(&self.short) }
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        vec![&mut self.full, &mut self.long, &mut self.medium, &mut self.short].into_iter()
+    }
+}
+
+/// Splits a CLDR pattern into fields, such as `"MMM"`, and literal text, including quoted text.
+pub(crate) fn pattern_fields(pattern: &str) -> Vec<(bool, String)> {
+    let mut r: Vec<(bool, String)> = vec![];
+    let mut quoted = false;
+    for ch in pattern.chars() {
+        if ch == '\'' {
+            quoted = !quoted;
+        }
+        let field = !quoted && ch.is_ascii_alphabetic();
+        match r.last_mut() {
+            Some((is_field, s)) if *is_field == field && (!field || s.ends_with(ch)) => s.push(ch),
+            _ => r.push((field, ch.to_string())),
+        }
+    }
+    r
+}
+
+/// Returns `true` if a date pattern has no month or weekday names.
+pub(crate) fn is_numeric_date_pattern(pattern: &str) -> bool {
+    pattern_fields(pattern).iter().all(|(field, s)| !field || !(s.starts_with('E') || (s.starts_with('M') && s.len() >= 3)))
+}
+
+/// Rewrites the hour fields of a time pattern for an hour cycle, adding the day
+/// period after the last time field for 12-hour cycles and removing it otherwise.
+pub(crate) fn with_hour_cycle(pattern: &str, hour_cycle: HourCycle) -> String {
+    let hour_field = match hour_cycle {
+        HourCycle::H11 => 'K',
+        HourCycle::H12 => 'h',
+        HourCycle::H23 => 'H',
+        HourCycle::H24 => 'k',
+    };
+    let twelve_hour = matches!(hour_cycle, HourCycle::H11 | HourCycle::H12);
+    let mut fields = pattern_fields(pattern);
+    for (field, s) in fields.iter_mut() {
+        if *field && s.starts_with(['h', 'H', 'K', 'k']) {
+            *s = hour_field.to_string().repeat(if twelve_hour { 1 } else { 2 });
+        }
+    }
+    let has_day_period = fields.iter().any(|(field, s)| *field && s.starts_with('a'));
+    if twelve_hour && !has_day_period {
+        if let Some(i) = fields.iter().rposition(|(field, s)| *field && s.starts_with(['h', 'K', 'm', 's', 'S'])) {
+            fields.insert(i + 1, (true, "a".to_string()));
+            fields.insert(i + 1, (false, " ".to_string()));
+        }
+    } else if !twelve_hour && has_day_period {
+        let i = fields.iter().position(|(field, s)| *field && s.starts_with('a')).unwrap();
+        fields.remove(i);
+        // Removes the space that separated the day period.
+        if i < fields.len() && !fields[i].0 && fields[i].1.trim().is_empty() {
+            fields.remove(i);
+        } else if i > 0 && !fields[i - 1].0 && fields[i - 1].1.trim().is_empty() {
+            fields.remove(i - 1);
+        }
+    }
+    fields.into_iter().map(|(_, s)| s).collect()
+}
+
+/// Returns the hour cycle of the first hour field of a time pattern.
+pub(crate) fn pattern_hour_cycle(pattern: &str) -> Option<HourCycle> {
+    pattern_fields(pattern).iter().filter(|(field, _)| *field).find_map(|(_, s)| match s.chars().next() {
+        Some('K') => Some(HourCycle::H11),
+        Some('h') => Some(HourCycle::H12),
+        Some('H') => Some(HourCycle::H23),
+        Some('k') => Some(HourCycle::H24),
+        _ => None,
+    })
+}
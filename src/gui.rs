@@ -0,0 +1,130 @@
+//! Bindings for immediate-mode GUI frameworks, such as egui and iced,
+//! available through the `gui` feature.
+//!
+//! A `LocalizationHandle` is a cheaply cloned, shared `LocaleMap` that widgets
+//! read from while the application changes its locale. Installing a handle for
+//! the UI thread lets widgets retrieve messages through `tr!` without threading
+//! the handle through every view function.
+//!
+//! ```
+//! use recoyx_localization::{tr, gui::LocalizationHandle, LocaleMap, LocaleMapOptions};
+//!
+//! let handle = LocalizationHandle::new(LocaleMap::new(&LocaleMapOptions::new()));
+//! handle.on_change(|_locale| {
+//!     // Request a redraw, such as through egui's `Context::request_repaint()`.
+//! });
+//! handle.install();
+//! assert_eq!(tr!("common.save"), "common.save");
+//! ```
+
+use std::{cell::{Cell, RefCell}, rc::Rc};
+use super::{Locale, LocaleMap};
+
+type ChangeListener = Rc<dyn Fn(&Locale)>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<LocalizationHandle>> = const { RefCell::new(None) };
+}
+
+/// A shared `LocaleMap` whose locale can be changed while widgets hold clones of it.
+#[derive(Clone)]
+pub struct LocalizationHandle {
+    _inner: Rc<HandleInner>,
+}
+
+struct HandleInner {
+    locale_map: RefCell<LocaleMap>,
+    generation: Cell<u64>,
+    listeners: RefCell<Vec<ChangeListener>>,
+}
+
+impl LocalizationHandle {
+    /// Constructs a handle sharing a `LocaleMap`.
+    pub fn new(locale_map: LocaleMap) -> Self {
+        Self {
+            _inner: Rc::new(HandleInner {
+                locale_map: RefCell::new(locale_map),
+                generation: Cell::new(0),
+                listeners: RefCell::new(vec![]),
+            }),
+        }
+    }
+
+    /// Makes this handle the one used by `tr!` in the current thread,
+    /// which is usually the UI thread.
+    pub fn install(&self) {
+        CURRENT.with(|current| current.replace(Some(self.clone())));
+    }
+
+    /// Returns the handle installed in the current thread, if any.
+    pub fn current() -> Option<LocalizationHandle> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Calls a function with the shared `LocaleMap`.
+    pub fn with<R, F: FnOnce(&LocaleMap) -> R>(&self, f: F) -> R {
+        f(&self._inner.locale_map.borrow())
+    }
+
+    /// Retrieves message by identifier; see `LocaleMap::get()`.
+    pub fn get<S: ToString>(&self, id: S) -> String {
+        self._inner.locale_map.borrow().get(id)
+    }
+
+    /// Retrieves message by identifier with formatting arguments; see `LocaleMap::get_formatted()`.
+    pub fn get_formatted<S: ToString>(&self, id: S, options: Vec<&dyn super::LocaleMapFormatArgument>) -> String {
+        self._inner.locale_map.borrow().get_formatted(id, options)
+    }
+
+    /// Returns the current locale of the shared `LocaleMap`.
+    pub fn current_locale(&self) -> Option<Locale> {
+        self._inner.locale_map.borrow().current_locale()
+    }
+
+    /// Returns a number that changes whenever the locale changes, so that
+    /// a frame can tell whether the messages it displays are outdated.
+    pub fn generation(&self) -> u64 {
+        self._inner.generation.get()
+    }
+
+    /// Registers a function called with the new locale after the locale changes,
+    /// such as for requesting a redraw.
+    pub fn on_change<F: Fn(&Locale) + 'static>(&self, listener: F) {
+        self._inner.listeners.borrow_mut().push(Rc::new(listener));
+    }
+
+    /// Attempts to load a locale and its fallbacks; see `LocaleMap::update_locale()`.
+    /// The locale is loaded into a copy of the `LocaleMap`, so widgets keep reading
+    /// the previous messages until it is loaded, and change listeners are called
+    /// once it replaces the shared one. Returns `false` if any resource fails to load.
+    pub async fn update_locale(&self, new_locale: Locale) -> bool {
+        let mut locale_map = self._inner.locale_map.borrow().clone();
+        if !locale_map.update_locale(new_locale).await {
+            return false;
+        }
+        let locale = locale_map.current_locale().unwrap();
+        self._inner.locale_map.replace(locale_map);
+        self._inner.generation.set(self._inner.generation.get() + 1);
+        let listeners = self._inner.listeners.borrow().clone();
+        for listener in listeners.iter() {
+            listener(&locale);
+        }
+        true
+    }
+}
+
+#[macro_export]
+/// Retrieves a message through the `LocalizationHandle` installed in the current
+/// thread, with optional formatting arguments, as in `tr!("common.greeting", vars)`.
+/// Returns the identifier if no handle is installed.
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::tr!($id,)
+    };
+    ($id:expr, $($arg:expr),* $(,)?) => {
+        match $crate::gui::LocalizationHandle::current() {
+            Some(handle) => handle.get_formatted($id, vec![$(&$arg as &dyn $crate::LocaleMapFormatArgument),*]),
+            None => $id.to_string(),
+        }
+    };
+}
@@ -0,0 +1,87 @@
+use std::{collections::HashMap, convert::TryInto, sync::Arc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+/// Verification of the asset files fetched over HTTP, specified through
+/// `LocaleMapAssetOptions::integrity()`, available through the `integrity` feature.
+/// A file that fails verification is not loaded, as if it could not be parsed.
+/// Compressed files are verified as fetched, before they are decompressed, so their
+/// checksums and signatures are of the compressed content at paths such as `pt-BR/common.json.gz`.
+#[derive(Clone)]
+pub enum AssetIntegrity {
+    /// SHA-256 checksums of the asset files, in hexadecimal, by path relative to the
+    /// assets source, such as `pt-BR/common.json`. Files without a checksum are rejected.
+    Sha256(Arc<HashMap<String, String>>),
+    /// An Ed25519 public key verifying a detached signature of every asset file,
+    /// fetched from the same URL with the `.sig` extension appended, such as
+    /// `pt-BR/common.json.sig`, in hexadecimal or as the raw 64 bytes.
+    /// The signature is of `signed_message()`, which binds the content to the path
+    /// and the catalog version, so that a signed file is rejected if served at
+    /// another path or in place of another version.
+    Ed25519([u8; 32]),
+}
+
+impl AssetIntegrity {
+    /// Parses a JSON manifest of SHA-256 checksums, such as
+    /// `{"pt-BR/common.json": "9f86d0..."}`, usually embedded in the application
+    /// through `include_str!()` so that it cannot be replaced along with the assets.
+    pub fn sha256_manifest(manifest: &str) -> Result<Self, String> {
+        let manifest: HashMap<String, String> = serde_json::from_str(manifest).map_err(|e| e.to_string())?;
+        Ok(AssetIntegrity::Sha256(Arc::new(manifest.into_iter().map(|(path, checksum)| (path, checksum.to_lowercase())).collect())))
+    }
+
+    /// Returns the message signed for `AssetIntegrity::Ed25519`: the path of an asset file
+    /// relative to the assets source, such as `pt-BR/common.json`, and the version that
+    /// the `AssetManifest` read through `LocaleMap::discover_assets()` lists for the first
+    /// component of the path, such as `pt-BR`, or an empty string if there is none, each
+    /// followed by a line feed, then the content.
+    ///
+    /// ```
+    /// use recoyx_localization::AssetIntegrity;
+    ///
+    /// assert_eq!(AssetIntegrity::signed_message("pt-BR/common.json", Some("9"), b"{}"), b"pt-BR/common.json\n9\n{}");
+    /// ```
+    pub fn signed_message(path: &str, version: Option<&str>, content: &[u8]) -> Vec<u8> {
+        let mut r = format!("{}\n{}\n", path, version.unwrap_or("")).into_bytes();
+        r.extend_from_slice(content);
+        r
+    }
+
+    /// Returns whether a detached signature is fetched along with every asset file.
+    pub(crate) fn requires_signature(&self) -> bool {
+        matches!(self, AssetIntegrity::Ed25519(_))
+    }
+
+    /// Verifies the content of an asset file at a path relative to the assets source
+    /// and with the catalog version of the path, if any, returning the reason it is rejected.
+    pub(crate) fn verify(&self, path: &str, version: Option<&str>, content: &[u8], signature: Option<&[u8]>) -> Result<(), String> {
+        match self {
+            AssetIntegrity::Sha256(checksums) => {
+                let expected = checksums.get(path).ok_or_else(|| "no checksum in the manifest".to_string())?;
+                let actual: String = Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect();
+                if actual == *expected { Ok(()) } else { Err("checksum mismatch".to_string()) }
+            },
+            AssetIntegrity::Ed25519(public_key) => {
+                let key = VerifyingKey::from_bytes(public_key).map_err(|_| "invalid public key".to_string())?;
+                let signature = signature.ok_or_else(|| "missing signature".to_string())?;
+                let signature = decode_signature(signature).ok_or_else(|| "malformed signature".to_string())?;
+                key.verify(&AssetIntegrity::signed_message(path, version, content), &signature).map_err(|_| "invalid signature".to_string())
+            },
+        }
+    }
+}
+
+fn decode_signature(bytes: &[u8]) -> Option<Signature> {
+    if let Ok(bytes) = bytes.try_into() {
+        return Some(Signature::from_bytes(bytes));
+    }
+    let hex = std::str::from_utf8(bytes).ok()?.trim();
+    if hex.len() != 128 || !hex.is_ascii() {
+        return None;
+    }
+    let mut r = [0u8; 64];
+    for (i, b) in r.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(Signature::from_bytes(&r))
+}
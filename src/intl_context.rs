@@ -0,0 +1,67 @@
+use std::sync::RwLock;
+use super::*;
+
+/// Owns the default locale and the available locales used for locale negotiation.
+///
+/// An `IntlContext` can be shared across threads, such as in an `Arc` by
+/// a multi-threaded server, and its default locale can be changed through
+/// a shared reference.
+pub struct IntlContext {
+    _default_locale: RwLock<Locale>,
+    _available_locales: Vec<Locale>,
+}
+
+impl IntlContext {
+    /// Constructs an `IntlContext`. The default locale does not need to be
+    /// one of the available locales.
+    pub fn new<S: ToString>(default_locale: S, available_locales: Vec<S>) -> Result<Self, String> {
+        let available_locales = available_locales.iter()
+            .map(|s| parse_locale(s.to_string()))
+            .collect::<Result<Vec<Locale>, String>>()?;
+        Ok(Self {
+            _default_locale: RwLock::new(parse_locale(default_locale)?),
+            _available_locales: available_locales,
+        })
+    }
+
+    pub fn default_locale(&self) -> Locale {
+        self._default_locale.read().unwrap().clone()
+    }
+
+    pub fn set_default_locale<S: ToString>(&self, value: S) -> Result<(), String> {
+        *self._default_locale.write().unwrap() = parse_locale(value)?;
+        Ok(())
+    }
+
+    pub fn available_locales(&self) -> Vec<Locale> {
+        self._available_locales.clone()
+    }
+
+    /// Returns a `LocaleMatcher` for the available locales.
+    pub fn locale_matcher(&self) -> LocaleMatcher {
+        LocaleMatcher::new(self.available_locales())
+    }
+
+    /// Returns the available locale that best matches the requested locales,
+    /// given in order of preference, or the default locale if none matches.
+    pub fn negotiate(&self, requested: &[Locale]) -> Locale {
+        self.locale_matcher().best_match(requested).unwrap_or_else(|| self.default_locale())
+    }
+
+    /// Resolves the requested locales, given in order of preference, to the closest
+    /// available locale and the other matching available locales in order, followed by
+    /// the default locale; see `LocaleMatcher::resolve()`. If no available locale matches,
+    /// the default locale is resolved.
+    pub fn resolve(&self, requested: &[Locale]) -> LocaleResolution {
+        let default_locale = self.default_locale();
+        let mut r = self.locale_matcher().resolve(requested)
+            .unwrap_or_else(|| LocaleResolution { locale: default_locale.clone(), fallbacks: vec![] });
+        r.push_fallback(default_locale);
+        r
+    }
+
+    /// Equivalent to `supported_locales_of()` with the available locales.
+    pub fn supported_locales_of(&self, requested: &[Locale], options: &SupportedLocalesOptions) -> Vec<Locale> {
+        supported_locales_of(&self.available_locales(), requested, options)
+    }
+}
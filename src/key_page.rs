@@ -0,0 +1,68 @@
+use std::{cmp::Ordering, collections::HashMap};
+use super::{Locale, MessageTree};
+
+/// A page of message identifiers, obtained from `LocaleMap::key_page()`.
+#[derive(Clone)]
+pub struct KeyPage {
+    pub entries: Vec<KeyPageEntry>,
+    /// Cursor for requesting the next page, or `None` if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// A message identifier listed in a `KeyPage`.
+#[derive(Clone)]
+pub struct KeyPageEntry {
+    pub id: String,
+    /// Whether each loaded locale has the message, ordered by locale.
+    pub presence: Vec<(Locale, bool)>,
+}
+
+impl KeyPageEntry {
+    /// Returns `true` if the given locale has the message.
+    pub fn is_present_in(&self, locale: &Locale) -> bool {
+        self.presence.iter().any(|(l, present)| l == locale && *present)
+    }
+}
+
+/// Compares message identifiers component by component, which is
+/// the order in which `MessageTree::keys()` enumerates them.
+pub(crate) fn compare_ids(a: &str, b: &str) -> Ordering {
+    a.split('.').cmp(b.split('.'))
+}
+
+/// Lists a page of the identifiers present in any of the given asset trees.
+pub(crate) fn key_page(assets: &HashMap<Locale, MessageTree>, prefix: &str, cursor: Option<&str>, limit: usize) -> KeyPage {
+    let mut locales: Vec<&Locale> = assets.keys().collect();
+    locales.sort();
+    let mut walkers: Vec<_> = locales.iter()
+        .map(|l| assets[*l].keys().filter(|id| id.starts_with(prefix)).peekable())
+        .collect();
+
+    let mut entries = Vec::<KeyPageEntry>::new();
+    let mut next_cursor = None;
+    loop {
+        let min = walkers.iter_mut()
+            .filter_map(|w| w.peek().cloned())
+            .min_by(|a, b| compare_ids(a, b));
+        if min.is_none() {
+            break;
+        }
+        let id = min.unwrap();
+        let presence: Vec<(Locale, bool)> = locales.iter().zip(walkers.iter_mut()).map(|(l, w)| {
+            let present = w.peek() == Some(&id);
+            if present {
+                w.next();
+            }
+            ((*l).clone(), present)
+        }).collect();
+        if cursor.is_some() && compare_ids(id, cursor.unwrap()) != Ordering::Greater {
+            continue;
+        }
+        if entries.len() == limit {
+            next_cursor = entries.last().map(|e| e.id.clone());
+            break;
+        }
+        entries.push(KeyPageEntry { id: id.to_string(), presence });
+    }
+    KeyPage { entries, next_cursor }
+}
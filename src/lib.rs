@@ -1,34 +1,219 @@
-mod locale_basic_data;
-use locale_basic_data::{
-    LOCALE_BASIC_DATA, LocaleBasicData,
-};
-pub use locale_basic_data::Direction;
-
-mod locale;
-pub use locale::{Locale, parse_locale};
-
-mod country;
-pub use country::{Country, parse_country};
-
-mod locale_map;
-pub use locale_map::{
-    LocaleMap, LocaleMapOptions, LocaleMapAssetOptions,
-    LocaleMapLoaderType, LocaleMapFormatArgument,
-    Gender,
-};
-
-pub mod pluralrules {
-    pub use intl_pluralrules::{PluralCategory, PluralRuleType, operands::PluralOperands};
-}
-pub use pluralrules::{
-    PluralCategory, PluralRuleType, PluralOperands,
-};
-
-pub mod relative_time_format {
-    pub type Formatter = timeago::Formatter<timeago::BoxedLanguage>;
-    pub use timeago::TimeUnit;
-}
-pub use relative_time_format::{
-    Formatter as RelativeTimeFormatter,
-    TimeUnit as RelativeTimeUnit,
+mod locale_basic_data;
+use locale_basic_data::{
+    LOCALE_BASIC_DATA, LOCALE_EXEMPLAR_CHARACTERS, EXCLUDED_LANGUAGES, LocaleBasicData,
+};
+pub use locale_basic_data::{Direction, is_language_bundled};
+
+/// Version of the CLDR release the bundled locale data was generated from,
+/// such as `"41.0.0"`. The data is regenerated through `cargo xtask cldr`.
+pub const CLDR_VERSION: &str = include_str!("../locale-data/cldr_version.txt");
+
+mod country_data;
+use country_data::{
+    COUNTRY_DATA, CountryData, SUBDIVISION_DATA, SubdivisionData,
+};
+pub use country_data::{Continent, PhoneFormat};
+
+mod numbering_system;
+pub use numbering_system::{NumberingSystem, parse_numbering_system};
+
+mod unicode_extension;
+pub use unicode_extension::{HourCycle, Weekday, MeasurementSystem};
+
+mod week_info;
+pub use week_info::WeekInfo;
+
+mod region_calendar;
+pub use region_calendar::{HolidayProvider, RegionCalendar};
+
+mod locale;
+pub use locale::{Locale, LocaleBuilder, parse_locale, canonicalize_language_tag, normalize_locale_code};
+
+mod user_locale_prefs;
+pub use user_locale_prefs::UserLocalePrefs;
+
+mod localization_context;
+pub use localization_context::LocalizationContext;
+
+mod locale_matcher;
+pub use locale_matcher::{LocaleMatcher, LocaleMatcherAlgorithm, LocaleResolution, SupportedLocalesOptions, supported_locales_of};
+
+mod intl_context;
+pub use intl_context::IntlContext;
+
+mod formatting_data;
+
+mod currency_data;
+
+mod format_overrides;
+pub use format_overrides::{FormatOverrides, DateOrder};
+
+mod number_format;
+pub use number_format::{NumberFormatter, NumberFormatOptions, Notation};
+
+mod number_parse;
+pub use number_parse::NumberParser;
+
+mod currency_format;
+pub use currency_format::{CurrencyFormatter, CurrencyFormatOptions, CurrencyStyle};
+
+mod date_time_format;
+pub use date_time_format::{DateTimeFormatter, DateTimeFormatOptions, DateTimeLength, NameWidth, NameContext};
+
+mod date_parse;
+pub use date_parse::DateParser;
+
+mod segmenter;
+#[cfg(feature = "segmentation")]
+pub use segmenter::Segmenter;
+
+mod normalization;
+pub use normalization::NormalizationForm;
+#[cfg(feature = "normalization")]
+pub use normalization::{normalize, is_normalized};
+
+#[cfg(feature = "normalization")]
+mod search_collator;
+#[cfg(feature = "normalization")]
+pub use search_collator::{SearchCollator, SearchCollatorOptions};
+
+mod text_width;
+pub use text_width::TextWidthTable;
+
+#[cfg(feature = "segmentation")]
+mod line_breaker;
+#[cfg(feature = "segmentation")]
+pub use line_breaker::{LineBreaker, LineBreakOpportunity, WrappedLine};
+
+mod person_name_format;
+pub use person_name_format::{PersonName, PersonNameFormatter, PersonNameFormatOptions, PersonNameOrder};
+
+mod format_value;
+pub use format_value::FormatValue;
+
+mod formatted_parts;
+pub use formatted_parts::{FormattedPart, AttributedString, AttributedSegment};
+
+mod country;
+pub use country::{Country, parse_country};
+
+mod subdivision;
+pub use subdivision::{Subdivision, parse_subdivision};
+
+mod csv;
+
+pub use recoyx_localization_core::{parse_message, MessageAst, MessagePart, MessageVariable, MessageFormatterCall, SyntaxError, SyntaxErrorKind};
+use recoyx_localization_core::{tokenize, quantity_suffix, quantity_suffixes, gender_suffix, format_message, MessageArguments, MessageResolver, MessageWriter, ValueKind};
+
+mod message_metadata;
+pub use message_metadata::MessageMetadata;
+
+mod message_tree;
+pub use message_tree::MessageTree;
+
+mod validation;
+pub use validation::{ValidationReport, LocaleValidation, PlaceholderMismatch};
+
+mod diagnostic;
+pub use diagnostic::{Diagnostic, DiagnosticSeverity, DiagnosticKind};
+
+mod metrics;
+pub use metrics::MetricsSink;
+
+mod locale_preference;
+pub use locale_preference::{LocalePreferenceStore, MemoryLocalePreferenceStore, FileLocalePreferenceStore};
+#[cfg(target_arch = "wasm32")]
+pub use locale_preference::LocalStorageLocalePreferenceStore;
+
+mod missing_translation;
+pub use missing_translation::{MissingTranslationProvider, MissingTranslation, ProvidedTranslation};
+
+mod fallback_trace;
+pub use fallback_trace::{FallbackTrace, FallbackAttempt, FallbackOutcome};
+
+mod load_report;
+pub use load_report::{LoadReport, AssetLoadError, AssetLoadErrorKind};
+
+mod asset_manifest;
+pub use asset_manifest::{AssetManifest, ManifestChanges, CatalogDelta};
+
+mod platform_strings;
+pub use platform_strings::{parse_android_strings, parse_apple_strings, parse_apple_stringsdict};
+
+mod locale_map;
+pub use locale_map::{
+    LocaleMap, LocaleMapConfig, LocaleSession, LocaleMapOptions, LocaleMapAssetOptions,
+    LocaleMapLoaderType, LocaleMapAssetFormat, LocaleMapFormatArgument,
+    GenderOf, Count, BilingualMessage, KeyReveal, LanguageMenuItem,
+};
+pub use recoyx_localization_core::Gender;
+
+mod options_config;
+
+mod formatter_support;
+pub use formatter_support::FormatterSupport;
+
+mod lookup_record;
+pub use lookup_record::{LookupRecord, lookup_records_to_json};
+
+mod key_page;
+pub use key_page::{KeyPage, KeyPageEntry};
+
+mod catalog_diff;
+pub use catalog_diff::{CatalogDiff, ChangedMessage, diff_catalogs, diff_messages};
+
+mod shared_catalog;
+pub use shared_catalog::{SharedCatalog, Localization};
+
+mod translation_editor;
+pub use translation_editor::{TranslationEditor, TranslationEditError};
+
+#[cfg(feature = "test-support")]
+pub mod test_support;
+
+#[cfg(feature = "live-updates")]
+pub mod live_updates;
+
+#[cfg(feature = "gui")]
+pub mod gui;
+
+#[cfg(feature = "axum")]
+pub mod web;
+
+#[cfg(feature = "build")]
+pub mod build;
+
+#[cfg(feature = "compression")]
+mod compression;
+
+#[cfg(feature = "integrity")]
+mod integrity;
+#[cfg(feature = "integrity")]
+pub use integrity::AssetIntegrity;
+
+#[cfg(any(feature = "tera", feature = "handlebars", feature = "askama"))]
+pub mod templates;
+
+#[cfg(feature = "editor-protocol")]
+mod editor_protocol;
+#[cfg(feature = "editor-protocol")]
+pub use editor_protocol::{EditorServer, EditorRequest, EditorResponse, EditorKey};
+
+pub mod pluralrules {
+    pub use intl_pluralrules::{PluralCategory, PluralRuleType, operands::PluralOperands};
+}
+pub use pluralrules::{
+    PluralCategory, PluralRuleType, PluralOperands,
+};
+
+mod plural_ranges;
+pub use plural_ranges::{PluralRange, plural_range_category, plural_category_keyword};
+
+pub mod relative_time_format {
+    pub type Formatter = timeago::Formatter<timeago::BoxedLanguage>;
+    pub use timeago::TimeUnit;
+}
+pub use relative_time_format::{
+    Formatter as RelativeTimeFormatter,
+    TimeUnit as RelativeTimeUnit,
 };
\ No newline at end of file
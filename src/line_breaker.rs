@@ -0,0 +1,123 @@
+use icu_segmenter::{options::{LineBreakOptions, LineBreakWordOption}, LineSegmenter, LineSegmenterBorrowed};
+use super::{Direction, Locale};
+
+
+/// A position at which a line can begin, as a byte index into the text.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct LineBreakOpportunity {
+    pub index: usize,
+    /// Whether the line must be broken there, such as after a line feed.
+    /// The end of the text is a mandatory break.
+    pub mandatory: bool,
+}
+
+/// A line of wrapped text, along with the direction to lay it out in.
+#[derive(Clone, PartialEq, Debug)]
+pub struct WrappedLine<'a> {
+    /// The text of the line, without trailing whitespace and line breaks.
+    pub text: &'a str,
+    /// The direction of the line's paragraph, from its first strong character
+    /// or else the locale, which determines the edge the line starts from.
+    pub direction: Direction,
+}
+
+/// Finds the line breaking opportunities of text following the Unicode line breaking
+/// algorithm (UAX #14), such as for wrapping localized text in games and user interfaces.
+/// Available through the `segmentation` feature, which is enabled by default.
+///
+/// Text is broken between Han characters and kana, but, for Korean, Hangul is kept together
+/// as in the `keep-all` word breaking of CSS, breaking only at spaces. Text in scripts written
+/// without spaces, such as Thai, Lao, Khmer and Burmese, is broken between words found with
+/// the dictionaries of ICU4X.
+///
+/// ```
+/// use recoyx_localization::{LineBreaker, parse_locale};
+///
+/// let breaker = LineBreaker::new(&parse_locale("en").unwrap());
+/// let lines: Vec<&str> = breaker.wrap("The quick brown fox", 10.0, |s| s.chars().count() as f64).iter().map(|l| l.text).collect();
+/// assert_eq!(lines, vec!["The quick", "brown fox"]);
+/// ```
+#[derive(Clone)]
+pub struct LineBreaker {
+    _segmenter: LineSegmenterBorrowed<'static>,
+    _direction: Direction,
+}
+
+impl LineBreaker {
+    pub fn new(locale: &Locale) -> Self {
+        let mut options = LineBreakOptions::default();
+        if locale.language() == "ko" {
+            options.word_option = Some(LineBreakWordOption::KeepAll);
+        }
+        Self {
+            _segmenter: LineSegmenter::new_dictionary(options),
+            _direction: locale.direction(),
+        }
+    }
+
+    /// Returns the text direction of the locale, used for paragraphs without
+    /// strong characters.
+    pub fn direction(&self) -> Direction {
+        self._direction
+    }
+
+    /// Returns the positions at which a line can begin, in increasing order,
+    /// ending with the end of the text.
+    pub fn break_opportunities(&self, text: &str) -> Vec<LineBreakOpportunity> {
+        self._segmenter.segment_str(text).filter(|i| *i > 0).map(|index| LineBreakOpportunity {
+            index,
+            mandatory: index == text.len() || text[..index].ends_with(is_hard_line_break),
+        }).collect()
+    }
+
+    /// Wraps text into lines no wider than a maximum width, as measured by a function,
+    /// such as the advance of a font. A word wider than the maximum width is kept on
+    /// a line of its own.
+    pub fn wrap<'a, F: Fn(&str) -> f64>(&self, text: &'a str, max_width: f64, width: F) -> Vec<WrappedLine<'a>> {
+        let mut r = vec![];
+        let mut line_start = 0;
+        let mut last_fit: Option<usize> = None;
+        let mut direction = paragraph_direction(text).unwrap_or(self._direction);
+        for opportunity in self.break_opportunities(text) {
+            let candidate = text[line_start..opportunity.index].trim_end();
+            if width(candidate) > max_width {
+                if let Some(end) = last_fit {
+                    r.push(WrappedLine { text: text[line_start..end].trim_end(), direction });
+                    line_start = end;
+                }
+            }
+            if opportunity.mandatory {
+                r.push(WrappedLine { text: text[line_start..opportunity.index].trim_end(), direction });
+                line_start = opportunity.index;
+                last_fit = None;
+                direction = paragraph_direction(&text[line_start..]).unwrap_or(self._direction);
+            } else {
+                last_fit = Some(opportunity.index);
+            }
+        }
+        r
+    }
+}
+
+/// Whether a character ends a line, as those of the `BK`, `CR`, `LF` and `NL`
+/// classes of UAX #14.
+fn is_hard_line_break(ch: char) -> bool {
+    matches!(ch, '\n' | '\r' | '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}')
+}
+
+/// Returns the direction of the first strong character of a paragraph, following
+/// rules P2 and P3 of the Unicode bidirectional algorithm, or `None` if there is none.
+fn paragraph_direction(text: &str) -> Option<Direction> {
+    for ch in text.chars() {
+        if matches!(ch, '\n' | '\r' | '\u{2029}') {
+            return None;
+        }
+        if matches!(ch as u32, 0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF) && ch.is_alphabetic() {
+            return Some(Direction::RightToLeft);
+        }
+        if ch.is_alphabetic() {
+            return Some(Direction::LeftToRight);
+        }
+    }
+    None
+}
@@ -0,0 +1,274 @@
+//! Client of a translation service that pushes message updates to a running
+//! `LocaleMap`, so that copy fixes go live without redeploying, available through
+//! the `live-updates` feature.
+//!
+//! The service is reached over HTTP through either transport:
+//!
+//! - Long polling: `GET <endpoint>?cursor=<cursor>` answers, once there are updates
+//!   after the cursor or after a timeout, with `{"cursor": "...", "updates": [...]}`.
+//!   The cursor is omitted from the first request.
+//! - Server-sent events: `GET <endpoint>` answers with a `text/event-stream` whose
+//!   events carry an update or an array of updates as `data`, and the cursor as `id`,
+//!   sent back as `Last-Event-ID` when reconnecting.
+//!
+//! An update is `{"locale": "pt-BR", "id": "common.greeting", "message": "Olá"}`,
+//! where a `null` message removes the message of the locale, so that its fallbacks
+//! provide it.
+//!
+//! ```no_run
+//! use recoyx_localization::{live_updates::{LiveUpdateClient, LiveUpdateTransport}, LocaleMap, LocaleMapOptions};
+//!
+//! # async fn run() -> Result<(), String> {
+//! let mut locale_map = LocaleMap::new(&LocaleMapOptions::new());
+//! locale_map.load(None).await;
+//! let mut client = LiveUpdateClient::new("https://example.com/translations/live", LiveUpdateTransport::ServerSentEvents);
+//! client.on_update(|update| println!("{} changed in {}", update.id, update.locale));
+//! loop {
+//!     client.poll(&mut locale_map).await?;
+//! }
+//! # }
+//! ```
+
+use std::rc::Rc;
+use serde::{Serialize, Deserialize};
+use super::{parse_locale, LocaleMap};
+
+type UpdateListener = Rc<dyn Fn(&MessageUpdate)>;
+
+/// Maximum size in bytes of an event of a server-sent event stream, beyond which
+/// the stream is closed, so that a misbehaving service cannot exhaust memory.
+const MAX_EVENT_SIZE: usize = 1 << 20;
+
+/// Message change pushed by a translation service.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageUpdate {
+    /// The locale tag of the message, such as `pt-BR`.
+    pub locale: String,
+    pub id: String,
+    /// The new message, or `None` to remove the message.
+    pub message: Option<String>,
+}
+
+/// How a `LiveUpdateClient` receives updates.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LiveUpdateTransport {
+    LongPolling,
+    ServerSentEvents,
+}
+
+#[derive(Deserialize)]
+struct LongPollResponse {
+    cursor: Option<String>,
+    #[serde(default)]
+    updates: Vec<MessageUpdate>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EventData {
+    One(MessageUpdate),
+    Many(Vec<MessageUpdate>),
+}
+
+/// Receives message updates from a translation service and applies them to a `LocaleMap`.
+///
+/// The updates are remembered, so that `reapply()` restores them after a locale
+/// is reloaded, which discards messages inserted at runtime.
+pub struct LiveUpdateClient {
+    _endpoint: String,
+    _transport: LiveUpdateTransport,
+    _http: reqwest::Client,
+    _cursor: Option<String>,
+    /// The open event stream and the bytes received of its incomplete event,
+    /// which are decoded once the event is complete, since a chunk may end
+    /// within a character.
+    _stream: Option<reqwest::Response>,
+    _buffer: Vec<u8>,
+    _updates: Vec<MessageUpdate>,
+    _listeners: Vec<UpdateListener>,
+}
+
+impl LiveUpdateClient {
+    pub fn new<S: ToString>(endpoint: S, transport: LiveUpdateTransport) -> Self {
+        Self {
+            _endpoint: endpoint.to_string(),
+            _transport: transport,
+            _http: reqwest::Client::new(),
+            _cursor: None,
+            _stream: None,
+            _buffer: vec![],
+            _updates: vec![],
+            _listeners: vec![],
+        }
+    }
+
+    /// Returns the cursor of the last updates received, if any.
+    pub fn cursor(&self) -> Option<&str> {
+        self._cursor.as_deref()
+    }
+
+    /// Registers a function called with every update applied to a `LocaleMap`,
+    /// such as for requesting a redraw.
+    pub fn on_update<F: Fn(&MessageUpdate) + 'static>(&mut self, listener: F) {
+        self._listeners.push(Rc::new(listener));
+    }
+
+    /// Waits for the next updates from the service without applying them,
+    /// so that the `LocaleMap` can be used meanwhile; see `apply()`.
+    /// Long polling returns an empty list when the request times out.
+    pub async fn next_updates(&mut self) -> Result<Vec<MessageUpdate>, String> {
+        match self._transport {
+            LiveUpdateTransport::LongPolling => self.next_long_poll().await,
+            LiveUpdateTransport::ServerSentEvents => self.next_events().await,
+        }
+    }
+
+    /// Waits for the next updates and applies them; see `next_updates()` and `apply()`.
+    pub async fn poll(&mut self, locale_map: &mut LocaleMap) -> Result<usize, String> {
+        let updates = self.next_updates().await?;
+        Ok(self.apply(locale_map, updates))
+    }
+
+    /// Applies updates to the loaded locales of a `LocaleMap` and calls the update
+    /// listeners with them, returning the number applied. Updates of other supported
+    /// locales are remembered for `reapply()`, and those of unsupported locales are ignored.
+    pub fn apply(&mut self, locale_map: &mut LocaleMap, updates: Vec<MessageUpdate>) -> usize {
+        let mut count = 0;
+        for update in updates {
+            match apply_update(locale_map, &update) {
+                None => continue,
+                Some(true) => {
+                    count += 1;
+                    self._listeners.iter().for_each(|listener| listener(&update));
+                },
+                Some(false) => {},
+            }
+            self._updates.retain(|u| u.locale != update.locale || u.id != update.id);
+            self._updates.push(update);
+        }
+        count
+    }
+
+    /// Applies the updates received so far again, such as after loading a locale,
+    /// which discards the messages inserted at runtime. Returns the number applied.
+    pub fn reapply(&self, locale_map: &mut LocaleMap) -> usize {
+        self._updates.iter().filter(|update| apply_update(locale_map, update) == Some(true)).count()
+    }
+
+    async fn next_long_poll(&mut self) -> Result<Vec<MessageUpdate>, String> {
+        let mut request = self._http.get(&self._endpoint);
+        if let Some(cursor) = self._cursor.as_ref() {
+            request = request.query(&[("cursor", cursor)]);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("live updates: status {}", response.status()));
+        }
+        let body: LongPollResponse = response.json().await.map_err(|e| e.to_string())?;
+        if body.cursor.is_some() {
+            self._cursor = body.cursor;
+        }
+        Ok(body.updates)
+    }
+
+    async fn next_events(&mut self) -> Result<Vec<MessageUpdate>, String> {
+        loop {
+            if let Some(updates) = self.take_event()? {
+                return Ok(updates);
+            }
+            if self._stream.is_none() {
+                let mut request = self._http.get(&self._endpoint).header("Accept", "text/event-stream");
+                if let Some(cursor) = self._cursor.as_ref() {
+                    request = request.header("Last-Event-ID", cursor);
+                }
+                let response = request.send().await.map_err(|e| e.to_string())?;
+                if !response.status().is_success() {
+                    return Err(format!("live updates: status {}", response.status()));
+                }
+                self._stream = Some(response);
+                self._buffer.clear();
+            }
+            if self._buffer.len() > MAX_EVENT_SIZE {
+                self._stream = None;
+                return Err(format!("live updates: event exceeds {} bytes", MAX_EVENT_SIZE));
+            }
+            match self._stream.as_mut().unwrap().chunk().await.map_err(|e| e.to_string())? {
+                Some(chunk) => self._buffer.extend_from_slice(&chunk),
+                // The stream ended; the next call reconnects from the cursor.
+                None => {
+                    self._stream = None;
+                    return Ok(vec![]);
+                },
+            }
+        }
+    }
+
+    /// Removes the first complete event from the buffer, returning its updates,
+    /// or `None` if no event is complete. Events without data are skipped.
+    fn take_event(&mut self) -> Result<Option<Vec<MessageUpdate>>, String> {
+        while let Some(end) = event_end(&self._buffer) {
+            let event: Vec<u8> = self._buffer.drain(..end).collect();
+            let event = String::from_utf8_lossy(&event);
+            let mut data = String::new();
+            for line in event.split(['\r', '\n']).filter(|line| !line.is_empty()) {
+                let (field, value) = line.split_once(':').unwrap_or((line, ""));
+                let value = value.strip_prefix(' ').unwrap_or(value);
+                match field {
+                    "data" => {
+                        if !data.is_empty() { data.push('\n') }
+                        data.push_str(value);
+                    },
+                    "id" => self._cursor = Some(value.to_string()),
+                    _ => {},
+                }
+            }
+            if data.is_empty() {
+                continue;
+            }
+            return match serde_json::from_str::<EventData>(&data).map_err(|e| format!("live updates: {}", e))? {
+                EventData::One(update) => Ok(Some(vec![update])),
+                EventData::Many(updates) => Ok(Some(updates)),
+            };
+        }
+        Ok(None)
+    }
+}
+
+/// Returns the position after the blank line ending the first event of
+/// an event stream, whose lines end in `\n`, `\r\n` or `\r`.
+fn event_end(buffer: &[u8]) -> Option<usize> {
+    let mut line_start = 0;
+    let mut i = 0;
+    while i < buffer.len() {
+        let line_end = i;
+        match buffer[i] {
+            // A `\r` at the end of the buffer may be followed by `\n` in the next chunk.
+            b'\r' if i + 1 == buffer.len() => return None,
+            b'\r' if buffer[i + 1] == b'\n' => i += 2,
+            b'\r' | b'\n' => i += 1,
+            _ => {
+                i += 1;
+                continue;
+            },
+        }
+        if line_end == line_start {
+            return Some(i);
+        }
+        line_start = i;
+    }
+    None
+}
+
+/// Applies an update if its locale is loaded, returning whether it was applied,
+/// or `None` if its locale is not supported.
+fn apply_update(locale_map: &mut LocaleMap, update: &MessageUpdate) -> Option<bool> {
+    let locale = parse_locale(&update.locale).ok().filter(|locale| locale_map.supports_locale(locale))?;
+    if !locale_map.session().loaded_locales().contains(&locale) {
+        return Some(false);
+    }
+    match update.message.as_ref() {
+        Some(message) => locale_map.insert_message(&locale, &update.id, message),
+        None => { locale_map.remove_message(&locale, &update.id); },
+    }
+    Some(true)
+}
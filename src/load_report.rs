@@ -0,0 +1,70 @@
+use std::fmt::{Display, Formatter};
+use super::Locale;
+
+/// Outcome of `LocaleMap::load_partial()`.
+#[derive(Clone)]
+pub struct LoadReport {
+    /// The locale that was requested, or the supported locale it resolved to.
+    pub locale: Locale,
+    /// Whether the locale became the current locale. This is only `false` if
+    /// `check_placeholders()` was specified with `DiagnosticSeverity::Error`
+    /// and a message does not match the default locale.
+    pub loaded: bool,
+    /// The asset files of the locale and its fallbacks that failed to load,
+    /// whose messages are missing.
+    pub errors: Vec<AssetLoadError>,
+}
+
+impl LoadReport {
+    /// Returns `true` if the locale became the current locale and every asset file loaded.
+    pub fn is_ok(&self) -> bool {
+        self.loaded && self.errors.is_empty()
+    }
+}
+
+impl Display for LoadReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}: {}", self.locale.standard_tag(), if self.is_ok() { "ok" } else if self.loaded { "partially loaded" } else { "failed to load" })?;
+        for error in self.errors.iter() {
+            writeln!(f, "  {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Asset file that failed to load, reported in a `LoadReport`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AssetLoadError {
+    /// The locale whose messages the file provides.
+    pub locale: Locale,
+    /// The path or URL of the file.
+    pub path: String,
+    pub kind: AssetLoadErrorKind,
+}
+
+impl Display for AssetLoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AssetLoadErrorKind {
+    /// The file does not exist or could not be fetched.
+    NotFound,
+    /// The file could not be parsed, with the reason.
+    Invalid(String),
+    /// The file failed the verification specified through
+    /// `LocaleMapAssetOptions::integrity()`, with the reason.
+    Untrusted(String),
+}
+
+impl Display for AssetLoadErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetLoadErrorKind::NotFound => write!(f, "not found"),
+            AssetLoadErrorKind::Invalid(reason) => write!(f, "{}", reason),
+            AssetLoadErrorKind::Untrusted(reason) => write!(f, "untrusted: {}", reason),
+        }
+    }
+}
@@ -1,102 +1,692 @@
-use super::{
-    LocaleBasicData, Direction, Country,
-    LOCALE_BASIC_DATA,
-};
-use std::{fmt::{Display, Formatter}, hash::{Hash, Hasher}, rc::Rc, str::FromStr};
-use language_tag::LangTag;
-
-/// Parses a locale code. If the given string is a valid language tag but its
-/// language subtag is not a known language, an error is returned instead.
-///
-/// Some region codes are specially translated into the correct language identifier,
-/// such as from `jp` to `ja` and `br` to `pt-BR`.
-//
-///
-pub fn parse_locale<S: ToString>(src: S) -> Result<Locale, String> {
-    let src = src.to_string();
-    let src: &str = src.as_ref();
-    let tag = LangTag::from_str(src);
-    if tag.is_err() {
-        return Err(tag.unwrap_err());
-    }
-    let mut tag = tag.unwrap();
-    if tag.get_region().is_none() {
-        let src = src.to_lowercase();
-        if src == "br" { tag = LangTag::from_str("pt_BR").unwrap(); }
-        if src == "us" { tag = LangTag::from_str("en_US").unwrap(); }
-        if src == "jp" { tag = LangTag::from_str("ja").unwrap(); }
-    }
-    if LOCALE_BASIC_DATA.get(&tag.get_language().to_string().replace("-", "")).is_none() {
-        return Err(String::from("Invalid locale code."));
-    }
-    Ok(Locale {
-        _tag: Rc::new(tag),
-    })
-}
-
-#[derive(Clone, Eq)]
-pub struct Locale {
-    pub(crate) _tag: Rc<LangTag>,
-}
-
-impl Locale {
-    fn _get_basic_info(&self) -> Option<&LocaleBasicData> {
-        let langscript = self._tag.get_language().to_string().replace("-", "");
-        let langscript: &str = langscript.as_ref();
-        LOCALE_BASIC_DATA.get(langscript)
-    }
-
-    pub fn direction(&self) -> Direction {
-        let data = self._get_basic_info();
-        if let Some(data) = data { data.direction } else { Direction::LeftToRight }
-    }
-
-    pub fn universal_name(&self) -> &str {
-        let data = self._get_basic_info();
-        if let Some(data) = data { &data.universal_name } else { "" }
-    }
-
-    pub fn native_name(&self) -> &str {
-        let data = self._get_basic_info();
-        if let Some(data) = data { &data.native_name } else { "" }
-    }
-
-    pub fn country(&self) -> Option<Country> {
-        if let Some(r) = self.standard_tag().get_region() {
-            let r = isocountry::CountryCode::for_alpha2_caseless((&r.to_string()).as_ref());
-            if let Ok(r) = r {
-                return Some(Country { _standard_code: r });
-            }
-        }
-        let s = self.standard_tag().to_string();
-        if s == "fr" { return Some(Country { _standard_code: isocountry::CountryCode::for_alpha3_caseless(&"FRA").unwrap() }); }
-        if s == "ja" { return Some(Country { _standard_code: isocountry::CountryCode::for_alpha3_caseless(&"JPN").unwrap() }); }
-        if s == "ru" { return Some(Country { _standard_code: isocountry::CountryCode::for_alpha3_caseless(&"RUS").unwrap() }); }
-        None
-    }
-
-    pub fn standard_tag(&self) -> &LangTag {
-        self._tag.as_ref()
-    }
-}
-
-impl Display for Locale {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let country = self.country();
-        if let Some(country) = country {
-            write!(f, "{} ({})", self.native_name(), country.universal_name())
-        } else { write!(f, "{}", self.native_name()) }
-    }
-}
-
-impl PartialEq for Locale {
-    fn eq(&self, rhs: &Locale) -> bool {
-        self._tag == rhs._tag
-    }
-}
-
-impl Hash for Locale {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self._tag.to_string().hash(state);
-    }
-}
\ No newline at end of file
+use super::{
+    LocaleBasicData, Direction, Country, NumberingSystem, HourCycle, Weekday, WeekInfo, MeasurementSystem, parse_country,
+    LOCALE_BASIC_DATA, LOCALE_EXEMPLAR_CHARACTERS, EXCLUDED_LANGUAGES, parse_numbering_system,
+    formatting_data::{formatting_data, pattern_hour_cycle},
+};
+use std::{cell::RefCell, cmp::Ordering, fmt::{Display, Formatter}, hash::{Hash, Hasher}, str::FromStr, sync::Arc};
+use language_tag::{LangTag, LangTagBuilder};
+
+/// Scripts that distinguish uppercase and lowercase letters.
+static CASED_SCRIPTS: &[&str] = &["Latn", "Cyrl", "Grek", "Armn", "Copt", "Glag", "Adlm", "Cher", "Osge", "Dsrt", "Geor"];
+
+/// Languages with a cased script whose typography discourages all-caps emphasis,
+/// such as German, where capitalized nouns become indistinguishable.
+static UPPERCASE_EMPHASIS_DISCOURAGED: &[&str] = &["de"];
+
+/// Grandfathered tags of the IANA language subtag registry along with their preferred values.
+static GRANDFATHERED_TAGS: &[(&str, &str)] = &[
+    ("art-lojban", "jbo"),
+    ("en-gb-oed", "en-GB-oxendict"),
+    ("i-ami", "ami"),
+    ("i-bnn", "bnn"),
+    ("i-hak", "hak"),
+    ("i-klingon", "tlh"),
+    ("i-lux", "lb"),
+    ("i-navajo", "nv"),
+    ("i-pwn", "pwn"),
+    ("i-tao", "tao"),
+    ("i-tay", "tay"),
+    ("i-tsu", "tsu"),
+    ("no-bok", "nb"),
+    ("no-nyn", "nn"),
+    ("sgn-be-fr", "sfb"),
+    ("sgn-be-nl", "vgt"),
+    ("sgn-ch-de", "sgg"),
+    ("zh-guoyu", "zh"),
+    ("zh-hakka", "hak"),
+    ("zh-min-nan", "nan"),
+    ("zh-xiang", "hsn"),
+];
+
+/// Deprecated language subtags and the languages they are aliases of, including
+/// the individual languages the locale data identifies by their macrolanguage,
+/// such as `cmn` from `zh-cmn`.
+static LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("iw", "he"),
+    ("in", "id"),
+    ("ji", "yi"),
+    ("jw", "jv"),
+    ("cmn", "zh"),
+    ("arb", "ar"),
+    ("zsm", "ms"),
+    ("swh", "sw"),
+    ("pes", "fa"),
+    ("ekk", "et"),
+    ("lvs", "lv"),
+    ("uzn", "uz"),
+];
+
+/// Parses a locale code. If the given string is a valid language tag but its
+/// language subtag is not a known language, an error is returned instead.
+///
+/// Some region codes are specially translated into the correct language identifier,
+/// such as from `jp` to `ja` and `br` to `pt-BR`.
+///
+/// The code is normalized first through `normalize_locale_code()`, so `en_us`, `EN-US`
+/// and `en_US.UTF-8` all parse to the same locale. Grandfathered tags, extended language
+/// subtags and deprecated languages are then replaced by their preferred values,
+/// as `canonicalize_language_tag()` does, so `i-navajo` parses as `nv` and `zh-cmn-Hans` as `zh-Hans`.
+//
+///
+pub fn parse_locale<S: ToString>(src: S) -> Result<Locale, String> {
+    let src = replace_legacy_subtags(&normalize_locale_code(src));
+    let src: &str = src.as_ref();
+    let mut tag = LangTag::from_str(src)?;
+    if tag.get_region().is_none() {
+        let src = src.to_lowercase();
+        if src == "br" { tag = LangTag::from_str("pt_BR").unwrap(); }
+        if src == "us" { tag = LangTag::from_str("en_US").unwrap(); }
+        if src == "jp" { tag = LangTag::from_str("ja").unwrap(); }
+    }
+    locale_from_tag(tag)
+}
+
+/// Normalizes a locale code, which may be a POSIX locale string such as `pt_BR.UTF-8`
+/// or `sr_RS@latin`, into a language tag: surrounding whitespace and the codeset are removed,
+/// underscores become hyphens, the `@latin` and `@cyrillic` modifiers become scripts,
+/// other modifiers are removed, and subtags are cased as in `canonicalize_language_tag()`.
+///
+/// ## Example
+///
+/// ```
+/// use recoyx_localization::normalize_locale_code;
+///
+/// assert_eq!(normalize_locale_code("zh_hans_cn"), "zh-Hans-CN");
+/// assert_eq!(normalize_locale_code("pt_BR.UTF-8"), "pt-BR");
+/// assert_eq!(normalize_locale_code("sr_RS.UTF-8@latin"), "sr-Latn-RS");
+/// ```
+pub fn normalize_locale_code<S: ToString>(src: S) -> String {
+    let src = src.to_string();
+    let mut code = src.trim();
+    let mut modifier = None;
+    if let Some(i) = code.find('@') {
+        modifier = Some(code[i + 1..].to_lowercase());
+        code = &code[..i];
+    }
+    if let Some(i) = code.find('.') {
+        code = &code[..i];
+    }
+    let mut subtags: Vec<String> = code.split(['_', '-']).map(|s| s.to_string()).collect();
+    let script = match modifier.as_deref() {
+        Some("latin") => Some("Latn"),
+        Some("cyrillic") => Some("Cyrl"),
+        _ => None,
+    };
+    if let Some(script) = script {
+        if !subtags.iter().skip(1).any(|s| s.len() == 4 && s.chars().all(|c| c.is_ascii_alphabetic())) {
+            subtags.insert(1.min(subtags.len()), script.to_string());
+        }
+    }
+    let mut singleton = false;
+    for (i, subtag) in subtags.iter_mut().enumerate() {
+        let lowercase = subtag.to_lowercase();
+        *subtag = if i == 0 || singleton {
+            lowercase
+        } else if subtag.len() == 1 {
+            singleton = true;
+            lowercase
+        } else if subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+            lowercase[..1].to_uppercase() + &lowercase[1..]
+        } else if subtag.len() == 2 || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit())) {
+            subtag.to_uppercase()
+        } else {
+            lowercase
+        };
+    }
+    subtags.join("-")
+}
+
+/// Replaces a grandfathered tag by its preferred value, an extended language subtag
+/// by the language it identifies, as in `zh-yue` to `yue`, and a deprecated language
+/// by its alias, as in `iw` to `he`.
+/// Returns the most likely locale of a region, such as `de-DE` for `"DE"`,
+/// whose formats are used for the `-u-rg-` extension.
+pub(crate) fn likely_locale_of_region(region: &str) -> Option<Locale> {
+    let mut langid = unic_langid::LanguageIdentifier::from_bytes(format!("und-{}", region).as_ref()).ok()?;
+    if !langid.maximize() {
+        return None;
+    }
+    parse_locale(format!("{}-{}", langid.language, region)).ok()
+}
+
+fn replace_legacy_subtags(code: &str) -> String {
+    let lowercase = code.to_lowercase();
+    if let Some((_, preferred)) = GRANDFATHERED_TAGS.iter().find(|(tag, _)| *tag == lowercase) {
+        return preferred.to_string();
+    }
+    let mut subtags: Vec<&str> = code.split('-').collect();
+    let is_extlang = |s: &str| s.len() == 3 && s.chars().all(|c| c.is_ascii_alphabetic());
+    if subtags.len() > 1 && (2..=3).contains(&subtags[0].len()) && is_extlang(subtags[1]) {
+        subtags.remove(0);
+    }
+    if let Some((_, alias)) = LANGUAGE_ALIASES.iter().find(|(language, _)| subtags[0].eq_ignore_ascii_case(language)) {
+        subtags[0] = alias;
+    }
+    subtags.join("-")
+}
+
+fn locale_from_tag(tag: LangTag) -> Result<Locale, String> {
+    let language = tag.get_language().to_string().replace("-", "");
+    if LOCALE_BASIC_DATA.get(&language).is_none() {
+        if EXCLUDED_LANGUAGES.contains(&language.as_str()) {
+            return Err(format!("The locale data of {} is not bundled; add it to RECOYX_LOCALIZATION_LOCALES.", language));
+        }
+        return Err(String::from("Invalid locale code."));
+    }
+    Ok(Locale {
+        _canonical_tag: Arc::new(canonical_tag(&tag)),
+        _tag: Arc::new(tag),
+    })
+}
+
+/// Canonicalizes a language tag per RFC 5646 §4.5: the language is lowercased,
+/// the script titlecased, the region uppercased, variants and extensions lowercased,
+/// and extensions sorted by their singleton. Grandfathered tags, extended language
+/// subtags and deprecated languages are replaced by their preferred values.
+///
+/// ## Example
+///
+/// ```
+/// use recoyx_localization::canonicalize_language_tag;
+///
+/// assert_eq!(canonicalize_language_tag("EN-latn-us-B-Foo-A-Bar").unwrap(), "en-Latn-US-a-bar-b-foo");
+/// assert_eq!(canonicalize_language_tag("zh-min-nan").unwrap(), "nan");
+/// ```
+pub fn canonicalize_language_tag<S: ToString>(src: S) -> Result<String, String> {
+    Ok(canonical_tag(&LangTag::from_str(replace_legacy_subtags(src.to_string().trim()).as_ref())?))
+}
+
+fn canonical_tag(tag: &LangTag) -> String {
+    // The language tag parser already normalizes the case of
+    // the language, script and region subtags.
+    let mut subtags: Vec<String> = vec![tag.get_language().get_mainlang().to_string()];
+    subtags.extend(tag.get_language().get_lang_extensions().iter().cloned());
+    if let Some(script) = tag.get_script() { subtags.push(script.get_script().to_string()); }
+    if let Some(region) = tag.get_region() { subtags.push(region.get_region().to_string()); }
+    subtags.extend(tag.get_variants().iter().map(|v| v.get_variant().to_lowercase()));
+    let mut extensions: Vec<String> = tag.get_extensions().iter().map(|e| e.to_string().to_lowercase()).collect();
+    extensions.sort();
+    subtags.extend(extensions);
+    if let Some(privateuse) = tag.get_privateuse() { subtags.push(privateuse.to_string().to_lowercase()); }
+    subtags.join("-")
+}
+
+/// A locale identified by a language tag. Locales are compared, hashed and
+/// ordered by their canonical form, as returned by `canonicalize()`.
+#[derive(Clone, Eq)]
+pub struct Locale {
+    pub(crate) _tag: Arc<LangTag>,
+    _canonical_tag: Arc<String>,
+}
+
+impl Locale {
+    /// Creates a builder for constructing a locale from its components.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use recoyx_localization::Locale;
+    ///
+    /// let locale = Locale::builder().language("zh").script("Hant").region("TW").build().unwrap();
+    /// assert_eq!(locale.standard_tag().to_string(), "zh-Hant-TW");
+    /// ```
+    pub fn builder() -> LocaleBuilder {
+        LocaleBuilder::new()
+    }
+
+    fn _get_basic_info(&self) -> Option<&LocaleBasicData> {
+        let langscript = self._tag.get_language().to_string().replace("-", "");
+        let langscript: &str = langscript.as_ref();
+        LOCALE_BASIC_DATA.get(langscript)
+    }
+
+    /// Returns the text direction of the locale. The script subtag overrides the
+    /// language's direction, so `az-Arab` and `pa-Arab` are right-to-left while `az`
+    /// and `pa` are not; without a script subtag, the script most likely used for the
+    /// language and region is considered, as in `pa-PK`. If the script is not known,
+    /// the direction of the language's locale data is returned.
+    pub fn direction(&self) -> Direction {
+        match self.script().map(|s| s.to_string()).or_else(|| self.default_script()) {
+            Some(script) => Direction::of_script(&script),
+            None => self.language_direction(),
+        }
+    }
+
+    /// Returns the direction of the language's locale data, regardless of the script.
+    pub fn language_direction(&self) -> Direction {
+        let data = self._get_basic_info();
+        if let Some(data) = data { data.direction } else { Direction::LeftToRight }
+    }
+
+    /// Returns the writing direction of the locale's script; same as `direction()`.
+    pub fn script_direction(&self) -> Direction {
+        self.direction()
+    }
+
+    /// Returns the script most likely used for the locale, such as `Cyrl` for `sr`
+    /// and `Hant` for `zh-TW`, regardless of the script subtag.
+    pub fn default_script(&self) -> Option<String> {
+        let without_script = Locale::builder();
+        without_script.language(self.language());
+        if let Some(region) = self.region() { without_script.region(region); }
+        without_script.build().ok()?.maximize().script().map(|s| s.to_string())
+    }
+
+    /// Returns the main set of letters used for writing the locale's language
+    /// in its script, in lowercase where applicable, or `None` if there is no data
+    /// for the locale, such as for languages written in Han characters.
+    pub fn exemplar_characters(&self) -> Option<Vec<char>> {
+        let default_script = self.default_script();
+        let script = self.script().map(|s| s.to_string()).or_else(|| default_script.clone());
+        if let Some(r) = script.as_ref().and_then(|script| LOCALE_EXEMPLAR_CHARACTERS.get(&format!("{}-{}", self.language(), script))) {
+            return Some(r.chars().collect());
+        }
+        if script != default_script {
+            return None;
+        }
+        LOCALE_EXEMPLAR_CHARACTERS.get(self.language()).map(|r| r.chars().collect())
+    }
+
+    pub fn universal_name(&self) -> &str {
+        let data = self._get_basic_info();
+        if let Some(data) = data { &data.universal_name } else { "" }
+    }
+
+    pub fn native_name(&self) -> &str {
+        let data = self._get_basic_info();
+        if let Some(data) = data { &data.native_name } else { "" }
+    }
+
+    pub fn country(&self) -> Option<Country> {
+        if let Some(r) = self.standard_tag().get_region() {
+            let r = isocountry::CountryCode::for_alpha2_caseless(r.to_string().as_ref());
+            if let Ok(r) = r {
+                return Some(Country { _standard_code: r });
+            }
+        }
+        let s = self.standard_tag().to_string();
+        if s == "fr" { return Some(Country { _standard_code: isocountry::CountryCode::for_alpha3_caseless("FRA").unwrap() }); }
+        if s == "ja" { return Some(Country { _standard_code: isocountry::CountryCode::for_alpha3_caseless("JPN").unwrap() }); }
+        if s == "ru" { return Some(Country { _standard_code: isocountry::CountryCode::for_alpha3_caseless("RUS").unwrap() }); }
+        None
+    }
+
+    /// Returns the language subtag, such as `zh` in `zh-Hant-TW`.
+    pub fn language(&self) -> &str {
+        self._tag.get_language().get_mainlang()
+    }
+
+    /// Returns the script subtag, such as `Hant` in `zh-Hant-TW`.
+    pub fn script(&self) -> Option<&str> {
+        self._tag.get_script().map(|s| s.get_script())
+    }
+
+    /// Returns the region subtag, such as `TW` in `zh-Hant-TW`.
+    pub fn region(&self) -> Option<&str> {
+        self._tag.get_region().map(|r| r.get_region())
+    }
+
+    /// Returns the variant subtags, such as `1901` in `de-CH-1901`.
+    pub fn variants(&self) -> Vec<&str> {
+        self._tag.get_variants().iter().map(|v| v.get_variant()).collect()
+    }
+
+    /// Returns the extensions, each one starting with its singleton,
+    /// such as `u-nu-arab` in `ar-EG-u-nu-arab`.
+    pub fn extensions(&self) -> Vec<String> {
+        self._tag.get_extensions().iter().map(|e| e.to_string()).collect()
+    }
+
+    /// Returns a copy of this locale with the region subtag replaced.
+    pub fn with_region<S: ToString>(&self, region: S) -> Result<Locale, String> {
+        locale_from_tag(LangTagBuilder::from(self.standard_tag().clone()).region(region.to_string().as_ref()).build()?)
+    }
+
+    /// Returns a copy of this locale with the script subtag replaced.
+    pub fn with_script<S: ToString>(&self, script: S) -> Result<Locale, String> {
+        locale_from_tag(LangTagBuilder::from(self.standard_tag().clone()).script(script.to_string().as_ref()).build()?)
+    }
+
+    /// Returns a copy of this locale with a keyword of the `-u-` extension set,
+    /// such as `"hc"` with the value `"h23"`, replacing its previous value.
+    pub fn with_unicode_extension_keyword<K: ToString, V: ToString>(&self, key: K, value: V) -> Result<Locale, String> {
+        let builder = Locale::builder();
+        builder.language(self.language());
+        if let Some(script) = self.script() { builder.script(script); }
+        if let Some(region) = self.region() { builder.region(region); }
+        for variant in self.variants() { builder.variant(variant); }
+        for extension in self.extensions() {
+            let lowercase = extension.to_lowercase();
+            let tags: Vec<&str> = match lowercase.strip_prefix("u-") {
+                Some(tags) => tags.split('-').collect(),
+                None => {
+                    builder.extension(extension);
+                    continue;
+                },
+            };
+            let mut i = 0;
+            while i < tags.len() {
+                let values: Vec<&str> = tags[i + 1..].iter().take_while(|t| t.len() > 2).cloned().collect();
+                if tags[i].len() == 2 {
+                    builder.unicode_extension_keyword(tags[i], values.join("-"));
+                }
+                i += 1 + values.len();
+            }
+        }
+        builder.unicode_extension_keyword(key, value);
+        builder.build()
+    }
+
+    /// Adds likely subtags from the CLDR data, such as expanding `zh` into `zh-Hans-CN`.
+    /// If there is no data for the locale, it is returned unchanged.
+    pub fn maximize(&self) -> Locale {
+        self.with_likely_subtags(|langid| langid.maximize())
+    }
+
+    /// Removes subtags that would be added by `maximize()`,
+    /// such as reducing `en-Latn-US` into `en`.
+    /// If there is no data for the locale, it is returned unchanged.
+    pub fn minimize(&self) -> Locale {
+        self.with_likely_subtags(|langid| langid.minimize())
+    }
+
+    fn with_likely_subtags<F: FnOnce(&mut unic_langid::LanguageIdentifier) -> bool>(&self, f: F) -> Locale {
+        let mut code = self.language().to_string();
+        if let Some(script) = self.script() { code.push('-'); code.push_str(script); }
+        if let Some(region) = self.region() { code.push('-'); code.push_str(region); }
+        let mut langid = match unic_langid::LanguageIdentifier::from_bytes(code.as_ref()) {
+            Ok(langid) => langid,
+            Err(_) => return self.clone(),
+        };
+        if !f(&mut langid) {
+            return self.clone();
+        }
+        let builder = Locale::builder();
+        builder.language(langid.language);
+        if let Some(script) = langid.script { builder.script(script); }
+        if let Some(region) = langid.region { builder.region(region); }
+        for variant in self.variants() { builder.variant(variant); }
+        for extension in self.extensions() { builder.extension(extension); }
+        builder.build().unwrap_or_else(|_| self.clone())
+    }
+
+    /// Returns `true` if all-caps styling is a culturally appropriate form of emphasis
+    /// for this locale. This is `false` for scripts without letter case, such as
+    /// Arabic, Han and Devanagari, and for languages that discourage it, such as German.
+    pub fn supports_uppercase_emphasis(&self) -> bool {
+        let maximized = self.maximize();
+        let script = maximized.script().unwrap_or("Latn");
+        CASED_SCRIPTS.contains(&script) && !UPPERCASE_EMPHASIS_DISCOURAGED.contains(&self.language())
+    }
+
+    /// Uppercases text for emphasis if `supports_uppercase_emphasis()` is `true`,
+    /// otherwise returns the text unchanged. Casing follows the language's conventions,
+    /// such as the dotted capital I in Turkish and the dropped accents in Greek.
+    pub fn uppercase_for_emphasis<S: ToString>(&self, text: S) -> String {
+        let text = text.to_string();
+        if !self.supports_uppercase_emphasis() {
+            return text;
+        }
+        match self.language() {
+            "tr" | "az" => text.chars().map(|ch| if ch == 'i' { "İ".to_string() } else { ch.to_uppercase().to_string() }).collect(),
+            "el" => text.to_uppercase().chars().map(|ch| match ch {
+                'Ά' => 'Α', 'Έ' => 'Ε', 'Ή' => 'Η', 'Ί' => 'Ι', 'Ό' => 'Ο', 'Ύ' => 'Υ', 'Ώ' => 'Ω',
+                ch => ch,
+            }).filter(|ch| *ch != '\u{301}').collect(),
+            _ => text.to_uppercase(),
+        }
+    }
+
+    /// Returns the numbering system requested through the `-u-nu-`
+    /// extension, such as `arab` in `ar-EG-u-nu-arab`.
+    pub fn numbering_system(&self) -> Option<NumberingSystem> {
+        self.unicode_extension_keyword("nu").and_then(|v| parse_numbering_system(v).ok())
+    }
+
+    /// Returns the calendar requested through the `-u-ca-` extension,
+    /// such as `buddhist` in `th-TH-u-ca-buddhist`.
+    pub fn calendar(&self) -> Option<String> {
+        self.unicode_extension_keyword("ca")
+    }
+
+    /// Returns the collation requested through the `-u-co-` extension,
+    /// such as `phonebk` in `de-DE-u-co-phonebk`.
+    pub fn collation(&self) -> Option<String> {
+        self.unicode_extension_keyword("co")
+    }
+
+    /// Returns the hour cycle requested through the `-u-hc-` extension.
+    pub fn hour_cycle(&self) -> Option<HourCycle> {
+        self.unicode_extension_keyword("hc").and_then(|v| HourCycle::from_code(&v))
+    }
+
+    /// Returns the hour cycle of the locale's times, which is the one requested
+    /// through the `-u-hc-` extension, if any; otherwise the locale's preferred one,
+    /// such as `HourCycle::H12` for `en-US` and `HourCycle::H23` for `de-DE`.
+    pub fn preferred_hour_cycle(&self) -> HourCycle {
+        self.hour_cycle().unwrap_or_else(|| pattern_hour_cycle(&formatting_data(self).time_formats.short).unwrap_or(HourCycle::H23))
+    }
+
+    /// Returns the first day of the week requested through the `-u-fw-` extension.
+    pub fn first_day(&self) -> Option<Weekday> {
+        self.unicode_extension_keyword("fw").and_then(|v| Weekday::from_code(&v))
+    }
+
+    /// Returns the region requested through the `-u-rg-` extension, whose number and
+    /// date formats and conventions are used instead of the locale's, such as `"DE"`
+    /// in `en-US-u-rg-dezzzz`.
+    pub fn region_override(&self) -> Option<String> {
+        let value = self.unicode_extension_keyword("rg")?;
+        value.get(..2).filter(|r| r.chars().all(|c| c.is_ascii_alphabetic())).map(|r| r.to_uppercase())
+    }
+
+    /// Returns the measurement system requested through the `-u-ms-` extension.
+    pub fn measurement_system(&self) -> Option<MeasurementSystem> {
+        self.unicode_extension_keyword("ms").and_then(|v| MeasurementSystem::from_code(&v))
+    }
+
+    /// Returns the measurement system of the locale, which is the one requested through
+    /// the `-u-ms-` extension, if any; otherwise the one of the locale's region,
+    /// such as `MeasurementSystem::UsSystem` for `en-US`.
+    pub fn preferred_measurement_system(&self) -> MeasurementSystem {
+        self.measurement_system().unwrap_or_else(|| {
+            self.conventions_country().map(|c| MeasurementSystem::of_region(c.standard_code().alpha2())).unwrap_or(MeasurementSystem::Metric)
+        })
+    }
+
+    /// Returns the country whose conventions the locale follows: the region override,
+    /// the locale's region or its likely region, in this order.
+    pub(crate) fn conventions_country(&self) -> Option<Country> {
+        self.region_override().and_then(|r| parse_country(r).ok())
+            .or_else(|| self.country())
+            .or_else(|| self.maximize().country())
+    }
+
+    /// Returns the week conventions of the locale's region, or of its likely region,
+    /// such as the United States for `en`, with the first day requested through the
+    /// `-u-fw-` extension, if any. A region requested through the `-u-rg-` extension
+    /// takes precedence.
+    pub fn week_info(&self) -> WeekInfo {
+        let mut r = self.conventions_country().map(|c| c.week_info()).unwrap_or_default();
+        if let Some(first_day) = self.first_day() {
+            r.first_day = first_day;
+        }
+        r
+    }
+
+    pub fn standard_tag(&self) -> &LangTag {
+        self._tag.as_ref()
+    }
+
+    /// Returns this locale in canonical form per RFC 5646 §4.5,
+    /// such as `de-CH-1901-a-bar-b-foo` for `de-CH-1901-B-Foo-A-Bar`.
+    pub fn canonicalize(&self) -> Locale {
+        Locale {
+            _tag: Arc::new(LangTag::from_str(self._canonical_tag.as_ref()).unwrap()),
+            _canonical_tag: self._canonical_tag.clone(),
+        }
+    }
+
+    /// Returns the value of a keyword from the `-u-` extension, such as
+    /// `"arab"` for the key `"nu"` in `ar-EG-u-nu-arab`. A keyword without
+    /// a value has the empty string as its value.
+    pub fn unicode_extension_keyword(&self, key: &str) -> Option<String> {
+        let ext = self._tag.get_extensions().iter().find(|ext| ext.get_singleton().eq_ignore_ascii_case("u"))?;
+        let tags = ext.get_tags();
+        let i = tags.iter().position(|t| t.eq_ignore_ascii_case(key))?;
+        let values: Vec<String> = tags[i + 1..].iter().take_while(|t| t.len() > 2).map(|t| t.to_lowercase()).collect();
+        Some(values.join("-"))
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let country = self.country();
+        if let Some(country) = country {
+            write!(f, "{} ({})", self.native_name(), country.universal_name())
+        } else { write!(f, "{}", self.native_name()) }
+    }
+}
+
+impl PartialEq for Locale {
+    fn eq(&self, rhs: &Locale) -> bool {
+        self._canonical_tag == rhs._canonical_tag
+    }
+}
+
+impl PartialOrd for Locale {
+    fn partial_cmp(&self, rhs: &Locale) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl Ord for Locale {
+    fn cmp(&self, rhs: &Locale) -> Ordering {
+        self._canonical_tag.cmp(&rhs._canonical_tag)
+    }
+}
+
+impl Hash for Locale {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self._canonical_tag.hash(state);
+    }
+}
+
+/// Builder for `Locale` values, obtained from `Locale::builder()`.
+pub struct LocaleBuilder {
+    _language: RefCell<String>,
+    _script: RefCell<Option<String>>,
+    _region: RefCell<Option<String>>,
+    _variants: RefCell<Vec<String>>,
+    _extensions: RefCell<Vec<String>>,
+    _unicode_extension_keywords: RefCell<Vec<(String, String)>>,
+}
+
+impl LocaleBuilder {
+    fn new() -> Self {
+        LocaleBuilder {
+            _language: RefCell::new("und".to_string()),
+            _script: RefCell::new(None),
+            _region: RefCell::new(None),
+            _variants: RefCell::new(vec![]),
+            _extensions: RefCell::new(vec![]),
+            _unicode_extension_keywords: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn language<S: ToString>(&self, value: S) -> &Self {
+        self._language.replace(value.to_string());
+        self
+    }
+
+    pub fn script<S: ToString>(&self, value: S) -> &Self {
+        self._script.replace(Some(value.to_string()));
+        self
+    }
+
+    pub fn region<S: ToString>(&self, value: S) -> &Self {
+        self._region.replace(Some(value.to_string()));
+        self
+    }
+
+    pub fn variant<S: ToString>(&self, value: S) -> &Self {
+        self._variants.borrow_mut().push(value.to_string());
+        self
+    }
+
+    /// Adds an extension, starting with its singleton, such as `"u-nu-arab"`.
+    pub fn extension<S: ToString>(&self, value: S) -> &Self {
+        self._extensions.borrow_mut().push(value.to_string());
+        self
+    }
+
+    /// Sets a keyword of the `-u-` extension, such as `"ca"` with the value `"buddhist"`.
+    pub fn unicode_extension_keyword<K: ToString, V: ToString>(&self, key: K, value: V) -> &Self {
+        let key = key.to_string().to_lowercase();
+        let mut keywords = self._unicode_extension_keywords.borrow_mut();
+        keywords.retain(|(k, _)| *k != key);
+        keywords.push((key, value.to_string().to_lowercase()));
+        drop(keywords);
+        self
+    }
+
+    pub fn calendar<S: ToString>(&self, value: S) -> &Self {
+        self.unicode_extension_keyword("ca", value)
+    }
+
+    pub fn collation<S: ToString>(&self, value: S) -> &Self {
+        self.unicode_extension_keyword("co", value)
+    }
+
+    pub fn numbering_system(&self, value: NumberingSystem) -> &Self {
+        self.unicode_extension_keyword("nu", value.code())
+    }
+
+    pub fn hour_cycle(&self, value: HourCycle) -> &Self {
+        self.unicode_extension_keyword("hc", value.code())
+    }
+
+    pub fn first_day(&self, value: Weekday) -> &Self {
+        self.unicode_extension_keyword("fw", value.code())
+    }
+
+    pub fn measurement_system(&self, value: MeasurementSystem) -> &Self {
+        self.unicode_extension_keyword("ms", value.code())
+    }
+
+    /// Requests the number and date formats and conventions of a region,
+    /// such as `"DE"`, through the `-u-rg-` extension.
+    pub fn region_override<S: ToString>(&self, region: S) -> &Self {
+        self.unicode_extension_keyword("rg", format!("{}zzzz", region.to_string()))
+    }
+
+    /// Builds the locale. An error is returned if any component is malformed
+    /// or if the language is not a known language.
+    pub fn build(&self) -> Result<Locale, String> {
+        let mut builder = LangTagBuilder::new(self._language.borrow().as_ref());
+        if let Some(script) = self._script.borrow().as_ref() {
+            builder = builder.script(script);
+        }
+        if let Some(region) = self._region.borrow().as_ref() {
+            builder = builder.region(region);
+        }
+        for variant in self._variants.borrow().iter() {
+            builder = builder.variant(variant);
+        }
+        let mut extensions = self._extensions.borrow().clone();
+        let keywords = self._unicode_extension_keywords.borrow();
+        if !keywords.is_empty() {
+            let keywords: Vec<String> = keywords.iter().map(|(k, v)| if v.is_empty() { k.clone() } else { format!("{}-{}", k, v) }).collect();
+            if let Some(ext) = extensions.iter_mut().find(|ext| ext.to_lowercase().starts_with("u-")) {
+                ext.push('-');
+                ext.push_str(&keywords.join("-"));
+            } else {
+                extensions.push(format!("u-{}", keywords.join("-")));
+            }
+        }
+        for extension in extensions.iter() {
+            builder = builder.extension(extension);
+        }
+        locale_from_tag(builder.build()?)
+    }
+}
@@ -1,22 +1,44 @@
-use serde::{Serialize, Deserialize};
-use serde_repr::*;
-use std::{collections::HashMap};
-use lazy_static::lazy_static;
-
-lazy_static! {
-    pub static ref LOCALE_BASIC_DATA: HashMap<String, LocaleBasicData> = serde_json::from_str::<HashMap<String, LocaleBasicData>>(&String::from_utf8_lossy(include_bytes!("../locale-data/basic_data.json"))).unwrap();
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct LocaleBasicData {
-    pub universal_name: String,
-    pub native_name: String,
-    pub direction: Direction
-}
-
-#[repr(u64)]
-#[derive(Copy, Clone, Serialize_repr, Deserialize_repr, PartialEq)]
-pub enum Direction {
-    LeftToRight = 1,
-    RightToLeft = 0,
+use serde::{Serialize, Deserialize};
+use serde_repr::*;
+use std::{collections::HashMap};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref LOCALE_BASIC_DATA: HashMap<String, LocaleBasicData> = serde_json::from_str::<HashMap<String, LocaleBasicData>>(&String::from_utf8_lossy(include_bytes!(concat!(env!("OUT_DIR"), "/basic_data.json")))).unwrap();
+    pub static ref LOCALE_EXEMPLAR_CHARACTERS: HashMap<String, String> = serde_json::from_str::<HashMap<String, String>>(&String::from_utf8_lossy(include_bytes!(concat!(env!("OUT_DIR"), "/exemplar_characters.json")))).unwrap();
+}
+
+include!(concat!(env!("OUT_DIR"), "/bundled_languages.rs"));
+
+/// Returns whether the locale data of a language, such as `"pt"`, is bundled.
+/// Every language is bundled unless the crate is built with the
+/// `RECOYX_LOCALIZATION_LOCALES` environment variable, such as `en,pt-BR,fr`,
+/// which keeps only the data of the listed languages and English.
+/// `parse_locale()` fails for the languages that are not bundled.
+pub fn is_language_bundled(language: &str) -> bool {
+    LOCALE_BASIC_DATA.contains_key(&language.to_lowercase())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LocaleBasicData {
+    pub universal_name: String,
+    pub native_name: String,
+    pub direction: Direction
+}
+
+#[repr(u64)]
+#[derive(Copy, Clone, Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug)]
+pub enum Direction {
+    LeftToRight = 1,
+    RightToLeft = 0,
+}
+
+/// Scripts written from right to left.
+static RIGHT_TO_LEFT_SCRIPTS: &[&str] = &["Adlm", "Arab", "Hebr", "Mand", "Mend", "Nkoo", "Rohg", "Samr", "Syrc", "Thaa", "Yezi"];
+
+impl Direction {
+    /// Returns the direction of a script given by its ISO 15924 code, such as `Arab`.
+    pub fn of_script(script: &str) -> Direction {
+        if RIGHT_TO_LEFT_SCRIPTS.iter().any(|s| s.eq_ignore_ascii_case(script)) { Direction::RightToLeft } else { Direction::LeftToRight }
+    }
 }
\ No newline at end of file
@@ -1,548 +1,2843 @@
-use std::{cell::{Cell, RefCell}, collections::{HashMap, HashSet}, convert::TryInto, rc::Rc};
-use super::*;
-use super::pluralrules::{PluralCategory, PluralRuleType};
-use maplit::{hashmap, hashset};
-use lazy_static::lazy_static;
-use lazy_regex::regex;
-
-/// Gender enumeration. This enumeration can be used as a message formatting argument.
-#[derive(Copy, Clone)]
-pub enum Gender {
-    Male,
-    Female,
-    Other,
-}
-
-#[macro_export]
-/// Creates a `HashMap<String, String>` from a list of key-value pairs.
-/// This is based on the [`maplit`](https://github.com/bluss/maplit) crate.
-///
-/// ## Example
-///
-/// ```
-/// fn main() {
-///     let map = localization_vars!{
-///         "a" => "foo",
-///         "b" => "bar",
-///     };
-///     assert_eq!(map["a".to_string()], "foo");
-///     assert_eq!(map["b".to_string()], "bar");
-///     assert_eq!(map.get("c".to_string()), None);
-/// }
-/// ```
-macro_rules! localization_vars {
-    (@single $($x:tt)*) => (());
-    (@count $($rest:expr),*) => (<[()]>::len(&[$(localization_vars!(@single $rest)),*]));
-
-    ($($key:expr => $value:expr,)+) => { localization_vars!($($key => $value),+) };
-    ($($key:expr => $value:expr),*) => {
-        {
-            let _cap = localization_vars!(@count $($key),*);
-            let mut _map = ::std::collections::HashMap::<String, String>::with_capacity(_cap);
-            $(
-                let _ = _map.insert($key.to_string(), $value.to_string());
-            )*
-            _map
-        }
-    };
-}
-
-/// Flexible locale mapping with support for loading message resources,
-/// plural rule selection and relative-time formatting.
-pub struct LocaleMap {
-    _current_locale: Option<Locale>,
-    _current_ordinal_plural_rules: Option<intl_pluralrules::PluralRules>,
-    _current_cardinal_plural_rules: Option<intl_pluralrules::PluralRules>,
-    _current_relative_time_formatter: Option<Rc<super::RelativeTimeFormatter>>,
-    _locale_path_components: Rc<HashMap<Locale, String>>,
-    _supported_locales: Rc<HashSet<Locale>>,
-    _default_locale: Locale,
-    _fallbacks: Rc<HashMap<Locale, Vec<Locale>>>,
-    _assets: Rc<HashMap<Locale, serde_json::Value>>,
-    _assets_src: String,
-    _assets_base_file_names: Vec<String>,
-    _assets_auto_clean: bool,
-    _assets_loader_type: LocaleMapLoaderType,
-}
-
-impl LocaleMap {
-    /// Constructs a `LocaleMap` object.
-    pub fn new(options: &LocaleMapOptions) -> Self {
-        let mut locale_path_components = HashMap::<Locale, String>::new();
-        let mut supported_locales = HashSet::<Locale>::new();
-        for code in options._supported_locales.borrow().iter() {
-            let locale_parse = parse_locale(code).unwrap();
-            locale_path_components.insert(locale_parse.clone(), code.clone());
-            supported_locales.insert(locale_parse);
-        }
-        let mut fallbacks = HashMap::<Locale, Vec<Locale>>::new();
-        for (k, v) in options._fallbacks.borrow().iter() {
-            fallbacks.insert(parse_locale(k).unwrap(), v.iter().map(|s| parse_locale(s).unwrap()).collect());
-        }
-        let default_locale = options._default_locale.borrow().clone();
-        Self {
-            _current_locale: None,
-            _current_cardinal_plural_rules: None,
-            _current_ordinal_plural_rules: None,
-            _current_relative_time_formatter: None,
-            _locale_path_components: Rc::new(locale_path_components),
-            _supported_locales: Rc::new(supported_locales),
-            _default_locale: parse_locale(&default_locale).unwrap(),
-            _fallbacks: Rc::new(fallbacks),
-            _assets: Rc::new(HashMap::new()),
-            _assets_src: options._assets.borrow()._src.borrow().clone(),
-            _assets_base_file_names: options._assets.borrow()._base_file_names.borrow().iter().map(|s| s.clone()).collect(),
-            _assets_auto_clean: options._assets.borrow()._auto_clean.get(),
-            _assets_loader_type: options._assets.borrow()._loader_type.get(),
-        }
-    }
-
-    /// Returns a set of supported locale codes, reflecting
-    /// the ones that were specified when constructing the `LocaleMap`.
-    pub fn supported_locales(&self) -> HashSet<Locale> {
-        self._supported_locales.as_ref().clone()
-    }
-
-    /// Returns `true` if the locale is one of the supported locales
-    /// that were specified when constructing the `LocaleMap`,
-    /// otherwise `false`.
-    pub fn supports_locale(&self, arg: &Locale) -> bool {
-        self._supported_locales.contains(arg)
-    }
-
-    /// Returns the currently loaded locale.
-    pub fn current_locale(&self) -> Option<Locale> {
-        self._current_locale.clone()
-    }
-
-    /// Attempts to load the specified locale and its fallbacks.
-    /// If any resource fails to load, the method returns `false`, otherwise `true`.
-    pub async fn update_locale(&mut self, new_locale: Locale) -> bool {
-        self.load(Some(new_locale)).await
-    }
-
-    /// Attempts to load a locale and its fallbacks.
-    /// If the locale argument is specified, it is loaded.
-    /// Otherwise, if there is a default locale, it is loaded, and if not,
-    /// the method panics.
-    ///
-    /// If any resource fails to load, the method returns `false`, otherwise `true`.
-    pub async fn load(&mut self, mut new_locale: Option<Locale>) -> bool {
-        if new_locale.is_none() { new_locale = Some(self._default_locale.clone()); }
-        let new_locale = new_locale.unwrap();
-        if !self.supports_locale(&new_locale) {
-            panic!("Unsupported locale {}", new_locale.standard_tag());
-        }
-        let mut to_load: HashSet<Locale> = hashset![new_locale.clone()];
-        self.enumerate_fallbacks(new_locale.clone(), &mut to_load);
-
-        let mut new_assets: HashMap<Locale, serde_json::Value> = hashmap![];
-        for locale in to_load {
-            let res = self.load_single_locale(&locale).await;
-            if res.is_none() {
-                return false;
-            }
-            new_assets.insert(locale.clone(), res.unwrap());
-        }
-        if self._assets_auto_clean {
-            Rc::get_mut(&mut self._assets).unwrap().clear();
-        }
-
-        for (locale, root) in new_assets {
-            Rc::get_mut(&mut self._assets).unwrap().insert(locale, root);
-        }
-        self._current_locale = Some(new_locale.clone());
-        let new_locale_code = unic_langid::LanguageIdentifier::from_bytes(new_locale.clone().standard_tag().to_string().as_ref()).unwrap();
-        self._current_ordinal_plural_rules = self.load_plural_rules(new_locale_code.clone(), intl_pluralrules::PluralRuleType::ORDINAL);
-        self._current_cardinal_plural_rules = self.load_plural_rules(new_locale_code.clone(), intl_pluralrules::PluralRuleType::CARDINAL);
-        self._current_relative_time_formatter = None;
-
-        let new_isolang_lang = isolang::Language::from_639_1(new_locale_code.clone().language.as_str()).unwrap();
-        let new_timeago_lang = timeago::from_isolang(new_isolang_lang);
-
-        if let Some(l) = new_timeago_lang {
-            self._current_relative_time_formatter = Some(Rc::new(timeago::Formatter::with_language(l)));
-        }
-
-        if self._current_relative_time_formatter.is_none() {
-            self._current_relative_time_formatter = Some(Rc::new(timeago::Formatter::with_language(Box::new(timeago::languages::english::English))));
-        }
-
-        true
-    }
-
-    fn load_plural_rules(&self, new_locale_code: unic_langid::LanguageIdentifier, prt: intl_pluralrules::PluralRuleType) -> Option<intl_pluralrules::PluralRules> {
-        if let Ok(pr) = intl_pluralrules::PluralRules::create(new_locale_code.clone(), prt) {
-            Some(pr)
-        }
-        else if let Ok(pr) = intl_pluralrules::PluralRules::create(unic_langid::LanguageIdentifier::from_parts(new_locale_code.language, None, None, &[]), prt) {
-            Some(pr)
-        }
-        else {
-            Some(intl_pluralrules::PluralRules::create(unic_langid::LanguageIdentifier::from_parts(unic_langid::subtags::Language::from_bytes(&"en".as_ref()).unwrap(), None, None, &[]), prt).unwrap())
-        }
-    }
-
-    async fn load_single_locale(&self, locale: &Locale) -> Option<serde_json::Value> {
-        let mut r = serde_json::Value::Object(serde_json::Map::new());
-        match self._assets_loader_type {
-            LocaleMapLoaderType::FileSystem => {
-                for base_name in self._assets_base_file_names.iter() {
-                    let locale_path_comp = self._locale_path_components.get(locale);
-                    if locale_path_comp.is_none() {
-                        panic!("Fallback locale is not supported a locale: {}", locale.standard_tag().to_string());
-                    }
-                    let res_path = format!("{}/{}/{}.json", self._assets_src, locale_path_comp.unwrap(), base_name);
-                    let content = std::fs::read(res_path.clone());
-                    if content.is_err() {
-                        println!("Failed to load resource at {}.", res_path);
-                        return None;
-                    }
-                    LocaleMap::apply_deep(base_name, serde_json::from_str(String::from_utf8(content.unwrap()).unwrap().as_ref()).unwrap(), &mut r);
-                }
-            },
-            LocaleMapLoaderType::Http => {
-                for base_name in self._assets_base_file_names.iter() {
-                    let locale_path_comp = self._locale_path_components.get(locale);
-                    if locale_path_comp.is_none() {
-                        panic!("Fallback locale is not supported a locale: {}", locale.standard_tag().to_string());
-                    }
-                    let res_path = format!("{}/{}/{}.json", self._assets_src, locale_path_comp.unwrap(), base_name);
-                    let content = reqwest::get(reqwest::Url::parse(res_path.clone().as_ref()).unwrap()).await;
-                    if content.is_err() {
-                        println!("Failed to load resource at {}.", res_path);
-                        return None;
-                    }
-                    let content = if content.is_ok() { Some(content.unwrap().text().await) } else { None };
-                    LocaleMap::apply_deep(base_name, serde_json::from_str(content.unwrap().unwrap().as_ref()).unwrap(), &mut r);
-                }
-            },
-        }
-        Some(r)
-    }
-
-    fn apply_deep(name: &String, assign: serde_json::Value, mut output: &mut serde_json::Value) {
-        let mut names: Vec<&str> = name.split("/").collect();
-        let last_name = names.pop();
-        for name in names {
-            let r = output.get(name);
-            if r.is_none() || r.unwrap().as_object().is_none() {
-                let r = serde_json::Value::Object(serde_json::Map::new());
-                output.as_object_mut().unwrap().insert(String::from(name), r);
-            }
-            output = output.get_mut(name).unwrap();
-        }
-        output.as_object_mut().unwrap().insert(String::from(last_name.unwrap()), assign);
-    }
-
-    fn enumerate_fallbacks(&self, locale: Locale, output: &mut HashSet<Locale>) {
-        for list in self._fallbacks.get(&locale).iter() {
-            for item in list.iter() {
-                output.insert(item.clone());
-                self.enumerate_fallbacks(item.clone(), output);
-            }
-        }
-    }
-
-    /// Retrieves message by identifier.
-    pub fn get<S: ToString>(&self, id: S) -> String {
-        self.get_formatted(id, vec![])
-    }
-
-    /// Retrieves message by identifier with formatting arguments.
-    pub fn get_formatted<S: ToString>(&self, id: S, options: Vec<&dyn LocaleMapFormatArgument>) -> String {
-        let mut variables: Option<HashMap<String, String>> = None;
-        let mut gender: Option<Gender> = None;
-        let mut amount_u64: Option<u64> = None;
-        let mut amount_i64: Option<i64> = None;
-        let mut amount_u128: Option<u128> = None;
-        let mut amount_i128: Option<i128> = None;
-        let mut amount_f64: Option<f64> = None;
-
-        for option in options.iter() {
-            if let Some(r) = option.as_gender() {
-                gender = Some(r);
-            }
-            else if let Some(r) = option.as_string_map() {
-                variables = Some(r.iter().map(|(k, v)| (k.clone(), v.clone())).collect());
-            }
-            else if let Some(r) = option.as_i64() { amount_i64 = Some(r) }
-            else if let Some(r) = option.as_u64() { amount_u64 = Some(r) }
-            else if let Some(r) = option.as_i128() { amount_i128 = Some(r) }
-            else if let Some(r) = option.as_u128() { amount_u128 = Some(r) }
-            else if let Some(r) = option.as_f64() { amount_f64 = Some(r) }
-        }
-
-        let mut id = id.to_string();
-        if let Some(g) = gender {
-            match g {
-                Gender::Male => { id.push_str("_male"); },
-                Gender::Female => { id.push_str("_female"); },
-                Gender::Other => { id.push_str("_other"); }
-            }
-        }
-
-        if variables.is_none() { variables = Some(HashMap::new()); }
-        let mut variables = variables.unwrap();
-
-        // id_empty, id_one, id_multiple and $number variable
-        if let Some(qty) = amount_u64 { id.push_str( if qty == 0 { "_empty" } else if qty == 1 { "_one" } else { "_multiple" } ); variables.insert("number".to_string(), qty.to_string()); }
-        else if let Some(qty) = amount_i64 { id.push_str( if qty == 0 { "_empty" } else if qty == 1 { "_one" } else { "_multiple" } ); variables.insert("number".to_string(), qty.to_string()); }
-        else if let Some(qty) = amount_u128 { id.push_str( if qty == 0 { "_empty" } else if qty == 1 { "_one" } else { "_multiple" } ); variables.insert("number".to_string(), qty.to_string()); }
-        else if let Some(qty) = amount_i128 { id.push_str( if qty == 0 { "_empty" } else if qty == 1 { "_one" } else { "_multiple" } ); variables.insert("number".to_string(), qty.to_string()); }
-        else if let Some(qty) = amount_f64 { id.push_str( if qty == 0.0 { "_empty" } else if qty == 1.0 { "_one" } else { "_multiple" } ); variables.insert("number".to_string(), qty.to_string()); }
-
-        let id: Vec<String> = id.split(".").map(|s| s.to_string()).collect();
-        if self._current_locale.is_none() {
-            return id.join(".");
-        }
-        let r = self.get_formatted_with_locale(self._current_locale.clone().unwrap(), &id, &variables);
-        if let Some(r) = r { r } else { id.join(".") }
-    }
-
-    fn get_formatted_with_locale(&self, locale: Locale, id: &Vec<String>, vars: &HashMap<String, String>) -> Option<String> {
-        let message = self.resolve_id(self._assets.get(&locale), id);
-        if message.is_some() {
-            return Some(self.apply_message(message.unwrap(), vars));
-        }
-
-        let fallbacks = self._fallbacks.get(&locale);
-        if fallbacks.is_some() {
-            for fl in fallbacks.unwrap().iter() {
-                let r = self.get_formatted_with_locale(fl.clone(), id, vars);
-                if r.is_some() {
-                    return r;
-                }
-            }
-        }
-        None
-    }
-
-    fn apply_message(&self, message: String, vars: &HashMap<String, String>) -> String {
-        // regex!(r"\$(\$|[A-Za-z0-9_-]+)").replace_all(&message, R { _vars: vars }).as_ref().to_string()
-        regex!(r"\$(\$|[A-Za-z0-9_-]+)").replace_all(&message, |s: &regex::Captures<'_>| {
-            let s = s.get(0).unwrap().as_str();
-            if s == "$$" {
-                "$"
-            } else {
-                let v = vars.get(&s.to_string().replace("$", ""));
-                if let Some(v) = v { v } else { "undefined" }
-            }
-        }).as_ref().to_string()
-    }
-
-    fn resolve_id(&self, root: Option<&serde_json::Value>, id: &Vec<String>) -> Option<String> {
-        let mut r = root;
-        for frag in id.iter() {
-            if r.is_none() {
-                return None;
-            }
-            r = r.unwrap().get(frag);
-        }
-        if r.is_none() {
-            return None;
-        }
-        let r = r.unwrap().as_str();
-        if let Some(r) = r { Some(r.to_string()) } else { None }
-    }
-
-    /// Selects the plural rule given a `PluralRuleType` and a number.
-    pub fn select_plural_rule<N: TryInto<super::PluralOperands>>(&self, prt: PluralRuleType, number: N) -> Result<PluralCategory, &'static str> {
-        if prt == PluralRuleType::ORDINAL {
-            if let Some(pr) = self._current_ordinal_plural_rules.clone() {
-                pr.select::<N>(number)
-            }
-            else {
-                Err(&"Plural rules missing.")
-            }
-        }
-        else {
-            if let Some(pr) = self._current_cardinal_plural_rules.clone() {
-                pr.select::<N>(number)
-            }
-            else {
-                Err(&"Plural rules missing.")
-            }
-        }
-    }
-
-    /// Creates a relative-time formatter, which by default
-    /// emits one item (chunk), limits to seconds and has no maximum duration.
-    pub fn create_relative_time_formatter(&self) -> super::RelativeTimeFormatter {
-        if self._current_relative_time_formatter.is_none() {
-            panic!("No locale has been loaded.");
-        }
-        self._current_relative_time_formatter.clone().unwrap().as_ref().clone()
-    }
-
-    /// Formats a duration into relative-time language, emitting one item.
-    pub fn format_relative_time(&self, duration: std::time::Duration) -> String {
-        self.create_relative_time_formatter().convert(duration)
-    }
-}
-
-impl Clone for LocaleMap {
-    fn clone(&self) -> Self {
-        Self {
-            _current_locale: self._current_locale.clone(),
-            _current_cardinal_plural_rules: self._current_cardinal_plural_rules.clone(),
-            _current_ordinal_plural_rules: self._current_ordinal_plural_rules.clone(),
-            _current_relative_time_formatter: self._current_relative_time_formatter.clone(),
-            _locale_path_components: self._locale_path_components.clone(),
-            _supported_locales: self._supported_locales.clone(),
-            _default_locale: self._default_locale.clone(),
-            _fallbacks: self._fallbacks.clone(),
-            _assets: self._assets.clone(),
-            _assets_src: self._assets_src.clone(),
-            _assets_base_file_names: self._assets_base_file_names.clone(),
-            _assets_auto_clean: self._assets_auto_clean,
-            _assets_loader_type: self._assets_loader_type,
-        }
-    }
-}
-
-pub trait LocaleMapFormatArgument {
-    fn as_gender(&self) -> Option<Gender> { None }
-    fn as_f64(&self) -> Option<f64> { None }
-    fn as_i64(&self) -> Option<i64> { None }
-    fn as_u64(&self) -> Option<u64> { None }
-    fn as_i128(&self) -> Option<i128> { None }
-    fn as_u128(&self) -> Option<u128> { None }
-    fn as_string_map(&self) -> Option<HashMap<String, String>> { None }
-}
-
-impl LocaleMapFormatArgument for Gender {
-    fn as_gender(&self) -> Option<Gender> { Some(*self) }
-}
-
-impl LocaleMapFormatArgument for f32 {
-    fn as_f64(&self) -> Option<f64> { Some(f64::from(*self)) }
-}
-
-impl LocaleMapFormatArgument for f64 {
-    fn as_f64(&self) -> Option<f64> { Some(*self) }
-}
-
-impl LocaleMapFormatArgument for i32 {
-    fn as_i64(&self) -> Option<i64> { Some(i64::from(*self)) }
-}
-
-impl LocaleMapFormatArgument for u32 {
-    fn as_u64(&self) -> Option<u64> { Some(u64::from(*self)) }
-}
-
-impl LocaleMapFormatArgument for i64 {
-    fn as_i64(&self) -> Option<i64> { Some(*self) }
-}
-
-impl LocaleMapFormatArgument for u64 {
-    fn as_u64(&self) -> Option<u64> { Some(*self) }
-}
-
-impl LocaleMapFormatArgument for i128 {
-    fn as_i128(&self) -> Option<i128> { Some(*self) }
-}
-
-impl LocaleMapFormatArgument for u128 {
-    fn as_u128(&self) -> Option<u128> { Some(*self) }
-}
-
-impl LocaleMapFormatArgument for HashMap<String, String> {
-    fn as_string_map(&self) -> Option<HashMap<String, String>> { Some(self.clone()) }
-}
-
-pub struct LocaleMapOptions {
-    _default_locale: RefCell<String>,
-    _supported_locales: RefCell<Vec<String>>,
-    _fallbacks: RefCell<HashMap<String, Vec<String>>>,
-    _assets: RefCell<LocaleMapAssetOptions>,
-}
-
-impl LocaleMapOptions {
-    pub fn new() -> Self {
-        LocaleMapOptions {
-            _default_locale: RefCell::new("en".to_string()),
-            _supported_locales: RefCell::new(vec!["en".to_string()]),
-            _fallbacks: RefCell::new(hashmap! {}),
-            _assets: RefCell::new(LocaleMapAssetOptions::new()),
-        }
-    }
-
-    pub fn default_locale<S: ToString>(&self, value: S) -> &Self {
-        self._default_locale.replace(value.to_string());
-        self
-    }
-
-    pub fn supported_locales<S: ToString>(&self, list: Vec<S>) -> &Self {
-        self._supported_locales.replace(list.iter().map(|name| name.to_string()).collect());
-        self
-    }
-
-    pub fn fallbacks<S: ToString>(&self, map: HashMap<S, Vec<S>>) -> &Self {
-        self._fallbacks.replace(map.iter().map(|(k, v)| (
-            k.to_string(),
-            v.iter().map(|s| s.to_string()).collect()
-        )).collect());
-        self
-    }
-
-    pub fn assets(&self, options: &LocaleMapAssetOptions) -> &Self {
-        self._assets.replace(options.clone());
-        self
-    }
-}
-
-pub struct LocaleMapAssetOptions {
-    _src: RefCell<String>,
-    _base_file_names: RefCell<Vec<String>>,
-    _auto_clean: Cell<bool>,
-    _loader_type: Cell<LocaleMapLoaderType>,
-}
-
-impl Clone for LocaleMapAssetOptions {
-    fn clone(&self) -> Self {
-        Self {
-            _src: self._src.clone(),
-            _base_file_names: self._base_file_names.clone(),
-            _auto_clean: self._auto_clean.clone(),
-            _loader_type: self._loader_type.clone(),
-        }
-    }
-}
-
-impl LocaleMapAssetOptions {
-    pub fn new() -> Self {
-        LocaleMapAssetOptions {
-            _src: RefCell::new("res/lang".to_string()),
-            _base_file_names: RefCell::new(vec![]),
-            _auto_clean: Cell::new(true),
-            _loader_type: Cell::new(LocaleMapLoaderType::Http),
-        }
-    }
-    
-    pub fn src<S: ToString>(&self, src: S) -> &Self {
-        self._src.replace(src.to_string());
-        self
-    } 
-
-    pub fn base_file_names<S: ToString>(&self, list: Vec<S>) -> &Self {
-        self._base_file_names.replace(list.iter().map(|name| name.to_string()).collect());
-        self
-    }
-
-    pub fn auto_clean(&self, value: bool) -> &Self {
-        self._auto_clean.set(value);
-        self
-    }
-
-    pub fn loader_type(&self, value: LocaleMapLoaderType) -> &Self {
-        self._loader_type.set(value);
-        self
-    }
-}
-
-#[derive(Copy, Clone)]
-pub enum LocaleMapLoaderType {
-    FileSystem,
-    Http,
-}
\ No newline at end of file
+use std::{cell::{Cell, RefCell}, collections::{HashMap, HashSet, hash_map::DefaultHasher}, convert::TryInto, hash::{Hash, Hasher}, rc::Rc, sync::Arc};
+use super::*;
+use super::pluralrules::{PluralCategory, PluralRuleType};
+use super::formatted_parts::{push_part, push_segment};
+use maplit::{hashmap, hashset};
+
+/// Quantity of a named message variable, used as a message formatting argument
+/// so that a message can pluralize over several quantities. The variable is replaced
+/// by the number and, as `${name|plural}`, by the message at the identifier followed by
+/// `_` and the name and the quantity suffix, such as `cart.summary_items_one`, in which
+/// `$number` is the number. `${name|plural:id}` uses the message at `id` followed by
+/// the suffix instead, such as `${files|plural:common.files}` for `common.files_multiple`.
+///
+/// ```
+/// use recoyx_localization::Count;
+///
+/// let items = Count::new("items", 3);
+/// assert_eq!(items.name(), "items");
+/// ```
+#[derive(Clone)]
+pub struct Count {
+    name: String,
+    number: String,
+}
+
+impl Count {
+    pub fn new<S: ToString, N: ToString>(name: S, number: N) -> Self {
+        Self { name: name.to_string(), number: number.to_string() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+}
+
+/// Gender of a named message variable, used as a message formatting argument so that
+/// a message can select words per person, such as `"${sender|gender} sent ${recipient|gender} a gift"`.
+/// `${name|gender}` is replaced by the message at the identifier followed by `_` and the name
+/// and the gender suffix, such as `gift.sent_recipient_male` for `"him"`, which may use the
+/// variables of the message, including `$name` if it is given. `${name|gender:id}` uses the
+/// message at `id` followed by the suffix instead, such as `${recipient|gender:pronoun.object}`
+/// for `pronoun.object_female`. The gender is not a value of the variable, so without
+/// such a message the variable is replaced by its value, or by `undefined` if it has none.
+///
+/// ```
+/// use recoyx_localization::{Gender, GenderOf};
+///
+/// let recipient = GenderOf("recipient", Gender::Female);
+/// ```
+#[derive(Copy, Clone)]
+pub struct GenderOf<S: ToString>(pub S, pub Gender);
+
+#[macro_export]
+/// Creates a `HashMap<String, String>` from a list of key-value pairs.
+/// This is based on the [`maplit`](https://github.com/bluss/maplit) crate.
+///
+/// ## Example
+///
+/// ```
+/// use recoyx_localization::localization_vars;
+///
+/// let map = localization_vars!{
+///     "a" => "foo",
+///     "b" => "bar",
+/// };
+/// assert_eq!(map["a"], "foo");
+/// assert_eq!(map["b"], "bar");
+/// assert_eq!(map.get("c"), None);
+/// ```
+macro_rules! localization_vars {
+    (@single $($x:tt)*) => (());
+    (@count $($rest:expr),*) => (<[()]>::len(&[$(localization_vars!(@single $rest)),*]));
+
+    ($($key:expr => $value:expr,)+) => { localization_vars!($($key => $value),+) };
+    ($($key:expr => $value:expr),*) => {
+        {
+            let _cap = localization_vars!(@count $($key),*);
+            let mut _map = ::std::collections::HashMap::<String, String>::with_capacity(_cap);
+            $(
+                let _ = _map.insert($key.to_string(), $value.to_string());
+            )*
+            _map
+        }
+    };
+}
+
+type UnsupportedFormatterCallback = Rc<dyn Fn(&Locale, &FormatterSupport)>;
+type DiagnosticCallback = Rc<dyn Fn(&Diagnostic)>;
+type MessageFormatter = Rc<dyn Fn(&str, &Locale, Option<&str>) -> String>;
+type VariantSelector = Rc<dyn Fn(&str) -> Option<String>>;
+/// Debug rendering of messages that reveals their identifiers, so that testers can tell
+/// which message produced a text on screen; see `LocaleMap::set_key_reveal()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeyReveal {
+    /// Prefixes messages with their identifier, as in `[common.qty] 3 items`.
+    Prefix,
+    /// Replaces messages with their identifier.
+    Replace,
+}
+
+type SharedMetricsSink = Rc<dyn MetricsSink>;
+type SharedMissingTranslationProvider = Rc<dyn MissingTranslationProvider>;
+type SharedLocalePreferenceStore = Rc<dyn LocalePreferenceStore>;
+
+/// Message resolved from a locale or its fallbacks and split into its parts,
+/// cached by `LocaleMap::compiled_message()`.
+struct CompiledMessage {
+    parts: Vec<MessagePart>,
+    /// The locale whose assets provided the message.
+    locale: Locale,
+    /// The number of fallbacks followed from the requested locale.
+    depth: usize,
+    /// Whether the message is a machine translation from a `MissingTranslationProvider`.
+    machine_translated: bool,
+}
+
+type MessageCache = HashMap<Locale, HashMap<String, Option<Rc<CompiledMessage>>>>;
+
+/// Maximum number of missing messages cached, beyond which they are evicted, so that
+/// looking up arbitrary identifiers, such as ones taken from requests, does not
+/// grow the cache without bound.
+const MAX_CACHED_MISSES: usize = 1024;
+
+/// Resolves the messages selected by `${name|plural}` and `${name|gender}` in the
+/// locale that provided the message containing them, applying registered formatters.
+struct CompiledMessages<'a>(&'a LocaleMap);
+
+impl MessageResolver for CompiledMessages<'_> {
+    type Message = Rc<CompiledMessage>;
+
+    fn parts<'m>(&self, message: &'m Self::Message) -> &'m [MessagePart] {
+        &message.parts
+    }
+
+    fn contains(&self, within: &Self::Message, id: &str) -> bool {
+        self.0.has_message(&within.locale, id)
+    }
+
+    fn message(&self, within: &Self::Message, id: &str) -> Option<Self::Message> {
+        self.0.compiled_message(&within.locale, id)
+    }
+
+    fn format_value(&self, _message: &Self::Message, formatter: &MessageFormatterCall, value: &str) -> Option<String> {
+        let f = self.0._formatters.borrow().get(&formatter.name).cloned()?;
+        Some(f(value, &self.0.formatting_locale(), formatter.argument.as_deref()))
+    }
+}
+
+/// Output of a message split into literal text and the values of variables.
+#[derive(Default)]
+struct FormattedParts(Vec<FormattedPart>);
+
+impl MessageWriter<Rc<CompiledMessage>> for FormattedParts {
+    fn write_text(&mut self, _message: &Rc<CompiledMessage>, text: &str) {
+        push_part(&mut self.0, FormattedPart::Literal(text.to_string()));
+    }
+
+    fn write_value(&mut self, _message: &Rc<CompiledMessage>, name: &str, value: String, kind: Option<ValueKind>, _language: Option<&str>) {
+        push_part(&mut self.0, FormattedPart::of_variable(name, value, kind));
+    }
+
+    fn append(&mut self, output: Self) {
+        output.0.into_iter().for_each(|part| push_part(&mut self.0, part));
+    }
+
+    fn text(&self) -> String {
+        self.0.iter().map(FormattedPart::value).collect()
+    }
+}
+
+/// Output of a message split into segments attributed the language of the locale
+/// that provided their text, or the language marked by `${name|lang:fr}`.
+#[derive(Default)]
+struct AttributedSegments(Vec<AttributedSegment>);
+
+impl MessageWriter<Rc<CompiledMessage>> for AttributedSegments {
+    fn write_text(&mut self, message: &Rc<CompiledMessage>, text: &str) {
+        let part = FormattedPart::Literal(text.to_string());
+        push_segment(&mut self.0, AttributedSegment { part, language: message.locale.standard_tag().to_string(), machine_translated: message.machine_translated });
+    }
+
+    fn write_value(&mut self, message: &Rc<CompiledMessage>, name: &str, value: String, kind: Option<ValueKind>, language: Option<&str>) {
+        let language = language.map(|l| l.to_string()).unwrap_or_else(|| message.locale.standard_tag().to_string());
+        push_segment(&mut self.0, AttributedSegment { part: FormattedPart::of_variable(name, value, kind), language, machine_translated: false });
+    }
+
+    fn append(&mut self, output: Self) {
+        output.0.into_iter().for_each(|segment| push_segment(&mut self.0, segment));
+    }
+
+    fn text(&self) -> String {
+        self.0.iter().map(|segment| segment.part.value()).collect()
+    }
+}
+
+/// Assets of a locale read by `LocaleMap::load_single_locale()`.
+pub(crate) struct LoadedAssets {
+    pub(crate) root: MessageTree,
+    /// Fingerprint of each asset file of the locale.
+    versions: HashMap<String, u64>,
+    /// If the assets are layered, the assets the locale's files override.
+    base: Option<serde_json::Value>,
+    /// Messages of each overlay that has asset files for the locale.
+    overlays: HashMap<String, MessageTree>,
+}
+
+/// Configuration of a `LocaleMap` that does not change as locales are loaded: the
+/// supported locales, the fallbacks, the asset options and the formatting preferences.
+/// It is immutable and shared through an `Arc`, including across threads, so that
+/// several `LocaleMap`s, such as one per request or per window, can use the same
+/// configuration while each owns a `LocaleSession`.
+///
+/// ```
+/// use std::sync::Arc;
+/// use recoyx_localization::{LocaleMap, LocaleMapConfig, LocaleMapOptions};
+///
+/// let config = Arc::new(LocaleMapConfig::new(LocaleMapOptions::new().supported_locales(vec!["en", "pt-BR"])));
+/// let a = LocaleMap::with_config(config.clone());
+/// let b = LocaleMap::with_config(config);
+/// assert!(Arc::ptr_eq(a.config(), b.config()));
+/// ```
+#[derive(Clone)]
+pub struct LocaleMapConfig {
+    pub(crate) _locale_path_components: HashMap<Locale, String>,
+    _supported_locales: HashSet<Locale>,
+    pub(crate) _default_locale: Locale,
+    _fallbacks: HashMap<Locale, Vec<Locale>>,
+    pub(crate) _assets_src: String,
+    pub(crate) _assets_base_file_names: Vec<String>,
+    _assets_auto_clean: bool,
+    _assets_layered: bool,
+    _assets_overlays: Vec<String>,
+    pub(crate) _assets_loader_type: LocaleMapLoaderType,
+    pub(crate) _assets_format: LocaleMapAssetFormat,
+    #[cfg(feature = "compression")]
+    _assets_compress_in_memory: bool,
+    #[cfg(feature = "integrity")]
+    _assets_integrity: Option<AssetIntegrity>,
+    _numbering_system: Option<NumberingSystem>,
+    _hour_cycle: Option<HourCycle>,
+    _placeholder_check: Option<DiagnosticSeverity>,
+    pub(crate) _normalization: Option<NormalizationForm>,
+    pub(crate) _text_widths: TextWidthTable,
+    pub(crate) _audit_variants: bool,
+    _format_overrides: HashMap<String, FormatOverrides>,
+    _formatting_locale: Option<Locale>,
+}
+
+impl LocaleMapConfig {
+    /// Constructs a `LocaleMapConfig` from options. The callbacks of the options,
+    /// such as `on_diagnostic()` and `metrics()`, are not part of the configuration;
+    /// they are kept by the `LocaleMap` constructed through `LocaleMap::new()`.
+    ///
+    /// Invalid options are reported as warnings and ignored: invalid locales and
+    /// fallbacks that `try_new()` would reject are dropped, an invalid default locale
+    /// is replaced by `en` and an unknown numbering system or formatting locale is
+    /// not applied.
+    pub fn new(options: &LocaleMapOptions) -> Self {
+        LocaleMapConfig::from_options(options, false).unwrap()
+    }
+
+    /// Constructs a `LocaleMapConfig` from options, returning an error naming the option
+    /// if a locale or the numbering system is invalid, or the fallbacks refer to locales
+    /// that are not supported, make a locale fall back to itself or form a cycle,
+    /// such as `pt-BR` → `en` → `pt-BR`.
+    pub fn try_new(options: &LocaleMapOptions) -> Result<Self, String> {
+        LocaleMapConfig::from_options(options, true)
+    }
+
+    /// Constructs a `LocaleMapConfig` from options, returning the first invalid option
+    /// as an error if `strict`, and otherwise reporting and ignoring each of them.
+    fn from_options(options: &LocaleMapOptions, strict: bool) -> Result<Self, String> {
+        let invalid = |message: String| -> Result<(), String> {
+            if strict {
+                return Err(message);
+            }
+            LocaleMap::report_load_failure(format!("Invalid LocaleMapOptions, ignored: {}", message));
+            Ok(())
+        };
+        let mut locale_path_components = HashMap::<Locale, String>::new();
+        let mut supported_locales = HashSet::<Locale>::new();
+        for (i, code) in options._supported_locales.borrow().iter().enumerate() {
+            match parse_locale(code) {
+                Ok(locale_parse) => {
+                    locale_path_components.insert(locale_parse.clone(), code.clone());
+                    supported_locales.insert(locale_parse);
+                },
+                Err(_) => invalid(format!("supported_locales[{}]: invalid locale {:?}", i, code))?,
+            }
+        }
+        let mut fallbacks = HashMap::<Locale, Vec<Locale>>::new();
+        let mut fallback_entries: Vec<_> = options._fallbacks.borrow().clone().into_iter().collect();
+        fallback_entries.sort();
+        for (k, v) in fallback_entries {
+            let locale = match parse_locale(&k) {
+                Ok(locale) => locale,
+                Err(_) => { invalid(format!("fallbacks: invalid locale {:?}", k))?; continue; },
+            };
+            let mut list = vec![];
+            for s in v.iter() {
+                match parse_locale(s) {
+                    Ok(fallback) => list.push(fallback),
+                    Err(_) => invalid(format!("fallbacks.{}: invalid locale {:?}", k, s))?,
+                }
+            }
+            fallbacks.insert(locale, list);
+        }
+        if strict {
+            check_fallbacks(&fallbacks, &supported_locales)?;
+        } else {
+            for message in remove_invalid_fallbacks(&mut fallbacks, &supported_locales) {
+                invalid(message)?;
+            }
+        }
+        let default_locale = options._default_locale.borrow().clone();
+        let default_locale = match parse_locale(&default_locale) {
+            Ok(locale) => locale,
+            Err(_) => {
+                invalid(format!("default_locale: invalid locale {:?}", default_locale))?;
+                parse_locale("en").unwrap()
+            },
+        };
+        let mut numbering_system = None;
+        if let Some(s) = options._numbering_system.borrow().as_ref() {
+            match parse_numbering_system(s) {
+                Ok(parsed) => numbering_system = Some(parsed),
+                Err(_) => invalid(format!("numbering_system: unknown numbering system {:?}", s))?,
+            }
+        }
+        let mut formatting_locale = None;
+        if let Some(s) = options._formatting_locale.borrow().as_ref() {
+            match parse_locale(s) {
+                Ok(parsed) => formatting_locale = Some(parsed),
+                Err(_) => invalid(format!("formatting_locale: invalid locale {:?}", s))?,
+            }
+        }
+        Ok(Self {
+            _locale_path_components: locale_path_components,
+            _supported_locales: supported_locales,
+            _default_locale: default_locale,
+            _fallbacks: fallbacks,
+            _assets_src: options._assets.borrow()._src.borrow().clone(),
+            _assets_base_file_names: options._assets.borrow()._base_file_names.borrow().to_vec(),
+            _assets_auto_clean: options._assets.borrow()._auto_clean.get(),
+            _assets_layered: options._assets.borrow()._layered.get(),
+            _assets_overlays: options._assets.borrow()._overlays.borrow().to_vec(),
+            _assets_loader_type: options._assets.borrow()._loader_type.get(),
+            _assets_format: options._assets.borrow()._format.get(),
+            #[cfg(feature = "compression")]
+            _assets_compress_in_memory: options._assets.borrow()._compress_in_memory.get(),
+            #[cfg(feature = "integrity")]
+            _assets_integrity: options._assets.borrow()._integrity.borrow().clone(),
+            _numbering_system: numbering_system,
+            _hour_cycle: options._hour_cycle.get(),
+            _placeholder_check: options._placeholder_check.get(),
+            _normalization: options._normalization.get(),
+            _text_widths: options._text_widths.borrow().clone(),
+            _audit_variants: options._audit_variants.get(),
+            _format_overrides: options._format_overrides.borrow().clone(),
+            _formatting_locale: formatting_locale,
+        })
+    }
+
+    /// Returns the default locale.
+    pub fn default_locale(&self) -> &Locale {
+        &self._default_locale
+    }
+
+    /// Returns the supported locales.
+    pub fn supported_locales(&self) -> &HashSet<Locale> {
+        &self._supported_locales
+    }
+
+    /// Returns `true` if the locale is one of the supported locales.
+    pub fn supports_locale(&self, locale: &Locale) -> bool {
+        self._supported_locales.contains(locale)
+    }
+
+    /// Returns the locales a locale falls back to, in order.
+    pub fn fallbacks(&self, locale: &Locale) -> &[Locale] {
+        self._fallbacks.get(locale).map(|l| l.as_slice()).unwrap_or(&[])
+    }
+}
+
+/// Reports fallbacks of locales that are not supported, locales falling back to themselves
+/// and cycles, such as `pt-BR` → `en` → `pt-BR`, with which loading would never end.
+pub(crate) fn check_fallbacks(fallbacks: &HashMap<Locale, Vec<Locale>>, supported_locales: &HashSet<Locale>) -> Result<(), String> {
+    let mut locales: Vec<&Locale> = fallbacks.keys().collect();
+    locales.sort();
+    for locale in locales.iter() {
+        let tag = locale.standard_tag().to_string();
+        if !supported_locales.contains(locale) {
+            return Err(format!("fallbacks: {:?} is not among the supported locales", tag));
+        }
+        for fallback in fallbacks[*locale].iter() {
+            if fallback == *locale {
+                return Err(format!("fallbacks.{}: {:?} falls back to itself", tag, tag));
+            }
+            if !supported_locales.contains(fallback) {
+                return Err(format!("fallbacks.{}: {:?} is not among the supported locales", tag, fallback.standard_tag().to_string()));
+            }
+        }
+    }
+
+    fn visit<'a>(locale: &'a Locale, fallbacks: &'a HashMap<Locale, Vec<Locale>>, path: &mut Vec<&'a Locale>, done: &mut HashSet<&'a Locale>) -> Result<(), String> {
+        if let Some(start) = path.iter().position(|l| *l == locale) {
+            let cycle: Vec<String> = path[start..].iter().chain(std::iter::once(&locale)).map(|l| l.standard_tag().to_string()).collect();
+            return Err(format!("fallbacks: cycle {}", cycle.join(" -> ")));
+        }
+        if done.contains(locale) {
+            return Ok(());
+        }
+        path.push(locale);
+        for fallback in fallbacks.get(locale).into_iter().flatten() {
+            visit(fallback, fallbacks, path, done)?;
+        }
+        path.pop();
+        done.insert(locale);
+        Ok(())
+    }
+    let mut done = HashSet::new();
+    for locale in locales {
+        visit(locale, fallbacks, &mut vec![], &mut done)?;
+    }
+    Ok(())
+}
+
+/// Removes the fallbacks that `check_fallbacks()` would report: those of locales
+/// that are not supported, fallbacks to locales that are not supported or to the
+/// locale itself, and fallbacks closing a cycle, returning the reason for each.
+fn remove_invalid_fallbacks(fallbacks: &mut HashMap<Locale, Vec<Locale>>, supported_locales: &HashSet<Locale>) -> Vec<String> {
+    fn reaches(from: &Locale, to: &Locale, fallbacks: &HashMap<Locale, Vec<Locale>>, visited: &mut HashSet<Locale>) -> bool {
+        if from == to {
+            return true;
+        }
+        if !visited.insert(from.clone()) {
+            return false;
+        }
+        fallbacks.get(from).into_iter().flatten().any(|next| reaches(next, to, fallbacks, visited))
+    }
+    let mut r = vec![];
+    let mut locales: Vec<Locale> = fallbacks.keys().cloned().collect();
+    locales.sort();
+    let mut valid = HashMap::<Locale, Vec<Locale>>::new();
+    for locale in locales {
+        let tag = locale.standard_tag().to_string();
+        if !supported_locales.contains(&locale) {
+            r.push(format!("fallbacks: {:?} is not among the supported locales", tag));
+            continue;
+        }
+        for fallback in fallbacks[&locale].iter() {
+            if *fallback == locale {
+                r.push(format!("fallbacks.{}: {:?} falls back to itself", tag, tag));
+            } else if !supported_locales.contains(fallback) {
+                r.push(format!("fallbacks.{}: {:?} is not among the supported locales", tag, fallback.standard_tag().to_string()));
+            } else if reaches(fallback, &locale, &valid, &mut HashSet::new()) {
+                r.push(format!("fallbacks.{}: {:?} forms a cycle", tag, fallback.standard_tag().to_string()));
+            } else {
+                valid.entry(locale.clone()).or_default().push(fallback.clone());
+            }
+        }
+    }
+    *fallbacks = valid;
+    r
+}
+
+/// State of a `LocaleMap` that changes as locales are loaded: the current locale, its
+/// plural rules and relative-time formatter, the loaded messages and the selected overlay.
+/// Each `LocaleMap` owns its session, so that loading a locale in one `LocaleMap`
+/// never affects another sharing the same `LocaleMapConfig`. Cloning a session
+/// shares the loaded messages until either copy changes them.
+pub struct LocaleSession {
+    pub(crate) _current_locale: Option<Locale>,
+    /// Locale of formatted numbers, dates and currencies, if not the current locale's.
+    _formatting_locale: Option<Locale>,
+    _current_ordinal_plural_rules: Option<intl_pluralrules::PluralRules>,
+    _current_cardinal_plural_rules: Option<intl_pluralrules::PluralRules>,
+    _current_relative_time_formatter: Option<Rc<super::RelativeTimeFormatter>>,
+    pub(crate) _assets: Rc<HashMap<Locale, MessageTree>>,
+    pub(crate) _asset_versions: Rc<HashMap<Locale, HashMap<String, u64>>>,
+    pub(crate) _asset_bases: Rc<HashMap<Locale, serde_json::Value>>,
+    /// Assets of every locale given to `from_in_memory()`, read instead of asset files.
+    pub(crate) _in_memory_assets: Option<Rc<HashMap<Locale, MessageTree>>>,
+    /// Messages of each overlay, by overlay name and locale.
+    _overlay_assets: Rc<HashMap<String, HashMap<Locale, MessageTree>>>,
+    _overlay: Option<String>,
+    _asset_manifest: Option<Rc<AssetManifest>>,
+    _catalog_versions: Rc<HashMap<Locale, String>>,
+    _captured_ids: RefCell<Option<Vec<String>>>,
+    _recorded_lookups: RefCell<Option<Vec<LookupRecord>>>,
+    /// Number of lookups of each message identifier, if usage recording is started.
+    _usage_counts: RefCell<Option<HashMap<String, usize>>>,
+    _key_reveal: Cell<Option<KeyReveal>>,
+    _message_cache: RefCell<MessageCache>,
+    /// Number of missing messages in the message cache.
+    _cached_misses: Cell<usize>,
+    /// Messages missing in a locale and its fallbacks, to be translated
+    /// by `provide_missing_translations()`.
+    _missing_translations: RefCell<Vec<(Locale, String)>>,
+    _provided_translations: Rc<HashMap<Locale, HashMap<String, ProvidedTranslation>>>,
+}
+
+impl LocaleSession {
+    fn new() -> Self {
+        Self {
+            _current_locale: None,
+            _formatting_locale: None,
+            _current_cardinal_plural_rules: None,
+            _current_ordinal_plural_rules: None,
+            _current_relative_time_formatter: None,
+            _assets: Rc::new(HashMap::new()),
+            _asset_versions: Rc::new(HashMap::new()),
+            _asset_bases: Rc::new(HashMap::new()),
+            _in_memory_assets: None,
+            _overlay_assets: Rc::new(HashMap::new()),
+            _overlay: None,
+            _asset_manifest: None,
+            _catalog_versions: Rc::new(HashMap::new()),
+            _captured_ids: RefCell::new(None),
+            _recorded_lookups: RefCell::new(None),
+            _usage_counts: RefCell::new(None),
+            _key_reveal: Cell::new(None),
+            _message_cache: RefCell::new(HashMap::new()),
+            _cached_misses: Cell::new(0),
+            _missing_translations: RefCell::new(vec![]),
+            _provided_translations: Rc::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the current locale.
+    pub fn current_locale(&self) -> Option<&Locale> {
+        self._current_locale.as_ref()
+    }
+
+    /// Returns the locales whose messages are loaded, in order.
+    pub fn loaded_locales(&self) -> Vec<Locale> {
+        let mut r: Vec<Locale> = self._assets.keys().cloned().collect();
+        r.sort();
+        r
+    }
+}
+
+impl Clone for LocaleSession {
+    fn clone(&self) -> Self {
+        Self {
+            _current_locale: self._current_locale.clone(),
+            _formatting_locale: self._formatting_locale.clone(),
+            _current_cardinal_plural_rules: self._current_cardinal_plural_rules.clone(),
+            _current_ordinal_plural_rules: self._current_ordinal_plural_rules.clone(),
+            _current_relative_time_formatter: self._current_relative_time_formatter.clone(),
+            _assets: self._assets.clone(),
+            _asset_versions: self._asset_versions.clone(),
+            _asset_bases: self._asset_bases.clone(),
+            _in_memory_assets: self._in_memory_assets.clone(),
+            _overlay_assets: self._overlay_assets.clone(),
+            _overlay: self._overlay.clone(),
+            _asset_manifest: self._asset_manifest.clone(),
+            _catalog_versions: self._catalog_versions.clone(),
+            _captured_ids: self._captured_ids.clone(),
+            _recorded_lookups: self._recorded_lookups.clone(),
+            _usage_counts: self._usage_counts.clone(),
+            _key_reveal: self._key_reveal.clone(),
+            _message_cache: RefCell::new(HashMap::new()),
+            _cached_misses: Cell::new(0),
+            _missing_translations: self._missing_translations.clone(),
+            _provided_translations: self._provided_translations.clone(),
+        }
+    }
+}
+
+/// Flexible locale mapping with support for loading message resources,
+/// plural rule selection and relative-time formatting.
+///
+/// A `LocaleMap` combines a shared `LocaleMapConfig`, its own `LocaleSession`
+/// and the callbacks and formatters registered with it.
+#[derive(Clone)]
+pub struct LocaleMap {
+    pub(crate) _config: Arc<LocaleMapConfig>,
+    pub(crate) _session: LocaleSession,
+    _unsupported_formatter_callback: Option<UnsupportedFormatterCallback>,
+    _diagnostic_callback: Option<DiagnosticCallback>,
+    _metrics: Option<SharedMetricsSink>,
+    _missing_translation_provider: Option<SharedMissingTranslationProvider>,
+    _locale_preference_store: Option<SharedLocalePreferenceStore>,
+    _formatters: RefCell<Rc<HashMap<String, MessageFormatter>>>,
+    _variant_selector: Option<VariantSelector>,
+}
+
+impl LocaleMap {
+    /// Constructs a `LocaleMap` object. Invalid options are reported as warnings and
+    /// ignored, as in `LocaleMapConfig::new()`.
+    pub fn new(options: &LocaleMapOptions) -> Self {
+        LocaleMap::from_options(options, false).unwrap()
+    }
+
+    /// Constructs a `LocaleMap` object, returning an error naming the option if the
+    /// options are invalid, such as fallbacks forming a cycle; see `LocaleMapConfig::try_new()`.
+    pub fn try_new(options: &LocaleMapOptions) -> Result<Self, String> {
+        LocaleMap::from_options(options, true)
+    }
+
+    fn from_options(options: &LocaleMapOptions, strict: bool) -> Result<Self, String> {
+        let mut r = LocaleMap::with_config(Arc::new(LocaleMapConfig::from_options(options, strict)?));
+        r._unsupported_formatter_callback = options._unsupported_formatter_callback.borrow().clone();
+        r._diagnostic_callback = options._diagnostic_callback.borrow().clone();
+        r._metrics = options._metrics.borrow().clone();
+        r._missing_translation_provider = options._missing_translation_provider.borrow().clone();
+        r._locale_preference_store = options._locale_preference_store.borrow().clone();
+        Ok(r)
+    }
+
+    /// Constructs a `LocaleMap` with no locale loaded from a configuration that may be
+    /// shared with other `LocaleMap`s, such as across threads.
+    pub fn with_config(config: Arc<LocaleMapConfig>) -> Self {
+        Self {
+            _config: config,
+            _session: LocaleSession::new(),
+            _unsupported_formatter_callback: None,
+            _diagnostic_callback: None,
+            _metrics: None,
+            _missing_translation_provider: None,
+            _locale_preference_store: None,
+            _formatters: RefCell::new(Rc::new(HashMap::new())),
+            _variant_selector: None,
+        }
+    }
+
+    /// Constructs a `LocaleMap` with no locale loaded that shares the configuration,
+    /// callbacks and formatters of this one, such as for a new window or request.
+    pub fn new_session(&self) -> Self {
+        Self { _session: LocaleSession::new(), ..self.clone() }
+    }
+
+    /// Returns the configuration, which may be shared with other `LocaleMap`s.
+    pub fn config(&self) -> &Arc<LocaleMapConfig> {
+        &self._config
+    }
+
+    /// Returns the current locale and loaded messages.
+    pub fn session(&self) -> &LocaleSession {
+        &self._session
+    }
+
+    /// Constructs a `LocaleMap` whose assets are embedded in the program, such as
+    /// the `ASSETS` generated by `build::generate()`, given as pairs of a locale tag of
+    /// `LocaleMapOptions::supported_locales()` and the JSON asset tree of the locale.
+    /// The default locale, or the locale remembered through
+    /// `LocaleMapOptions::remember_selection()`, is made the current locale; other
+    /// locales can be loaded through `load()`. The asset options are ignored.
+    pub fn from_embedded(options: &LocaleMapOptions, assets: &[(&str, &str)]) -> Self {
+        LocaleMap::with_in_memory_assets(options, assets.iter()
+            .map(|(tag, json)| (parse_locale(tag).unwrap(), serde_json::from_str(json).unwrap()))
+            .collect())
+    }
+
+    pub(crate) fn with_in_memory_assets(options: &LocaleMapOptions, assets: HashMap<Locale, serde_json::Value>) -> Self {
+        let mut r = LocaleMap::new(options);
+        let assets: HashMap<Locale, MessageTree> = assets.into_iter()
+            .map(|(locale, root)| (locale, MessageTree::from_json(&root, r._config._normalization)))
+            .collect();
+        let default_locale = r.remembered_locale().unwrap_or_else(|| r._config._default_locale.clone());
+        let mut to_load: HashSet<Locale> = HashSet::new();
+        to_load.insert(default_locale.clone());
+        r.enumerate_fallbacks(default_locale.clone(), &mut to_load);
+        for locale in to_load {
+            if let Some(root) = assets.get(&locale) {
+                Rc::make_mut(&mut r._session._assets).insert(locale, root.clone());
+            }
+        }
+        r._session._in_memory_assets = Some(Rc::new(assets));
+        r.set_current_locale(default_locale);
+        r
+    }
+
+    /// Returns a set of supported locale codes, reflecting
+    /// the ones that were specified when constructing the `LocaleMap`
+    /// or listed by the manifest read through `discover_assets()`.
+    pub fn supported_locales(&self) -> HashSet<Locale> {
+        self._config._supported_locales.clone()
+    }
+
+    /// Lists the supported locales with their names, flags and directions, such as for
+    /// populating a language selection dropdown, sorted by native name with the collation
+    /// of the current locale, or the default locale if none is loaded, or by code point
+    /// without the `normalization` feature.
+    pub fn language_menu(&self) -> Vec<LanguageMenuItem> {
+        #[cfg(feature = "normalization")]
+        let collator = self.search_collator();
+        #[cfg(feature = "normalization")]
+        let compare = |a: &str, b: &str| collator.compare(a, b);
+        #[cfg(not(feature = "normalization"))]
+        let compare = |a: &str, b: &str| a.cmp(b);
+        let mut r: Vec<LanguageMenuItem> = self._config._supported_locales.iter().map(|locale| LanguageMenuItem {
+            locale: locale.clone(),
+            native_name: locale.native_name().to_string(),
+            universal_name: locale.universal_name().to_string(),
+            flag: locale.country().or_else(|| locale.maximize().country()).map(|country| country.emoji_flag()),
+            direction: locale.direction(),
+        }).collect();
+        r.sort_by(|a, b| compare(&a.native_name, &b.native_name).then_with(|| a.locale.standard_tag().to_string().cmp(&b.locale.standard_tag().to_string())));
+        r
+    }
+
+    /// Returns `true` if the locale is one of the supported locales
+    /// that were specified when constructing the `LocaleMap`,
+    /// otherwise `false`.
+    pub fn supports_locale(&self, arg: &Locale) -> bool {
+        self._config._supported_locales.contains(arg)
+    }
+
+    /// Returns the currently loaded locale.
+    pub fn current_locale(&self) -> Option<Locale> {
+        self._session._current_locale.clone()
+    }
+
+    /// Returns the locale of formatted numbers, dates and currencies, such as `fr-CA`
+    /// for an application whose messages are in English. This is the one given to
+    /// `set_formatting_locale()`, if any; otherwise the one specified through
+    /// `LocaleMapOptions::formatting_locale()`, if any; otherwise the current locale,
+    /// or the default locale if none is loaded.
+    pub fn formatting_locale(&self) -> Locale {
+        self._session._formatting_locale.clone()
+            .or_else(|| self._config._formatting_locale.clone())
+            .or_else(|| self._session._current_locale.clone())
+            .unwrap_or_else(|| self._config._default_locale.clone())
+    }
+
+    /// Formats numbers, dates and currencies in a locale other than the one of the
+    /// messages, regardless of the locales loaded later, or, given `None`, in the locale
+    /// of `LocaleMapOptions::formatting_locale()` or the current locale again.
+    /// The locale needs no messages, so it need not be supported.
+    pub fn set_formatting_locale(&mut self, locale: Option<Locale>) {
+        self._session._formatting_locale = locale;
+    }
+
+    /// Returns the numbering system used for numbers inserted into messages.
+    /// This is the numbering system specified when constructing the `LocaleMap`, if any;
+    /// otherwise the one requested by the `-u-nu-` extension of the formatting locale,
+    /// if a locale is loaded or a formatting locale is specified; otherwise the Latin
+    /// numbering system.
+    pub fn numbering_system(&self) -> NumberingSystem {
+        if let Some(ns) = self._config._numbering_system {
+            return ns;
+        }
+        let locale = self._session._formatting_locale.as_ref()
+            .or(self._config._formatting_locale.as_ref())
+            .or(self._session._current_locale.as_ref());
+        locale.and_then(|l| l.numbering_system()).unwrap_or_else(NumberingSystem::latin)
+    }
+
+    /// Returns a number formatter for the formatting locale, using the numbering
+    /// system of `numbering_system()`. See `formatting_locale()`.
+    pub fn number_formatter(&self) -> NumberFormatter {
+        let locale = &self.formatting_locale();
+        NumberFormatter::new(locale, NumberFormatOptions::new()
+            .numbering_system(self.numbering_system().code())
+            .overrides(&self.format_overrides(locale)))
+    }
+
+    /// Returns a number parser for the formatting locale. See `NumberParser`.
+    pub fn number_parser(&self) -> NumberParser {
+        let locale = &self.formatting_locale();
+        NumberParser::new(locale).with_overrides(&self.format_overrides(locale))
+    }
+
+    /// Returns a formatter of amounts of a currency, given by its ISO 4217 code, for the
+    /// formatting locale, using the numbering system of `numbering_system()` and the
+    /// format overrides of the locale unless the options specify others.
+    pub fn currency_formatter<S: ToString>(&self, code: S, options: &CurrencyFormatOptions) -> CurrencyFormatter {
+        let locale = &self.formatting_locale();
+        let defaults = CurrencyFormatOptions::new();
+        defaults.numbering_system(self.numbering_system().code()).overrides(&self.format_overrides(locale));
+        CurrencyFormatter::new(locale, code, &options.with_defaults(&defaults))
+    }
+
+    /// Formats an amount of a currency, given by its ISO 4217 code, with the display name
+    /// of the currency in the plural form the amount selects in the current locale, or the
+    /// default locale if none is loaded, such as "1 US dollar", "2 US dollars" and
+    /// "1 dólar estadounidense". The amount is rounded to the fraction digits of the currency
+    /// and formatted in the formatting locale. Currencies without a display name in the
+    /// language of the locale are written with their code, such as "3 XTS".
+    pub fn format_currency_long<S: ToString>(&self, amount: f64, code: S) -> String {
+        let code = code.to_string().to_uppercase();
+        let formatting_locale = &self.formatting_locale();
+        let formatter = NumberFormatter::new(formatting_locale, NumberFormatOptions::new()
+            .maximum_fraction_digits(currency_data::currency_fraction_digits(&code))
+            .numbering_system(self.numbering_system().code())
+            .overrides(&self.format_overrides(formatting_locale)));
+        let category = self.select_plural_rule(PluralRuleType::CARDINAL, formatter.plural_operands(amount)).unwrap_or(PluralCategory::OTHER);
+        let locale = self._session._current_locale.as_ref().unwrap_or(&self._config._default_locale);
+        currency_data::format_currency_long(locale, &formatter.format(amount), &code, category)
+    }
+
+    /// Returns the locale of formatted dates, whose month and weekday names are in the
+    /// language of the messages: the formatting locale if it has the language of the current
+    /// locale, or the default locale if none is loaded; otherwise that locale with the region
+    /// of the formatting locale through the `-u-rg-` extension, whose patterns it uses.
+    fn date_locale(&self) -> Locale {
+        let formatting_locale = self.formatting_locale();
+        let locale = self._session._current_locale.as_ref().unwrap_or(&self._config._default_locale);
+        if locale.language() == formatting_locale.language() {
+            return formatting_locale;
+        }
+        formatting_locale.region_override()
+            .or_else(|| formatting_locale.maximize().region().map(|region| region.to_string()))
+            .and_then(|region| locale.with_unicode_extension_keyword("rg", format!("{}zzzz", region)).ok())
+            .unwrap_or_else(|| locale.clone())
+    }
+
+    /// Returns a date and time formatter for the formatting locale, with month and weekday
+    /// names in the language of the messages. See `formatting_locale()`.
+    pub fn date_time_formatter(&self) -> DateTimeFormatter {
+        DateTimeFormatter::new(&self.date_locale(), DateTimeFormatOptions::new()
+            .numbering_system(self.numbering_system().code())
+            .hour_cycle(self.hour_cycle())
+            .overrides(&self.format_overrides(&self.formatting_locale())))
+    }
+
+    /// Returns a date parser for the formatting locale, reading month and weekday names
+    /// in the language of the messages. See `DateParser`.
+    pub fn date_parser(&self) -> DateParser {
+        DateParser::new(&self.date_locale()).with_overrides(&self.format_overrides(&self.formatting_locale()))
+    }
+
+    /// Returns a search collator for the current locale, or the default locale
+    /// if none is loaded, ignoring case and diacritics. See `SearchCollator`.
+    #[cfg(feature = "normalization")]
+    pub fn search_collator(&self) -> SearchCollator {
+        let locale = self._session._current_locale.as_ref().unwrap_or(&self._config._default_locale);
+        SearchCollator::new(locale, &SearchCollatorOptions::new())
+    }
+
+    /// Formats a person's name in the order of the current locale, or the default locale
+    /// if none is loaded, such as "Jane Smith" in English and "Kovács János" in Hungarian.
+    /// See `PersonNameFormatter`.
+    pub fn format_person_name(&self, name: &PersonName) -> String {
+        let locale = self._session._current_locale.as_ref().unwrap_or(&self._config._default_locale);
+        PersonNameFormatter::new(locale, &PersonNameFormatOptions::new()).format(name)
+    }
+
+    /// Returns the month names, from January to December, in the language of the messages,
+    /// such as for calendar widgets.
+    pub fn month_names(&self, width: NameWidth, context: NameContext) -> Vec<String> {
+        self.date_time_formatter().month_names(width, context)
+    }
+
+    /// Returns the weekday names, from Sunday to Saturday, in the language of the messages.
+    pub fn weekday_names(&self, width: NameWidth, context: NameContext) -> Vec<String> {
+        self.date_time_formatter().weekday_names(width, context)
+    }
+
+    /// Formats the range of dates from `start` to `end` in the formatting locale,
+    /// collapsing the fields the dates share, as in "Mar 4 – 9, 2025".
+    /// See `DateTimeFormatter::format_range()`.
+    pub fn format_date_range(&self, start: &chrono::DateTime<chrono::FixedOffset>, end: &chrono::DateTime<chrono::FixedOffset>, length: DateTimeLength) -> String {
+        DateTimeFormatter::new(&self.date_locale(), DateTimeFormatOptions::new()
+            .date_length(Some(length))
+            .numbering_system(self.numbering_system().code())
+            .overrides(&self.format_overrides(&self.formatting_locale())))
+            .format_range(start, end)
+    }
+
+    /// Resolves the formatting overrides of a locale from its tag, its
+    /// language and region, its language and `"*"`, in this order.
+    fn format_overrides(&self, locale: &Locale) -> FormatOverrides {
+        let mut tags = vec![locale.standard_tag().to_string()];
+        if let Some(region) = locale.region() {
+            tags.push(format!("{}-{}", locale.language(), region));
+        }
+        tags.push(locale.language().to_string());
+        tags.push("*".to_string());
+        tags.iter().filter_map(|tag| self._config._format_overrides.get(tag))
+            .fold(FormatOverrides::new(), |r, overrides| r.or(overrides))
+    }
+
+    /// Returns the hour cycle of formatted times. This is the hour cycle specified when
+    /// constructing the `LocaleMap`, if any; otherwise the one requested by the formatting
+    /// locale's `-u-hc-` extension; otherwise the one of the formatting overrides, if any;
+    /// otherwise the formatting locale's preferred one.
+    pub fn hour_cycle(&self) -> HourCycle {
+        let locale = &self.formatting_locale();
+        self._config._hour_cycle.or_else(|| locale.hour_cycle())
+            .or_else(|| self.format_overrides(locale).get_hour_cycle())
+            .unwrap_or_else(|| locale.preferred_hour_cycle())
+    }
+
+    /// Returns the week conventions of the formatting locale, such as for numbering
+    /// weeks; see `Locale::week_info()`.
+    pub fn week_info(&self) -> WeekInfo {
+        let locale = &self.formatting_locale();
+        locale.week_info()
+    }
+
+    /// Returns the plural rules and direction of the current locale, or the default locale
+    /// if none is loaded, with the separators of the formatting locale and its format overrides,
+    /// such as for the responses of an API. See `LocalizationContext`.
+    pub fn localization_context(&self) -> LocalizationContext {
+        let locale = self._session._current_locale.as_ref().unwrap_or(&self._config._default_locale);
+        let formatting_locale = &self.formatting_locale();
+        LocalizationContext::with_overrides(locale, formatting_locale, &self.format_overrides(formatting_locale))
+    }
+
+    /// Resolves a user's preferred locales, such as `pt-BR`, `es` and `en`, to the supported
+    /// locale to load and the chain of locales to display missing messages in: the other
+    /// matching supported locales in order of preference, each followed by its configured
+    /// fallbacks, then the default locale. If no supported locale matches, the default
+    /// locale is resolved. The resolved locale is the one `load()` would load for the
+    /// first preferred locale alone.
+    pub fn negotiate(&self, preferred: &[Locale]) -> LocaleResolution {
+        let default_locale = self._config._default_locale.clone();
+        let resolved = self.locale_matcher().resolve(preferred)
+            .unwrap_or_else(|| LocaleResolution { locale: default_locale.clone(), fallbacks: vec![] });
+        let mut r = LocaleResolution { locale: resolved.locale.clone(), fallbacks: vec![] };
+        for locale in resolved.chain() {
+            r.push_fallback(locale.clone());
+            let mut fallbacks = vec![];
+            self.enumerate_fallbacks_in_order(locale, &mut fallbacks);
+            for fallback in fallbacks {
+                r.push_fallback(fallback);
+            }
+        }
+        r.push_fallback(default_locale);
+        r
+    }
+
+    fn locale_matcher(&self) -> LocaleMatcher {
+        let mut supported: Vec<Locale> = self._config._supported_locales.iter().cloned().collect();
+        supported.sort();
+        LocaleMatcher::new(supported)
+    }
+
+    /// Attempts to load the specified locale and its fallbacks.
+    /// If any resource fails to load, the method returns `false`, otherwise `true`.
+    pub async fn update_locale(&mut self, new_locale: Locale) -> bool {
+        self.load(Some(new_locale)).await
+    }
+
+    /// Attempts to load a locale and its fallbacks.
+    /// If the locale argument is specified, it is loaded. A locale that is not
+    /// one of the supported locales resolves to the closest supported locale
+    /// according to `LocaleMatcher`, such as `zh-Hans-CN` resolving to `zh`
+    /// and `en-AU` resolving to `en-GB` rather than `en-US`.
+    /// Otherwise, if there is a default locale, it is loaded, and if not,
+    /// the method panics.
+    ///
+    /// With `LocaleMapOptions::remember_selection()`, a locale given as the argument
+    /// is stored once loaded, and without the argument, the stored locale is loaded
+    /// rather than the default locale if it is still supported.
+    ///
+    /// If any resource fails to load, or if `check_placeholders()` was specified with
+    /// `DiagnosticSeverity::Error` and a message does not match the default locale,
+    /// the method returns `false` and the current locale is kept, otherwise `true`.
+    pub async fn load(&mut self, new_locale: Option<Locale>) -> bool {
+        self.load_with_report(new_locale, false).await.loaded
+    }
+
+    /// Attempts to load a locale and its fallbacks as `load()` does, except that the
+    /// asset files that fail to load are skipped, leaving their messages missing,
+    /// rather than keeping the current locale. The returned report lists them.
+    pub async fn load_partial(&mut self, new_locale: Option<Locale>) -> LoadReport {
+        self.load_with_report(new_locale, true).await
+    }
+
+    async fn load_with_report(&mut self, new_locale: Option<Locale>, partial: bool) -> LoadReport {
+        let future = self.load_locale(new_locale, partial);
+        #[cfg(feature = "tracing")]
+        let future = tracing::Instrument::instrument(future, tracing::info_span!("load"));
+        future.await
+    }
+
+    async fn load_locale(&mut self, mut new_locale: Option<Locale>, partial: bool) -> LoadReport {
+        let selected = new_locale.is_some();
+        if new_locale.is_none() { new_locale = self.remembered_locale(); }
+        if new_locale.is_none() { new_locale = Some(self._config._default_locale.clone()); }
+        let new_locale = new_locale.unwrap();
+        let new_locale = if self.supports_locale(&new_locale) { new_locale } else {
+            let r = self.locale_matcher().best_match(std::slice::from_ref(&new_locale));
+            if r.is_none() {
+                panic!("Unsupported locale {}", new_locale.standard_tag());
+            }
+            r.unwrap()
+        };
+        let mut to_load: HashSet<Locale> = hashset![new_locale.clone()];
+        self.enumerate_fallbacks(new_locale.clone(), &mut to_load);
+
+        let mut report = LoadReport { locale: new_locale.clone(), loaded: false, errors: vec![] };
+        let mut to_load: Vec<Locale> = to_load.into_iter().collect();
+        to_load.sort();
+        let mut new_assets: HashMap<Locale, LoadedAssets> = hashmap![];
+        for locale in to_load {
+            let res = self.load_single_locale(&locale, if partial { Some(&mut report.errors) } else { None }).await;
+            if res.is_none() {
+                return report;
+            }
+            new_assets.insert(locale.clone(), res.unwrap());
+        }
+        if !self.check_placeholders(&new_assets).await {
+            return report;
+        }
+        if self._config._assets_auto_clean {
+            Rc::make_mut(&mut self._session._assets).clear();
+            Rc::make_mut(&mut self._session._asset_versions).clear();
+            Rc::make_mut(&mut self._session._asset_bases).clear();
+            Rc::make_mut(&mut self._session._overlay_assets).clear();
+            Rc::make_mut(&mut self._session._catalog_versions).clear();
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(locale = %new_locale.standard_tag(), locales = new_assets.len(), errors = report.errors.len(), "loaded locale");
+        for (locale, loaded) in new_assets {
+            self.store_loaded_assets(locale, loaded);
+        }
+        if selected {
+            self.remember_locale(&new_locale);
+        }
+        self.set_current_locale(new_locale);
+        report.loaded = true;
+        report
+    }
+
+    /// Returns the locale stored through `LocaleMapOptions::remember_selection()`,
+    /// resolved to a supported locale, if any.
+    fn remembered_locale(&self) -> Option<Locale> {
+        let store = self._locale_preference_store.as_ref()?;
+        let tag = store.load().unwrap_or_else(|e| {
+            LocaleMap::report_load_failure(format!("Failed to read the remembered locale: {}", e));
+            None
+        })?;
+        let locale = parse_locale(&tag).ok()?;
+        if self.supports_locale(&locale) { Some(locale) } else { self.locale_matcher().best_match(&[locale]) }
+    }
+
+    fn remember_locale(&self, locale: &Locale) {
+        if let Some(store) = self._locale_preference_store.as_ref() {
+            if let Err(e) = store.save(&locale.standard_tag().to_string()) {
+                LocaleMap::report_load_failure(format!("Failed to remember the locale {}: {}", locale.standard_tag(), e));
+            }
+        }
+    }
+
+    /// Removes the locale stored through `LocaleMapOptions::remember_selection()`,
+    /// so that the default locale is loaded after a restart.
+    pub fn forget_selection(&self) {
+        if let Some(store) = self._locale_preference_store.as_ref() {
+            if let Err(e) = store.clear() {
+                LocaleMap::report_load_failure(format!("Failed to forget the remembered locale: {}", e));
+            }
+        }
+    }
+
+    /// Makes a locale whose assets are stored the current locale, loading its plural rules
+    /// and relative-time formatter.
+    pub(crate) fn set_current_locale(&mut self, new_locale: Locale) {
+        self._session._current_locale = Some(new_locale.clone());
+        let new_locale_code = unic_langid::LanguageIdentifier::from_bytes(new_locale.clone().standard_tag().to_string().as_ref()).unwrap();
+        self._session._current_ordinal_plural_rules = self.load_plural_rules(new_locale_code.clone(), intl_pluralrules::PluralRuleType::ORDINAL);
+        self._session._current_cardinal_plural_rules = self.load_plural_rules(new_locale_code.clone(), intl_pluralrules::PluralRuleType::CARDINAL);
+        self._session._current_relative_time_formatter = None;
+
+        if let Some(l) = formatter_support::relative_time_language(&new_locale) {
+            self._session._current_relative_time_formatter = Some(Rc::new(timeago::Formatter::with_language(l)));
+        }
+
+        if self._session._current_relative_time_formatter.is_none() {
+            self._session._current_relative_time_formatter = Some(Rc::new(timeago::Formatter::with_language(Box::new(timeago::languages::english::English))));
+        }
+
+        if let Some(callback) = self._unsupported_formatter_callback.as_ref() {
+            let support = self.formatter_support(&new_locale);
+            if !support.is_complete() {
+                callback(&new_locale, &support);
+            }
+        }
+    }
+
+    /// Compares the `$` variables of the messages being loaded against the default locale's,
+    /// reporting mismatches. Returns `false` if a mismatch is an error.
+    async fn check_placeholders(&self, new_assets: &HashMap<Locale, LoadedAssets>) -> bool {
+        let severity = match self._config._placeholder_check {
+            Some(severity) => severity,
+            None => return true,
+        };
+        let reference = match new_assets.get(&self._config._default_locale) {
+            Some(loaded) => Some(loaded.root.clone()),
+            None => match self._session._assets.get(&self._config._default_locale) {
+                Some(root) => Some(root.clone()),
+                None => self.load_single_locale(&self._config._default_locale, None).await.map(|loaded| loaded.root),
+            },
+        };
+        let mut locales: Vec<&Locale> = new_assets.keys().filter(|l| **l != self._config._default_locale).collect();
+        locales.sort();
+        let mut r = true;
+        for locale in locales {
+            let validation = validation::validate_locale(locale, reference.as_ref(), Some(&new_assets[locale].root), &self._config);
+            for mismatch in validation.placeholder_mismatches {
+                self.report(Diagnostic { severity, locale: locale.clone(), kind: DiagnosticKind::PlaceholderMismatch(mismatch) });
+                r = r && severity != DiagnosticSeverity::Error;
+            }
+        }
+        r
+    }
+
+    fn report(&self, diagnostic: Diagnostic) {
+        match self._diagnostic_callback.as_ref() {
+            Some(callback) => callback(&diagnostic),
+            None => println!("{}", diagnostic),
+        }
+    }
+
+    /// Returns which formatters have data for a locale. Formatters without data
+    /// fall back to English, so this can be used to decide whether
+    /// to expose a partially supported language.
+    pub fn formatter_support(&self, locale: &Locale) -> FormatterSupport {
+        formatter_support::formatter_support(locale)
+    }
+
+    /// Reads `manifest.json` from the assets source, which lists the available locales,
+    /// the base file names of their asset files and their versions; see `AssetManifest`.
+    /// The listed locales replace the supported locales and the listed base file names,
+    /// if any, replace the ones specified through `LocaleMapAssetOptions`, so that
+    /// locales published later are found by calling this method again.
+    ///
+    /// Returns the locales added, removed or whose version changed since the previous
+    /// call, such as for reloading the current locale, or an error if the manifest
+    /// cannot be read or does not list the default locale. The messages already loaded
+    /// are kept, and fallbacks must only refer to listed locales. If the configuration
+    /// is shared with other `LocaleMap`s, this `LocaleMap` receives a copy of it.
+    pub async fn discover_assets(&mut self) -> Result<ManifestChanges, String> {
+        let path = format!("{}/manifest.json", self._config._assets_src);
+        let content = self.read_verified_asset(&self._config._default_locale, &path).await.map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Failed to load resource at {}.", path))?;
+        let manifest: AssetManifest = serde_json::from_slice(&content)
+            .map_err(|e| format!("Failed to parse resource at {}: {}", path, e))?;
+        let mut locale_path_components = HashMap::<Locale, String>::new();
+        for tag in manifest.locales.iter() {
+            let locale = parse_locale(tag).map_err(|e| format!("Invalid locale {} in {}: {}", tag, path, e))?;
+            locale_path_components.insert(locale, tag.clone());
+        }
+        if !locale_path_components.contains_key(&self._config._default_locale) {
+            return Err(format!("The default locale {} is missing from {}.", self._config._default_locale.standard_tag(), path));
+        }
+        let changes = ManifestChanges::between(&self._config._locale_path_components, self._session._asset_manifest.as_deref(), &locale_path_components, &manifest);
+        let config = Arc::make_mut(&mut self._config);
+        config._supported_locales = locale_path_components.keys().cloned().collect();
+        config._locale_path_components = locale_path_components;
+        if !manifest.namespaces.is_empty() {
+            config._assets_base_file_names = manifest.namespaces.clone();
+        }
+        self._session._asset_manifest = Some(Rc::new(manifest));
+        Ok(changes)
+    }
+
+    /// Returns the manifest read by the last call to `discover_assets()`.
+    pub fn asset_manifest(&self) -> Option<&AssetManifest> {
+        self._session._asset_manifest.as_deref()
+    }
+
+    /// Returns the catalog version of the messages of a loaded locale, which is
+    /// the version listed by the manifest when the locale was loaded, or the version
+    /// of the last delta applied through `apply_delta()`.
+    pub fn catalog_version(&self, locale: &Locale) -> Option<String> {
+        self._session._catalog_versions.get(locale).cloned()
+    }
+
+    /// Specifies the catalog version of the messages of a loaded locale,
+    /// for assets that are not described by a manifest.
+    pub fn set_catalog_version<S: ToString>(&mut self, locale: &Locale, version: S) {
+        Rc::make_mut(&mut self._session._catalog_versions).insert(locale.clone(), version.to_string());
+    }
+
+    /// Requests the changes to the messages of a loaded locale since its catalog version
+    /// from `delta/{version}.json` under the directory of the locale, such as
+    /// `res/lang/pt-BR/delta/12.json`, and applies them, rather than loading every
+    /// asset file again; see `CatalogDelta`. Returns the number of messages changed
+    /// or removed, or an error if the locale has no catalog version or the delta
+    /// cannot be read, in which case the locale can be loaded again instead.
+    pub async fn apply_delta(&mut self, locale: &Locale) -> Result<usize, String> {
+        if !self._session._assets.contains_key(locale) {
+            return Err(format!("The locale {} is not loaded.", locale.standard_tag()));
+        }
+        let version = self.catalog_version(locale)
+            .ok_or_else(|| format!("The locale {} has no catalog version.", locale.standard_tag()))?;
+        let locale_path_comp = self._config._locale_path_components.get(locale).ok_or_else(|| {
+            AssetLoadError { locale: locale.clone(), path: format!("{}/delta/{}.json", locale.standard_tag(), version), kind: AssetLoadErrorKind::NotFound }.to_string()
+        })?;
+        let path = format!("{}/{}/delta/{}.json", self._config._assets_src, locale_path_comp, version);
+        let content = self.read_verified_asset(locale, &path).await.map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Failed to load resource at {}.", path))?;
+        let delta: CatalogDelta = serde_json::from_slice(&content)
+            .map_err(|e| format!("Failed to parse resource at {}: {}", path, e))?;
+        self.invalidate_message_cache();
+        let tree = Rc::make_mut(&mut self._session._assets).get_mut(locale).unwrap();
+        let mut r = 0;
+        for id in delta.removed.iter() {
+            r += tree.remove(id) as usize;
+        }
+        for (id, message) in delta.changed {
+            tree.set(&id, message);
+            r += 1;
+        }
+        self.set_catalog_version(locale, delta.version);
+        Ok(r)
+    }
+
+    /// Attempts to load the messages of a locale and its fallbacks without
+    /// changing the current locale, such as for `get_bilingual()`.
+    /// Loading another locale with `auto_clean` enabled discards them.
+    ///
+    /// If any resource fails to load, the method returns `false`, otherwise `true`.
+    pub async fn load_secondary_locale(&mut self, locale: &Locale) -> bool {
+        if !self.supports_locale(locale) {
+            panic!("Unsupported locale {}", locale.standard_tag());
+        }
+        let mut to_load: HashSet<Locale> = hashset![locale.clone()];
+        self.enumerate_fallbacks(locale.clone(), &mut to_load);
+        for locale in to_load {
+            if self._session._assets.contains_key(&locale) {
+                continue;
+            }
+            let res = self.load_single_locale(&locale, None).await;
+            if res.is_none() {
+                return false;
+            }
+            self.store_loaded_assets(locale, res.unwrap());
+        }
+        true
+    }
+
+    fn store_loaded_assets(&mut self, locale: Locale, loaded: LoadedAssets) {
+        self.invalidate_message_cache();
+        Rc::make_mut(&mut self._session._assets).insert(locale.clone(), loaded.root);
+        Rc::make_mut(&mut self._session._asset_versions).insert(locale.clone(), loaded.versions);
+        let catalog_version = self._session._asset_manifest.as_ref()
+            .zip(self._config._locale_path_components.get(&locale))
+            .and_then(|(manifest, locale_path_comp)| manifest.versions.get(locale_path_comp).cloned());
+        match catalog_version {
+            Some(version) => Rc::make_mut(&mut self._session._catalog_versions).insert(locale.clone(), version),
+            None => Rc::make_mut(&mut self._session._catalog_versions).remove(&locale),
+        };
+        if let Some(base) = loaded.base {
+            Rc::make_mut(&mut self._session._asset_bases).insert(locale.clone(), base);
+        }
+        let overlay_assets = Rc::make_mut(&mut self._session._overlay_assets);
+        for overlay_assets in overlay_assets.values_mut() {
+            overlay_assets.remove(&locale);
+        }
+        for (overlay, tree) in loaded.overlays {
+            overlay_assets.entry(overlay).or_default().insert(locale.clone(), tree);
+        }
+    }
+
+    /// Checks every supported locale against the default locale, reporting missing
+    /// and orphaned messages, messages whose `$` variables differ and empty messages,
+    /// as well as incomplete variants if `LocaleMapOptions::audit_variants()` was specified.
+    /// Locales that are not loaded are read from the asset files without being retained.
+    pub async fn validate(&self) -> ValidationReport {
+        let mut locales: Vec<Locale> = self._config._supported_locales.iter().cloned().collect();
+        locales.sort();
+        let mut roots = HashMap::<Locale, Option<MessageTree>>::new();
+        for locale in locales.iter() {
+            let root = match self._session._assets.get(locale) {
+                Some(root) => Some(root.clone()),
+                None => self.load_single_locale(locale, None).await.map(|loaded| loaded.root),
+            };
+            roots.insert(locale.clone(), root);
+        }
+        let reference = roots.get(&self._config._default_locale).and_then(|r| r.as_ref());
+        ValidationReport {
+            locales: locales.iter().map(|l| validation::validate_locale(l, reference, roots[l].as_ref(), &self._config)).collect(),
+        }
+    }
+
+    fn load_plural_rules(&self, new_locale_code: unic_langid::LanguageIdentifier, prt: intl_pluralrules::PluralRuleType) -> Option<intl_pluralrules::PluralRules> {
+        if let Ok(pr) = intl_pluralrules::PluralRules::create(new_locale_code.clone(), prt) {
+            Some(pr)
+        }
+        else if let Ok(pr) = intl_pluralrules::PluralRules::create(unic_langid::LanguageIdentifier::from_parts(new_locale_code.language, None, None, &[]), prt) {
+            Some(pr)
+        }
+        else {
+            Some(intl_pluralrules::PluralRules::create(unic_langid::LanguageIdentifier::from_parts(unic_langid::subtags::Language::from_bytes("en".as_ref()).unwrap(), None, None, &[]), prt).unwrap())
+        }
+    }
+
+    /// Reads the assets of a locale. If a vector of errors is given, the files that fail
+    /// to load are added to it and skipped, otherwise `None` is returned if any file fails.
+    pub(crate) async fn load_single_locale(&self, locale: &Locale, errors: Option<&mut Vec<AssetLoadError>>) -> Option<LoadedAssets> {
+        let future = self.read_locale_assets(locale, errors);
+        #[cfg(feature = "tracing")]
+        let future = tracing::Instrument::instrument(future, tracing::debug_span!("load_locale", locale = %locale.standard_tag()));
+        future.await
+    }
+
+    async fn read_locale_assets(&self, locale: &Locale, mut errors: Option<&mut Vec<AssetLoadError>>) -> Option<LoadedAssets> {
+        let locale_path_comp = self._config._locale_path_components.get(locale);
+        if locale_path_comp.is_none() {
+            panic!("Fallback locale is not supported a locale: {}", locale.standard_tag());
+        }
+        let locale_path_comp = locale_path_comp.unwrap();
+        let mut r = LoadedAssets {
+            root: MessageTree::new(),
+            versions: HashMap::new(),
+            base: None,
+            overlays: HashMap::new(),
+        };
+        if let Some(in_memory_assets) = self._session._in_memory_assets.as_ref() {
+            return match in_memory_assets.get(locale) {
+                Some(root) => Some(LoadedAssets { root: root.clone(), ..r }),
+                None => {
+                    LocaleMap::report_load_failure(format!("No in-memory assets for {}.", locale_path_comp));
+                    let error = AssetLoadError { locale: locale.clone(), path: locale_path_comp.clone(), kind: AssetLoadErrorKind::NotFound };
+                    errors.map(|errors| { errors.push(error); r })
+                },
+            };
+        }
+        let mut root = serde_json::Value::Object(serde_json::Map::new());
+        if self._config._assets_layered {
+            r.base = Some(serde_json::Value::Object(serde_json::Map::new()));
+        }
+        for base_name in self._config._assets_base_file_names.iter() {
+            let mut value = serde_json::Value::Object(serde_json::Map::new());
+            let mut found = false;
+            for layer in self.base_layers(locale) {
+                match self.load_asset_file(locale, &layer, base_name).await {
+                    Ok(Some((layer_value, _))) => {
+                        LocaleMap::merge_deep(&mut value, layer_value);
+                        found = true;
+                    },
+                    Ok(None) => {},
+                    Err(error) => errors.as_mut()?.push(error),
+                }
+            }
+            if let Some(base) = r.base.as_mut() {
+                LocaleMap::apply_deep(base_name, value.clone(), base);
+            }
+            match self.load_asset_file(locale, locale_path_comp, base_name).await {
+                Ok(Some((layer_value, version))) => {
+                    LocaleMap::merge_deep(&mut value, layer_value);
+                    r.versions.insert(base_name.clone(), version);
+                },
+                Ok(None) if found => {},
+                Ok(None) => {
+                    let path = format!("{}/{}/{}.{}", self._config._assets_src, locale_path_comp, base_name, self._config._assets_format.extension());
+                    LocaleMap::report_load_failure(format!("Failed to load resource at {}.", path));
+                    errors.as_mut()?.push(AssetLoadError { locale: locale.clone(), path, kind: AssetLoadErrorKind::NotFound });
+                    continue;
+                },
+                Err(error) => {
+                    errors.as_mut()?.push(error);
+                    continue;
+                },
+            }
+            LocaleMap::apply_deep(base_name, value, &mut root);
+        }
+        r.root = self.message_tree_of(&root);
+        for overlay in self._config._assets_overlays.iter() {
+            let mut root = serde_json::Value::Object(serde_json::Map::new());
+            let mut found = false;
+            for base_name in self._config._assets_base_file_names.iter() {
+                match self.load_asset_file(locale, &format!("{}/{}", overlay, locale_path_comp), base_name).await {
+                    Ok(Some((value, _))) => {
+                        LocaleMap::apply_deep(base_name, value, &mut root);
+                        found = true;
+                    },
+                    Ok(None) => {},
+                    Err(error) => match errors.as_mut() {
+                        Some(errors) => errors.push(error),
+                        // An overlay only rewords some messages, so the locale loads without it.
+                        None => self.report(Diagnostic {
+                            severity: DiagnosticSeverity::Warning,
+                            locale: locale.clone(),
+                            kind: DiagnosticKind::OverlayAssetFailed { path: error.path, reason: error.kind },
+                        }),
+                    },
+                }
+            }
+            if found {
+                r.overlays.insert(overlay.clone(), MessageTree::from_json(&root, self._config._normalization));
+            }
+        }
+        Some(r)
+    }
+
+    /// Indexes the messages of the asset tree of a locale, compressed if
+    /// `LocaleMapAssetOptions::compress_in_memory()` is specified.
+    fn message_tree_of(&self, root: &serde_json::Value) -> MessageTree {
+        #[cfg(feature = "compression")]
+        if self._config._assets_compress_in_memory {
+            return MessageTree::from_json_compressed(root, self._config._normalization);
+        }
+        MessageTree::from_json(root, self._config._normalization)
+    }
+
+    /// Returns the directories whose asset files a locale overrides if the assets are
+    /// layered, from the most general, such as `["zh", "zh-Hant"]` for `zh-Hant-TW`.
+    fn base_layers(&self, locale: &Locale) -> Vec<String> {
+        if !self._config._assets_layered {
+            return vec![];
+        }
+        let tag = locale.standard_tag().to_string();
+        let subtags: Vec<&str> = tag.split('-').take_while(|s| s.len() != 1).collect();
+        (1..subtags.len()).map(|n| {
+            let prefix = subtags[..n].join("-");
+            parse_locale(&prefix).ok()
+                .and_then(|l| self._config._locale_path_components.get(&l).cloned())
+                .unwrap_or(prefix)
+        }).collect()
+    }
+
+    /// Loads an asset file of a locale from a directory, returning `Ok(None)` if it does not exist
+    /// and an error if it cannot be parsed.
+    async fn load_asset_file(&self, locale: &Locale, locale_path_comp: &str, base_name: &str) -> Result<Option<(serde_json::Value, u64)>, AssetLoadError> {
+        let res_path = format!("{}/{}/{}", self._config._assets_src, locale_path_comp, base_name);
+        let res_path_ext = format!("{}.{}", res_path, self._config._assets_format.extension());
+        let content = match self.read_verified_asset(locale, &res_path_ext).await? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+        let version = LocaleMap::content_version(&content);
+        let mut value = match self._config._assets_format.parse(String::from_utf8_lossy(&content).as_ref()) {
+            Ok(value) => value,
+            Err(e) => {
+                LocaleMap::report_load_failure(format!("Failed to parse resource at {}: {}", res_path_ext, e));
+                return Err(AssetLoadError { locale: locale.clone(), path: res_path_ext, kind: AssetLoadErrorKind::Invalid(e.to_string()) });
+            },
+        };
+        if let LocaleMapAssetFormat::AppleStrings = self._config._assets_format {
+            // Plural messages live in an optional .stringsdict companion file.
+            if let Some(content) = self.read_verified_asset(locale, &format!("{}.stringsdict", res_path)).await? {
+                match parse_apple_stringsdict(String::from_utf8_lossy(&content).as_ref()) {
+                    Ok(plurals) => value.as_object_mut().unwrap().extend(plurals.as_object().unwrap().clone()),
+                    Err(e) => {
+                        LocaleMap::report_load_failure(format!("Failed to parse resource at {}.stringsdict: {}", res_path, e));
+                        return Err(AssetLoadError { locale: locale.clone(), path: format!("{}.stringsdict", res_path), kind: AssetLoadErrorKind::Invalid(e.to_string()) });
+                    },
+                }
+            }
+        }
+        Ok(Some((value, version)))
+    }
+
+    /// Verifies an asset file fetched over HTTP as specified through
+    /// `LocaleMapAssetOptions::integrity()`.
+    #[cfg(feature = "integrity")]
+    async fn verify_asset(&self, locale: &Locale, path: &str, content: &[u8]) -> Result<(), AssetLoadError> {
+        let integrity = match (self._config._assets_loader_type, self._config._assets_integrity.as_ref()) {
+            (LocaleMapLoaderType::Http, Some(integrity)) => integrity,
+            _ => return Ok(()),
+        };
+        let signature = if integrity.requires_signature() { self.fetch_file(&format!("{}.sig", path)).await } else { None };
+        let relative_path = path.strip_prefix(&self._config._assets_src).unwrap_or(path).trim_start_matches('/');
+        let directory = relative_path.split('/').next().unwrap_or_default();
+        let version = self._session._asset_manifest.as_ref().and_then(|manifest| manifest.versions.get(directory));
+        integrity.verify(relative_path, version.map(|v| v.as_str()), content, signature.as_deref()).map_err(|reason| {
+            LocaleMap::report_load_failure(format!("Failed to verify resource at {}: {}", path, reason));
+            AssetLoadError { locale: locale.clone(), path: path.to_string(), kind: AssetLoadErrorKind::Untrusted(reason) }
+        })
+    }
+
+    /// Reports why assets failed to load, as a `tracing` warning if the `tracing`
+    /// feature is enabled, or to the standard output otherwise.
+    fn report_load_failure(message: String) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!("{}", message);
+        #[cfg(not(feature = "tracing"))]
+        println!("{}", message);
+    }
+
+    /// Reads an asset file, verifying its content as read, which may be compressed, as
+    /// specified through `LocaleMapAssetOptions::integrity()`, before decompressing it.
+    #[cfg_attr(not(any(feature = "integrity", feature = "compression")), allow(unused_variables))]
+    async fn read_verified_asset(&self, locale: &Locale, path: &str) -> Result<Option<Vec<u8>>, AssetLoadError> {
+        let (path, content) = match self.read_asset(path).await {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+        #[cfg(feature = "integrity")]
+        self.verify_asset(locale, &path, &content).await?;
+        #[cfg(feature = "compression")]
+        let content = compression::decompress_asset(&path, content).map_err(|reason| {
+            LocaleMap::report_load_failure(format!("Failed to decompress resource at {}: {}", path, reason));
+            AssetLoadError { locale: locale.clone(), path: path.clone(), kind: AssetLoadErrorKind::Invalid(reason) }
+        })?;
+        Ok(Some(content))
+    }
+
+    async fn read_asset(&self, path: &str) -> Option<(String, Vec<u8>)> {
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::debug_span!("fetch_asset", path);
+            let start = std::time::Instant::now();
+            let r = tracing::Instrument::instrument(self.fetch_asset(path), span.clone()).await;
+            tracing::debug!(parent: &span, elapsed_ms = start.elapsed().as_millis() as u64, found = r.is_some(), "fetched asset");
+            r
+        }
+        #[cfg(not(feature = "tracing"))]
+        self.fetch_asset(path).await
+    }
+
+    /// Reads an asset file, returning the path it was read from along with its content.
+    /// With the `compression` feature, a file that is not found is read from the same
+    /// path with the `.gz` or `.br` extension appended.
+    async fn fetch_asset(&self, path: &str) -> Option<(String, Vec<u8>)> {
+        #[cfg(feature = "compression")]
+        {
+            for path in [path.to_string(), format!("{}.gz", path), format!("{}.br", path)] {
+                if let Some(content) = self.fetch_file(&path).await {
+                    return Some((path, content));
+                }
+            }
+            None
+        }
+        #[cfg(not(feature = "compression"))]
+        self.fetch_file(path).await.map(|content| (path.to_string(), content))
+    }
+
+    async fn fetch_file(&self, path: &str) -> Option<Vec<u8>> {
+        match self._config._assets_loader_type {
+            LocaleMapLoaderType::FileSystem => std::fs::read(path).ok(),
+            LocaleMapLoaderType::Http => {
+                let response = reqwest::get(reqwest::Url::parse(path).ok()?).await.ok()?;
+                if !response.status().is_success() {
+                    return None;
+                }
+                response.bytes().await.ok().map(|b| b.to_vec())
+            },
+        }
+    }
+
+    /// Returns the path or URL of an asset file.
+    pub(crate) fn asset_path(&self, locale: &Locale, base_name: &str) -> Option<String> {
+        let locale_path_comp = self._config._locale_path_components.get(locale)?;
+        Some(format!("{}/{}/{}.json", self._config._assets_src, locale_path_comp, base_name))
+    }
+
+    /// Fingerprints the content of an asset file, used for detecting
+    /// whether it changed since it was loaded.
+    pub(crate) fn content_version(content: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns an editor for changing the messages of a supported locale
+    /// and writing them back to the asset files.
+    pub fn translation_editor(&mut self, locale: &Locale) -> TranslationEditor<'_> {
+        if !self.supports_locale(locale) {
+            panic!("Unsupported locale {}", locale.standard_tag());
+        }
+        TranslationEditor::new(self, locale.clone())
+    }
+
+    /// Converts text to the normalization form of `LocaleMapOptions::normalization()`.
+    fn normalize_text(&self, text: String) -> String {
+        match self._config._normalization {
+            #[cfg(feature = "normalization")]
+            Some(form) if !is_normalized(&text, form) => normalize(text, form),
+            _ => text,
+        }
+    }
+
+    /// Adds or replaces a message of a supported locale at runtime, such as a corrected
+    /// translation fetched from a server, without changing the asset files.
+    /// The message is discarded when its locale is reloaded, such as by `load()`
+    /// with `auto_clean` enabled.
+    pub fn insert_message<S: ToString, M: ToString>(&mut self, locale: &Locale, id: S, message: M) {
+        if !self.supports_locale(locale) {
+            panic!("Unsupported locale {}", locale.standard_tag());
+        }
+        self.invalidate_message_cache();
+        let (id, message) = (self.normalize_text(id.to_string()), self.normalize_text(message.to_string()));
+        Rc::make_mut(&mut self._session._assets).entry(locale.clone()).or_default().set(&id, message);
+    }
+
+    /// Adds or replaces the message of an experiment arm at runtime, such as `"B"`
+    /// for `cta.signup`; see `set_variant_selector()`.
+    pub fn insert_variant<S: ToString, A: ToString, M: ToString>(&mut self, locale: &Locale, id: S, arm: A, message: M) {
+        self.insert_message(locale, format!("{}#{}", id.to_string(), arm.to_string()), message);
+    }
+
+    /// Removes a message of a locale at runtime without changing the asset files,
+    /// so that its fallbacks provide it instead. Returns `false` if the locale
+    /// has no such message.
+    pub fn remove_message<S: ToString>(&mut self, locale: &Locale, id: S) -> bool {
+        let id = id.to_string();
+        if !self._session._assets.get(locale).map(|tree| tree.contains(&id)).unwrap_or(false) {
+            return false;
+        }
+        self.invalidate_message_cache();
+        Rc::make_mut(&mut self._session._assets).get_mut(locale).unwrap().remove(&id)
+    }
+
+    fn apply_deep(name: &str, assign: serde_json::Value, mut output: &mut serde_json::Value) {
+        let mut names: Vec<&str> = name.split("/").collect();
+        let last_name = names.pop();
+        for name in names {
+            let r = output.get(name);
+            if r.is_none() || r.unwrap().as_object().is_none() {
+                let r = serde_json::Value::Object(serde_json::Map::new());
+                output.as_object_mut().unwrap().insert(String::from(name), r);
+            }
+            output = output.get_mut(name).unwrap();
+        }
+        output.as_object_mut().unwrap().insert(String::from(last_name.unwrap()), assign);
+    }
+
+    /// Merges an asset tree into another, replacing messages and merging
+    /// objects of nested messages.
+    pub(crate) fn merge_deep(output: &mut serde_json::Value, assign: serde_json::Value) {
+        let output = output.as_object_mut().unwrap();
+        if let serde_json::Value::Object(assign) = assign {
+            for (name, value) in assign {
+                let is_group = |v: &serde_json::Value| v.is_object() && message_metadata::message_of(v).is_none();
+                match output.get_mut(&name) {
+                    Some(existing) if is_group(existing) && is_group(&value) => LocaleMap::merge_deep(existing, value),
+                    _ => { output.insert(name, value); },
+                }
+            }
+        }
+    }
+
+    pub(crate) fn enumerate_fallbacks(&self, locale: Locale, output: &mut HashSet<Locale>) {
+        for list in self._config._fallbacks.get(&locale).iter() {
+            for item in list.iter() {
+                if output.insert(item.clone()) {
+                    self.enumerate_fallbacks(item.clone(), output);
+                }
+            }
+        }
+    }
+
+    /// Retrieves message by identifier.
+    pub fn get<S: ToString>(&self, id: S) -> String {
+        self.get_formatted(id, vec![])
+    }
+
+    /// Retrieves message by identifier with formatting arguments.
+    /// A number selects the `_empty`, `_one` or `_multiple` variant of the message,
+    /// or its `_zero` and `_negative` variants if present; see `quantity_variant()`.
+    /// Only the first number selects the variant, whatever its type, so that
+    /// `vec![&1u64, &5i64]` selects `_one`; further quantities are given as `Count`
+    /// arguments. Up to version 1.4, the last number of the highest-priority type did.
+    /// If a variant selector is set, the message of the selected experiment arm is
+    /// retrieved instead; see `set_variant_selector()`.
+    pub fn get_formatted<S: ToString>(&self, id: S, options: Vec<&dyn LocaleMapFormatArgument>) -> String {
+        let key = id.to_string();
+        let (id, variables, message) = self.formatted_message(key.clone(), options);
+        let recording = self._session._recorded_lookups.borrow().is_some();
+        let locale = message.as_ref().filter(|_| recording).map(|message| message.locale.clone());
+        let text = match (message, self._session._key_reveal.get()) {
+            (None, _) => id.clone(),
+            (Some(_), Some(KeyReveal::Replace)) => key,
+            (Some(message), Some(KeyReveal::Prefix)) => format!("[{}] {}", key, self.apply_message(&id, &message, &variables)),
+            (Some(message), None) => self.apply_message(&id, &message, &variables),
+        };
+        if recording {
+            self.record_lookup(id, locale, &text);
+        }
+        text
+    }
+
+    /// Renders messages retrieved through `get()`, `get_formatted()` and `get_formatted_parts()`
+    /// with their identifiers, as requested without the gender and quantity suffixes,
+    /// such as for testers to find the message of a text on screen, or stops doing so
+    /// if `None`. Missing messages are their identifiers either way.
+    pub fn set_key_reveal(&self, mode: Option<KeyReveal>) {
+        self._session._key_reveal.set(mode);
+    }
+
+    /// Returns the mode set through `set_key_reveal()`, if any.
+    pub fn key_reveal(&self) -> Option<KeyReveal> {
+        self._session._key_reveal.get()
+    }
+
+    /// Retrieves message by identifier with formatting arguments as `get_formatted()` does,
+    /// split into the literal text and the values of variables, such as for styling
+    /// the values apart from the text. Numbers and dates given in a `FormatValue` map
+    /// and quantities are `FormattedPart::Number` and `FormattedPart::DateTime` parts.
+    /// The messages selected by `${name|plural}` and `${name|gender}` are split as well.
+    /// A missing message is the identifier as a literal part.
+    pub fn get_formatted_parts<S: ToString>(&self, id: S, options: Vec<&dyn LocaleMapFormatArgument>) -> Vec<FormattedPart> {
+        let key = id.to_string();
+        let (id, variables, message) = self.formatted_message(key.clone(), options);
+        let recording = self._session._recorded_lookups.borrow().is_some();
+        let locale = message.as_ref().filter(|_| recording).map(|message| message.locale.clone());
+        let parts = match (message, self._session._key_reveal.get()) {
+            (None, _) => vec![FormattedPart::Literal(id.clone())],
+            (Some(_), Some(KeyReveal::Replace)) => vec![FormattedPart::Literal(key)],
+            (Some(message), Some(KeyReveal::Prefix)) => {
+                let mut parts = vec![FormattedPart::Literal(format!("[{}] ", key))];
+                self.message_parts(&id, &message, &variables).into_iter().for_each(|part| push_part(&mut parts, part));
+                parts
+            },
+            (Some(message), None) => self.message_parts(&id, &message, &variables),
+        };
+        if recording {
+            self.record_lookup(id, locale, &parts.iter().map(FormattedPart::value).collect::<String>());
+        }
+        parts
+    }
+
+    /// Retrieves message by identifier with formatting arguments as `get_formatted_parts()` does,
+    /// attributing each part the language it is written in, for screen readers and
+    /// text-to-speech engines. Text is in the language of the locale that provided
+    /// its message, which may be a fallback locale, and so are the values of variables,
+    /// unless marked with the `lang` formatter, as in `"The French say ${phrase|lang:fr}"`.
+    pub fn get_attributed<S: ToString>(&self, id: S, options: Vec<&dyn LocaleMapFormatArgument>) -> AttributedString {
+        let (id, variables, message) = self.formatted_message(id.to_string(), options);
+        match message {
+            Some(message) => AttributedString {
+                language: message.locale.standard_tag().to_string(),
+                segments: self.message_segments(&id, &message, &variables),
+            },
+            None => {
+                let language = self._session._current_locale.as_ref().unwrap_or(&self._config._default_locale).standard_tag().to_string();
+                AttributedString { segments: vec![AttributedSegment { part: FormattedPart::Literal(id), language: language.clone(), machine_translated: false }], language }
+            },
+        }
+    }
+
+    /// Selects the variant of a message and the experiment arm, returning the
+    /// selected identifier, the variables and the message, if it exists.
+    fn formatted_message(&self, id: String, options: Vec<&dyn LocaleMapFormatArgument>) -> (String, MessageArguments, Option<Rc<CompiledMessage>>) {
+        let id = self.normalize_text(id);
+        let arm = self._variant_selector.as_ref().and_then(|select| select(&id));
+        let (id, mut variables) = self.select_message_variant(id, options);
+        if self._config._normalization.is_some() {
+            variables.variables.values_mut().for_each(|value| *value = self.normalize_text(std::mem::take(value)));
+        }
+        self.capture_id(&id);
+        let message = self._session._current_locale.as_ref().and_then(|locale| match arm {
+            Some(arm) => self.experiment_variant(locale, &id, &arm),
+            None => self.compiled_message(locale, &id),
+        });
+        (id, variables, message)
+    }
+
+    /// Returns the variant of a message for an experiment arm, or the message itself
+    /// if the variant is missing. The variant is only used if it is provided by the same
+    /// locale as the message, so that an untranslated variant does not replace
+    /// a translated message.
+    fn experiment_variant(&self, locale: &Locale, id: &str, arm: &str) -> Option<Rc<CompiledMessage>> {
+        let mut message = self.compiled_message(locale, id);
+        if let Some(variant) = self.compiled_message(locale, &format!("{}#{}", id, arm)) {
+            if message.as_ref().map(|m| m.locale == variant.locale).unwrap_or(true) {
+                message = Some(variant);
+            }
+        }
+        message
+    }
+
+    /// Sets a function that selects the experiment arm, such as `"B"`, for a message
+    /// identifier, or `None` for the default message. The message of an arm is the
+    /// message at the identifier followed by `#` and the arm, such as `cta.signup#B`,
+    /// which may be written in the asset files or added through `insert_variant()`.
+    /// Gender and quantity suffixes are selected before the arm, such as in `qty_one#B`,
+    /// while the function receives the identifier without them.
+    pub fn set_variant_selector<F: Fn(&str) -> Option<String> + 'static>(&mut self, selector: F) {
+        self._variant_selector = Some(Rc::new(selector));
+    }
+
+    /// Removes the function set through `set_variant_selector()`.
+    pub fn clear_variant_selector(&mut self) {
+        self._variant_selector = None;
+    }
+
+    /// Retrieves the screen-reader label of a message by identifier.
+    /// This is the message at the `id@a11y` companion key if it exists,
+    /// such as `"Save the document"` for a visual `"Save"`,
+    /// otherwise the message at `id` itself.
+    pub fn get_accessibility<S: ToString>(&self, id: S) -> String {
+        self.get_accessibility_formatted(id, vec![])
+    }
+
+    /// Retrieves the screen-reader label of a message by identifier with formatting arguments.
+    /// Gender and quantity suffixes are selected before the `@a11y` suffix,
+    /// such as in `qty_one@a11y`.
+    pub fn get_accessibility_formatted<S: ToString>(&self, id: S, options: Vec<&dyn LocaleMapFormatArgument>) -> String {
+        let (id, variables) = self.select_message_variant(id.to_string(), options);
+        self.capture_id(&id);
+        self.format_message(&format!("{}@a11y", id), &variables)
+            .or_else(|| self.format_message(&id, &variables))
+            .unwrap_or(id)
+    }
+
+    /// Retrieves a message by identifier both in the current locale and in a secondary locale,
+    /// such as for review builds and language-learning interfaces. The secondary locale's
+    /// messages must have been loaded, such as through `load_secondary_locale()`.
+    pub fn get_bilingual<S: ToString>(&self, id: S, secondary: &Locale) -> BilingualMessage {
+        self.get_bilingual_formatted(id, secondary, vec![])
+    }
+
+    /// Retrieves a message by identifier with formatting arguments
+    /// both in the current locale and in a secondary locale.
+    pub fn get_bilingual_formatted<S: ToString>(&self, id: S, secondary: &Locale, options: Vec<&dyn LocaleMapFormatArgument>) -> BilingualMessage {
+        let (id, variables) = self.select_message_variant(id.to_string(), options);
+        self.capture_id(&id);
+        BilingualMessage {
+            primary: self.format_message(&id, &variables).unwrap_or_else(|| id.clone()),
+            secondary: self.get_formatted_with_locale(secondary, &id, &variables).unwrap_or(id),
+        }
+    }
+
+    /// Appends the gender and quantity suffixes selected by the formatting arguments
+    /// to a message identifier and collects the message variables.
+    /// Only the first number selects the quantity suffix, regardless of its type;
+    /// further quantities are given as `Count` arguments.
+    fn select_message_variant(&self, id: String, options: Vec<&dyn LocaleMapFormatArgument>) -> (String, MessageArguments) {
+        let mut variables: Option<MessageArguments> = None;
+        let mut gender: Option<Gender> = None;
+        let mut amount: Option<String> = None;
+        let mut counts: Vec<Count> = vec![];
+        let mut genders: Vec<(String, Gender)> = vec![];
+        let mut range: Option<PluralRange> = None;
+        // A floating-point number selects the quantity suffix as formatted by `number_formatter()`,
+        // so that 0.0001 selects `_empty` when displayed as 0.
+        let mut float_amount: Option<f64> = None;
+
+        for option in options.iter() {
+            if let Some(r) = option.as_gender() {
+                gender = Some(r);
+            }
+            else if let Some(r) = option.as_string_map() {
+                variables = Some(MessageArguments { variables: r.into_iter().collect(), ..MessageArguments::new() });
+            }
+            else if let Some(r) = option.as_value_map() {
+                let (numbers, dates) = (self.number_formatter(), self.date_time_formatter());
+                variables = Some(MessageArguments {
+                    variables: r.iter().map(|(k, v)| (k.clone(), v.format(&numbers, &dates))).collect(),
+                    kinds: r.iter().filter_map(|(k, v)| Some((k.clone(), v.kind()?))).collect(),
+                    ..MessageArguments::new()
+                });
+            }
+            else if let Some(r) = option.as_count() { counts.push(r) }
+            else if let Some(r) = option.as_gender_of() { genders.push(r) }
+            else if let Some(r) = option.as_plural_range() { range = Some(r) }
+            else if amount.is_none() {
+                float_amount = option.as_f64();
+                amount = option.as_i64().map(|n| n.to_string())
+                    .or_else(|| option.as_u64().map(|n| n.to_string()))
+                    .or_else(|| option.as_i128().map(|n| n.to_string()))
+                    .or_else(|| option.as_u128().map(|n| n.to_string()))
+                    .or_else(|| option.as_f64().map(|n| n.to_string()));
+            }
+        }
+
+        let mut id = id;
+        if let Some(g) = gender {
+            id.push_str(gender_suffix(g));
+        }
+
+        let mut variables = variables.unwrap_or_default();
+
+        // id_empty, id_one, id_multiple, id_zero, id_negative and $number variable
+        if let Some(qty) = amount {
+            let operands = float_amount.map(|n| self.number_formatter().plural_operands(n));
+            id = self.select_quantity_variant(id, &qty, operands);
+            variables.insert_number("number", self.numbering_system().transliterate(qty));
+        }
+
+        // id_range_few, with the $start and $end variables.
+        if let Some(range) = range {
+            let category = self.select_plural_range(range.start.as_str(), range.end.as_str()).unwrap_or(PluralCategory::OTHER);
+            id.push_str("_range_");
+            id.push_str(plural_category_keyword(&category));
+            variables.insert_number("start", self.numbering_system().transliterate(range.start));
+            variables.insert_number("end", self.numbering_system().transliterate(range.end));
+        }
+
+        // Counts and genders select the messages of `${name|plural}` and `${name|gender}`.
+        for count in counts {
+            variables.plurals.insert(count.name.clone(), self.quantity_suffixes(&count.number, None));
+            variables.insert_number(count.name, self.numbering_system().transliterate(count.number));
+        }
+        variables.genders.extend(genders);
+
+        (id, variables)
+    }
+
+    /// Lists the quantity suffixes a number selects, from the most specific, of which
+    /// a message uses the first it has, or else the last one:
+    /// `_negative` for a negative number, before `_multiple`, and `_zero` for zero
+    /// and the numbers in the `zero` plural category of the current locale,
+    /// such as 10 in Latvian, before `_empty` or the suffix they otherwise select.
+    fn quantity_suffixes(&self, number: &str, operands: Option<PluralOperands>) -> Vec<&'static str> {
+        let operands = operands.or_else(|| number.try_into().ok());
+        let suffix = operands.as_ref().map(plural_operands_suffix).unwrap_or_else(|| quantity_suffix(number));
+        let zero_category = operands.map(|o| self.select_plural_rule(PluralRuleType::CARDINAL, o) == Ok(PluralCategory::ZERO)).unwrap_or(false);
+        quantity_suffixes(number, suffix, zero_category)
+    }
+
+    /// Returns the identifier of the message variant selected by a quantity in the current
+    /// locale, such as `cart.balance_negative` for -5 if the message has the variant
+    /// or else `cart.balance_multiple`; see `get_formatted()`.
+    pub fn quantity_variant<S: ToString, N: ToString>(&self, id: S, number: N) -> String {
+        self.select_quantity_variant(id.to_string(), &number.to_string(), None)
+    }
+
+    fn select_quantity_variant(&self, id: String, number: &str, operands: Option<PluralOperands>) -> String {
+        let suffixes = self.quantity_suffixes(number, operands);
+        let suffix = suffixes.iter().find(|s| self.has_key(format!("{}{}", id, s))).unwrap_or_else(|| suffixes.last().unwrap());
+        id + suffix
+    }
+
+    /// Whether a message exists in a locale or its fallbacks, without reporting it as missing.
+    fn has_message(&self, locale: &Locale, id: &str) -> bool {
+        self.resolve_message(locale, id, 0, None).is_some()
+    }
+
+    fn capture_id(&self, id: &str) {
+        if let Some(captured_ids) = self._session._captured_ids.borrow_mut().as_mut() {
+            if !captured_ids.iter().any(|captured_id| captured_id == id) {
+                captured_ids.push(id.to_string());
+            }
+        }
+    }
+
+    /// Formats the message at a complete identifier in the current locale or its fallbacks.
+    fn format_message(&self, id: &str, vars: &MessageArguments) -> Option<String> {
+        self.get_formatted_with_locale(self._session._current_locale.as_ref()?, id, vars)
+    }
+
+    /// Lists up to `limit` message identifiers that start with `prefix` and are present
+    /// in any loaded locale, with whether each loaded locale has them. Identifiers are
+    /// ordered component by component; pass the `next_cursor` of a page as `cursor`
+    /// to request the page that follows it.
+    pub fn key_page(&self, prefix: &str, cursor: Option<&str>, limit: usize) -> KeyPage {
+        key_page::key_page(&self._session._assets, prefix, cursor, limit)
+    }
+
+    /// Lists the identifiers of the messages present in any loaded locale,
+    /// ordered component by component.
+    pub fn keys(&self) -> Vec<String> {
+        self.key_page("", None, usize::MAX).entries.into_iter().map(|e| e.id).collect()
+    }
+
+    /// Returns the messages stored for a loaded locale, not resolved through fallbacks.
+    pub fn messages_for(&self, locale: &Locale) -> Option<&MessageTree> {
+        self._session._assets.get(locale)
+    }
+
+    /// Returns a `LocaleMap` sharing the loaded assets whose messages are taken from
+    /// an overlay, such as the wording of a tenant or brand, before the base messages
+    /// of each locale. Overlays are specified through `LocaleMapAssetOptions::overlays()`.
+    /// Locales loaded afterwards through either `LocaleMap` are not shared.
+    pub fn with_overlay<S: ToString>(&self, name: S) -> LocaleMap {
+        let mut r = self.clone();
+        r._session._overlay = Some(name.to_string());
+        r
+    }
+
+    /// Returns the overlay selected through `with_overlay()`, if any.
+    pub fn overlay(&self) -> Option<String> {
+        self._session._overlay.clone()
+    }
+
+    /// Returns `true` if a message exists in the current locale or its fallbacks,
+    /// that is, if `get()` would not return the identifier itself.
+    pub fn has_key<S: ToString>(&self, id: S) -> bool {
+        let id = id.to_string();
+        self._session._current_locale.as_ref().map(|l| self.has_message(l, &id) || self.provided_translation(l, &id).is_some()).unwrap_or(false)
+    }
+
+    /// Returns the number of messages stored for a locale, not counting its fallbacks,
+    /// or 0 if the locale is not loaded.
+    pub fn len_for(&self, locale: &Locale) -> usize {
+        self._session._assets.get(locale).map(|tree| tree.len()).unwrap_or(0)
+    }
+
+    /// Exports the messages of a loaded locale as CSV with the columns `key`, `source`,
+    /// `translation` and `comment`, for translators working in spreadsheets. The source
+    /// is the message of the default locale, the comment is the message description
+    /// and messages are not resolved through fallbacks.
+    /// The edited file can be merged back with `import_csv()`.
+    pub fn export_csv(&self, locale: &Locale) -> String {
+        let source = self._session._assets.get(&self._config._default_locale);
+        let translation = self._session._assets.get(locale);
+        let mut ids: Vec<&str> = source.into_iter().chain(translation).flat_map(|tree| tree.keys()).collect();
+        ids.sort_by(|a, b| key_page::compare_ids(a, b));
+        ids.dedup();
+
+        let mut r = String::new();
+        csv::write_record(&mut r, &["key", "source", "translation", "comment"]);
+        for id in ids {
+            let description = translation.into_iter().chain(source)
+                .find_map(|tree| tree.metadata(id).and_then(|metadata| metadata.description.clone()));
+            csv::write_record(&mut r, &[
+                id.to_string(),
+                source.and_then(|tree| tree.get(id)).unwrap_or_default().to_string(),
+                translation.and_then(|tree| tree.get(id)).unwrap_or_default().to_string(),
+                description.unwrap_or_default(),
+            ]);
+        }
+        r
+    }
+
+    /// Exports the messages of every loaded locale as CSV with a `key` column
+    /// followed by one column per locale, named by its tag, such as `pt-BR`.
+    pub fn export_csv_wide(&self) -> String {
+        let page = key_page::key_page(&self._session._assets, "", None, usize::MAX);
+        let mut locales: Vec<&Locale> = self._session._assets.keys().collect();
+        locales.sort();
+
+        let mut r = String::new();
+        let mut header = vec!["key".to_string()];
+        header.extend(locales.iter().map(|l| l.standard_tag().to_string()));
+        csv::write_record(&mut r, &header);
+        for entry in page.entries {
+            let mut record = vec![entry.id.clone()];
+            record.extend(locales.iter().map(|l| self._session._assets[*l].get(&entry.id).unwrap_or_default().to_string()));
+            csv::write_record(&mut r, &record);
+        }
+        r
+    }
+
+    /// Merges the translations of a CSV file produced by `export_csv()` or
+    /// `export_csv_wide()` into a supported locale and writes the changed asset files.
+    /// Returns how many messages changed; see `TranslationEditor::import_csv()`.
+    pub fn import_csv(&mut self, locale: &Locale, content: &str) -> Result<usize, TranslationEditError> {
+        let mut editor = self.translation_editor(locale);
+        let r = editor.import_csv(content)?;
+        editor.save()?;
+        Ok(r)
+    }
+
+    /// Starts recording the identifiers of the messages retrieved through `get()`
+    /// and `get_formatted()`, such as the ones displayed in the current screen.
+    /// Any previously captured identifiers are discarded.
+    pub fn start_capture(&self) {
+        self._session._captured_ids.replace(Some(vec![]));
+    }
+
+    /// Stops recording message identifiers and returns the captured ones.
+    pub fn stop_capture(&self) -> Vec<String> {
+        self._session._captured_ids.replace(None).unwrap_or_default()
+    }
+
+    /// Returns the message identifiers captured so far, in the order they were first retrieved,
+    /// including the suffixes selected by gender and quantity arguments.
+    pub fn captured_ids(&self) -> Vec<String> {
+        self._session._captured_ids.borrow().clone().unwrap_or_default()
+    }
+
+    /// Starts recording the identifier, the locale that provided the message and the text
+    /// of every message retrieved through `get()`, `get_formatted()` and `get_formatted_parts()`,
+    /// such as during an automated UI run taking screenshots; see `lookup_records_to_json()`.
+    /// Any previously recorded lookups are discarded.
+    pub fn start_lookup_recording(&self) {
+        self._session._recorded_lookups.replace(Some(vec![]));
+    }
+
+    /// Stops recording lookups and returns the recorded ones.
+    pub fn stop_lookup_recording(&self) -> Vec<LookupRecord> {
+        self._session._recorded_lookups.replace(None).unwrap_or_default()
+    }
+
+    /// Returns the lookups recorded so far, in the order they first happened,
+    /// without repeating identical ones.
+    pub fn recorded_lookups(&self) -> Vec<LookupRecord> {
+        self._session._recorded_lookups.borrow().clone().unwrap_or_default()
+    }
+
+    /// Starts counting the lookups of every message identifier, such as over a session
+    /// of a user or an automated UI run, to find the messages that are never used;
+    /// see `unused_keys()`. The identifiers include the suffixes selected by gender and
+    /// quantity arguments, and messages referenced by other messages are counted as well.
+    /// Any previous counts are discarded.
+    pub fn start_usage_recording(&self) {
+        self._session._usage_counts.replace(Some(HashMap::new()));
+    }
+
+    /// Stops counting lookups and returns the number of lookups of each message identifier.
+    pub fn stop_usage_recording(&self) -> HashMap<String, usize> {
+        self._session._usage_counts.replace(None).unwrap_or_default()
+    }
+
+    /// Returns the number of lookups of each message identifier counted so far,
+    /// including identifiers of missing messages.
+    pub fn usage_counts(&self) -> HashMap<String, usize> {
+        self._session._usage_counts.borrow().clone().unwrap_or_default()
+    }
+
+    /// Lists the identifiers of the messages present in any loaded locale that have
+    /// not been looked up since usage recording started, ordered component by component,
+    /// such as for pruning stale translations.
+    pub fn unused_keys(&self) -> Vec<String> {
+        let counts = self._session._usage_counts.borrow();
+        self.keys().into_iter().filter(|id| !counts.as_ref().is_some_and(|counts| counts.contains_key(id))).collect()
+    }
+
+    fn record_lookup(&self, id: String, locale: Option<Locale>, text: &str) {
+        if let Some(records) = self._session._recorded_lookups.borrow_mut().as_mut() {
+            let locale = locale.map(|locale| locale.standard_tag().to_string());
+            if !records.iter().any(|r| r.id == id && r.locale == locale && r.text == text) {
+                records.push(LookupRecord { id, locale, text: text.to_string() });
+            }
+        }
+    }
+
+    fn get_formatted_with_locale(&self, locale: &Locale, id: &str, vars: &MessageArguments) -> Option<String> {
+        self.compiled_message(locale, id).map(|message| self.apply_message(id, &message, vars))
+    }
+
+    /// Resolves a message from a locale or its fallbacks, caching the result until
+    /// the assets change, so that repeated lookups skip traversing the assets
+    /// and tokenizing the message.
+    fn compiled_message(&self, locale: &Locale, id: &str) -> Option<Rc<CompiledMessage>> {
+        if let Some(counts) = self._session._usage_counts.borrow_mut().as_mut() {
+            *counts.entry(id.to_string()).or_insert(0) += 1;
+        }
+        let cached = self._session._message_cache.borrow().get(locale).and_then(|m| m.get(id)).cloned();
+        let cache_hit = cached.is_some();
+        let r = cached.unwrap_or_else(|| {
+            let r = self.resolve_message(locale, id, 0, None).map(|(source, locale, depth)| Rc::new(CompiledMessage {
+                parts: tokenize(&source),
+                locale,
+                depth,
+                machine_translated: false,
+            })).or_else(|| self.provided_translation(locale, id));
+            // Reported once per message until the assets change, since the result is cached.
+            // Identifiers containing `#` are experiment variants, which are optional.
+            #[cfg(feature = "tracing")]
+            match r.as_ref() {
+                None if !id.contains('#') => tracing::warn!(id, locale = %locale.standard_tag(), "missing message"),
+                Some(message) if message.depth > 0 => tracing::debug!(id, locale = %locale.standard_tag(), fallback = %message.locale.standard_tag(), "message resolved from fallback"),
+                _ => {},
+            }
+            let mut cache = self._session._message_cache.borrow_mut();
+            if r.is_none() {
+                if self._session._cached_misses.get() >= MAX_CACHED_MISSES {
+                    for messages in cache.values_mut() {
+                        messages.retain(|_, message| message.is_some());
+                    }
+                    self._session._cached_misses.set(0);
+                }
+                self._session._cached_misses.set(self._session._cached_misses.get() + 1);
+            }
+            cache.entry(locale.clone()).or_default().insert(id.to_string(), r.clone());
+            r
+        });
+        // Recorded on every lookup, cached or not, so that a message the provider failed
+        // to translate is requested again the next time it is looked up.
+        if r.is_none() {
+            self.record_missing_translation(locale, id);
+        }
+        if let Some(metrics) = self._metrics.as_ref().filter(|_| !id.contains('#')) {
+            metrics.lookup(locale, id);
+            if cache_hit {
+                metrics.cache_hit(locale, id);
+            }
+            match r.as_ref() {
+                None => metrics.missing(locale, id),
+                Some(message) if message.depth > 0 => metrics.fallback(locale, id, message.depth),
+                _ => {},
+            }
+        }
+        r
+    }
+
+    /// Returns the translation of a message missing in a locale and its fallbacks
+    /// given by the `MissingTranslationProvider`.
+    fn provided_translation(&self, locale: &Locale, id: &str) -> Option<Rc<CompiledMessage>> {
+        let translation = self._session._provided_translations.get(locale)?.get(id)?;
+        Some(Rc::new(CompiledMessage {
+            parts: tokenize(&translation.message),
+            locale: locale.clone(),
+            depth: 0,
+            machine_translated: translation.machine_translated,
+        }))
+    }
+
+    /// Records a message missing in a locale and its fallbacks for `provide_missing_translations()`.
+    /// Identifiers containing `#` and `@` are experiment variants and companion keys,
+    /// which are optional.
+    fn record_missing_translation(&self, locale: &Locale, id: &str) {
+        if self._missing_translation_provider.is_none() || id.contains(['#', '@']) {
+            return;
+        }
+        let mut missing = self._session._missing_translations.borrow_mut();
+        if !missing.iter().any(|(l, i)| l == locale && i == id) {
+            missing.push((locale.clone(), id.to_string()));
+        }
+    }
+
+    /// Translates the messages found missing in a locale and its fallbacks since
+    /// the last call through the `MissingTranslationProvider`, such as in the background
+    /// after rendering a screen, so that later lookups of them return the translations.
+    /// The message in the default locale is given as the source to translate,
+    /// read from its asset files if the locale is not loaded. Returns the number of messages translated.
+    pub async fn provide_missing_translations(&mut self) -> usize {
+        let provider = match self._missing_translation_provider.clone() {
+            Some(provider) => provider,
+            None => return 0,
+        };
+        let missing = self._session._missing_translations.replace(vec![]);
+        if missing.is_empty() {
+            return 0;
+        }
+        let default_locale = self._config._default_locale.clone();
+        let sources = match self._session._assets.get(&default_locale) {
+            Some(tree) => Some(tree.clone()),
+            None => self.load_single_locale(&default_locale, None).await.map(|assets| assets.root),
+        };
+        let mut provided = 0;
+        for (locale, id) in missing {
+            let source = sources.as_ref().and_then(|tree| tree.get(&id)).map(|source| source.to_string());
+            let request = MissingTranslation { locale, id, source };
+            if let Some(translation) = provider.translate(&request).await {
+                let translation = ProvidedTranslation { message: self.normalize_text(translation.message), ..translation };
+                Rc::make_mut(&mut self._session._provided_translations).entry(request.locale).or_default().insert(request.id, translation);
+                provided += 1;
+            }
+        }
+        if provided > 0 {
+            self.invalidate_message_cache();
+        }
+        provided
+    }
+
+    /// Returns `true` if a message is a machine translation given by the
+    /// `MissingTranslationProvider` for the current locale.
+    pub fn is_machine_translated<S: ToString>(&self, id: S) -> bool {
+        self._session._current_locale.as_ref()
+            .and_then(|locale| self._session._provided_translations.get(locale)?.get(&id.to_string()))
+            .map(|translation| translation.machine_translated)
+            .unwrap_or(false)
+    }
+
+    /// Returns a message from a locale or its fallbacks, the locale that provided it
+    /// and the number of fallbacks followed, adding the locales searched to `attempts`, if given,
+    /// as only `resolve_with_trace()` does.
+    fn resolve_message(&self, locale: &Locale, id: &str, depth: usize, mut attempts: Option<&mut Vec<FallbackAttempt>>) -> Option<(String, Locale, usize)> {
+        let overlay = self._session._overlay.as_ref()
+            .and_then(|overlay| self._session._overlay_assets.get(overlay))
+            .and_then(|assets| assets.get(locale))
+            .and_then(|tree| tree.get(id));
+        let tree = self._session._assets.get(locale);
+        let message = overlay.or_else(|| tree.and_then(|tree| tree.get(id)));
+        if let Some(attempts) = attempts.as_mut() {
+            let outcome = match (overlay, tree, message) {
+                (Some(_), _, _) => FallbackOutcome::FoundInOverlay,
+                (None, None, _) => FallbackOutcome::NotLoaded,
+                (None, Some(_), None) => FallbackOutcome::Missing,
+                (None, Some(_), Some(_)) => FallbackOutcome::Found,
+            };
+            attempts.push(FallbackAttempt { locale: locale.clone(), outcome });
+        }
+        if let Some(message) = message {
+            return Some((message.to_string(), locale.clone(), depth));
+        }
+        self._config._fallbacks.get(locale)?.iter().find_map(|fl| self.resolve_message(fl, id, depth + 1, attempts.as_deref_mut()))
+    }
+
+    /// Looks up a message in the current locale and its fallbacks as `get()` does,
+    /// returning the locales searched and the one that provided the message,
+    /// such as for finding out why a message is displayed in another language.
+    /// The gender and quantity suffixes are not selected, so `id` must include them,
+    /// as in `common.qty_one`. The trace is empty if no locale is loaded.
+    pub fn resolve_with_trace<S: ToString>(&self, id: S) -> FallbackTrace {
+        let id = id.to_string();
+        let mut attempts = vec![];
+        let message = self._session._current_locale.as_ref()
+            .and_then(|locale| self.resolve_message(locale, &id, 0, Some(&mut attempts)))
+            .map(|(message, _, _)| message);
+        FallbackTrace { id, attempts, message }
+    }
+
+    /// Discards the cached messages, called whenever the assets change.
+    pub(crate) fn invalidate_message_cache(&self) {
+        self._session._message_cache.borrow_mut().clear();
+        self._session._cached_misses.set(0);
+    }
+
+    fn apply_message(&self, id: &str, message: &Rc<CompiledMessage>, vars: &MessageArguments) -> String {
+        let mut r = String::new();
+        format_message(&CompiledMessages(self), id, message, vars, &mut r);
+        r
+    }
+
+    fn message_parts(&self, id: &str, message: &Rc<CompiledMessage>, vars: &MessageArguments) -> Vec<FormattedPart> {
+        let mut r = FormattedParts::default();
+        format_message(&CompiledMessages(self), id, message, vars, &mut r);
+        r.0
+    }
+
+    fn message_segments(&self, id: &str, message: &Rc<CompiledMessage>, vars: &MessageArguments) -> Vec<AttributedSegment> {
+        let mut r = AttributedSegments::default();
+        format_message(&CompiledMessages(self), id, message, vars, &mut r);
+        r.0
+    }
+
+    /// Registers a function for formatting message variables, used in messages as
+    /// `${name|formatter}` or `${name|formatter:argument}`, such as `${price|currency:EUR}`.
+    /// Formatters can be chained, as in `${name|trim|upper}`. The function receives
+    /// the value, the formatting locale and the argument, if any; see `formatting_locale()`.
+    /// Clones of the `LocaleMap` keep the formatters registered before cloning.
+    pub fn register_formatter<S: ToString, F: Fn(&str, &Locale, Option<&str>) -> String + 'static>(&self, name: S, formatter: F) {
+        Rc::make_mut(&mut self._formatters.borrow_mut()).insert(name.to_string(), Rc::new(formatter));
+    }
+
+    /// Returns the metadata of a message, such as its description, from the first of
+    /// the current locale, its fallbacks and the default locale that specifies any.
+    /// Returns `None` if the message does not exist.
+    pub fn message_metadata<S: ToString>(&self, id: S) -> Option<MessageMetadata> {
+        let id = id.to_string();
+        let mut locales = vec![];
+        if let Some(locale) = self._session._current_locale.clone() {
+            locales.push(locale.clone());
+            self.enumerate_fallbacks_in_order(locale, &mut locales);
+        }
+        locales.push(self._config._default_locale.clone());
+        let mut r = None;
+        for locale in locales {
+            let metadata = self._session._assets.get(&locale).and_then(|tree| tree.metadata(&id)).cloned();
+            if let Some(metadata) = metadata {
+                if metadata != MessageMetadata::default() {
+                    return Some(metadata);
+                }
+                r = Some(metadata);
+            }
+        }
+        r
+    }
+
+    fn enumerate_fallbacks_in_order(&self, locale: Locale, output: &mut Vec<Locale>) {
+        for list in self._config._fallbacks.get(&locale).iter() {
+            for item in list.iter() {
+                output.push(item.clone());
+                self.enumerate_fallbacks_in_order(item.clone(), output);
+            }
+        }
+    }
+
+    /// Selects the plural rule given a `PluralRuleType` and a number.
+    pub fn select_plural_rule<N: TryInto<super::PluralOperands>>(&self, prt: PluralRuleType, number: N) -> Result<PluralCategory, &'static str> {
+        if prt == PluralRuleType::ORDINAL {
+            if let Some(pr) = self._session._current_ordinal_plural_rules.clone() {
+                pr.select::<N>(number)
+            }
+            else {
+                Err("Plural rules missing.")
+            }
+        }
+        else {
+            if let Some(pr) = self._session._current_cardinal_plural_rules.clone() {
+                pr.select::<N>(number)
+            }
+            else {
+                Err("Plural rules missing.")
+            }
+        }
+    }
+
+    /// Selects the cardinal plural category of a range of numbers, such as "3–5 days",
+    /// in the current locale, following the plural ranges of CLDR; see `plural_range_category()`.
+    pub fn select_plural_range<N: TryInto<super::PluralOperands>>(&self, start: N, end: N) -> Result<PluralCategory, &'static str> {
+        let locale = self._session._current_locale.as_ref().ok_or("Plural rules missing.")?;
+        let start = self.select_plural_rule(PluralRuleType::CARDINAL, start)?;
+        let end = self.select_plural_rule(PluralRuleType::CARDINAL, end)?;
+        Ok(plural_range_category(locale, &start, &end))
+    }
+
+    /// Creates a relative-time formatter, which by default
+    /// emits one item (chunk), limits to seconds and has no maximum duration.
+    pub fn create_relative_time_formatter(&self) -> super::RelativeTimeFormatter {
+        if self._session._current_relative_time_formatter.is_none() {
+            panic!("No locale has been loaded.");
+        }
+        self._session._current_relative_time_formatter.clone().unwrap().as_ref().clone()
+    }
+
+    /// Formats a duration into relative-time language, emitting one item.
+    pub fn format_relative_time(&self, duration: std::time::Duration) -> String {
+        self.create_relative_time_formatter().convert(duration)
+    }
+
+    /// Formats the time from now until a point in time, or since it, such as
+    /// "in 5 minutes" or "5 minutes ago". This accepts `std::time::SystemTime` and
+    /// `chrono::DateTime` values in any time zone.
+    pub fn format_relative_time_from<T: Into<std::time::SystemTime>>(&self, time: T) -> String {
+        self.format_relative_time_between(time, std::time::SystemTime::now())
+    }
+
+    /// Formats the time from `now` until a point in time, or since it,
+    /// as `format_relative_time_from()` does.
+    pub fn format_relative_time_between<T: Into<std::time::SystemTime>, N: Into<std::time::SystemTime>>(&self, time: T, now: N) -> String {
+        let (time, now) = (time.into(), now.into());
+        let duration = match time.duration_since(now) {
+            Ok(duration) if duration.as_secs() > 0 => duration,
+            _ => return self.format_relative_time(now.duration_since(time).unwrap_or_default()),
+        };
+        let mut formatter = self.create_relative_time_formatter();
+        formatter.ago("");
+        // The future pattern must be in the language of the relative-time formatter,
+        // which is English for the locales it does not support.
+        let locale = self._session._current_locale.clone().unwrap();
+        let locale = if formatter_support::relative_time_language(&locale).is_some() { locale } else { parse_locale("en").unwrap() };
+        formatting_data::formatting_data(&locale).relative_time_future.replace("{0}", &formatter.convert(duration))
+    }
+}
+
+/// A message rendered in two locales, obtained from `LocaleMap::get_bilingual()`.
+/// It is displayed as both renderings separated by a slash, such as `"Guardar / Save"`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BilingualMessage {
+    pub primary: String,
+    pub secondary: String,
+}
+
+impl std::fmt::Display for BilingualMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} / {}", self.primary, self.secondary)
+    }
+}
+
+/// Entry of a language selection menu, obtained from `LocaleMap::language_menu()`.
+#[derive(Clone)]
+pub struct LanguageMenuItem {
+    pub locale: Locale,
+    /// The name of the language in itself, such as "Português" for `pt-BR`.
+    pub native_name: String,
+    /// The name of the language in English, such as "Portuguese".
+    pub universal_name: String,
+    /// The emoji flag of the locale's region, or of its likely region, if any.
+    pub flag: Option<String>,
+    /// The direction of the names, such as for aligning right-to-left entries.
+    pub direction: Direction,
+}
+
+/// Selects the `_empty`, `_one` or `_multiple` suffix of a message for the plural
+/// operands of a formatted number, where `1.0` with a visible fraction digit selects `_multiple`.
+fn plural_operands_suffix(operands: &PluralOperands) -> &'static str {
+    match operands {
+        PluralOperands { n, .. } if *n == 0.0 => "_empty",
+        PluralOperands { i: 1, v: 0, .. } => "_one",
+        _ => "_multiple",
+    }
+}
+
+pub trait LocaleMapFormatArgument {
+    fn as_gender(&self) -> Option<Gender> { None }
+    fn as_count(&self) -> Option<Count> { None }
+    fn as_gender_of(&self) -> Option<(String, Gender)> { None }
+    fn as_plural_range(&self) -> Option<PluralRange> { None }
+    fn as_f64(&self) -> Option<f64> { None }
+    fn as_i64(&self) -> Option<i64> { None }
+    fn as_u64(&self) -> Option<u64> { None }
+    fn as_i128(&self) -> Option<i128> { None }
+    fn as_u128(&self) -> Option<u128> { None }
+    fn as_string_map(&self) -> Option<HashMap<String, String>> { None }
+    fn as_value_map(&self) -> Option<HashMap<String, FormatValue>> { None }
+}
+
+impl LocaleMapFormatArgument for Gender {
+    fn as_gender(&self) -> Option<Gender> { Some(*self) }
+}
+
+impl LocaleMapFormatArgument for Count {
+    fn as_count(&self) -> Option<Count> { Some(self.clone()) }
+}
+
+impl LocaleMapFormatArgument for PluralRange {
+    fn as_plural_range(&self) -> Option<PluralRange> { Some(self.clone()) }
+}
+
+impl<S: ToString> LocaleMapFormatArgument for GenderOf<S> {
+    fn as_gender_of(&self) -> Option<(String, Gender)> { Some((self.0.to_string(), self.1)) }
+}
+
+impl LocaleMapFormatArgument for f32 {
+    fn as_f64(&self) -> Option<f64> { Some(f64::from(*self)) }
+}
+
+impl LocaleMapFormatArgument for f64 {
+    fn as_f64(&self) -> Option<f64> { Some(*self) }
+}
+
+impl LocaleMapFormatArgument for i32 {
+    fn as_i64(&self) -> Option<i64> { Some(i64::from(*self)) }
+}
+
+impl LocaleMapFormatArgument for u32 {
+    fn as_u64(&self) -> Option<u64> { Some(u64::from(*self)) }
+}
+
+impl LocaleMapFormatArgument for i64 {
+    fn as_i64(&self) -> Option<i64> { Some(*self) }
+}
+
+impl LocaleMapFormatArgument for u64 {
+    fn as_u64(&self) -> Option<u64> { Some(*self) }
+}
+
+impl LocaleMapFormatArgument for i128 {
+    fn as_i128(&self) -> Option<i128> { Some(*self) }
+}
+
+impl LocaleMapFormatArgument for u128 {
+    fn as_u128(&self) -> Option<u128> { Some(*self) }
+}
+
+impl LocaleMapFormatArgument for HashMap<String, String> {
+    fn as_string_map(&self) -> Option<HashMap<String, String>> { Some(self.clone()) }
+}
+
+impl LocaleMapFormatArgument for HashMap<String, FormatValue> {
+    fn as_value_map(&self) -> Option<HashMap<String, FormatValue>> { Some(self.clone()) }
+}
+
+impl Default for LocaleMapOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct LocaleMapOptions {
+    _default_locale: RefCell<String>,
+    _supported_locales: RefCell<Vec<String>>,
+    _fallbacks: RefCell<HashMap<String, Vec<String>>>,
+    _assets: RefCell<LocaleMapAssetOptions>,
+    _numbering_system: RefCell<Option<String>>,
+    _hour_cycle: Cell<Option<HourCycle>>,
+    _unsupported_formatter_callback: RefCell<Option<UnsupportedFormatterCallback>>,
+    _placeholder_check: Cell<Option<DiagnosticSeverity>>,
+    _diagnostic_callback: RefCell<Option<DiagnosticCallback>>,
+    _metrics: RefCell<Option<SharedMetricsSink>>,
+    _missing_translation_provider: RefCell<Option<SharedMissingTranslationProvider>>,
+    _format_overrides: RefCell<HashMap<String, FormatOverrides>>,
+    _normalization: Cell<Option<NormalizationForm>>,
+    _text_widths: RefCell<TextWidthTable>,
+    _audit_variants: Cell<bool>,
+    _locale_preference_store: RefCell<Option<SharedLocalePreferenceStore>>,
+    _formatting_locale: RefCell<Option<String>>,
+}
+
+impl LocaleMapOptions {
+    pub fn new() -> Self {
+        LocaleMapOptions {
+            _default_locale: RefCell::new("en".to_string()),
+            _supported_locales: RefCell::new(vec!["en".to_string()]),
+            _fallbacks: RefCell::new(hashmap! {}),
+            _assets: RefCell::new(LocaleMapAssetOptions::new()),
+            _numbering_system: RefCell::new(None),
+            _hour_cycle: Cell::new(None),
+            _unsupported_formatter_callback: RefCell::new(None),
+            _placeholder_check: Cell::new(None),
+            _diagnostic_callback: RefCell::new(None),
+            _metrics: RefCell::new(None),
+            _missing_translation_provider: RefCell::new(None),
+            _format_overrides: RefCell::new(HashMap::new()),
+            _normalization: Cell::new(Some(NormalizationForm::Nfc)),
+            _text_widths: RefCell::new(TextWidthTable::default()),
+            _audit_variants: Cell::new(false),
+            _locale_preference_store: RefCell::new(None),
+            _formatting_locale: RefCell::new(None),
+        }
+    }
+
+    pub fn default_locale<S: ToString>(&self, value: S) -> &Self {
+        self._default_locale.replace(value.to_string());
+        self
+    }
+
+    pub fn supported_locales<S: ToString>(&self, list: Vec<S>) -> &Self {
+        self._supported_locales.replace(list.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    pub fn fallbacks<S: ToString>(&self, map: HashMap<S, Vec<S>>) -> &Self {
+        self._fallbacks.replace(map.iter().map(|(k, v)| (
+            k.to_string(),
+            v.iter().map(|s| s.to_string()).collect()
+        )).collect());
+        self
+    }
+
+    pub fn assets(&self, options: &LocaleMapAssetOptions) -> &Self {
+        self._assets.replace(options.clone());
+        self
+    }
+
+    /// Specifies a CLDR numbering system identifier, such as `"deva"`, used for
+    /// numbers inserted into messages regardless of the current locale.
+    pub fn numbering_system<S: ToString>(&self, value: S) -> &Self {
+        self._numbering_system.replace(Some(value.to_string()));
+        self
+    }
+
+    /// Specifies the locale of formatted numbers, dates and currencies, such as `"fr-CA"`,
+    /// regardless of the current locale, which remains the locale of the messages.
+    /// See `LocaleMap::formatting_locale()`.
+    pub fn formatting_locale<S: ToString>(&self, value: S) -> &Self {
+        self._formatting_locale.replace(Some(value.to_string()));
+        self
+    }
+
+    /// Specifies the hour cycle of formatted times regardless of the current locale.
+    pub fn hour_cycle(&self, value: HourCycle) -> &Self {
+        self._hour_cycle.set(Some(value));
+        self
+    }
+
+    /// Specifies a callback invoked when a locale is loaded for which some
+    /// formatters have no data and fall back to English.
+    pub fn on_unsupported_formatter<F: Fn(&Locale, &FormatterSupport) + 'static>(&self, callback: F) -> &Self {
+        self._unsupported_formatter_callback.replace(Some(Rc::new(callback)));
+        self
+    }
+
+    /// Specifies the Unicode normalization form that the identifiers and messages of
+    /// loaded asset files, the identifiers of lookups and the values of formatting arguments
+    /// are converted to, so that text decomposed on macOS matches text composed on Windows,
+    /// or `None` to keep them as they are. The default is `NormalizationForm::Nfc`.
+    /// Text is kept as it is without the `normalization` feature, which is enabled by default.
+    pub fn normalization(&self, value: Option<NormalizationForm>) -> &Self {
+        self._normalization.set(value);
+        self
+    }
+
+    /// Specifies the estimated character widths used by `LocaleMap::validate()` to check
+    /// the `max_width` metadata of messages. The default is `TextWidthTable::sans_serif(16.0)`.
+    pub fn text_widths(&self, value: TextWidthTable) -> &Self {
+        self._text_widths.replace(value);
+        self
+    }
+
+    /// Makes `LocaleMap::validate()` report messages with gender variants for only one
+    /// gender, such as `_male` without `_female`, and messages with quantity variants
+    /// lacking any of `_empty`, `_one` and `_multiple`. The default is `false`.
+    pub fn audit_variants(&self, value: bool) -> &Self {
+        self._audit_variants.set(value);
+        self
+    }
+
+    /// Compares the `$` variables of every message against the default locale's
+    /// when a locale is loaded, reporting mismatches with the given severity.
+    pub fn check_placeholders(&self, severity: DiagnosticSeverity) -> &Self {
+        self._placeholder_check.set(Some(severity));
+        self
+    }
+
+    /// Specifies a callback that receives the issues found while loading locales.
+    /// Without a callback, they are printed to the standard output.
+    pub fn on_diagnostic<F: Fn(&Diagnostic) + 'static>(&self, callback: F) -> &Self {
+        self._diagnostic_callback.replace(Some(Rc::new(callback)));
+        self
+    }
+
+    /// Specifies a `MetricsSink` that receives statistics of the message lookups.
+    pub fn metrics<M: MetricsSink + 'static>(&self, sink: M) -> &Self {
+        self._metrics.replace(Some(Rc::new(sink)));
+        self
+    }
+
+    /// Makes `LocaleMap::load()` store the locale it is given in a store, such as a
+    /// `FileLocalePreferenceStore` or, on WebAssembly, a `LocalStorageLocalePreferenceStore`,
+    /// and load the stored locale when it is given none, so that the locale a user selects
+    /// is kept across restarts. By default, the selection is not remembered.
+    pub fn remember_selection<S: LocalePreferenceStore + 'static>(&self, store: S) -> &Self {
+        self._locale_preference_store.replace(Some(Rc::new(store)));
+        self
+    }
+
+    /// Specifies a `MissingTranslationProvider` that translates the messages missing
+    /// in a locale and its fallbacks; see `LocaleMap::provide_missing_translations()`.
+    pub fn missing_translation_provider<P: MissingTranslationProvider + 'static>(&self, provider: P) -> &Self {
+        self._missing_translation_provider.replace(Some(Rc::new(provider)));
+        self
+    }
+
+    /// Specifies number and date formatting preferences that take precedence over the
+    /// CLDR defaults for a locale, such as `"en-US"`, a language, such as `"en"`, or every
+    /// locale, given as `"*"`. The preferences of more specific tags take precedence.
+    pub fn format_overrides<S: ToString>(&self, locale: S, overrides: &FormatOverrides) -> &Self {
+        self._format_overrides.borrow_mut().insert(locale.to_string(), overrides.clone());
+        self
+    }
+}
+
+pub struct LocaleMapAssetOptions {
+    _src: RefCell<String>,
+    _base_file_names: RefCell<Vec<String>>,
+    _auto_clean: Cell<bool>,
+    _layered: Cell<bool>,
+    _overlays: RefCell<Vec<String>>,
+    _loader_type: Cell<LocaleMapLoaderType>,
+    _format: Cell<LocaleMapAssetFormat>,
+    #[cfg(feature = "compression")]
+    _compress_in_memory: Cell<bool>,
+    #[cfg(feature = "integrity")]
+    _integrity: RefCell<Option<AssetIntegrity>>,
+}
+
+impl Clone for LocaleMapAssetOptions {
+    fn clone(&self) -> Self {
+        Self {
+            _src: self._src.clone(),
+            _base_file_names: self._base_file_names.clone(),
+            _auto_clean: self._auto_clean.clone(),
+            _layered: self._layered.clone(),
+            _overlays: self._overlays.clone(),
+            _loader_type: self._loader_type.clone(),
+            _format: self._format.clone(),
+            #[cfg(feature = "compression")]
+            _compress_in_memory: self._compress_in_memory.clone(),
+            #[cfg(feature = "integrity")]
+            _integrity: self._integrity.clone(),
+        }
+    }
+}
+
+impl Default for LocaleMapAssetOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocaleMapAssetOptions {
+    pub fn new() -> Self {
+        LocaleMapAssetOptions {
+            _src: RefCell::new("res/lang".to_string()),
+            _base_file_names: RefCell::new(vec![]),
+            _auto_clean: Cell::new(true),
+            _layered: Cell::new(false),
+            _overlays: RefCell::new(vec![]),
+            _loader_type: Cell::new(LocaleMapLoaderType::Http),
+            _format: Cell::new(LocaleMapAssetFormat::Json),
+            #[cfg(feature = "compression")]
+            _compress_in_memory: Cell::new(false),
+            #[cfg(feature = "integrity")]
+            _integrity: RefCell::new(None),
+        }
+    }
+    
+    pub fn src<S: ToString>(&self, src: S) -> &Self {
+        self._src.replace(src.to_string());
+        self
+    } 
+
+    pub fn base_file_names<S: ToString>(&self, list: Vec<S>) -> &Self {
+        self._base_file_names.replace(list.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    pub fn auto_clean(&self, value: bool) -> &Self {
+        self._auto_clean.set(value);
+        self
+    }
+
+    /// Specifies whether the asset files of a locale only contain the messages that
+    /// differ from a more general locale, such as `en-US` overriding `en` and `zh-Hant-TW`
+    /// overriding `zh-Hant` and `zh`. If `true`, the files of every more general locale
+    /// found are merged deeply, with nested objects merged and messages replaced,
+    /// and a locale whose own files are missing uses the more general ones.
+    /// The default is `false`.
+    ///
+    /// The directory of a more general locale is its path among the supported locales,
+    /// or its tag if it is not supported. `TranslationEditor::save()` writes only the
+    /// messages that differ from the more general locales, so deleting a message that
+    /// a more general locale also has only removes the override.
+    pub fn layered(&self, value: bool) -> &Self {
+        self._layered.set(value);
+        self
+    }
+
+    /// Specifies overlays, such as one per tenant or brand, whose asset files
+    /// replace some of the messages of a locale when selected through
+    /// `LocaleMap::with_overlay()`. The files of an overlay are found under
+    /// a directory named after it, such as `res/lang/tenant-a/en-US/common.json`,
+    /// and may be missing for any locale.
+    pub fn overlays<S: ToString>(&self, list: Vec<S>) -> &Self {
+        self._overlays.replace(list.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    pub fn loader_type(&self, value: LocaleMapLoaderType) -> &Self {
+        self._loader_type.set(value);
+        self
+    }
+
+    /// Specifies the format of the asset files. The default is `LocaleMapAssetFormat::Json`.
+    pub fn format(&self, value: LocaleMapAssetFormat) -> &Self {
+        self._format.set(value);
+        self
+    }
+
+    /// Specifies whether the messages of each base file name are kept compressed
+    /// in memory until one of them is retrieved, for very large message sets
+    /// of which few are displayed. The default is `false`.
+    #[cfg(feature = "compression")]
+    pub fn compress_in_memory(&self, value: bool) -> &Self {
+        self._compress_in_memory.set(value);
+        self
+    }
+
+    /// Specifies how the asset files fetched over HTTP are verified before
+    /// their messages are loaded, so that a compromised server cannot replace them.
+    #[cfg(feature = "integrity")]
+    pub fn integrity(&self, value: AssetIntegrity) -> &Self {
+        self._integrity.replace(Some(value));
+        self
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum LocaleMapLoaderType {
+    FileSystem,
+    Http,
+}
+
+/// Format of the asset files, each found at `src/locale/base_file_name.extension`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LocaleMapAssetFormat {
+    /// JSON files with the `.json` extension.
+    Json,
+    /// Android string resource files with the `.xml` extension, as read by `parse_android_strings()`.
+    AndroidXml,
+    /// Apple `.strings` files, as read by `parse_apple_strings()`, each optionally
+    /// accompanied by a `.stringsdict` file with plural messages, as read by `parse_apple_stringsdict()`.
+    AppleStrings,
+}
+
+impl LocaleMapAssetFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            LocaleMapAssetFormat::Json => "json",
+            LocaleMapAssetFormat::AndroidXml => "xml",
+            LocaleMapAssetFormat::AppleStrings => "strings",
+        }
+    }
+
+    fn parse(&self, src: &str) -> Result<serde_json::Value, String> {
+        match self {
+            LocaleMapAssetFormat::Json => serde_json::from_str(src).map_err(|e| e.to_string()),
+            LocaleMapAssetFormat::AndroidXml => parse_android_strings(src),
+            LocaleMapAssetFormat::AppleStrings => parse_apple_strings(src),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::cell::Cell;
+use super::Locale;
+
+/// Distance between languages considered closely related enough
+/// to be understood, following the CLDR language matching data.
+static LANGUAGE_DISTANCES: &[(&str, &str, u32)] = &[
+    ("nb", "no", 1),
+    ("no", "nb", 1),
+    ("nn", "nb", 20),
+    ("nn", "no", 20),
+    ("da", "no", 12),
+    ("da", "nb", 12),
+    ("hr", "bs", 4),
+    ("bs", "hr", 4),
+    ("sr", "bs", 4),
+    ("sr", "hr", 10),
+    ("ms", "id", 8),
+    ("id", "ms", 8),
+    ("gl", "es", 10),
+    ("ca", "es", 20),
+    ("af", "nl", 20),
+    ("lb", "de", 20),
+    ("gsw", "de", 4),
+    ("yue", "zh", 10),
+    ("uk", "ru", 30),
+    ("be", "ru", 30),
+    ("kk", "ru", 30),
+];
+
+/// Regions grouped the way CLDR groups regional variants of a language,
+/// where variants in the same group are closer to each other than to other variants.
+static REGION_GROUPS: &[(&str, &[&str])] = &[
+    ("en", &["US", "AS", "GU", "MH", "FM", "MP", "PR", "PW", "UM", "VI"]),
+    ("es", &["419", "AR", "BO", "BR", "BZ", "CL", "CO", "CR", "CU", "DO", "EC", "GT", "HN", "MX", "NI", "PA", "PE", "PR", "PY", "SV", "US", "UY", "VE"]),
+    ("pt", &["BR", "AR", "BO", "CL", "CO", "EC", "GY", "PE", "PY", "SR", "UY", "VE"]),
+];
+
+/// Distance from which two locales are considered not to match.
+const NO_MATCH_DISTANCE: u32 = 80;
+
+/// Distance added for each position a desired locale is behind
+/// the first one in the user's preference list.
+const DEMOTION_PER_DESIRED_LOCALE: u32 = 5;
+
+/// Selects the best of a list of supported locales for a user's desired locales,
+/// using distances based on the CLDR language matching algorithm.
+///
+/// Locales are compared after adding likely subtags, so `zh-TW` and `zh-Hant`
+/// match exactly, while `en-GB` is closer to `en-AU` than to `en-US`.
+#[derive(Clone)]
+pub struct LocaleMatcher {
+    _supported: Vec<Locale>,
+    _threshold: u32,
+}
+
+impl LocaleMatcher {
+    pub fn new(supported: Vec<Locale>) -> Self {
+        Self { _supported: supported, _threshold: NO_MATCH_DISTANCE }
+    }
+
+    pub fn supported_locales(&self) -> &[Locale] {
+        &self._supported
+    }
+
+    /// Sets the distance from which two locales are considered not to match.
+    /// The default is 80, which excludes different unrelated languages.
+    pub fn threshold(mut self, value: u32) -> Self {
+        self._threshold = value;
+        self
+    }
+
+    /// Returns the distance between a desired and a supported locale, where `0` is
+    /// an exact match, a different region is 4 or 5, a different script is 50 and
+    /// a different unrelated language is 80 or more.
+    pub fn distance(desired: &Locale, supported: &Locale) -> u32 {
+        let desired = desired.maximize();
+        let supported = supported.maximize();
+        let mut r = 0;
+        if desired.language() != supported.language() {
+            r += LANGUAGE_DISTANCES.iter()
+                .find(|(d, s, _)| *d == desired.language() && *s == supported.language())
+                .map(|(_, _, distance)| *distance)
+                .unwrap_or(NO_MATCH_DISTANCE);
+        }
+        if desired.script() != supported.script() {
+            r += 50;
+        }
+        if desired.region() != supported.region() {
+            let group = REGION_GROUPS.iter().find(|(lang, _)| *lang == supported.language()).map(|(_, regions)| *regions);
+            let in_group = |locale: &Locale| group.map(|g| g.contains(&locale.region().unwrap_or(""))).unwrap_or(false);
+            r += if in_group(&desired) == in_group(&supported) { 4 } else { 5 };
+        }
+        r
+    }
+
+    /// Returns the supported locale closest to the desired locales, given in order
+    /// of preference, or `None` if none is below the threshold.
+    pub fn best_match(&self, desired: &[Locale]) -> Option<Locale> {
+        self.best_match_with_distance(desired).map(|(locale, _)| locale)
+    }
+
+    /// Returns the supported locale closest to the desired locales along with its distance,
+    /// which includes a demotion of 5 for each position the matched desired locale
+    /// is behind the first one.
+    pub fn best_match_with_distance(&self, desired: &[Locale]) -> Option<(Locale, u32)> {
+        self.ranked_matches(desired).into_iter().next()
+    }
+
+    /// Returns the supported locales below the threshold along with their distances,
+    /// closest first, where the distance of each supported locale is the one to the
+    /// desired locale it is closest to, including the demotion of `best_match_with_distance()`.
+    /// Ties are ordered by the position of the desired locale, then of the supported locale.
+    pub fn ranked_matches(&self, desired: &[Locale]) -> Vec<(Locale, u32)> {
+        let mut ranked: Vec<(usize, usize, u32)> = vec![];
+        for (j, s) in self._supported.iter().enumerate() {
+            let closest = desired.iter().enumerate()
+                .map(|(i, d)| (i, LocaleMatcher::distance(d, s) + i as u32 * DEMOTION_PER_DESIRED_LOCALE))
+                .filter(|(_, distance)| *distance < self._threshold)
+                .min_by_key(|(_, distance)| *distance);
+            if let Some((i, distance)) = closest {
+                ranked.push((i, j, distance));
+            }
+        }
+        ranked.sort_by_key(|(i, j, distance)| (*distance, *i, *j));
+        ranked.into_iter().map(|(_, j, distance)| (self._supported[j].clone(), distance)).collect()
+    }
+
+    /// Resolves a user's desired locales, given in order of preference, such as
+    /// `pt-BR`, `es` and `en`, to the closest supported locale and the other matching
+    /// supported locales in order, for display when a message is missing.
+    /// Returns `None` if no supported locale is below the threshold.
+    pub fn resolve(&self, desired: &[Locale]) -> Option<LocaleResolution> {
+        let mut ranked = self.ranked_matches(desired).into_iter().map(|(locale, _)| locale);
+        let locale = ranked.next()?;
+        Some(LocaleResolution { locale, fallbacks: ranked.collect() })
+    }
+
+    /// Returns the first supported locale found by the RFC 4647 lookup algorithm,
+    /// which progressively truncates each desired locale's subtags, as in
+    /// `zh-Hant-TW`, `zh-Hant` and `zh`.
+    pub fn lookup(&self, desired: &[Locale]) -> Option<Locale> {
+        for d in desired.iter() {
+            let mut subtags: Vec<String> = d.canonicalize().standard_tag().to_string().split('-').map(|s| s.to_string()).collect();
+            if let Some(i) = subtags.iter().position(|s| s.len() == 1) {
+                subtags.truncate(i);
+            }
+            while !subtags.is_empty() {
+                let candidate = subtags.join("-");
+                if let Some(r) = self._supported.iter().find(|s| s.canonicalize().standard_tag().to_string().eq_ignore_ascii_case(&candidate)) {
+                    return Some(r.clone());
+                }
+                subtags.pop();
+                if subtags.last().map(|s| s.len() == 1).unwrap_or(false) {
+                    subtags.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The result of resolving a user's preferred locales against the supported locales;
+/// see `LocaleMatcher::resolve()` and `LocaleMap::negotiate()`.
+#[derive(Clone)]
+pub struct LocaleResolution {
+    /// The supported locale to display.
+    pub locale: Locale,
+    /// The supported locales to display messages missing from `locale` in, in order.
+    pub fallbacks: Vec<Locale>,
+}
+
+impl LocaleResolution {
+    /// Returns the resolved locale followed by its fallbacks.
+    pub fn chain(&self) -> Vec<Locale> {
+        std::iter::once(self.locale.clone()).chain(self.fallbacks.iter().cloned()).collect()
+    }
+
+    pub(crate) fn push_fallback(&mut self, locale: Locale) {
+        if locale != self.locale && !self.fallbacks.contains(&locale) {
+            self.fallbacks.push(locale);
+        }
+    }
+}
+
+/// Locale matching algorithm, as specified by the `localeMatcher` option of ECMA-402.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum LocaleMatcherAlgorithm {
+    /// The RFC 4647 lookup algorithm, as in `LocaleMatcher::lookup()`.
+    Lookup,
+    /// The distance-based algorithm, as in `LocaleMatcher::best_match()`.
+    #[default]
+    BestFit,
+}
+
+impl Default for SupportedLocalesOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct SupportedLocalesOptions {
+    _locale_matcher: Cell<LocaleMatcherAlgorithm>,
+}
+
+impl SupportedLocalesOptions {
+    pub fn new() -> Self {
+        SupportedLocalesOptions {
+            _locale_matcher: Cell::new(LocaleMatcherAlgorithm::default()),
+        }
+    }
+
+    pub fn locale_matcher(&self, value: LocaleMatcherAlgorithm) -> &Self {
+        self._locale_matcher.set(value);
+        self
+    }
+}
+
+/// Returns the requested locales that can be serviced by one of the available locales,
+/// following the `SupportedLocales` abstract operation of ECMA-402 (§9.2.8).
+///
+/// Duplicate requested locales are removed and the `-u-` extension is ignored
+/// when matching, but the returned locales keep it, as in `Intl.*.supportedLocalesOf()`.
+pub fn supported_locales_of(available: &[Locale], requested: &[Locale], options: &SupportedLocalesOptions) -> Vec<Locale> {
+    let matcher = LocaleMatcher::new(available.to_vec());
+    let mut r = Vec::<Locale>::new();
+    for locale in requested.iter() {
+        if r.contains(locale) {
+            continue;
+        }
+        let no_extensions_locale = without_unicode_extension(locale);
+        let desired = std::slice::from_ref(&no_extensions_locale);
+        let found = match options._locale_matcher.get() {
+            LocaleMatcherAlgorithm::Lookup => matcher.lookup(desired).is_some(),
+            LocaleMatcherAlgorithm::BestFit => matcher.best_match(desired).is_some(),
+        };
+        if found {
+            r.push(locale.clone());
+        }
+    }
+    r
+}
+
+fn without_unicode_extension(locale: &Locale) -> Locale {
+    let builder = Locale::builder();
+    builder.language(locale.language());
+    if let Some(script) = locale.script() { builder.script(script); }
+    if let Some(region) = locale.region() { builder.region(region); }
+    for variant in locale.variants() { builder.variant(variant); }
+    for extension in locale.extensions().into_iter().filter(|e| !e.to_lowercase().starts_with("u-")) {
+        builder.extension(extension);
+    }
+    builder.build().unwrap_or_else(|_| locale.clone())
+}
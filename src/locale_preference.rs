@@ -0,0 +1,123 @@
+use std::{cell::RefCell, path::PathBuf, rc::Rc};
+
+/// Storage of the locale a user selected, so that a `LocaleMap` created with
+/// `LocaleMapOptions::remember_selection()` loads it again after a restart.
+/// The locale is stored as its tag, such as `pt-BR`.
+pub trait LocalePreferenceStore {
+    /// Returns the stored locale tag, if any.
+    fn load(&self) -> Result<Option<String>, String>;
+    fn save(&self, tag: &str) -> Result<(), String>;
+    /// Removes the stored locale tag, if any.
+    fn clear(&self) -> Result<(), String>;
+}
+
+/// Keeps the selected locale in memory, such as for tests. Clones share the stored tag.
+///
+/// ```
+/// use recoyx_localization::{LocalePreferenceStore, MemoryLocalePreferenceStore};
+///
+/// let store = MemoryLocalePreferenceStore::new();
+/// store.clone().save("pt-BR").unwrap();
+/// assert_eq!(store.load(), Ok(Some("pt-BR".to_string())));
+/// ```
+#[derive(Clone, Default)]
+pub struct MemoryLocalePreferenceStore {
+    _tag: Rc<RefCell<Option<String>>>,
+}
+
+impl MemoryLocalePreferenceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LocalePreferenceStore for MemoryLocalePreferenceStore {
+    fn load(&self) -> Result<Option<String>, String> {
+        Ok(self._tag.borrow().clone())
+    }
+
+    fn save(&self, tag: &str) -> Result<(), String> {
+        self._tag.replace(Some(tag.to_string()));
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        self._tag.replace(None);
+        Ok(())
+    }
+}
+
+/// Keeps the selected locale in a text file, such as in the configuration directory
+/// of a desktop application. The directories of the path are created when saving.
+#[derive(Clone)]
+pub struct FileLocalePreferenceStore {
+    _path: PathBuf,
+}
+
+impl FileLocalePreferenceStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { _path: path.into() }
+    }
+}
+
+impl LocalePreferenceStore for FileLocalePreferenceStore {
+    fn load(&self) -> Result<Option<String>, String> {
+        match std::fs::read_to_string(&self._path) {
+            Ok(tag) => Ok(Some(tag.trim().to_string()).filter(|tag| !tag.is_empty())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("{}: {}", self._path.display(), e)),
+        }
+    }
+
+    fn save(&self, tag: &str) -> Result<(), String> {
+        if let Some(parent) = self._path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&self._path, tag).map_err(|e| format!("{}: {}", self._path.display(), e))
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        match std::fs::remove_file(&self._path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("{}: {}", self._path.display(), e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Keeps the selected locale in the `localStorage` of the browser under a key,
+/// available on WebAssembly.
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone)]
+pub struct LocalStorageLocalePreferenceStore {
+    _key: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorageLocalePreferenceStore {
+    pub fn new<S: ToString>(key: S) -> Self {
+        Self { _key: key.to_string() }
+    }
+
+    fn storage(&self) -> Result<web_sys::Storage, String> {
+        web_sys::window()
+            .ok_or("localStorage: no window")?
+            .local_storage()
+            .map_err(|e| format!("localStorage: {:?}", e))?
+            .ok_or_else(|| "localStorage: not available".to_string())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalePreferenceStore for LocalStorageLocalePreferenceStore {
+    fn load(&self) -> Result<Option<String>, String> {
+        self.storage()?.get_item(&self._key).map_err(|e| format!("localStorage: {:?}", e))
+    }
+
+    fn save(&self, tag: &str) -> Result<(), String> {
+        self.storage()?.set_item(&self._key, tag).map_err(|e| format!("localStorage: {:?}", e))
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        self.storage()?.remove_item(&self._key).map_err(|e| format!("localStorage: {:?}", e))
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use super::{formatting_data::formatting_data, Direction, FormatOverrides, Locale};
+
+/// Localization conventions of a locale that thin clients need in order to format
+/// numbers and select plural forms consistently with a server, to be embedded
+/// in JSON API responses along with the data they apply to.
+///
+/// ```
+/// use recoyx_localization::{parse_locale, LocalizationContext};
+///
+/// let context = LocalizationContext::new(&parse_locale("ar-EG").unwrap());
+/// assert_eq!(context.plural_rules, "ar");
+/// assert_eq!(context.direction, "rtl");
+/// assert_eq!(serde_json::to_string(&LocalizationContext::new(&parse_locale("pt-BR").unwrap())).unwrap(),
+///     r#"{"locale":"pt-BR","plural_rules":"pt","direction":"ltr","decimal_separator":",","group_separator":"."}"#);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct LocalizationContext {
+    /// The tag of the locale, such as `pt-BR`.
+    pub locale: String,
+    /// The CLDR locale whose plural rules apply, such as `pt` or `pt-PT`,
+    /// which is `en` for languages without plural rules.
+    pub plural_rules: String,
+    /// The text direction, `ltr` or `rtl`.
+    pub direction: String,
+    pub decimal_separator: String,
+    pub group_separator: String,
+}
+
+impl LocalizationContext {
+    pub fn new(locale: &Locale) -> Self {
+        Self::with_overrides(locale, locale, &FormatOverrides::new())
+    }
+
+    /// Constructs the context of messages in a locale and numbers formatted in another,
+    /// applying format overrides, as in `LocaleMap::localization_context()`.
+    pub(crate) fn with_overrides(locale: &Locale, formatting_locale: &Locale, overrides: &FormatOverrides) -> Self {
+        let data = formatting_data(formatting_locale).with_overrides(overrides);
+        Self {
+            locale: locale.standard_tag().to_string(),
+            plural_rules: plural_rules_locale(locale),
+            direction: match locale.direction() {
+                Direction::LeftToRight => "ltr".to_string(),
+                Direction::RightToLeft => "rtl".to_string(),
+            },
+            decimal_separator: data.decimal,
+            group_separator: data.group,
+        }
+    }
+}
+
+/// Returns the locale whose cardinal plural rules apply to a locale, trying its language
+/// and region, then its language and then English, as `LocaleMap` does when loading a locale.
+fn plural_rules_locale(locale: &Locale) -> String {
+    let language = unic_langid::subtags::Language::from_bytes(locale.language().as_ref()).ok();
+    let region = locale.region().and_then(|region| unic_langid::subtags::Region::from_bytes(region.as_ref()).ok());
+    let candidates = language.into_iter().flat_map(|language| vec![
+        unic_langid::LanguageIdentifier::from_parts(language, None, region, &[]),
+        unic_langid::LanguageIdentifier::from_parts(language, None, None, &[]),
+    ]);
+    for langid in candidates {
+        if intl_pluralrules::PluralRules::create(langid.clone(), intl_pluralrules::PluralRuleType::CARDINAL).is_ok() {
+            return langid.to_string();
+        }
+    }
+    "en".to_string()
+}
@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+/// A message lookup recorded by `LocaleMap::start_lookup_recording()`, relating
+/// a text displayed on screen to the message that produced it, such as for mapping
+/// the texts in screenshots of automated UI runs back to messages for review.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct LookupRecord {
+    /// The identifier of the message, including the suffixes selected by gender
+    /// and quantity arguments, such as `cart.qty_one`.
+    pub id: String,
+    /// The tag of the locale that provided the message, which may be a fallback
+    /// of the current locale, or `None` if the message is missing.
+    pub locale: Option<String>,
+    /// The text produced.
+    pub text: String,
+}
+
+/// Encodes lookup records as a JSON array of objects with the `id`, `locale` and `text` fields.
+///
+/// ```
+/// use recoyx_localization::{lookup_records_to_json, LookupRecord};
+///
+/// let records = vec![LookupRecord { id: "cart.qty_one".to_string(), locale: Some("en-US".to_string()), text: "One item".to_string() }];
+/// assert_eq!(lookup_records_to_json(&records), r#"[{"id":"cart.qty_one","locale":"en-US","text":"One item"}]"#);
+/// ```
+pub fn lookup_records_to_json(records: &[LookupRecord]) -> String {
+    serde_json::to_string(records).unwrap()
+}
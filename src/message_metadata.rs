@@ -0,0 +1,28 @@
+pub(crate) use recoyx_localization_core::message_of;
+
+/// Metadata of a message for translators and tooling, specified by writing the message
+/// as an object such as `{ "message": "Save", "description": "Toolbar button", "max_length": 12 }`,
+/// with at least one metadata field, since an object with only a `message` field is
+/// an object of nested messages.
+/// The limits apply to the message of every locale unless its own metadata specifies others.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct MessageMetadata {
+    /// Explanation of where and how the message is used.
+    pub description: Option<String>,
+    /// Maximum number of characters the message may have, counted as grapheme clusters,
+    /// as for a button label, checked by `LocaleMap::validate()`.
+    pub max_length: Option<usize>,
+    /// Maximum width of the message in pixels, as estimated by the `TextWidthTable`
+    /// of `LocaleMapOptions::text_widths()`, checked by `LocaleMap::validate()`.
+    pub max_width: Option<u32>,
+}
+
+/// Returns the metadata of an asset value that is a message.
+pub(crate) fn metadata_of(value: &serde_json::Value) -> Option<MessageMetadata> {
+    message_of(value)?;
+    Some(MessageMetadata {
+        description: value.get("description").and_then(|d| d.as_str()).map(|d| d.to_string()),
+        max_length: value.get("max_length").and_then(|l| l.as_u64()).map(|l| l as usize),
+        max_width: value.get("max_width").and_then(|w| w.as_u64()).map(|w| w as u32),
+    })
+}
@@ -0,0 +1,274 @@
+use std::{collections::HashMap, rc::Rc};
+use super::{key_page::compare_ids, message_metadata::{message_of, metadata_of}, normalization::normalize_text, MessageMetadata, NormalizationForm};
+
+/// The messages of a locale, indexed by their complete identifiers,
+/// such as `common.message_id`. Obtained from `LocaleMap::messages_for()`.
+///
+/// Each identifier is stored once and shared by the index and the message,
+/// so that looking up a message takes a single hash lookup rather than
+/// a traversal of the nested asset objects.
+#[derive(Clone, Default)]
+pub struct MessageTree {
+    _index: HashMap<Rc<str>, usize>,
+    _entries: Vec<MessageEntry>,
+    /// Namespaces kept compressed until one of their messages is needed.
+    #[cfg(feature = "compression")]
+    _compressed: Vec<CompressedNamespace>,
+}
+
+/// The messages under a top-level component of their identifiers, such as
+/// `common` for `common.save`, as gzip-compressed JSON. The identifiers are kept
+/// uncompressed, so that counting and listing them needs no decompression.
+#[cfg(feature = "compression")]
+#[derive(Clone)]
+struct CompressedNamespace {
+    name: String,
+    ids: Rc<[Rc<str>]>,
+    content: Rc<[u8]>,
+    normalization: Option<NormalizationForm>,
+    tree: std::cell::OnceCell<MessageTree>,
+}
+
+#[cfg(feature = "compression")]
+impl CompressedNamespace {
+    /// Returns the messages of the namespace, decompressing them the first time,
+    /// or the reason they cannot be decompressed.
+    fn tree(&self) -> Result<&MessageTree, String> {
+        if let Some(tree) = self.tree.get() {
+            return Ok(tree);
+        }
+        let content = super::compression::gunzip(&self.content)?;
+        let value = serde_json::from_slice(&content).map_err(|e| e.to_string())?;
+        let mut r = MessageTree::new();
+        r.insert_json(&self.name, &value, self.normalization);
+        Ok(self.tree.get_or_init(|| r))
+    }
+}
+
+#[derive(Clone)]
+struct MessageEntry {
+    id: Rc<str>,
+    message: String,
+    metadata: MessageMetadata,
+}
+
+impl MessageTree {
+    /// Constructs an empty `MessageTree`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a `MessageTree` from an asset tree, where messages are either
+    /// strings or objects with metadata, converting the identifiers, messages and
+    /// descriptions to a normalization form, if given. Values that are neither
+    /// messages nor objects of nested messages are ignored.
+    pub(crate) fn from_json(root: &serde_json::Value, normalization: Option<NormalizationForm>) -> Self {
+        let mut r = Self::new();
+        r.insert_json("", root, normalization);
+        r
+    }
+
+    /// Constructs a `MessageTree` from an asset tree as `from_json()` does, except that
+    /// every top-level object, such as the messages of a base file name, is kept
+    /// compressed until one of its messages is needed.
+    #[cfg(feature = "compression")]
+    pub(crate) fn from_json_compressed(root: &serde_json::Value, normalization: Option<NormalizationForm>) -> Self {
+        let mut r = Self::new();
+        for (name, value) in root.as_object().into_iter().flatten() {
+            if value.is_object() && message_of(value).is_none() {
+                let name = normalize_text(name, normalization);
+                let mut messages = MessageTree::new();
+                messages.insert_json(&name, value, normalization);
+                r._compressed.push(CompressedNamespace {
+                    name,
+                    ids: messages._entries.into_iter().map(|e| e.id).collect(),
+                    content: Rc::from(super::compression::gzip(&serde_json::to_vec(value).unwrap())),
+                    normalization,
+                    tree: std::cell::OnceCell::new(),
+                });
+            } else {
+                let mut object = serde_json::Map::new();
+                object.insert(name.clone(), value.clone());
+                r.insert_json("", &serde_json::Value::Object(object), normalization);
+            }
+        }
+        r
+    }
+
+    fn insert_json(&mut self, path: &str, value: &serde_json::Value, normalization: Option<NormalizationForm>) {
+        for (name, value) in value.as_object().into_iter().flatten() {
+            let name = normalize_text(name, normalization);
+            let id = if path.is_empty() { name } else { format!("{}.{}", path, name) };
+            match (message_of(value), metadata_of(value)) {
+                (Some(message), Some(mut metadata)) => {
+                    metadata.description = metadata.description.map(|d| normalize_text(&d, normalization));
+                    self.push(&id, normalize_text(message, normalization), metadata);
+                },
+                _ => self.insert_json(&id, value, normalization),
+            }
+        }
+    }
+
+    /// Converts the messages back into an asset tree, writing messages
+    /// with metadata as objects.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        let mut root = serde_json::Value::Object(serde_json::Map::new());
+        for entry in self.entries() {
+            let frags: Vec<&str> = entry.id.split('.').collect();
+            let mut output = &mut root;
+            for frag in frags[..frags.len() - 1].iter() {
+                output = output.as_object_mut().unwrap().entry(frag.to_string())
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+            }
+            let message = serde_json::Value::String(entry.message.clone());
+            let value = if entry.metadata == MessageMetadata::default() { message } else {
+                let mut object = serde_json::Map::new();
+                object.insert("message".to_string(), message);
+                if let Some(description) = entry.metadata.description.as_ref() {
+                    object.insert("description".to_string(), serde_json::Value::String(description.clone()));
+                }
+                if let Some(max_length) = entry.metadata.max_length {
+                    object.insert("max_length".to_string(), serde_json::Value::from(max_length));
+                }
+                if let Some(max_width) = entry.metadata.max_width {
+                    object.insert("max_width".to_string(), serde_json::Value::from(max_width));
+                }
+                serde_json::Value::Object(object)
+            };
+            output.as_object_mut().unwrap().insert(frags[frags.len() - 1].to_string(), value);
+        }
+        root
+    }
+
+    /// Returns the number of messages.
+    pub fn len(&self) -> usize {
+        let r = self._entries.len();
+        #[cfg(feature = "compression")]
+        let r = r + self._compressed.iter().map(|n| n.ids.len()).sum::<usize>();
+        r
+    }
+
+    /// Returns `true` if there are no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if there is a message at the given identifier.
+    pub fn contains(&self, id: &str) -> bool {
+        #[cfg(feature = "compression")]
+        if let Some(namespace) = self._compressed.iter().find(|n| is_within(id, &n.name)) {
+            return namespace.ids.iter().any(|i| **i == *id);
+        }
+        self._index.contains_key(id)
+    }
+
+    /// Returns the message at the given identifier.
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.entry(id).map(|e| e.message.as_str())
+    }
+
+    /// Returns the metadata of the message at the given identifier.
+    pub fn metadata(&self, id: &str) -> Option<&MessageMetadata> {
+        self.entry(id).map(|e| &e.metadata)
+    }
+
+    fn entry(&self, id: &str) -> Option<&MessageEntry> {
+        if let Some(i) = self._index.get(id) {
+            return Some(&self._entries[*i]);
+        }
+        #[cfg(feature = "compression")]
+        if let Some(namespace) = self._compressed.iter().find(|n| is_within(id, &n.name)) {
+            return namespace.tree().ok()?.entry(id);
+        }
+        None
+    }
+
+    /// Enumerates every entry, decompressing the compressed namespaces.
+    /// Namespaces that cannot be decompressed are skipped.
+    fn entries(&self) -> impl Iterator<Item = &MessageEntry> + '_ {
+        let entries = self._entries.iter();
+        #[cfg(feature = "compression")]
+        let entries = entries.chain(self._compressed.iter().filter_map(|n| n.tree().ok()).flat_map(|t| t._entries.iter()));
+        entries
+    }
+
+    /// Enumerates the message identifiers, ordered component by component.
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        let ids = self._entries.iter().map(|e| e.id.as_ref());
+        #[cfg(feature = "compression")]
+        let ids = ids.chain(self._compressed.iter().flat_map(|n| n.ids.iter().map(|id| id.as_ref())));
+        let mut r: Vec<&str> = ids.collect();
+        r.sort_by(|a, b| compare_ids(a, b));
+        r.into_iter()
+    }
+
+    /// Enumerates the message identifiers along with the messages,
+    /// ordered component by component.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.sorted_entries().into_iter().map(|e| (e.id.as_ref(), e.message.as_str()))
+    }
+
+    fn sorted_entries(&self) -> Vec<&MessageEntry> {
+        let mut r: Vec<&MessageEntry> = self.entries().collect();
+        r.sort_by(|a, b| compare_ids(&a.id, &b.id));
+        r
+    }
+
+    /// Sets a message, keeping the metadata of an existing message. Since a message
+    /// cannot contain other messages, messages at the components of the identifier,
+    /// such as `common` for `common.save`, and messages under it are removed.
+    pub(crate) fn set(&mut self, id: &str, message: String) {
+        #[cfg(feature = "compression")]
+        self.decompress();
+        if let Some(i) = self._index.get(id) {
+            self._entries[*i].message = message;
+            return;
+        }
+        let conflicts: Vec<Rc<str>> = self._entries.iter()
+            .filter(|e| is_within(&e.id, id) || is_within(id, &e.id))
+            .map(|e| e.id.clone())
+            .collect();
+        for conflict in conflicts {
+            self.remove(&conflict);
+        }
+        self.push(id, message, MessageMetadata::default());
+    }
+
+    /// Removes a message. Returns `false` if the message does not exist.
+    pub(crate) fn remove(&mut self, id: &str) -> bool {
+        #[cfg(feature = "compression")]
+        self.decompress();
+        let i = match self._index.remove(id) {
+            Some(i) => i,
+            None => return false,
+        };
+        self._entries.swap_remove(i);
+        if let Some(moved) = self._entries.get(i) {
+            self._index.insert(moved.id.clone(), i);
+        }
+        true
+    }
+
+    /// Moves the messages of the compressed namespaces into the index, before changing them.
+    #[cfg(feature = "compression")]
+    fn decompress(&mut self) {
+        for namespace in std::mem::take(&mut self._compressed) {
+            // The content was compressed by `from_json_compressed()`, so it only fails
+            // to decompress if it was corrupted, in which case its messages are lost.
+            for entry in namespace.tree().into_iter().flat_map(|t| t._entries.iter()) {
+                self.push(&entry.id, entry.message.clone(), entry.metadata.clone());
+            }
+        }
+    }
+
+    fn push(&mut self, id: &str, message: String, metadata: MessageMetadata) {
+        let id: Rc<str> = Rc::from(id);
+        self._index.insert(id.clone(), self._entries.len());
+        self._entries.push(MessageEntry { id, message, metadata });
+    }
+}
+
+/// Returns `true` if `id` is nested in `group`, such as `common.save` in `common`.
+fn is_within(id: &str, group: &str) -> bool {
+    id.len() > group.len() && id.starts_with(group) && id.as_bytes()[group.len()] == b'.'
+}
@@ -0,0 +1,25 @@
+use super::Locale;
+
+/// Receives statistics of the message lookups of a `LocaleMap`, specified through
+/// `LocaleMapOptions::metrics()`, such as for incrementing counters of a metrics
+/// library. Every method does nothing by default.
+///
+/// Lookups of experiment variants are not reported, since variants are optional;
+/// the lookup of the message they replace is.
+pub trait MetricsSink {
+    /// Called for every message lookup, with the locale whose message is requested.
+    fn lookup(&self, _locale: &Locale, _id: &str) {}
+
+    /// Called for the lookups resolved from the message cache, without
+    /// traversing the assets.
+    fn cache_hit(&self, _locale: &Locale, _id: &str) {}
+
+    /// Called for the lookups resolved from a fallback locale, with the number
+    /// of fallbacks followed, such as 1 for a fallback of the requested locale
+    /// and 2 for a fallback of that fallback.
+    fn fallback(&self, _locale: &Locale, _id: &str, _depth: usize) {}
+
+    /// Called for the lookups of messages missing in the requested locale
+    /// and its fallbacks.
+    fn missing(&self, _locale: &Locale, _id: &str) {}
+}
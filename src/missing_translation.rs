@@ -0,0 +1,56 @@
+use std::{future::Future, pin::Pin};
+use super::Locale;
+
+/// Synthesizes translations of messages missing in a locale and all of its fallbacks,
+/// such as from a translation memory database or a machine translation API,
+/// specified through `LocaleMapOptions::missing_translation_provider()`.
+///
+/// Message lookups cannot wait for a translation, so the missing messages are recorded
+/// as they are looked up and translated by `LocaleMap::provide_missing_translations()`,
+/// after which the translations are used for the rest of the session.
+///
+/// ```
+/// use std::{future::Future, pin::Pin};
+/// use recoyx_localization::{MissingTranslation, MissingTranslationProvider, ProvidedTranslation};
+///
+/// struct Uppercase;
+///
+/// impl MissingTranslationProvider for Uppercase {
+///     fn translate<'a>(&'a self, request: &'a MissingTranslation) -> Pin<Box<dyn Future<Output = Option<ProvidedTranslation>> + 'a>> {
+///         Box::pin(async move {
+///             request.source.as_ref().map(|source| ProvidedTranslation::new(source.to_uppercase(), true))
+///         })
+///     }
+/// }
+/// ```
+pub trait MissingTranslationProvider {
+    /// Returns the translation of a missing message, or `None` if there is none,
+    /// in which case the message is requested again once it is looked up again.
+    fn translate<'a>(&'a self, request: &'a MissingTranslation) -> Pin<Box<dyn Future<Output = Option<ProvidedTranslation>> + 'a>>;
+}
+
+/// Message missing in a locale and its fallbacks, given to a `MissingTranslationProvider`.
+#[derive(Clone)]
+pub struct MissingTranslation {
+    /// The locale the message is requested in.
+    pub locale: Locale,
+    /// The identifier of the message, including its variant suffixes, such as `cart.qty_one`.
+    pub id: String,
+    /// The message in the default locale, as the source to translate, if it has it.
+    pub source: Option<String>,
+}
+
+/// Translation synthesized by a `MissingTranslationProvider`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ProvidedTranslation {
+    pub message: String,
+    /// Whether the translation comes from machine translation rather than, for
+    /// instance, a translation memory, as reported by `AttributedSegment::machine_translated`.
+    pub machine_translated: bool,
+}
+
+impl ProvidedTranslation {
+    pub fn new<S: ToString>(message: S, machine_translated: bool) -> Self {
+        Self { message: message.to_string(), machine_translated }
+    }
+}
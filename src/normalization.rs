@@ -0,0 +1,77 @@
+#[cfg(feature = "normalization")]
+use icu_normalizer::{ComposingNormalizerBorrowed, DecomposingNormalizerBorrowed};
+
+/// Unicode normalization form, as defined by UAX #15.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum NormalizationForm {
+    /// Canonical composition, such as `é` as a single character. Text produced on
+    /// Windows and Linux is usually in this form.
+    Nfc,
+    /// Canonical decomposition, such as `é` as `e` followed by a combining accent,
+    /// as in file names produced on macOS.
+    Nfd,
+    /// Compatibility composition, which also replaces compatibility characters,
+    /// such as `ﬁ` by `fi` and fullwidth letters by their usual forms.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Parses a form name, such as `"nfc"`, ignoring case.
+    pub fn parse(name: &str) -> Option<NormalizationForm> {
+        match name.to_lowercase().as_ref() {
+            "nfc" => Some(NormalizationForm::Nfc),
+            "nfd" => Some(NormalizationForm::Nfd),
+            "nfkc" => Some(NormalizationForm::Nfkc),
+            "nfkd" => Some(NormalizationForm::Nfkd),
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes text to a Unicode normalization form, so that visually identical
+/// strings, such as `é` as a single character and as `e` and a combining accent,
+/// compare equal. Available through the `normalization` feature.
+///
+/// ```
+/// use recoyx_localization::{normalize, is_normalized, NormalizationForm};
+///
+/// let decomposed = "Cafe\u{301}";
+/// assert_eq!(normalize(decomposed, NormalizationForm::Nfc), "Café");
+/// assert!(!is_normalized(decomposed, NormalizationForm::Nfc));
+/// assert_eq!(normalize("ﬁ", NormalizationForm::Nfkc), "fi");
+/// ```
+#[cfg(feature = "normalization")]
+pub fn normalize<S: AsRef<str>>(text: S, form: NormalizationForm) -> String {
+    let text = text.as_ref();
+    match form {
+        NormalizationForm::Nfc => ComposingNormalizerBorrowed::new_nfc().normalize(text).into_owned(),
+        NormalizationForm::Nfd => DecomposingNormalizerBorrowed::new_nfd().normalize(text).into_owned(),
+        NormalizationForm::Nfkc => ComposingNormalizerBorrowed::new_nfkc().normalize(text).into_owned(),
+        NormalizationForm::Nfkd => DecomposingNormalizerBorrowed::new_nfkd().normalize(text).into_owned(),
+    }
+}
+
+/// Whether text is already in a Unicode normalization form.
+#[cfg(feature = "normalization")]
+pub fn is_normalized<S: AsRef<str>>(text: S, form: NormalizationForm) -> bool {
+    let text = text.as_ref();
+    match form {
+        NormalizationForm::Nfc => ComposingNormalizerBorrowed::new_nfc().is_normalized(text),
+        NormalizationForm::Nfd => DecomposingNormalizerBorrowed::new_nfd().is_normalized(text),
+        NormalizationForm::Nfkc => ComposingNormalizerBorrowed::new_nfkc().is_normalized(text),
+        NormalizationForm::Nfkd => DecomposingNormalizerBorrowed::new_nfkd().is_normalized(text),
+    }
+}
+
+/// Converts text to a normalization form, if given, such as the identifiers and
+/// messages of the assets for `LocaleMapOptions::normalization()`, which is
+/// ignored without the `normalization` feature.
+pub(crate) fn normalize_text(text: &str, form: Option<NormalizationForm>) -> String {
+    match form {
+        #[cfg(feature = "normalization")]
+        Some(form) if !is_normalized(text, form) => normalize(text, form),
+        _ => text.to_string(),
+    }
+}
@@ -0,0 +1,247 @@
+use std::cell::{Cell, RefCell};
+use std::convert::TryFrom;
+use super::{formatting_data::{formatting_data, FormattingData}, FormatOverrides, Locale, NumberingSystem, PluralOperands, parse_numbering_system};
+
+/// Formats numbers with the decimal and grouping separators of a locale.
+///
+/// ```
+/// use recoyx_localization::{NumberFormatter, NumberFormatOptions, parse_locale};
+///
+/// let formatter = NumberFormatter::new(&parse_locale("de").unwrap(), &NumberFormatOptions::new());
+/// assert_eq!(formatter.format(1234567.891), "1.234.567,891");
+/// ```
+#[derive(Clone)]
+pub struct NumberFormatter {
+    _data: FormattingData,
+    _minimum_fraction_digits: usize,
+    _maximum_fraction_digits: usize,
+    _use_grouping: bool,
+    _notation: Notation,
+    _numbering_system: NumberingSystem,
+    _minus: &'static str,
+}
+
+/// Notation of the numbers of a `NumberFormatter`.
+///
+/// ```
+/// use recoyx_localization::{NumberFormatter, NumberFormatOptions, Notation, parse_locale};
+///
+/// let formatter = NumberFormatter::new(&parse_locale("de").unwrap(), NumberFormatOptions::new().notation(Notation::Scientific).maximum_fraction_digits(5));
+/// assert_eq!(formatter.format(123456.0), "1,23456E5");
+/// let formatter = NumberFormatter::new(&parse_locale("en").unwrap(), NumberFormatOptions::new().notation(Notation::Engineering));
+/// assert_eq!(formatter.format(0.00012), "120E-6");
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Notation {
+    /// Numbers are written in full, as in 123,456.
+    Standard,
+    /// Numbers are written as a mantissa from 1 to 10 times a power of ten, as in 1.23456E5.
+    Scientific,
+    /// Like scientific notation, but with exponents that are multiples of three
+    /// and mantissas from 1 to 1000, as in 123.456E3.
+    Engineering,
+}
+
+impl NumberFormatter {
+    pub fn new(locale: &Locale, options: &NumberFormatOptions) -> Self {
+        let numbering_system = options._numbering_system.borrow().as_ref().and_then(|s| parse_numbering_system(s).ok())
+            .or_else(|| locale.numbering_system())
+            .unwrap_or_else(NumberingSystem::latin);
+        let minimum_fraction_digits = options._minimum_fraction_digits.get();
+        Self {
+            _data: formatting_data(locale).with_numbering_system(&numbering_system).with_overrides(&options._overrides.borrow()),
+            _minimum_fraction_digits: minimum_fraction_digits,
+            _maximum_fraction_digits: options._maximum_fraction_digits.get().max(minimum_fraction_digits),
+            _use_grouping: options._use_grouping.get(),
+            _notation: options._notation.get(),
+            _numbering_system: numbering_system,
+            _minus: numbering_system.symbols().map_or("-", |symbols| symbols.minus),
+        }
+    }
+
+    /// Formats a number, rounded to the maximum fraction digits, or those of
+    /// its mantissa in scientific and engineering notation.
+    pub fn format(&self, value: f64) -> String {
+        if value.is_nan() {
+            return "NaN".to_string();
+        }
+        let sign = if value.is_sign_negative() && value != 0.0 { "-" } else { "" };
+        if value.is_infinite() {
+            return format!("{}∞", sign);
+        }
+        if self._notation != Notation::Standard {
+            return self.format_exponential(value);
+        }
+        let (integer, fraction) = self.visible_digits(value);
+        let sign = if integer.bytes().all(|d| d == b'0') && fraction.is_empty() { "" } else { sign };
+        self.format_digits(sign, &integer, &fraction)
+    }
+
+    /// Formats a finite number in scientific or engineering notation, with the exponential
+    /// symbol of the locale or numbering system, such as "E" in "1.2E5". Zero has the exponent 0.
+    fn format_exponential(&self, value: f64) -> String {
+        let step = if self._notation == Notation::Engineering { 3 } else { 1 };
+        let mut exponent = if value == 0.0 { 0 } else { (value.abs().log10().floor() as i32).div_euclid(step) * step };
+        let (mut integer, mut fraction) = self.visible_digits(divide_by_power_of_ten(value, exponent));
+        // Rounding may carry the mantissa to the next power, as 9.9996 to 10.
+        if integer.len() > step as usize {
+            exponent += step;
+            let digits = self.visible_digits(divide_by_power_of_ten(value, exponent));
+            integer = digits.0;
+            fraction = digits.1;
+        }
+        let mantissa = self.format_digits(if value < 0.0 { "-" } else { "" }, &integer, &fraction);
+        let exponent_sign = if exponent < 0 { self._minus } else { "" };
+        mantissa + &self._data.exponential + exponent_sign + &self._numbering_system.transliterate(exponent.unsigned_abs())
+    }
+
+    /// Returns the plural operands of a number as formatted, so that plural rules
+    /// consider its visible fraction digits, such as `1.0` being `other` in English
+    /// when formatted with a minimum of one fraction digit, and its rounding.
+    ///
+    /// ```
+    /// use recoyx_localization::{NumberFormatter, NumberFormatOptions, parse_locale};
+    ///
+    /// let formatter = NumberFormatter::new(&parse_locale("en").unwrap(), NumberFormatOptions::new().minimum_fraction_digits(1));
+    /// let operands = formatter.plural_operands(1.0);
+    /// assert_eq!((operands.i, operands.v), (1, 1));
+    /// ```
+    pub fn plural_operands(&self, value: f64) -> PluralOperands {
+        if !value.is_finite() {
+            return PluralOperands::try_from(0).unwrap();
+        }
+        let (integer, fraction) = self.visible_digits(value);
+        let digits = if fraction.is_empty() { integer } else { format!("{}.{}", integer, fraction) };
+        PluralOperands::try_from(digits.as_str()).unwrap()
+    }
+
+    /// Returns the minus sign of the numbering system, such as "-" for Latin digits.
+    pub(crate) fn minus_sign(&self) -> &'static str {
+        self._minus
+    }
+
+    /// Returns the integer and fraction digits of the absolute value of a finite number,
+    /// rounded to the maximum fraction digits and padded to the minimum.
+    fn visible_digits(&self, value: f64) -> (String, String) {
+        let digits = format!("{:.*}", self._maximum_fraction_digits, value.abs());
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        let trimmed = fraction.trim_end_matches('0');
+        let fraction = if trimmed.len() < self._minimum_fraction_digits { &fraction[..self._minimum_fraction_digits] } else { trimmed };
+        (integer.to_string(), fraction.to_string())
+    }
+
+    /// Formats an integer without losing precision.
+    pub fn format_integer(&self, value: i128) -> String {
+        let digits = value.unsigned_abs().to_string();
+        let fraction = "0".repeat(self._minimum_fraction_digits);
+        self.format_digits(if value < 0 { "-" } else { "" }, &digits, &fraction)
+    }
+
+    /// Formats the digits of a number, preceded by the minus sign
+    /// of the numbering system if the given sign is `-`.
+    fn format_digits(&self, sign: &str, integer: &str, fraction: &str) -> String {
+        let mut r = if sign.is_empty() { String::new() } else { self._minus.to_string() };
+        r.push_str(&self.group(integer));
+        if !fraction.is_empty() {
+            r.push_str(&self._data.decimal);
+            r.push_str(fraction);
+        }
+        self._numbering_system.transliterate(r)
+    }
+
+    /// Inserts the group separators into the integer digits of a number.
+    fn group(&self, integer: &str) -> String {
+        let first_size = self._data.grouping[0];
+        if !self._use_grouping || integer.len() < first_size + self._data.minimum_grouping_digits {
+            return integer.to_string();
+        }
+        let mut groups: Vec<&str> = vec![];
+        let mut end = integer.len();
+        let mut size = first_size;
+        while end > size {
+            groups.push(&integer[end - size..end]);
+            end -= size;
+            size = self._data.grouping.get(groups.len()).copied().unwrap_or(size);
+        }
+        groups.push(&integer[..end]);
+        groups.reverse();
+        groups.join(&self._data.group)
+    }
+}
+
+/// Divides a number by a power of ten in two steps if the power alone would overflow,
+/// as for the exponents of subnormal numbers such as 1e-310.
+fn divide_by_power_of_ten(value: f64, exponent: i32) -> f64 {
+    if exponent.abs() < 300 {
+        value / 10f64.powi(exponent)
+    } else {
+        value / 10f64.powi(exponent / 2) / 10f64.powi(exponent - exponent / 2)
+    }
+}
+
+/// Options for constructing a `NumberFormatter`.
+pub struct NumberFormatOptions {
+    _minimum_fraction_digits: Cell<usize>,
+    _maximum_fraction_digits: Cell<usize>,
+    _use_grouping: Cell<bool>,
+    _notation: Cell<Notation>,
+    _numbering_system: RefCell<Option<String>>,
+    _overrides: RefCell<FormatOverrides>,
+}
+
+impl NumberFormatOptions {
+    pub fn new() -> Self {
+        NumberFormatOptions {
+            _minimum_fraction_digits: Cell::new(0),
+            _maximum_fraction_digits: Cell::new(3),
+            _use_grouping: Cell::new(true),
+            _notation: Cell::new(Notation::Standard),
+            _numbering_system: RefCell::new(None),
+            _overrides: RefCell::new(FormatOverrides::new()),
+        }
+    }
+
+    /// Minimum number of fraction digits, padded with zeros. The default is 0.
+    pub fn minimum_fraction_digits(&self, value: usize) -> &Self {
+        self._minimum_fraction_digits.set(value);
+        self
+    }
+
+    /// Maximum number of fraction digits, rounding the rest. The default is 3.
+    pub fn maximum_fraction_digits(&self, value: usize) -> &Self {
+        self._maximum_fraction_digits.set(value);
+        self
+    }
+
+    /// Whether to insert group separators, such as in 1,000. The default is `true`.
+    pub fn use_grouping(&self, value: bool) -> &Self {
+        self._use_grouping.set(value);
+        self
+    }
+
+    /// The notation of the numbers, such as scientific. The default is `Notation::Standard`.
+    pub fn notation(&self, value: Notation) -> &Self {
+        self._notation.set(value);
+        self
+    }
+
+    /// CLDR numbering system identifier of the digits, such as `"arab"`. The default,
+    /// also used if the identifier is unknown, is the one requested by the locale's
+    /// `-u-nu-` extension, or else the Latin one.
+    pub fn numbering_system<S: ToString>(&self, value: S) -> &Self {
+        self._numbering_system.replace(Some(value.to_string()));
+        self
+    }
+
+    /// Preferences that take precedence over the CLDR data of the locale.
+    pub fn overrides(&self, value: &FormatOverrides) -> &Self {
+        self._overrides.replace(value.clone());
+        self
+    }
+}
+
+impl Default for NumberFormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,143 @@
+use super::{currency_data::currency_symbols, formatting_data::{formatting_data, FormattingData}, numbering_system::digit_value, FormatOverrides, Locale, NumberingSystem};
+
+/// Parses numbers written with the separators of a locale, the inverse of `NumberFormatter`,
+/// such as for validating localized user input in forms. Digits of any numbering system,
+/// percent signs, currency symbols and codes, parentheses around negative amounts and
+/// scientific notation are understood. Group separators are only accepted where
+/// `NumberFormatter` places them, so that `1,5` is not read as 15 in English.
+///
+/// ```
+/// use recoyx_localization::{NumberParser, parse_locale};
+///
+/// let parser = NumberParser::new(&parse_locale("de").unwrap());
+/// assert_eq!(parser.parse("1.234,56"), Ok(1234.56));
+/// assert_eq!(parser.parse("-12,5 %"), Ok(-0.125));
+/// assert_eq!(parser.parse("1.234,50 €"), Ok(1234.5));
+/// assert!(parser.parse("1,234.56").is_err());
+/// ```
+#[derive(Clone)]
+pub struct NumberParser {
+    _data: FormattingData,
+    _currency_symbols: Vec<String>,
+}
+
+impl NumberParser {
+    pub fn new(locale: &Locale) -> Self {
+        Self {
+            _data: formatting_data(locale).with_numbering_system(&locale.numbering_system().unwrap_or_else(NumberingSystem::latin)),
+            _currency_symbols: currency_symbols(locale),
+        }
+    }
+
+    /// Applies the separators of format overrides, as in `LocaleMap::number_parser()`.
+    pub(crate) fn with_overrides(mut self, overrides: &FormatOverrides) -> Self {
+        self._data = self._data.with_overrides(overrides);
+        self
+    }
+
+    /// Parses a number, returning an error if the input is not a number
+    /// written with the separators of the locale.
+    pub fn parse(&self, input: &str) -> Result<f64, String> {
+        let error = || format!("Invalid number: {}.", input);
+        // Bidirectional marks, as in the minus sign of Arabic-Indic digits, are removed.
+        let mut text: String = input.chars().filter(|ch| !['\u{61C}', '\u{200E}', '\u{200F}'].contains(ch)).map(|ch| match digit_value(ch) {
+            Some(d) => std::char::from_digit(d, 10).unwrap(),
+            None => ch,
+        }).collect();
+
+        let mut negative = false;
+        let trimmed = text.trim();
+        if let Some(inner) = trimmed.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            negative = true;
+            text = inner.to_string();
+        }
+        let mut percent = false;
+        for sign in ['%', '٪'].iter() {
+            if text.contains(*sign) {
+                percent = true;
+                text = text.replacen(*sign, "", 1);
+            }
+        }
+        for symbol in self._currency_symbols.iter() {
+            if text.contains(symbol.as_str()) {
+                text = text.replacen(symbol.as_str(), "", 1);
+                break;
+            }
+        }
+        let stripped = strip_currency_code(&text);
+        // Spaces are removed, except between digits if groups are separated by spaces,
+        // including no-break spaces, in which case they are written as a single space.
+        let group = self._data.group.trim_matches(char::is_whitespace);
+        let group = if group.is_empty() { " " } else { group };
+        let mut text = String::new();
+        for word in stripped.split_whitespace() {
+            if group == " " && text.ends_with(|ch: char| ch.is_ascii_digit()) && word.starts_with(|ch: char| ch.is_ascii_digit()) {
+                text.push(' ');
+            }
+            text.push_str(word);
+        }
+        for minus in ['-', '\u{2212}', '+'].iter() {
+            if let Some(rest) = text.strip_prefix(*minus).or_else(|| text.strip_suffix(*minus)) {
+                if negative {
+                    return Err(error());
+                }
+                negative = *minus != '+';
+                text = rest.to_string();
+                break;
+            }
+        }
+
+        let (mantissa, exponent) = match text.split_once(self._data.exponential.as_str()).or_else(|| text.split_once(['E', 'e'])) {
+            Some((mantissa, exponent)) => {
+                let exponent = exponent.strip_prefix('+').unwrap_or(exponent).replace('\u{2212}', "-");
+                (mantissa.to_string(), exponent.parse::<i32>().map_err(|_| error())?)
+            },
+            None => (text, 0),
+        };
+        let (integer, fraction) = mantissa.split_once(self._data.decimal.as_str()).unwrap_or((&mantissa, ""));
+        if !self.is_grouped(integer, group) {
+            return Err(error());
+        }
+        let integer = integer.replace(group, "");
+        let digits = |s: &str| s.chars().all(|ch| ch.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty()) || !digits(&integer) || !digits(fraction) {
+            return Err(error());
+        }
+        let value: f64 = format!("{}.{}e{}", if integer.is_empty() { "0" } else { &integer }, fraction, exponent).parse().map_err(|_| error())?;
+        let value = if percent { value / 100.0 } else { value };
+        Ok(if negative { -value } else { value })
+    }
+
+    /// Returns `true` if the group separators of the integer digits of a number are
+    /// at the positions where `NumberFormatter` places them, such as in `12,34,567`
+    /// in Hindi, whose groups after the first have two digits.
+    fn is_grouped(&self, integer: &str, group: &str) -> bool {
+        let mut groups: Vec<&str> = integer.split(group).collect();
+        let first = groups.remove(0);
+        if groups.is_empty() {
+            return true;
+        }
+        let mut size = self._data.grouping[0];
+        for (i, g) in groups.iter().rev().enumerate() {
+            size = self._data.grouping.get(i).copied().unwrap_or(size);
+            if g.len() != size {
+                return false;
+            }
+        }
+        let size = self._data.grouping.get(groups.len()).copied().unwrap_or(size);
+        !first.is_empty() && first.len() <= size
+    }
+}
+
+/// Removes an ISO 4217 currency code, such as `EUR`, from the start or end of a number.
+fn strip_currency_code(text: &str) -> &str {
+    let text = text.trim();
+    let is_code = |s: &str| s.len() == 3 && s.chars().all(|ch| ch.is_ascii_uppercase());
+    if text.len() >= 3 && text.is_char_boundary(3) && is_code(&text[..3]) {
+        return &text[3..];
+    }
+    if text.len() >= 3 && text.is_char_boundary(text.len() - 3) && is_code(&text[text.len() - 3..]) {
+        return &text[..text.len() - 3];
+    }
+    text
+}
@@ -0,0 +1,164 @@
+use std::fmt::{Display, Formatter};
+
+/// Numeric numbering systems from CLDR whose digits occupy a contiguous
+/// range of code points, given as the code point of their zero digit.
+static CONTIGUOUS_NUMBERING_SYSTEMS: &[(&str, u32)] = &[
+    ("adlm", 0x1E950),
+    ("ahom", 0x11730),
+    ("arab", 0x0660),
+    ("arabext", 0x06F0),
+    ("bali", 0x1B50),
+    ("beng", 0x09E6),
+    ("bhks", 0x11C50),
+    ("brah", 0x11066),
+    ("cakm", 0x11136),
+    ("cham", 0xAA50),
+    ("deva", 0x0966),
+    ("diak", 0x11950),
+    ("fullwide", 0xFF10),
+    ("gong", 0x11DA0),
+    ("gonm", 0x11D50),
+    ("gujr", 0x0AE6),
+    ("guru", 0x0A66),
+    ("hmng", 0x16B50),
+    ("hmnp", 0x1E140),
+    ("java", 0xA9D0),
+    ("kali", 0xA900),
+    ("kawi", 0x11F50),
+    ("khmr", 0x17E0),
+    ("knda", 0x0CE6),
+    ("lana", 0x1A80),
+    ("lanatham", 0x1A90),
+    ("laoo", 0x0ED0),
+    ("latn", 0x0030),
+    ("lepc", 0x1C40),
+    ("limb", 0x1946),
+    ("mathbold", 0x1D7CE),
+    ("mathdbl", 0x1D7D8),
+    ("mathmono", 0x1D7F6),
+    ("mathsanb", 0x1D7EC),
+    ("mathsans", 0x1D7E2),
+    ("mlym", 0x0D66),
+    ("modi", 0x11650),
+    ("mong", 0x1810),
+    ("mroo", 0x16A60),
+    ("mtei", 0xABF0),
+    ("mymr", 0x1040),
+    ("mymrshan", 0x1090),
+    ("mymrtlng", 0xA9F0),
+    ("nagm", 0x1E4F0),
+    ("newa", 0x11450),
+    ("nkoo", 0x07C0),
+    ("olck", 0x1C50),
+    ("orya", 0x0B66),
+    ("osma", 0x104A0),
+    ("rohg", 0x10D30),
+    ("saur", 0xA8D0),
+    ("segment", 0x1FBF0),
+    ("shrd", 0x111D0),
+    ("sind", 0x112F0),
+    ("sinh", 0x0DE6),
+    ("sora", 0x110F0),
+    ("sund", 0x1BB0),
+    ("takr", 0x116C0),
+    ("talu", 0x19D0),
+    ("tamldec", 0x0BE6),
+    ("telu", 0x0C66),
+    ("thai", 0x0E50),
+    ("tibt", 0x0F20),
+    ("tirh", 0x114D0),
+    ("tnsa", 0x16AC0),
+    ("vaii", 0xA620),
+    ("wara", 0x118E0),
+    ("wcho", 0x1E2F0),
+];
+
+/// Numeric numbering systems from CLDR whose digits are not contiguous.
+static SPARSE_NUMBERING_SYSTEMS: &[(&str, [char; 10])] = &[
+    ("hanidec", ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九']),
+];
+
+/// Number symbols from CLDR of the numbering systems whose symbols differ from
+/// the Latin ones, as used by the languages that write numbers in them by default.
+static NUMBERING_SYSTEM_SYMBOLS: &[(&str, NumberSymbols)] = &[
+    ("arab", NumberSymbols { decimal: "٫", group: "٬", exponential: "أس", minus: "\u{61C}-" }),
+    ("arabext", NumberSymbols { decimal: "٫", group: "٬", exponential: "×۱۰^", minus: "\u{200E}\u{2212}" }),
+];
+
+/// Separators, exponential symbol and minus sign of the numbers of a numbering system.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct NumberSymbols {
+    pub decimal: &'static str,
+    pub group: &'static str,
+    pub exponential: &'static str,
+    pub minus: &'static str,
+}
+
+/// A numeric numbering system, such as `latn` (ASCII digits),
+/// `arab` (Arabic-Indic digits) or `deva` (Devanagari digits).
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NumberingSystem {
+    _code: &'static str,
+    _digits: [char; 10],
+}
+
+impl NumberingSystem {
+    /// Returns the Latin numbering system, which uses ASCII digits.
+    pub fn latin() -> Self {
+        parse_numbering_system("latn").unwrap()
+    }
+
+    /// Returns the CLDR identifier of the numbering system, such as `"arab"`.
+    pub fn code(&self) -> &'static str {
+        self._code
+    }
+
+    /// Returns the digits zero through nine of the numbering system.
+    pub fn digits(&self) -> [char; 10] {
+        self._digits
+    }
+
+    /// Returns the number symbols of the numbering system, or `None` if numbers
+    /// written in it use the symbols of the locale, as with Devanagari digits.
+    pub(crate) fn symbols(&self) -> Option<&'static NumberSymbols> {
+        NUMBERING_SYSTEM_SYMBOLS.iter().find(|(code, _)| *code == self._code).map(|(_, symbols)| symbols)
+    }
+
+    /// Replaces every ASCII digit in the given string by the
+    /// corresponding digit of this numbering system.
+    pub fn transliterate<S: ToString>(&self, src: S) -> String {
+        src.to_string().chars().map(|ch| {
+            if let Some(d) = ch.to_digit(10) { self._digits[d as usize] } else { ch }
+        }).collect()
+    }
+}
+
+impl Display for NumberingSystem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self._code)
+    }
+}
+
+/// Returns the value of a digit of any numbering system, such as 5 for `'٥'`.
+pub(crate) fn digit_value(ch: char) -> Option<u32> {
+    CONTIGUOUS_NUMBERING_SYSTEMS.iter()
+        .find_map(|(_, zero)| (ch as u32).checked_sub(*zero).filter(|d| *d < 10))
+        .or_else(|| SPARSE_NUMBERING_SYSTEMS.iter().find_map(|(_, digits)| digits.iter().position(|d| *d == ch).map(|d| d as u32)))
+}
+
+/// Parses a CLDR numeric numbering system identifier, such as `"deva"`.
+/// The identifier is case-insensitive.
+pub fn parse_numbering_system<S: ToString>(src: S) -> Result<NumberingSystem, String> {
+    let src = src.to_string().to_lowercase();
+    if let Some((code, zero)) = CONTIGUOUS_NUMBERING_SYSTEMS.iter().find(|(code, _)| *code == src) {
+        let mut digits = ['0'; 10];
+        for (i, d) in digits.iter_mut().enumerate() {
+            *d = char::from_u32(zero + i as u32).unwrap();
+        }
+        return Ok(NumberingSystem { _code: code, _digits: digits });
+    }
+    if let Some((code, digits)) = SPARSE_NUMBERING_SYSTEMS.iter().find(|(code, _)| *code == src) {
+        return Ok(NumberingSystem { _code: code, _digits: *digits });
+    }
+    Err(format!("Unknown numbering system: {}.", src))
+}
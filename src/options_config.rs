@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use serde::Deserialize;
+use super::{locale_map::check_fallbacks, NormalizationForm, parse_locale, parse_numbering_system, LocaleMapAssetFormat, LocaleMapAssetOptions, LocaleMapLoaderType, LocaleMapOptions};
+
+/// Configuration read by `LocaleMapOptions::from_json()`, `from_toml()` and `from_env()`.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LocaleMapConfig {
+    default_locale: Option<String>,
+    supported_locales: Option<Vec<String>>,
+    fallbacks: Option<HashMap<String, Vec<String>>>,
+    numbering_system: Option<String>,
+    formatting_locale: Option<String>,
+    normalization: Option<String>,
+    #[serde(default)]
+    assets: LocaleMapAssetConfig,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LocaleMapAssetConfig {
+    src: Option<String>,
+    base_file_names: Option<Vec<String>>,
+    loader_type: Option<String>,
+    format: Option<String>,
+    auto_clean: Option<bool>,
+    layered: Option<bool>,
+    overlays: Option<Vec<String>>,
+}
+
+impl LocaleMapOptions {
+    /// Reads the options from a JSON configuration, so that deployments can change them
+    /// without recompiling, such as:
+    ///
+    /// ```json
+    /// {
+    ///     "default_locale": "en-US",
+    ///     "supported_locales": ["en-US", "pt-BR", "pt-PT"],
+    ///     "fallbacks": { "pt-PT": ["pt-BR"] },
+    ///     "numbering_system": "latn",
+    ///     "formatting_locale": "fr-CA",
+    ///     "normalization": "nfc",
+    ///     "assets": {
+    ///         "src": "res/lang",
+    ///         "base_file_names": ["common"],
+    ///         "loader_type": "file_system",
+    ///         "format": "json",
+    ///         "auto_clean": true,
+    ///         "layered": false,
+    ///         "overlays": ["tenant-a"]
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Every field is optional, keeping the default of `LocaleMapOptions::new()`, except
+    /// `default_locale`, which defaults to the first supported locale. `loader_type` is
+    /// `"http"` or `"file_system"`, `format` is `"json"`, `"android_xml"` or `"apple_strings"`
+    /// and `normalization` is `"nfc"`, `"nfd"`, `"nfkc"`, `"nfkd"` or `"none"`.
+    /// Unknown fields, invalid locales, a default locale that is not supported,
+    /// fallbacks of unsupported locales, locales falling back to themselves and
+    /// cycles of fallbacks are reported as errors naming the field.
+    pub fn from_json(config: &str) -> Result<Self, String> {
+        let config: LocaleMapConfig = serde_json::from_str(config).map_err(|e| e.to_string())?;
+        config.into_options()
+    }
+
+    /// Reads the options from a TOML configuration with the fields described in `from_json()`,
+    /// available through the `toml` feature.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(config: &str) -> Result<Self, String> {
+        let config: LocaleMapConfig = toml::from_str(config).map_err(|e| e.to_string().trim_end().to_string())?;
+        config.into_options()
+    }
+
+    /// Reads the options from environment variables named after the fields described
+    /// in `from_json()` in upper case, following a prefix, such as `APP_DEFAULT_LOCALE`
+    /// and `APP_ASSETS_LOADER_TYPE` for the prefix `APP`. Lists are separated by commas,
+    /// such as `en-US,pt-BR`, and fallbacks by semicolons, such as `pt-PT=pt-BR;es-MX=es-ES,en-US`.
+    /// Variables that are not set keep the default.
+    pub fn from_env(prefix: &str) -> Result<Self, String> {
+        let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok();
+        let list = |name: &str| var(name).map(|value| split_list(&value, ','));
+        let flag = |name: &str| -> Result<Option<bool>, String> {
+            var(name).map(|value| value.trim().parse().map_err(|_| format!("{}_{}: expected true or false, found {:?}", prefix, name, value))).transpose()
+        };
+        let fallbacks = match var("FALLBACKS") {
+            Some(value) => {
+                let mut fallbacks = HashMap::new();
+                for entry in split_list(&value, ';') {
+                    let (locale, list) = entry.split_once('=').ok_or_else(|| format!("{}_FALLBACKS: expected locale=fallback,..., found {:?}", prefix, entry))?;
+                    fallbacks.insert(locale.trim().to_string(), split_list(list, ','));
+                }
+                Some(fallbacks)
+            },
+            None => None,
+        };
+        let config = LocaleMapConfig {
+            default_locale: var("DEFAULT_LOCALE"),
+            supported_locales: list("SUPPORTED_LOCALES"),
+            fallbacks,
+            numbering_system: var("NUMBERING_SYSTEM"),
+            formatting_locale: var("FORMATTING_LOCALE"),
+            normalization: var("NORMALIZATION"),
+            assets: LocaleMapAssetConfig {
+                src: var("ASSETS_SRC"),
+                base_file_names: list("ASSETS_BASE_FILE_NAMES"),
+                loader_type: var("ASSETS_LOADER_TYPE"),
+                format: var("ASSETS_FORMAT"),
+                auto_clean: flag("ASSETS_AUTO_CLEAN")?,
+                layered: flag("ASSETS_LAYERED")?,
+                overlays: list("ASSETS_OVERLAYS"),
+            },
+        };
+        config.into_options().map_err(|e| {
+            // Names the variable instead of the field, such as `APP_FALLBACKS` for `fallbacks.pt-PT`.
+            let (field, reason) = e.split_once(": ").unwrap_or(("", &e));
+            let field = field.split(['[', '.']).take(if field.starts_with("assets.") { 2 } else { 1 }).collect::<Vec<_>>().join("_");
+            format!("{}_{}: {}", prefix, field.to_uppercase(), reason)
+        })
+    }
+}
+
+fn split_list(value: &str, separator: char) -> Vec<String> {
+    value.split(separator).map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+impl LocaleMapConfig {
+    fn into_options(self) -> Result<LocaleMapOptions, String> {
+        let options = LocaleMapOptions::new();
+        let supported_locales = self.supported_locales.unwrap_or_else(|| vec!["en".to_string()]);
+        if supported_locales.is_empty() {
+            return Err("supported_locales: expected at least one locale".to_string());
+        }
+        let mut parsed_locales = vec![];
+        for (i, tag) in supported_locales.iter().enumerate() {
+            parsed_locales.push(parse_locale(tag).map_err(|_| format!("supported_locales[{}]: invalid locale {:?}", i, tag))?);
+        }
+        // The default locale of `LocaleMapOptions::new()` may not be among the supported locales.
+        let default_locale = self.default_locale.unwrap_or_else(|| supported_locales[0].clone());
+        let parsed = parse_locale(&default_locale).map_err(|_| format!("default_locale: invalid locale {:?}", default_locale))?;
+        if !parsed_locales.contains(&parsed) {
+            return Err(format!("default_locale: {:?} is not among the supported locales", default_locale));
+        }
+        options.default_locale(default_locale);
+        if let Some(fallbacks) = self.fallbacks {
+            let mut parsed_fallbacks = HashMap::new();
+            for (tag, list) in fallbacks.iter() {
+                let parsed = parse_locale(tag).map_err(|_| format!("fallbacks: invalid locale {:?}", tag))?;
+                if !parsed_locales.contains(&parsed) {
+                    return Err(format!("fallbacks: {:?} is not among the supported locales", tag));
+                }
+                let mut parsed_list = vec![];
+                for fallback in list.iter() {
+                    let parsed = parse_locale(fallback).map_err(|_| format!("fallbacks.{}: invalid locale {:?}", tag, fallback))?;
+                    if !parsed_locales.contains(&parsed) {
+                        return Err(format!("fallbacks.{}: {:?} is not among the supported locales", tag, fallback));
+                    }
+                    parsed_list.push(parsed);
+                }
+                parsed_fallbacks.insert(parsed, parsed_list);
+            }
+            check_fallbacks(&parsed_fallbacks, &parsed_locales.iter().cloned().collect())?;
+            options.fallbacks(fallbacks.iter().map(|(k, v)| (k.as_str(), v.iter().map(|s| s.as_str()).collect())).collect());
+        }
+        options.supported_locales(supported_locales);
+        if let Some(numbering_system) = self.numbering_system {
+            parse_numbering_system(&numbering_system).map_err(|_| format!("numbering_system: unknown numbering system {:?}", numbering_system))?;
+            options.numbering_system(numbering_system);
+        }
+        if let Some(formatting_locale) = self.formatting_locale {
+            parse_locale(&formatting_locale).map_err(|_| format!("formatting_locale: invalid locale {:?}", formatting_locale))?;
+            options.formatting_locale(formatting_locale);
+        }
+        if let Some(normalization) = self.normalization {
+            let form = NormalizationForm::parse(&normalization);
+            if form.is_none() && normalization != "none" {
+                return Err(format!("normalization: expected nfc, nfd, nfkc, nfkd or none, found {:?}", normalization));
+            }
+            options.normalization(form);
+        }
+        options.assets(&self.assets.into_options()?);
+        Ok(options)
+    }
+}
+
+impl LocaleMapAssetConfig {
+    fn into_options(self) -> Result<LocaleMapAssetOptions, String> {
+        let options = LocaleMapAssetOptions::new();
+        if let Some(src) = self.src {
+            options.src(src);
+        }
+        if let Some(base_file_names) = self.base_file_names {
+            options.base_file_names(base_file_names);
+        }
+        if let Some(loader_type) = self.loader_type {
+            options.loader_type(match loader_type.as_ref() {
+                "http" => LocaleMapLoaderType::Http,
+                "file_system" => LocaleMapLoaderType::FileSystem,
+                _ => return Err(format!("assets.loader_type: expected \"http\" or \"file_system\", found {:?}", loader_type)),
+            });
+        }
+        if let Some(format) = self.format {
+            options.format(match format.as_ref() {
+                "json" => LocaleMapAssetFormat::Json,
+                "android_xml" => LocaleMapAssetFormat::AndroidXml,
+                "apple_strings" => LocaleMapAssetFormat::AppleStrings,
+                _ => return Err(format!("assets.format: expected \"json\", \"android_xml\" or \"apple_strings\", found {:?}", format)),
+            });
+        }
+        if let Some(auto_clean) = self.auto_clean {
+            options.auto_clean(auto_clean);
+        }
+        if let Some(layered) = self.layered {
+            options.layered(layered);
+        }
+        if let Some(overlays) = self.overlays {
+            options.overlays(overlays);
+        }
+        Ok(options)
+    }
+}
@@ -0,0 +1,143 @@
+use std::cell::Cell;
+use super::Locale;
+
+/// Languages that write the family name before the given name.
+static FAMILY_FIRST_LANGUAGES: &[&str] = &["hu", "ja", "ko", "vi", "yue", "zh"];
+
+/// Languages whose honorifics follow the name, as in `山田様`, rather than precede it.
+static HONORIFIC_SUFFIX_LANGUAGES: &[&str] = &["ja", "ko", "yue", "zh"];
+
+/// A person's name, formatted through a `PersonNameFormatter`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PersonName {
+    pub given: String,
+    pub family: String,
+    /// A title or honorific, such as `"Dr."` or `"様"`.
+    pub honorific: Option<String>,
+}
+
+impl PersonName {
+    pub fn new<S: ToString>(given: S, family: S) -> Self {
+        Self { given: given.to_string(), family: family.to_string(), honorific: None }
+    }
+
+    pub fn with_honorific<S: ToString>(mut self, value: S) -> Self {
+        self.honorific = Some(value.to_string());
+        self
+    }
+}
+
+/// Order of the given and family names.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PersonNameOrder {
+    GivenFirst,
+    FamilyFirst,
+}
+
+/// Formats person names in the order of a locale, such as family-first for Japanese,
+/// Chinese and Hungarian, with the honorific before or after the name as the
+/// language places it.
+///
+/// Names written in a script without spaces between words, such as `山田` and `太郎`,
+/// are joined without a space.
+///
+/// ```
+/// use recoyx_localization::{PersonName, PersonNameFormatter, PersonNameFormatOptions, parse_locale};
+///
+/// let options = PersonNameFormatOptions::new();
+/// let en = PersonNameFormatter::new(&parse_locale("en").unwrap(), &options);
+/// assert_eq!(en.format(&PersonName::new("Jane", "Smith").with_honorific("Dr.")), "Dr. Jane Smith");
+/// let ja = PersonNameFormatter::new(&parse_locale("ja").unwrap(), &options);
+/// assert_eq!(ja.format(&PersonName::new("太郎", "山田").with_honorific("様")), "山田太郎様");
+/// let hu = PersonNameFormatter::new(&parse_locale("hu").unwrap(), &options);
+/// assert_eq!(hu.format(&PersonName::new("János", "Kovács")), "Kovács János");
+/// ```
+#[derive(Clone)]
+pub struct PersonNameFormatter {
+    _order: PersonNameOrder,
+    _honorific_suffix: bool,
+}
+
+impl PersonNameFormatter {
+    pub fn new(locale: &Locale, options: &PersonNameFormatOptions) -> Self {
+        let language = locale.language();
+        let order = if FAMILY_FIRST_LANGUAGES.contains(&language) { PersonNameOrder::FamilyFirst } else { PersonNameOrder::GivenFirst };
+        Self {
+            _order: options._order.get().unwrap_or(order),
+            _honorific_suffix: HONORIFIC_SUFFIX_LANGUAGES.contains(&language),
+        }
+    }
+
+    pub fn order(&self) -> PersonNameOrder {
+        self._order
+    }
+
+    /// Formats the full name along with the honorific, if any.
+    pub fn format(&self, name: &PersonName) -> String {
+        let parts = match self._order {
+            PersonNameOrder::GivenFirst => [name.given.as_str(), name.family.as_str()],
+            PersonNameOrder::FamilyFirst => [name.family.as_str(), name.given.as_str()],
+        };
+        self.with_honorific(&join_name_parts(&parts), name)
+    }
+
+    /// Formats the family name along with the honorific, if any, for addressing
+    /// a person formally, as in "Dr. Smith" and `山田様`.
+    pub fn format_formal(&self, name: &PersonName) -> String {
+        self.with_honorific(&name.family, name)
+    }
+
+    fn with_honorific(&self, formatted: &str, name: &PersonName) -> String {
+        match name.honorific.as_deref().filter(|h| !h.is_empty()) {
+            Some(honorific) if self._honorific_suffix => join_name_parts(&[formatted, honorific]),
+            Some(honorific) => format!("{} {}", honorific, formatted),
+            None => formatted.to_string(),
+        }
+    }
+}
+
+/// Joins non-empty name parts with a space, unless both sides of a join are
+/// written in a script without spaces between words.
+fn join_name_parts(parts: &[&str]) -> String {
+    let mut r = String::new();
+    for part in parts.iter().filter(|p| !p.is_empty()) {
+        let unspaced = r.chars().last().map(is_unspaced_script).unwrap_or(true)
+            && part.chars().next().map(is_unspaced_script).unwrap_or(true);
+        if !r.is_empty() && !unspaced {
+            r.push(' ');
+        }
+        r.push_str(part);
+    }
+    r
+}
+
+/// Whether a character belongs to the Han, Hiragana, Katakana or Hangul scripts,
+/// or CJK punctuation.
+fn is_unspaced_script(ch: char) -> bool {
+    matches!(ch as u32, 0x2E80..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF | 0x20000..=0x3FFFF)
+}
+
+/// Options for constructing a `PersonNameFormatter`.
+pub struct PersonNameFormatOptions {
+    _order: Cell<Option<PersonNameOrder>>,
+}
+
+impl PersonNameFormatOptions {
+    pub fn new() -> Self {
+        PersonNameFormatOptions {
+            _order: Cell::new(None),
+        }
+    }
+
+    /// Order of the given and family names. The default is the one of the locale's language.
+    pub fn order(&self, value: PersonNameOrder) -> &Self {
+        self._order.set(Some(value));
+        self
+    }
+}
+
+impl Default for PersonNameFormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
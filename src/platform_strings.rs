@@ -0,0 +1,265 @@
+use lazy_static::lazy_static;
+use lazy_regex::regex;
+
+/// Converts an Android string resource file, such as `res/values-pt-rBR/strings.xml`,
+/// into the asset representation used by `LocaleMap`.
+///
+/// Resource names are used as message identifiers, with `.` separating nested
+/// objects. `<plurals>` become the `_empty`, `_one` and `_multiple` variants, where the
+/// quantity is available as `$number`, and `<string-array>` items become `name.0`,
+/// `name.1` and so on. Format arguments such as `%s` and `%2$d` become `$1` and `$2`.
+pub fn parse_android_strings(src: &str) -> Result<serde_json::Value, String> {
+    let document = parse_xml(src)?;
+    let mut r = serde_json::Value::Object(serde_json::Map::new());
+    for node in document.root_element().children().filter(|n| n.is_element()) {
+        let name = node.attribute("name").ok_or_else(|| format!("<{}> element is missing the name attribute", node.tag_name().name()))?;
+        match node.tag_name().name() {
+            "string" => {
+                insert_message(&mut r, name, convert_format_arguments(&unescape_android(&element_text(&node)), false));
+            },
+            "plurals" => {
+                let quantities: Vec<(String, String)> = node.children()
+                    .filter(|n| n.has_tag_name("item"))
+                    .map(|n| (n.attribute("quantity").unwrap_or("other").to_string(), unescape_android(&element_text(&n))))
+                    .collect();
+                insert_plural(&mut r, name, &quantities);
+            },
+            "string-array" => {
+                for (i, item) in node.children().filter(|n| n.has_tag_name("item")).enumerate() {
+                    insert_message(&mut r, &format!("{}.{}", name, i), convert_format_arguments(&unescape_android(&element_text(&item)), false));
+                }
+            },
+            _ => {},
+        }
+    }
+    Ok(r)
+}
+
+/// Converts an Apple `.strings` file into the asset representation used by `LocaleMap`.
+///
+/// Keys are used as message identifiers, with `.` separating nested objects.
+/// Format arguments such as `%@` and `%2$d` become `$1` and `$2`.
+pub fn parse_apple_strings(src: &str) -> Result<serde_json::Value, String> {
+    let mut r = serde_json::Value::Object(serde_json::Map::new());
+    let mut chars = src.trim_start_matches('\u{feff}').chars().peekable();
+    loop {
+        skip_strings_whitespace(&mut chars)?;
+        if chars.peek().is_none() {
+            break;
+        }
+        let key = read_strings_token(&mut chars)?;
+        skip_strings_whitespace(&mut chars)?;
+        if chars.next() != Some('=') {
+            return Err(format!("Expected '=' after key \"{}\"", key));
+        }
+        skip_strings_whitespace(&mut chars)?;
+        let value = read_strings_token(&mut chars)?;
+        skip_strings_whitespace(&mut chars)?;
+        if chars.next() != Some(';') {
+            return Err(format!("Expected ';' after the value of \"{}\"", key));
+        }
+        insert_message(&mut r, &key, convert_format_arguments(&value, false));
+    }
+    Ok(r)
+}
+
+/// Converts an Apple `.stringsdict` file into the asset representation used by `LocaleMap`.
+///
+/// Each entry must have a format key with a single plural variable, such as
+/// `%#@files@`, whose `zero`, `one` and `other` forms become the `_empty`, `_one`
+/// and `_multiple` variants, where the quantity is available as `$number`.
+pub fn parse_apple_stringsdict(src: &str) -> Result<serde_json::Value, String> {
+    let document = parse_xml(src)?;
+    let root = document.root_element().children().find(|n| n.has_tag_name("dict"))
+        .ok_or_else(|| "Missing root <dict> element".to_string())?;
+    let mut r = serde_json::Value::Object(serde_json::Map::new());
+    for (name, entry) in plist_dict(&root) {
+        let entry = plist_dict(&entry);
+        let format = entry.iter().find(|(k, _)| k == "NSStringLocalizedFormatKey")
+            .and_then(|(_, v)| v.text())
+            .ok_or_else(|| format!("\"{}\" is missing NSStringLocalizedFormatKey", name))?;
+        let variable = regex!(r"%#@([A-Za-z0-9_]+)@").captures(format)
+            .map(|c| c.get(1).unwrap().as_str().to_string())
+            .ok_or_else(|| format!("\"{}\" has no plural variable", name))?;
+        let rule = entry.iter().find(|(k, _)| *k == variable)
+            .map(|(_, v)| plist_dict(v))
+            .ok_or_else(|| format!("\"{}\" is missing the rule for {}", name, variable))?;
+        let placeholder = format!("%#@{}@", variable);
+        let quantities: Vec<(String, String)> = rule.iter()
+            .filter(|(k, _)| !k.starts_with("NSStringFormat"))
+            .map(|(k, v)| (k.clone(), format.replace(&placeholder, v.text().unwrap_or(""))))
+            .collect();
+        insert_plural(&mut r, &name, &quantities);
+    }
+    Ok(r)
+}
+
+fn insert_message(output: &mut serde_json::Value, id: &str, message: String) {
+    let mut names: Vec<&str> = id.split('.').collect();
+    let last_name = names.pop().unwrap();
+    let mut output = output;
+    for name in names {
+        if output.get(name).and_then(|r| r.as_object()).is_none() {
+            output.as_object_mut().unwrap().insert(name.to_string(), serde_json::Value::Object(serde_json::Map::new()));
+        }
+        output = output.get_mut(name).unwrap();
+    }
+    output.as_object_mut().unwrap().insert(last_name.to_string(), serde_json::Value::String(message));
+}
+
+/// Inserts the `_empty`, `_one` and `_multiple` variants from CLDR plural categories,
+/// falling back to the `other` form.
+fn insert_plural(output: &mut serde_json::Value, id: &str, quantities: &[(String, String)]) {
+    let quantity = |category: &str| quantities.iter().find(|(q, _)| q == category).map(|(_, m)| m.clone());
+    let other = quantity("other").unwrap_or_default();
+    for (suffix, category) in [("empty", "zero"), ("one", "one"), ("multiple", "other")].iter() {
+        let message = quantity(category).unwrap_or_else(|| other.clone());
+        insert_message(output, &format!("{}_{}", id, suffix), convert_format_arguments(&message, true));
+    }
+}
+
+/// Converts printf-style format arguments into `$` variables. In plural
+/// messages, the first integer argument is the quantity and becomes `$number`.
+fn convert_format_arguments(message: &str, plural: bool) -> String {
+    let message = message.replace('$', "$$");
+    let mut position = 0;
+    regex!(r"%(?:(\d+)\$\$)?[-+ 0#]*\d*(?:\.\d+)?(?:hh|h|ll|l|q|z|t|j|L)?([@dDiuUxXoOfFeEgGcCsSpaA%])").replace_all(&message, |c: &regex::Captures<'_>| {
+        let conversion = c.get(2).unwrap().as_str();
+        if conversion == "%" {
+            return "%".to_string();
+        }
+        let index = match c.get(1) {
+            Some(i) => i.as_str().parse::<usize>().unwrap_or(1),
+            None => { position += 1; position },
+        };
+        if plural && index == 1 && "dDiuU".contains(conversion) {
+            "$number".to_string()
+        } else {
+            format!("${}", index)
+        }
+    }).to_string()
+}
+
+fn parse_xml(src: &str) -> Result<roxmltree::Document<'_>, String> {
+    // Property lists always declare a DOCTYPE.
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..roxmltree::ParsingOptions::default() };
+    roxmltree::Document::parse_with_options(src, options).map_err(|e| e.to_string())
+}
+
+fn element_text(node: &roxmltree::Node) -> String {
+    node.descendants().filter(|n| n.is_text()).filter_map(|n| n.text()).collect()
+}
+
+/// Applies Android string resource escaping: surrounding double quotes preserve
+/// whitespace, which is otherwise collapsed, and backslash escapes such as `\'` and `\n`.
+fn unescape_android(text: &str) -> String {
+    let mut r = String::new();
+    let mut chars = text.trim().chars();
+    let mut quoted = false;
+    let mut last_whitespace = false;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => { quoted = !quoted; last_whitespace = false; },
+            '\\' => {
+                match chars.next() {
+                    Some('n') => r.push('\n'),
+                    Some('t') => r.push('\t'),
+                    Some('u') => {
+                        let code: String = chars.by_ref().take(4).collect();
+                        if let Some(ch) = u32::from_str_radix(&code, 16).ok().and_then(std::char::from_u32) {
+                            r.push(ch);
+                        }
+                    },
+                    Some(ch) => r.push(ch),
+                    None => {},
+                }
+                last_whitespace = false;
+            },
+            ch if ch.is_whitespace() && !quoted => {
+                if !last_whitespace {
+                    r.push(' ');
+                }
+                last_whitespace = true;
+            },
+            ch => { r.push(ch); last_whitespace = false; },
+        }
+    }
+    r
+}
+
+fn skip_strings_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<(), String> {
+    loop {
+        match chars.peek() {
+            Some(ch) if ch.is_whitespace() => { chars.next(); },
+            Some('/') => {
+                chars.next();
+                match chars.next() {
+                    Some('/') => {
+                        for ch in chars.by_ref() {
+                            if ch == '\n' { break; }
+                        }
+                    },
+                    Some('*') => {
+                        let mut last = ' ';
+                        loop {
+                            match chars.next() {
+                                Some('/') if last == '*' => break,
+                                Some(ch) => last = ch,
+                                None => return Err("Unterminated comment".to_string()),
+                            }
+                        }
+                    },
+                    _ => return Err("Unexpected '/'".to_string()),
+                }
+            },
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn read_strings_token(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, String> {
+    let mut r = String::new();
+    if chars.peek() != Some(&'"') {
+        while let Some(ch) = chars.peek() {
+            if !(ch.is_alphanumeric() || "_.-".contains(*ch)) { break; }
+            r.push(*ch);
+            chars.next();
+        }
+        return if r.is_empty() { Err("Expected a string".to_string()) } else { Ok(r) };
+    }
+    chars.next();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(r),
+            Some('\\') => match chars.next() {
+                Some('n') => r.push('\n'),
+                Some('t') => r.push('\t'),
+                Some('r') => r.push('\r'),
+                Some('U') | Some('u') => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    if let Some(ch) = u32::from_str_radix(&code, 16).ok().and_then(std::char::from_u32) {
+                        r.push(ch);
+                    }
+                },
+                Some(ch) => r.push(ch),
+                None => return Err("Unterminated string".to_string()),
+            },
+            Some(ch) => r.push(ch),
+            None => return Err("Unterminated string".to_string()),
+        }
+    }
+}
+
+/// Lists the key and value elements of a property list `<dict>`.
+fn plist_dict<'a, 'input>(node: &roxmltree::Node<'a, 'input>) -> Vec<(String, roxmltree::Node<'a, 'input>)> {
+    let mut r = vec![];
+    let mut key: Option<String> = None;
+    for child in node.children().filter(|n| n.is_element()) {
+        if child.has_tag_name("key") {
+            key = Some(child.text().unwrap_or("").to_string());
+        } else if let Some(k) = key.take() {
+            r.push((k, child));
+        }
+    }
+    r
+}
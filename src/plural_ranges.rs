@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use lazy_static::lazy_static;
+use super::{pluralrules::PluralCategory, Locale};
+
+lazy_static! {
+    /// Plural range rules of CLDR whose category is not the category of the end of the
+    /// range, by language, keyed by the start and end categories, such as `"other-one"`.
+    static ref PLURAL_RANGE_EXCEPTIONS: HashMap<String, HashMap<String, String>> = serde_json::from_str::<HashMap<String, HashMap<String, String>>>(&String::from_utf8_lossy(include_bytes!("../locale-data/plural_ranges.json"))).unwrap();
+}
+
+/// Returns the plural category of a range of numbers, such as "3–5 days", given the
+/// categories of its start and end, following the plural ranges of CLDR. The category
+/// is the one of the end of the range, except where CLDR specifies otherwise
+/// for the locale's language, such as `other` for "0–1" in English.
+///
+/// ```
+/// use recoyx_localization::{plural_range_category, parse_locale, PluralCategory};
+///
+/// let en = parse_locale("en").unwrap();
+/// assert_eq!(plural_range_category(&en, &PluralCategory::OTHER, &PluralCategory::ONE), PluralCategory::OTHER);
+/// let ru = parse_locale("ru").unwrap();
+/// assert_eq!(plural_range_category(&ru, &PluralCategory::ONE, &PluralCategory::FEW), PluralCategory::FEW);
+/// ```
+pub fn plural_range_category(locale: &Locale, start: &PluralCategory, end: &PluralCategory) -> PluralCategory {
+    let (start, end) = (plural_category_keyword(start), plural_category_keyword(end));
+    let result = PLURAL_RANGE_EXCEPTIONS.get(locale.language())
+        .and_then(|exceptions| exceptions.get(&format!("{}-{}", start, end)))
+        .map(|result| result.as_str())
+        .unwrap_or(end);
+    plural_category_of_keyword(result)
+}
+
+/// Returns the CLDR keyword of a plural category, such as `"few"`.
+pub fn plural_category_keyword(category: &PluralCategory) -> &'static str {
+    match category {
+        PluralCategory::ZERO => "zero",
+        PluralCategory::ONE => "one",
+        PluralCategory::TWO => "two",
+        PluralCategory::FEW => "few",
+        PluralCategory::MANY => "many",
+        PluralCategory::OTHER => "other",
+    }
+}
+
+fn plural_category_of_keyword(keyword: &str) -> PluralCategory {
+    match keyword {
+        "zero" => PluralCategory::ZERO,
+        "one" => PluralCategory::ONE,
+        "two" => PluralCategory::TWO,
+        "few" => PluralCategory::FEW,
+        "many" => PluralCategory::MANY,
+        _ => PluralCategory::OTHER,
+    }
+}
+
+/// A range of numbers, used as a message formatting argument so that a message can
+/// agree with the range, such as "3–5 days". The identifier of the message is followed
+/// by `_range_` and the plural category of the range, such as `delivery_range_few`,
+/// and the message receives the `$start` and `$end` variables.
+#[derive(Clone)]
+pub struct PluralRange {
+    pub(crate) start: String,
+    pub(crate) end: String,
+}
+
+impl PluralRange {
+    pub fn new<N: ToString>(start: N, end: N) -> Self {
+        Self { start: start.to_string(), end: end.to_string() }
+    }
+
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    pub fn end(&self) -> &str {
+        &self.end
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use chrono::{Datelike, Duration, NaiveDate};
+use super::{Country, Weekday};
+
+/// Source of the holidays of countries for a `RegionCalendar`, such as a table
+/// of public holidays or a company calendar. Functions taking a date and a country
+/// and returning the name of the holiday are holiday providers.
+pub trait HolidayProvider {
+    /// Returns the name of the holiday on a date in a country, if any.
+    fn holiday(&self, date: NaiveDate, country: &Country) -> Option<String>;
+}
+
+impl<F: Fn(NaiveDate, &Country) -> Option<String>> HolidayProvider for F {
+    fn holiday(&self, date: NaiveDate, country: &Country) -> Option<String> {
+        self(date, country)
+    }
+}
+
+/// Tells apart business days from weekends and holidays by region, such as for
+/// scheduling views and delivery estimates. The weekend days of each country come
+/// from CLDR, such as Friday and Saturday in Egypt, and can be replaced, while
+/// holidays come from the registered providers, as the crate bundles none.
+///
+/// ```
+/// use recoyx_localization::{parse_country, RegionCalendar};
+/// use chrono::{Datelike, NaiveDate};
+///
+/// let mut calendar = RegionCalendar::new();
+/// calendar.add_holiday_provider(|date: NaiveDate, country: &recoyx_localization::Country| {
+///     if country.standard_code().alpha2() == "BR" && date.month() == 9 && date.day() == 7 { Some("Independence Day".to_string()) } else { None }
+/// });
+/// let friday = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+/// assert!(calendar.is_business_day(friday, &parse_country("US").unwrap()));
+/// assert!(!calendar.is_business_day(friday, &parse_country("SA").unwrap()));
+/// assert!(!calendar.is_business_day(NaiveDate::from_ymd_opt(2025, 9, 7).unwrap(), &parse_country("BR").unwrap()));
+/// ```
+#[derive(Clone, Default)]
+pub struct RegionCalendar {
+    /// Weekend days replacing the CLDR ones, keyed by ISO 3166-1 alpha-2 code.
+    _weekends: HashMap<String, Vec<Weekday>>,
+    _providers: Vec<Rc<dyn HolidayProvider>>,
+}
+
+impl RegionCalendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a holiday provider. Providers are asked in the order they were registered.
+    pub fn add_holiday_provider<P: HolidayProvider + 'static>(&mut self, provider: P) {
+        self._providers.push(Rc::new(provider));
+    }
+
+    /// Replaces the weekend days of a country, such as for a business open on Saturdays.
+    pub fn set_weekend(&mut self, country: &Country, days: &[Weekday]) {
+        self._weekends.insert(country.standard_code().alpha2().to_string(), days.to_vec());
+    }
+
+    /// Returns the weekend days of a country; see `Country::weekend()`.
+    pub fn weekend(&self, country: &Country) -> Vec<Weekday> {
+        self._weekends.get(country.standard_code().alpha2()).cloned().unwrap_or_else(|| country.weekend())
+    }
+
+    pub fn is_weekend(&self, date: NaiveDate, country: &Country) -> bool {
+        self.weekend(country).contains(&Weekday::from_chrono(date.weekday()))
+    }
+
+    /// Returns the name of the holiday on a date in a country from the first
+    /// provider that knows one, if any.
+    pub fn holiday(&self, date: NaiveDate, country: &Country) -> Option<String> {
+        self._providers.iter().find_map(|provider| provider.holiday(date, country))
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate, country: &Country) -> bool {
+        self.holiday(date, country).is_some()
+    }
+
+    /// Returns `true` if a date is neither a weekend day nor a holiday in a country.
+    pub fn is_business_day(&self, date: NaiveDate, country: &Country) -> bool {
+        !self.is_weekend(date, country) && !self.is_holiday(date, country)
+    }
+
+    /// Returns the first business day after a date in a country, or `None` if there
+    /// is none in the following year.
+    pub fn next_business_day(&self, date: NaiveDate, country: &Country) -> Option<NaiveDate> {
+        (1..=366).map(|days| date + Duration::days(days)).find(|date| self.is_business_day(*date, country))
+    }
+
+    /// Returns the number of business days from a date to another in a country,
+    /// counting the start and not the end.
+    pub fn business_days_between(&self, start: NaiveDate, end: NaiveDate, country: &Country) -> u32 {
+        start.iter_days().take_while(|date| *date < end).filter(|date| self.is_business_day(*date, country)).count() as u32
+    }
+}
@@ -0,0 +1,174 @@
+use std::{cell::Cell, cmp::Ordering, ops::Range};
+use icu_normalizer::{ComposingNormalizerBorrowed, DecomposingNormalizerBorrowed};
+use super::{segmenter::grapheme_indices, Locale};
+
+/// Letters with diacritics that a language's alphabet treats as distinct letters,
+/// which therefore do not match their base letter when diacritics are ignored.
+static DISTINCT_LETTERS: &[(&str, &str)] = &[
+    ("az", "çğıöşü"),
+    ("da", "å"),
+    ("es", "ñ"),
+    ("et", "äõöüšž"),
+    ("fi", "åäö"),
+    ("nb", "å"),
+    ("nn", "å"),
+    ("no", "å"),
+    ("sv", "åäö"),
+    ("tr", "çğıöşü"),
+];
+
+/// Compares text for searching, such as in a localized search box, ignoring case and
+/// diacritics by default as the locale's language does, so `Šimon` matches a search
+/// for `simon` while, in Swedish, `ö` does not match `o`. Compatibility characters,
+/// such as fullwidth letters and ligatures, match their usual forms.
+///
+/// The Turkish and Azerbaijani dotted and dotless `i` are respected, so `I` only matches
+/// `ı` and `İ` only matches `i` for those languages.
+///
+/// ```
+/// use recoyx_localization::{SearchCollator, SearchCollatorOptions, parse_locale};
+///
+/// let en = SearchCollator::new(&parse_locale("en").unwrap(), &SearchCollatorOptions::new());
+/// assert!(en.contains("Dr. Šimon Novák", "simon nov"));
+/// assert_eq!(en.find("Crème Brûlée", "brulee"), Some(7..15));
+/// let tr = SearchCollator::new(&parse_locale("tr").unwrap(), &SearchCollatorOptions::new());
+/// assert!(!tr.contains("ISTANBUL", "istanbul"));
+/// assert!(tr.contains("İSTANBUL", "istanbul"));
+/// ```
+#[derive(Clone)]
+pub struct SearchCollator {
+    _ignore_case: bool,
+    _ignore_diacritics: bool,
+    _turkic: bool,
+    _distinct_letters: &'static str,
+}
+
+impl SearchCollator {
+    pub fn new(locale: &Locale, options: &SearchCollatorOptions) -> Self {
+        let language = locale.language();
+        Self {
+            _ignore_case: options._ignore_case.get(),
+            _ignore_diacritics: options._ignore_diacritics.get(),
+            _turkic: language == "tr" || language == "az",
+            _distinct_letters: DISTINCT_LETTERS.iter().find(|(l, _)| *l == language).map(|(_, letters)| *letters).unwrap_or(""),
+        }
+    }
+
+    /// Folds text into the form it is compared in.
+    pub fn fold(&self, text: &str) -> String {
+        let mut r = String::new();
+        for (_, cluster) in grapheme_indices(text) {
+            self.fold_cluster(cluster, &mut r);
+        }
+        r
+    }
+
+    /// Whether two strings are equal once folded.
+    pub fn equals(&self, a: &str, b: &str) -> bool {
+        self.fold(a) == self.fold(b)
+    }
+
+    /// Orders two strings alphabetically by their folded forms, where the letters
+    /// the language treats as distinct follow the basic Latin ones, as `ö` follows `z`
+    /// in Swedish, breaking ties by the strings themselves. This approximates the
+    /// collation of the locale, such as for sorting short lists of names.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.fold(a).cmp(&self.fold(b)).then_with(|| a.cmp(b))
+    }
+
+    pub fn contains(&self, text: &str, query: &str) -> bool {
+        self.find(text, query).is_some()
+    }
+
+    pub fn starts_with(&self, text: &str, query: &str) -> bool {
+        self.fold(text).starts_with(&self.fold(query))
+    }
+
+    /// Returns the byte range of the first match of a query in a text, extended to
+    /// the grapheme clusters it overlaps, such as for highlighting search results.
+    pub fn find(&self, text: &str, query: &str) -> Option<Range<usize>> {
+        let query = self.fold(query);
+        // Maps each byte of the folded text to the grapheme cluster it comes from.
+        let mut folded = String::new();
+        let mut clusters: Vec<Range<usize>> = vec![];
+        for (i, cluster) in grapheme_indices(text) {
+            let start = folded.len();
+            self.fold_cluster(cluster, &mut folded);
+            clusters.extend(std::iter::repeat_n(i..i + cluster.len(), folded.len() - start));
+        }
+        let start = folded.find(&query)?;
+        if query.is_empty() {
+            let index = clusters.get(start).map(|c| c.start).unwrap_or(text.len());
+            return Some(index..index);
+        }
+        Some(clusters[start].start..clusters[start + query.len() - 1].end)
+    }
+
+    fn fold_cluster(&self, cluster: &str, output: &mut String) {
+        let cluster = ComposingNormalizerBorrowed::new_nfc().normalize(cluster);
+        for ch in cluster.chars() {
+            let ch = match ch {
+                'I' if self._turkic && self._ignore_case => 'ı',
+                'İ' if self._turkic && self._ignore_case => 'i',
+                _ => ch,
+            };
+            let lowercase: String = if self._ignore_case {
+                match ch {
+                    'ß' | 'ẞ' => "ss".to_string(),
+                    'ς' => "σ".to_string(),
+                    _ => ch.to_lowercase().collect(),
+                }
+            } else {
+                ch.to_string()
+            };
+            for ch in lowercase.chars() {
+                if !self._ignore_diacritics || self._distinct_letters.contains(ch.to_lowercase().next().unwrap_or(ch)) {
+                    output.push(ch);
+                    continue;
+                }
+                let decomposed = ch.to_string();
+                output.extend(DecomposingNormalizerBorrowed::new_nfkd().normalize(&decomposed).chars().filter(|c| !is_diacritic(*c)));
+            }
+        }
+    }
+}
+
+/// Whether a character is a combining diacritical mark, excluding the Japanese
+/// voiced sound marks, which distinguish kana such as `が` from `か`.
+fn is_diacritic(ch: char) -> bool {
+    matches!(ch as u32, 0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F)
+}
+
+/// Options for constructing a `SearchCollator`.
+pub struct SearchCollatorOptions {
+    _ignore_case: Cell<bool>,
+    _ignore_diacritics: Cell<bool>,
+}
+
+impl SearchCollatorOptions {
+    pub fn new() -> Self {
+        SearchCollatorOptions {
+            _ignore_case: Cell::new(true),
+            _ignore_diacritics: Cell::new(true),
+        }
+    }
+
+    /// Whether uppercase and lowercase letters match. The default is `true`.
+    pub fn ignore_case(&self, value: bool) -> &Self {
+        self._ignore_case.set(value);
+        self
+    }
+
+    /// Whether letters match regardless of diacritics, except for the letters the
+    /// locale's language treats as distinct. The default is `true`.
+    pub fn ignore_diacritics(&self, value: bool) -> &Self {
+        self._ignore_diacritics.set(value);
+        self
+    }
+}
+
+impl Default for SearchCollatorOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
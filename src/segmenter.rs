@@ -0,0 +1,114 @@
+#[cfg(feature = "segmentation")]
+use icu_segmenter::{options::{SentenceBreakInvariantOptions, WordBreakInvariantOptions}, GraphemeClusterSegmenter, SentenceSegmenter, WordSegmenter};
+#[cfg(feature = "segmentation")]
+use super::Locale;
+
+/// Abbreviations after which a full stop does not end a sentence, per language,
+/// following the sentence break suppressions of CLDR.
+#[cfg(feature = "segmentation")]
+static SENTENCE_BREAK_SUPPRESSIONS: &[(&str, &[&str])] = &[
+    ("de", &["Dr.", "Hr.", "Fr.", "Nr.", "Prof.", "bzw.", "ca.", "vgl.", "z.B.", "d.h."]),
+    ("en", &["Mr.", "Mrs.", "Ms.", "Dr.", "Prof.", "St.", "Jr.", "Sr.", "vs.", "e.g.", "i.e.", "No."]),
+    ("es", &["Sr.", "Sra.", "Srta.", "Dr.", "Dra.", "Ud.", "Uds.", "pág."]),
+    ("fr", &["M.", "MM.", "Mme.", "Mlle.", "Dr.", "p.", "cf."]),
+    ("it", &["Sig.", "Sig.ra", "Dott.", "Prof.", "pag."]),
+    ("pt", &["Sr.", "Sra.", "Dr.", "Dra.", "Prof.", "pág.", "p.ex."]),
+];
+
+/// Splits text into grapheme clusters, words and sentences following the Unicode text
+/// segmentation algorithm (UAX #29), such as for truncating localized strings without
+/// splitting a character sequence, or counting words where splitting at whitespace fails.
+/// Available through the `segmentation` feature, which is enabled by default.
+///
+/// Text in scripts written without spaces, such as Chinese, Japanese, Thai, Lao, Khmer
+/// and Burmese, is split into words with the dictionaries of ICU4X, whichever the locale.
+///
+/// ```
+/// use recoyx_localization::{Segmenter, parse_locale};
+///
+/// let en = parse_locale("en").unwrap();
+/// assert_eq!(Segmenter::words("The quick (\"brown\") fox", &en).collect::<Vec<_>>(), vec!["The", "quick", "brown", "fox"]);
+/// assert_eq!(Segmenter::graphemes("e\u{301}🇧🇷", &en).count(), 2);
+/// assert_eq!(Segmenter::sentences("Mr. Smith is here. He waits.", &en).collect::<Vec<_>>(), vec!["Mr. Smith is here. ", "He waits."]);
+/// ```
+#[cfg(feature = "segmentation")]
+pub struct Segmenter;
+
+#[cfg(feature = "segmentation")]
+impl Segmenter {
+    /// Returns the extended grapheme clusters of a text, which are what users
+    /// perceive as characters, such as `é` written as `e` and a combining accent.
+    pub fn graphemes<'a>(text: &'a str, _locale: &Locale) -> impl Iterator<Item = &'a str> + 'a {
+        grapheme_indices(text).map(|(_, cluster)| cluster)
+    }
+
+    /// Returns the words of a text, skipping whitespace and punctuation.
+    pub fn words<'a>(text: &'a str, _locale: &Locale) -> impl Iterator<Item = &'a str> + 'a {
+        word_segments(text).into_iter().filter(|(_, word_like)| *word_like).map(|(word, _)| word)
+    }
+
+    /// Returns the segments between word boundaries, including whitespace and
+    /// punctuation, so that joining them restores the text.
+    pub fn word_boundaries<'a>(text: &'a str, _locale: &Locale) -> impl Iterator<Item = &'a str> + 'a {
+        word_segments(text).into_iter().map(|(segment, _)| segment)
+    }
+
+    /// Returns the number of words of a text; see `words()`.
+    pub fn word_count(text: &str, locale: &Locale) -> usize {
+        Segmenter::words(text, locale).count()
+    }
+
+    /// Returns the sentences of a text, each including its trailing whitespace.
+    /// A full stop after an abbreviation of the locale's language, such as `Mr.`
+    /// in English or `z.B.` in German, does not end a sentence.
+    pub fn sentences<'a>(text: &'a str, locale: &Locale) -> impl Iterator<Item = &'a str> + 'a {
+        let suppressions = SENTENCE_BREAK_SUPPRESSIONS.iter()
+            .find(|(language, _)| *language == locale.language())
+            .map(|(_, abbreviations)| *abbreviations)
+            .unwrap_or(&[]);
+        let mut sentences: Vec<&'a str> = vec![];
+        let mut start = 0;
+        for end in SentenceSegmenter::new(SentenceBreakInvariantOptions::default()).segment_str(text).filter(|end| *end > 0) {
+            let last_word = text[start..end].trim_end().rsplit(char::is_whitespace).next().unwrap_or("");
+            if suppressions.contains(&last_word) && end < text.len() {
+                continue;
+            }
+            sentences.push(&text[start..end]);
+            start = end;
+        }
+        if start < text.len() {
+            sentences.push(&text[start..]);
+        }
+        sentences.into_iter()
+    }
+}
+
+/// Returns the segments between word boundaries of a text, along with whether
+/// each is a word rather than whitespace or punctuation.
+#[cfg(feature = "segmentation")]
+fn word_segments(text: &str) -> Vec<(&str, bool)> {
+    let mut r = vec![];
+    let mut start = 0;
+    for (end, word_type) in WordSegmenter::new_dictionary(WordBreakInvariantOptions::default()).segment_str(text).iter_with_word_type().filter(|(end, _)| *end > 0) {
+        r.push((&text[start..end], word_type.is_word_like()));
+        start = end;
+    }
+    r
+}
+
+/// Returns the extended grapheme clusters of a text with their byte indices,
+/// or its characters without the `segmentation` feature.
+#[cfg(feature = "segmentation")]
+pub(crate) fn grapheme_indices(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = 0;
+    GraphemeClusterSegmenter::new().segment_str(text).filter(|end| *end > 0).map(move |end| {
+        let cluster = (start, &text[start..end]);
+        start = end;
+        cluster
+    })
+}
+
+#[cfg(not(feature = "segmentation"))]
+pub(crate) fn grapheme_indices(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.char_indices().map(move |(i, ch)| (i, &text[i..i + ch.len_utf8()]))
+}
@@ -0,0 +1,190 @@
+use std::{cell::RefCell, collections::HashMap, future::Future, rc::Rc, sync::{Arc, atomic::{AtomicUsize, Ordering}}};
+use tokio::task::futures::TaskLocalFuture;
+use super::{parse_locale, Locale, LocaleMap, LocaleMapFormatArgument, LocaleMatcher, MessageTree};
+
+type LocaleMapFactory = Box<dyn Fn() -> LocaleMap + Send + Sync>;
+
+static NEXT_CATALOG_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The `LocaleMap` of each catalog and locale tag built by the current thread,
+    /// shared so that its message cache lasts across calls to `Localization::with()`.
+    static LOCALE_MAPS: RefCell<HashMap<(usize, String), Rc<LocaleMap>>> = RefCell::new(HashMap::new());
+
+    /// The localizations entered by the current thread through `Localization::enter()`, innermost last.
+    static ENTERED: RefCell<Vec<Localization>> = const { RefCell::new(Vec::new()) };
+}
+
+tokio::task_local! {
+    /// The localization of the current task, set through `Localization::scope()`.
+    static TASK_LOCALIZATION: Localization;
+}
+
+/// The messages of every supported locale of a `LocaleMap`, loaded once and
+/// shared across threads, such as by the handlers of a multi-threaded server
+/// or by template engines.
+///
+/// Since a `LocaleMap` cannot be shared across threads, each thread builds its
+/// own `LocaleMap` from these messages, through the function given to `load()`,
+/// when it first uses a locale.
+#[derive(Clone)]
+pub struct SharedCatalog {
+    _inner: Arc<CatalogInner>,
+}
+
+struct CatalogInner {
+    id: usize,
+    make_locale_map: LocaleMapFactory,
+    /// Messages of each locale, by locale tag.
+    assets: HashMap<String, serde_json::Value>,
+    supported_locales: Vec<String>,
+    default_locale: String,
+}
+
+impl SharedCatalog {
+    /// Loads every supported locale of the `LocaleMap` returned by a function,
+    /// which is called again by each thread, so it should only construct
+    /// the `LocaleMap` and register its formatters. Returns an error naming
+    /// the first locale whose resources fail to load.
+    pub async fn load<F: Fn() -> LocaleMap + Send + Sync + 'static>(make_locale_map: F) -> Result<Self, String> {
+        let mut locale_map = make_locale_map();
+        let mut supported_locales: Vec<Locale> = locale_map.supported_locales().into_iter().collect();
+        supported_locales.sort();
+        for locale in supported_locales.iter() {
+            if !locale_map.load_secondary_locale(locale).await {
+                return Err(format!("Failed to load {}", locale.standard_tag()));
+            }
+        }
+        let assets = locale_map._session._assets.iter()
+            .map(|(locale, tree)| (locale.standard_tag().to_string(), tree.to_json()))
+            .collect();
+        Ok(Self {
+            _inner: Arc::new(CatalogInner {
+                id: NEXT_CATALOG_ID.fetch_add(1, Ordering::Relaxed),
+                make_locale_map: Box::new(make_locale_map),
+                assets,
+                supported_locales: supported_locales.iter().map(|l| l.standard_tag().to_string()).collect(),
+                default_locale: locale_map._config._default_locale.standard_tag().to_string(),
+            }),
+        })
+    }
+
+    /// Returns the supported locales.
+    pub fn supported_locales(&self) -> Vec<Locale> {
+        self._inner.supported_locales.iter().map(|tag| parse_locale(tag).unwrap()).collect()
+    }
+
+    /// Returns the default locale.
+    pub fn default_locale(&self) -> Locale {
+        parse_locale(&self._inner.default_locale).unwrap()
+    }
+
+    /// Returns the localization for the supported locale that best matches
+    /// the requested locales, given in order of preference, or the default locale.
+    pub fn negotiate(&self, requested: &[Locale]) -> Localization {
+        let locale = LocaleMatcher::new(self.supported_locales()).best_match(requested)
+            .unwrap_or_else(|| self.default_locale());
+        Localization { _catalog: self.clone(), _locale: locale.standard_tag().to_string() }
+    }
+
+    /// Calls a function with the localization for the supported locale that best matches
+    /// a locale as the current localization of the thread; see `Localization::enter()`.
+    pub fn with_locale<R, F: FnOnce() -> R>(&self, locale: &Locale, f: F) -> R {
+        self.negotiate(std::slice::from_ref(locale)).enter(f)
+    }
+
+    /// Returns the current localization, set through `Localization::enter()` or
+    /// `Localization::scope()`, if it is one of this catalog, otherwise the localization
+    /// of the default locale. This lets code deep within a request retrieve messages
+    /// in the language of the request without the `Localization` being passed to it.
+    pub fn current(&self) -> Localization {
+        Localization::current()
+            .filter(|localization| Arc::ptr_eq(&localization._catalog._inner, &self._inner))
+            .unwrap_or_else(|| Localization { _catalog: self.clone(), _locale: self._inner.default_locale.clone() })
+    }
+
+    /// Retrieves message by identifier in the current localization; see `current()`.
+    pub fn get<S: ToString>(&self, id: S) -> String {
+        self.current().get(id)
+    }
+
+    /// Retrieves message by identifier with formatting arguments in the current localization;
+    /// see `current()`.
+    pub fn get_formatted<S: ToString>(&self, id: S, options: Vec<&dyn LocaleMapFormatArgument>) -> String {
+        self.current().get_formatted(id, options)
+    }
+
+    fn locale_map(&self, tag: &str) -> LocaleMap {
+        let mut r = (self._inner.make_locale_map)();
+        for (locale, root) in self._inner.assets.iter() {
+            std::rc::Rc::make_mut(&mut r._session._assets).insert(parse_locale(locale).unwrap(), MessageTree::from_json(root, r._config._normalization));
+        }
+        r.set_current_locale(parse_locale(tag).unwrap());
+        r
+    }
+}
+
+/// The messages of a `SharedCatalog` in a locale, obtained from `SharedCatalog::negotiate()`.
+/// Unlike a `LocaleMap`, a `Localization` can be sent across threads.
+#[derive(Clone)]
+pub struct Localization {
+    _catalog: SharedCatalog,
+    _locale: String,
+}
+
+impl Localization {
+    /// Returns the locale.
+    pub fn locale(&self) -> Locale {
+        parse_locale(&self._locale).unwrap()
+    }
+
+    /// Calls a function with a `LocaleMap` of the current thread whose current locale is the locale.
+    pub fn with<R, F: FnOnce(&LocaleMap) -> R>(&self, f: F) -> R {
+        let key = (self._catalog._inner.id, self._locale.clone());
+        // The map is not borrowed while the function runs, which may retrieve
+        // messages of other localizations.
+        let locale_map = LOCALE_MAPS.with(|maps| {
+            maps.borrow_mut().entry(key).or_insert_with(|| Rc::new(self._catalog.locale_map(&self._locale))).clone()
+        });
+        f(&locale_map)
+    }
+
+    /// Calls a function with this localization as the current localization of the thread,
+    /// restoring the previous one afterwards, even if the function panics.
+    /// See `SharedCatalog::current()`.
+    pub fn enter<R, F: FnOnce() -> R>(&self, f: F) -> R {
+        struct Exit;
+        impl Drop for Exit {
+            fn drop(&mut self) {
+                ENTERED.with(|entered| entered.borrow_mut().pop());
+            }
+        }
+        ENTERED.with(|entered| entered.borrow_mut().push(self.clone()));
+        let _exit = Exit;
+        f()
+    }
+
+    /// Runs a future with this localization as the current localization of its task,
+    /// across `.await` points and the threads the task moves to, such as for
+    /// a request handler. See `SharedCatalog::current()`.
+    pub fn scope<F: Future>(self, future: F) -> TaskLocalFuture<Localization, F> {
+        TASK_LOCALIZATION.scope(self, future)
+    }
+
+    /// Returns the localization of the innermost `enter()` on the current thread,
+    /// otherwise of the `scope()` of the current task, if any.
+    pub fn current() -> Option<Localization> {
+        ENTERED.with(|entered| entered.borrow().last().cloned())
+            .or_else(|| TASK_LOCALIZATION.try_with(|localization| localization.clone()).ok())
+    }
+
+    /// Retrieves message by identifier; see `LocaleMap::get()`.
+    pub fn get<S: ToString>(&self, id: S) -> String {
+        self.with(|locale_map| locale_map.get(id))
+    }
+
+    /// Retrieves message by identifier with formatting arguments; see `LocaleMap::get_formatted()`.
+    pub fn get_formatted<S: ToString>(&self, id: S, options: Vec<&dyn LocaleMapFormatArgument>) -> String {
+        self.with(|locale_map| locale_map.get_formatted(id, options))
+    }
+}
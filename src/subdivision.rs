@@ -0,0 +1,65 @@
+use super::{Country, Locale, SubdivisionData, SUBDIVISION_DATA};
+use std::fmt::{Display, Formatter};
+
+/// A country subdivision, such as a state, province or region, identified by
+/// its ISO 3166-2 code, such as `BR-SP` for São Paulo.
+#[derive(Clone)]
+pub struct Subdivision {
+    pub(crate) _code: &'static str,
+    pub(crate) _data: &'static SubdivisionData,
+}
+
+impl Subdivision {
+    /// Returns the ISO 3166-2 code, such as `"BR-SP"`.
+    pub fn code(&self) -> &str {
+        self._code
+    }
+
+    pub fn country(&self) -> Country {
+        super::parse_country(&self._code[..2]).unwrap()
+    }
+
+    /// Returns the kind of subdivision in English, such as `"state"`, `"province"`
+    /// or `"territory"`.
+    pub fn category(&self) -> &str {
+        &self._data.category
+    }
+
+    /// Returns the English name, such as `"Bavaria"`, or the ISO 3166-2 name
+    /// if there is none.
+    pub fn universal_name(&self) -> &str {
+        self._data.names.get("en").unwrap_or(&self._data.iso_name)
+    }
+
+    /// Returns the ISO 3166-2 name, which is usually in or romanized from a language
+    /// of the country rather than English, such as `"Bayern"`.
+    pub fn iso_name(&self) -> &str {
+        &self._data.iso_name
+    }
+
+    /// Returns the name in the language of a locale, such as `"Bayern"` for `de`,
+    /// or the universal name if there is none.
+    pub fn name(&self, locale: &Locale) -> &str {
+        self._data.names.get(locale.language()).map(|s| s.as_ref()).unwrap_or_else(|| self.universal_name())
+    }
+}
+
+impl PartialEq for Subdivision {
+    fn eq(&self, rhs: &Subdivision) -> bool {
+        self._code == rhs._code
+    }
+}
+
+impl Display for Subdivision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self._code)
+    }
+}
+
+/// Parses an ISO 3166-2 subdivision code, such as `BR-SP` or `us-ca`.
+pub fn parse_subdivision<S: ToString>(src: S) -> Result<Subdivision, String> {
+    let src = src.to_string().trim().replace('_', "-").to_uppercase();
+    SUBDIVISION_DATA.get_key_value(&src)
+        .map(|(code, data)| Subdivision { _code: code.as_ref(), _data: data })
+        .ok_or_else(|| format!("Unknown subdivision code {:?}.", src))
+}
@@ -0,0 +1,128 @@
+//! Message lookup for template engines, available through the `tera`,
+//! `handlebars` and `askama` features.
+//!
+//! Tera and Handlebars templates receive a `t` function or helper backed by
+//! a `SharedCatalog`, taking the message identifier, an optional `lang` argument
+//! with the locale tag, an optional `count` argument selecting the quantity
+//! suffix and any variables:
+//!
+//! ```text
+//! {{ t(key="common.greeting", lang=lang, name=user) }}   Tera
+//! {{t "common.greeting" lang=lang name=user}}            Handlebars
+//! ```
+//!
+//! Without `lang`, the Handlebars helper uses the `lang` field of the rendered data,
+//! and both fall back to the default locale. Askama templates use the `t` filter
+//! with a `Localization`, and the `t_args` filter to also pass variables and `count`
+//! as a tuple of pairs or any value serialized as a map:
+//!
+//! ```text
+//! {{ "common.greeting"|t(loc) }}
+//! {{ "common.greeting"|t_args(loc, (("name", user.as_str()), ("count", 2))) }}
+//! ```
+
+use std::collections::HashMap;
+use super::Localization;
+#[cfg(any(feature = "tera", feature = "handlebars"))]
+use super::{parse_locale, SharedCatalog};
+
+/// Formats a message with the arguments given in a template, where `count`
+/// selects the quantity suffix and every argument is a variable.
+#[cfg(any(feature = "tera", feature = "handlebars", feature = "askama"))]
+fn format_message<'a, I: Iterator<Item = (&'a str, &'a serde_json::Value)>>(localization: &Localization, key: &str, args: I) -> String {
+    let mut vars = HashMap::<String, String>::new();
+    let mut count = None;
+    for (name, value) in args {
+        if name == "count" {
+            count = value.as_i64();
+        }
+        vars.insert(name.to_string(), match value {
+            serde_json::Value::String(s) => s.clone(),
+            value => value.to_string(),
+        });
+    }
+    match count {
+        Some(count) => localization.get_formatted(key, vec![&vars, &count]),
+        None => localization.get_formatted(key, vec![&vars]),
+    }
+}
+
+/// Returns the localization of a `lang` argument, or of the default locale.
+#[cfg(any(feature = "tera", feature = "handlebars"))]
+fn localization_of(catalog: &SharedCatalog, lang: Option<&serde_json::Value>) -> Localization {
+    let requested: Vec<_> = lang.and_then(|l| l.as_str()).and_then(|l| parse_locale(l).ok()).into_iter().collect();
+    catalog.negotiate(&requested)
+}
+
+/// Returns a Tera function retrieving messages, registered as in
+/// `tera.register_function("t", tera_function(catalog))`.
+#[cfg(feature = "tera")]
+pub fn tera_function(catalog: SharedCatalog) -> impl tera::Function {
+    move |args: &HashMap<String, tera::Value>| -> tera::Result<tera::Value> {
+        let key = args.get("key").and_then(|k| k.as_str())
+            .ok_or_else(|| tera::Error::msg("Function `t` requires a string `key` argument"))?;
+        let localization = localization_of(&catalog, args.get("lang"));
+        let args = args.iter().filter(|(name, _)| *name != "key" && *name != "lang");
+        Ok(tera::Value::String(format_message(&localization, key, args.map(|(name, value)| (name.as_str(), value)))))
+    }
+}
+
+/// A Handlebars helper retrieving messages, registered as in
+/// `handlebars.register_helper("t", Box::new(HandlebarsHelper::new(catalog)))`.
+#[cfg(feature = "handlebars")]
+pub struct HandlebarsHelper {
+    _catalog: SharedCatalog,
+}
+
+#[cfg(feature = "handlebars")]
+impl HandlebarsHelper {
+    pub fn new(catalog: SharedCatalog) -> Self {
+        Self { _catalog: catalog }
+    }
+}
+
+#[cfg(feature = "handlebars")]
+impl handlebars::HelperDef for HandlebarsHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &handlebars::Helper<'rc>,
+        r: &'reg handlebars::Handlebars<'reg>,
+        ctx: &'rc handlebars::Context,
+        _: &mut handlebars::RenderContext<'reg, 'rc>,
+        out: &mut dyn handlebars::Output,
+    ) -> handlebars::HelperResult {
+        let key = h.param(0).and_then(|p| p.value().as_str())
+            .ok_or(handlebars::RenderErrorReason::ParamNotFoundForIndex("t", 0))?;
+        let lang = h.hash_get("lang").map(|l| l.value()).or_else(|| ctx.data().get("lang"));
+        let localization = localization_of(&self._catalog, lang);
+        let args = h.hash().iter().filter(|(name, _)| **name != "lang").map(|(name, value)| (*name, value.value()));
+        out.write(&r.get_escape_fn()(&format_message(&localization, key, args)))?;
+        Ok(())
+    }
+}
+
+/// An Askama filter retrieving the message at an identifier, used by declaring
+/// `pub use recoyx_localization::templates::t;` in the `filters` module of the template.
+#[cfg(feature = "askama")]
+pub fn t<K: std::fmt::Display>(key: K, localization: &Localization) -> askama::Result<String> {
+    Ok(localization.get(key))
+}
+
+/// An Askama filter retrieving the message at an identifier with variables, where
+/// `count` selects the quantity suffix. The arguments are a tuple of `(name, value)`
+/// pairs or a value serialized as a map, such as a `HashMap` or a struct.
+#[cfg(feature = "askama")]
+pub fn t_args<K: std::fmt::Display, A: serde::Serialize>(key: K, localization: &Localization, args: A) -> askama::Result<String> {
+    let args = match serde_json::to_value(args).map_err(|e| askama::Error::Custom(Box::new(e)))? {
+        serde_json::Value::Object(map) => map.into_iter().collect::<Vec<_>>(),
+        serde_json::Value::Array(pairs) => pairs.into_iter().filter_map(|pair| match pair {
+            serde_json::Value::Array(mut pair) if pair.len() == 2 => {
+                let value = pair.pop().unwrap();
+                pair.pop().unwrap().as_str().map(|name| (name.to_string(), value))
+            },
+            _ => None,
+        }).collect(),
+        _ => vec![],
+    };
+    Ok(format_message(localization, &key.to_string(), args.iter().map(|(name, value)| (name.as_str(), value))))
+}
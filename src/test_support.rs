@@ -0,0 +1,57 @@
+//! Helpers for unit-testing localization without asset files,
+//! available through the `test-support` feature.
+//!
+//! ```
+//! use recoyx_localization::{LocaleMap, LocaleMapOptions, test_support::*};
+//!
+//! let locale_map = LocaleMap::from_in_memory(
+//!     LocaleMapOptions::new().supported_locales(vec!["en", "pt"]).default_locale("en"),
+//!     maplit::hashmap!{
+//!         "en" => serde_json::json!({ "common": { "greeting": "Hello, $name!" } }),
+//!         "pt" => serde_json::json!({ "common": { "greeting": "Olá, $name!" } }),
+//!     },
+//! );
+//! assert_message_exists(&locale_map, "common.greeting");
+//! assert_all_placeholders_match(&locale_map);
+//! ```
+
+use std::collections::HashMap;
+use super::{parse_locale, validation, Locale, LocaleMap, LocaleMapOptions};
+
+impl LocaleMap {
+    /// Constructs a `LocaleMap` whose assets are the given values, keyed by the
+    /// locale tags of `LocaleMapOptions::supported_locales()`, rather than asset files.
+    /// The default locale is made the current locale; other locales can be
+    /// loaded through `load()`. The asset options are ignored.
+    pub fn from_in_memory<S: ToString>(options: &LocaleMapOptions, assets: HashMap<S, serde_json::Value>) -> Self {
+        LocaleMap::with_in_memory_assets(options, assets.into_iter()
+            .map(|(tag, root)| (parse_locale(tag.to_string()).unwrap(), root))
+            .collect())
+    }
+}
+
+/// Panics if a message does not exist in the current locale, its fallbacks or the default locale.
+pub fn assert_message_exists<S: ToString>(locale_map: &LocaleMap, id: S) {
+    let id = id.to_string();
+    if locale_map.message_metadata(&id).is_none() {
+        let locale = locale_map.current_locale().map(|l| l.standard_tag().to_string()).unwrap_or_default();
+        panic!("Message {} does not exist in {} or its fallbacks.", id, locale);
+    }
+}
+
+/// Panics if the `$` variables of any message of a stored locale differ from the
+/// default locale's, listing every mismatch.
+pub fn assert_all_placeholders_match(locale_map: &LocaleMap) {
+    let default_locale = &locale_map._config._default_locale;
+    let assets = locale_map._session._in_memory_assets.as_deref().unwrap_or(&locale_map._session._assets);
+    let reference = assets.get(default_locale);
+    let mut locales: Vec<&Locale> = assets.keys().filter(|l| *l != default_locale).collect();
+    locales.sort();
+    let mismatches: Vec<String> = locales.into_iter().flat_map(|locale| {
+        validation::validate_locale(locale, reference, assets.get(locale), &locale_map._config).placeholder_mismatches.into_iter()
+            .map(move |m| format!("{}: {}", locale.standard_tag(), m))
+    }).collect();
+    if !mismatches.is_empty() {
+        panic!("Placeholders do not match the default locale:\n{}", mismatches.join("\n"));
+    }
+}
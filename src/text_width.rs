@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use super::segmenter::grapheme_indices;
+
+/// Estimated widths of characters in pixels, used by `LocaleMap::validate()` to check
+/// the `max_width` metadata of messages, such as for button labels that must not be
+/// clipped in German or Finnish. The width of a grapheme cluster is the width of its
+/// first character, so that combining marks add nothing.
+///
+/// ```
+/// use recoyx_localization::TextWidthTable;
+///
+/// let mut widths = TextWidthTable::sans_serif(16.0);
+/// assert!(widths.width_of("Speichern") > widths.width_of("Save"));
+/// widths.set('W', 20.0);
+/// assert_eq!(widths.width_of("WW"), 40.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextWidthTable {
+    _font_size: f32,
+    _widths: HashMap<char, f32>,
+}
+
+impl TextWidthTable {
+    /// Constructs a table approximating a proportional sans-serif font at the given size
+    /// in pixels, with East Asian wide characters and emoji one em wide.
+    pub fn sans_serif(font_size: f32) -> Self {
+        Self { _font_size: font_size, _widths: HashMap::new() }
+    }
+
+    /// Sets the width of a character in pixels, such as one measured in the font
+    /// of the application.
+    pub fn set(&mut self, c: char, width: f32) {
+        self._widths.insert(c, width);
+    }
+
+    /// Returns the estimated width of a text in pixels.
+    pub fn width_of(&self, text: &str) -> f32 {
+        grapheme_indices(text).filter_map(|(_, g)| g.chars().next()).map(|c| self.char_width(c)).sum()
+    }
+
+    fn char_width(&self, c: char) -> f32 {
+        if let Some(width) = self._widths.get(&c) {
+            return *width;
+        }
+        let em = match c {
+            'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 0.25,
+            'f' | 't' | 'r' | ' ' | '(' | ')' | '[' | ']' | '-' | '/' => 0.3,
+            'm' | 'w' => 0.8,
+            'M' | 'W' => 0.85,
+            '0'..='9' => 0.55,
+            c if is_wide(c) => 1.0,
+            c if c.is_uppercase() => 0.65,
+            _ => 0.5,
+        };
+        em * self._font_size
+    }
+}
+
+impl Default for TextWidthTable {
+    fn default() -> Self {
+        Self::sans_serif(16.0)
+    }
+}
+
+/// Returns `true` for East Asian wide and fullwidth characters and emoji.
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6 | 0x1F300..=0x1F64F | 0x1F900..=0x1F9FF | 0x20000..=0x3FFFD)
+}
@@ -0,0 +1,269 @@
+use std::{collections::{BTreeMap, BTreeSet}, fmt::{Display, Formatter}, rc::Rc};
+use serde::Serialize;
+use super::{csv, message_metadata::message_of, normalization::normalize_text, Locale, LocaleMap, LocaleMapLoaderType, LocaleMapAssetFormat, NormalizationForm};
+
+/// Error returned by a `TranslationEditor`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TranslationEditError {
+    /// The message identifier does not belong to any of the base file names.
+    UnknownKey(String),
+    /// The asset file at the given path changed since it was last loaded or saved.
+    Conflict(String),
+    /// The asset loader or format does not support writing back, such as
+    /// `LocaleMapLoaderType::Http`, `LocaleMapAssetFormat::AndroidXml` or in-memory assets.
+    Unsupported,
+    /// Reading or writing an asset file failed.
+    Io(String),
+    /// Imported content, such as a CSV file, could not be parsed.
+    InvalidContent(String),
+}
+
+impl Display for TranslationEditError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranslationEditError::UnknownKey(id) => write!(f, "Message {} does not belong to any base file.", id),
+            TranslationEditError::Conflict(path) => write!(f, "Resource at {} changed since it was loaded.", path),
+            TranslationEditError::Unsupported => write!(f, "The asset loader or format does not support writing."),
+            TranslationEditError::Io(message) => write!(f, "{}", message),
+            TranslationEditError::InvalidContent(message) => write!(f, "Invalid content: {}.", message),
+        }
+    }
+}
+
+/// Edits the messages of a locale in memory and writes the changes
+/// back to its asset files. Obtained from `LocaleMap::translation_editor()`.
+///
+/// Edits are visible to the `LocaleMap` immediately. `save()` only rewrites the
+/// edited messages of the asset files, keeping the rest of their content, and refuses
+/// to overwrite an asset file that changed since it was loaded, reporting a
+/// `TranslationEditError::Conflict` instead.
+pub struct TranslationEditor<'a> {
+    _locale_map: &'a mut LocaleMap,
+    _locale: Locale,
+    /// Identifiers of the edited messages, by the base file name they belong to.
+    _edits: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl<'a> TranslationEditor<'a> {
+    pub(crate) fn new(locale_map: &'a mut LocaleMap, locale: Locale) -> Self {
+        Self {
+            _locale_map: locale_map,
+            _locale: locale,
+            _edits: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the locale being edited.
+    pub fn locale(&self) -> Locale {
+        self._locale.clone()
+    }
+
+    /// Returns `true` if there are edits that were not saved yet.
+    pub fn has_changes(&self) -> bool {
+        !self._edits.is_empty()
+    }
+
+    /// Returns the message currently stored for the given identifier,
+    /// without consulting fallbacks.
+    pub fn get<S: ToString>(&self, id: S) -> Option<String> {
+        self._locale_map._session._assets.get(&self._locale)?.get(&id.to_string()).map(|r| r.to_string())
+    }
+
+    /// Sets a message, adding it if it does not exist. The metadata
+    /// of an existing message, such as its description, is kept.
+    pub fn set<S: ToString, M: ToString>(&mut self, id: S, message: M) -> Result<(), TranslationEditError> {
+        let id = id.to_string();
+        let base_name = self.base_name_of(&id)?;
+        self._locale_map.invalidate_message_cache();
+        let assets = Rc::make_mut(&mut self._locale_map._session._assets);
+        assets.entry(self._locale.clone()).or_default().set(&id, message.to_string());
+        self._edits.entry(base_name).or_default().insert(id);
+        Ok(())
+    }
+
+    /// Replaces an existing message. Returns `false` if the message does not exist.
+    pub fn update<S: ToString, M: ToString>(&mut self, id: S, message: M) -> Result<bool, TranslationEditError> {
+        let id = id.to_string();
+        if self.get(&id).is_none() {
+            self.base_name_of(&id)?;
+            return Ok(false);
+        }
+        self.set(id, message)?;
+        Ok(true)
+    }
+
+    /// Deletes a message. Returns `false` if the message does not exist.
+    pub fn delete<S: ToString>(&mut self, id: S) -> Result<bool, TranslationEditError> {
+        let id = id.to_string();
+        let base_name = self.base_name_of(&id)?;
+        if self.get(&id).is_none() {
+            return Ok(false);
+        }
+        self._locale_map.invalidate_message_cache();
+        let assets = Rc::make_mut(&mut self._locale_map._session._assets);
+        assets.get_mut(&self._locale).unwrap().remove(&id);
+        self._edits.entry(base_name).or_default().insert(id);
+        Ok(true)
+    }
+
+    /// Sets the translations of a CSV file produced by `LocaleMap::export_csv()`,
+    /// read from its `key` and `translation` columns, or by `LocaleMap::export_csv_wide()`,
+    /// read from its `key` column and the column of this locale. Empty translations and
+    /// the other columns are ignored. Returns how many messages changed.
+    pub fn import_csv(&mut self, content: &str) -> Result<usize, TranslationEditError> {
+        let records = csv::parse(content).map_err(TranslationEditError::InvalidContent)?;
+        let mut records = records.into_iter();
+        let header = records.next().unwrap_or_default();
+        let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        let key_column = column("key").ok_or_else(|| TranslationEditError::InvalidContent("Missing key column".to_string()))?;
+        let translation_column = column("translation").or_else(|| column(&self._locale.standard_tag().to_string()))
+            .ok_or_else(|| TranslationEditError::InvalidContent("Missing translation column".to_string()))?;
+
+        let mut r = 0;
+        for record in records {
+            let id = record.get(key_column).map(|s| s.trim()).unwrap_or("");
+            let message = record.get(translation_column).map(|s| s.as_str()).unwrap_or("");
+            if id.is_empty() || message.is_empty() || self.get(id).as_deref() == Some(message) {
+                continue;
+            }
+            self.set(id, message)?;
+            r += 1;
+        }
+        Ok(r)
+    }
+
+    /// Writes every changed asset file back. If an asset file changed since it was
+    /// loaded, nothing is written for it and `TranslationEditError::Conflict` is returned;
+    /// reloading the locale discards the local edits and resolves the conflict.
+    pub fn save(&mut self) -> Result<(), TranslationEditError> {
+        if let LocaleMapLoaderType::Http = self._locale_map._config._assets_loader_type {
+            return Err(TranslationEditError::Unsupported);
+        }
+        if self._locale_map._config._assets_format != LocaleMapAssetFormat::Json || self._locale_map._session._in_memory_assets.is_some() {
+            return Err(TranslationEditError::Unsupported);
+        }
+        while let Some((base_name, ids)) = self._edits.pop_first() {
+            if let Err(error) = self.save_base_file(&base_name, &ids) {
+                self._edits.insert(base_name, ids);
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    fn save_base_file(&mut self, base_name: &str, ids: &BTreeSet<String>) -> Result<(), TranslationEditError> {
+        let path = self._locale_map.asset_path(&self._locale, base_name).unwrap();
+        let loaded_version = self._locale_map._session._asset_versions.get(&self._locale).and_then(|v| v.get(base_name)).cloned();
+        let current = std::fs::read(&path).ok();
+        if current.as_ref().map(|content| LocaleMap::content_version(content)) != loaded_version {
+            return Err(TranslationEditError::Conflict(path));
+        }
+
+        // The file is as it was loaded, so only the edited messages are written into it.
+        let mut r = match current {
+            Some(content) => serde_json::from_slice(&content).map_err(|e| TranslationEditError::Io(format!("Failed to parse resource at {}: {}", path, e)))?,
+            None => serde_json::Value::Object(serde_json::Map::new()),
+        };
+        // Layered assets only store the messages that differ from the more general locales.
+        let mut base = self._locale_map._session._asset_bases.get(&self._locale);
+        for frag in base_name.split('/') {
+            base = base.and_then(|b| b.get(frag));
+        }
+        let normalization = self._locale_map._config._normalization;
+        let tree = self._locale_map._session._assets.get(&self._locale);
+        let skipped = base_name.split('/').count();
+        for id in ids {
+            let frags: Vec<&str> = id.split('.').skip(skipped).collect();
+            let base_message = base.and_then(|base| find_message(base, &frags, normalization)).map(|m| normalize_text(m, normalization));
+            match tree.and_then(|tree| tree.get(id)) {
+                Some(message) if base_message.as_deref() != Some(message) => set_message(&mut r, &frags, message, normalization),
+                _ => { remove_message(&mut r, &frags, normalization); },
+            }
+        }
+
+        let mut content = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+        r.serialize(&mut serializer).map_err(|e| TranslationEditError::Io(e.to_string()))?;
+
+        if let Some(parent) = std::path::Path::new(&path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| TranslationEditError::Io(format!("Failed to write resource at {}: {}", path, e)))?;
+        }
+        std::fs::write(&path, &content).map_err(|e| TranslationEditError::Io(format!("Failed to write resource at {}: {}", path, e)))?;
+        Rc::make_mut(&mut self._locale_map._session._asset_versions)
+            .entry(self._locale.clone()).or_default()
+            .insert(base_name.to_string(), LocaleMap::content_version(&content));
+        Ok(())
+    }
+
+    /// Returns the base file name that a message identifier belongs to.
+    fn base_name_of(&self, id: &str) -> Result<String, TranslationEditError> {
+        let frags: Vec<&str> = id.split('.').collect();
+        self._locale_map._config._assets_base_file_names.iter()
+            .filter(|base_name| {
+                let base_frags: Vec<&str> = base_name.split('/').collect();
+                frags.len() > base_frags.len() && frags[..base_frags.len()] == base_frags[..]
+            })
+            .max_by_key(|base_name| base_name.len())
+            .cloned()
+            .ok_or_else(|| TranslationEditError::UnknownKey(id.to_string()))
+    }
+}
+
+/// Returns the key of an object matching a component of a message identifier,
+/// which is normalized if the asset file is not.
+fn find_key(object: &serde_json::Map<String, serde_json::Value>, frag: &str, normalization: Option<NormalizationForm>) -> Option<String> {
+    if object.contains_key(frag) {
+        return Some(frag.to_string());
+    }
+    object.keys().find(|key| normalize_text(key, normalization) == frag).cloned()
+}
+
+/// Returns the message at the components of an identifier within an asset tree.
+fn find_message<'v>(value: &'v serde_json::Value, frags: &[&str], normalization: Option<NormalizationForm>) -> Option<&'v str> {
+    let mut value = value;
+    for frag in frags {
+        let object = value.as_object().filter(|_| message_of(value).is_none())?;
+        value = &object[&find_key(object, frag, normalization)?];
+    }
+    message_of(value)
+}
+
+/// Sets the message at the components of an identifier within an asset tree, keeping
+/// the metadata of an existing message and replacing messages in the way of the identifier.
+fn set_message(value: &mut serde_json::Value, frags: &[&str], message: &str, normalization: Option<NormalizationForm>) {
+    if !value.is_object() || message_of(value).is_some() {
+        *value = serde_json::Value::Object(serde_json::Map::new());
+    }
+    let object = value.as_object_mut().unwrap();
+    let key = find_key(object, frags[0], normalization).unwrap_or_else(|| frags[0].to_string());
+    if frags.len() > 1 {
+        let nested = object.entry(key).or_insert(serde_json::Value::Null);
+        return set_message(nested, &frags[1..], message, normalization);
+    }
+    match object.get_mut(&key) {
+        Some(existing) if existing.is_object() && message_of(existing).is_some() => existing["message"] = serde_json::Value::from(message),
+        _ => { object.insert(key, serde_json::Value::from(message)); },
+    }
+}
+
+/// Removes the message at the components of an identifier within an asset tree, along
+/// with the objects left empty. Returns `true` if the given value was left empty.
+fn remove_message(value: &mut serde_json::Value, frags: &[&str], normalization: Option<NormalizationForm>) -> bool {
+    if message_of(value).is_some() {
+        return false;
+    }
+    let object = match value.as_object_mut() {
+        Some(object) => object,
+        None => return false,
+    };
+    let key = match find_key(object, frags[0], normalization) {
+        Some(key) => key,
+        None => return false,
+    };
+    if frags.len() == 1 || remove_message(object.get_mut(&key).unwrap(), &frags[1..], normalization) {
+        object.remove(&key);
+        return object.is_empty();
+    }
+    false
+}
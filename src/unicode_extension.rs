@@ -0,0 +1,175 @@
+use std::fmt::{Display, Formatter};
+
+/// Hour cycle, as specified by the `-u-hc-` extension.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum HourCycle {
+    /// Hours 0 to 11, as in `h11`.
+    H11,
+    /// Hours 1 to 12, as in `h12`.
+    H12,
+    /// Hours 0 to 23, as in `h23`.
+    H23,
+    /// Hours 1 to 24, as in `h24`.
+    H24,
+}
+
+impl HourCycle {
+    /// Parses a `-u-hc-` value, such as `"h23"`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.to_lowercase().as_ref() {
+            "h11" => Some(HourCycle::H11),
+            "h12" => Some(HourCycle::H12),
+            "h23" => Some(HourCycle::H23),
+            "h24" => Some(HourCycle::H24),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            HourCycle::H11 => "h11",
+            HourCycle::H12 => "h12",
+            HourCycle::H23 => "h23",
+            HourCycle::H24 => "h24",
+        }
+    }
+}
+
+impl Display for HourCycle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Day of the week, as specified by the `-u-fw-` extension.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Weekday {
+    Sunday,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+impl Weekday {
+    /// Parses a `-u-fw-` value, such as `"mon"`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.to_lowercase().as_ref() {
+            "sun" => Some(Weekday::Sunday),
+            "mon" => Some(Weekday::Monday),
+            "tue" => Some(Weekday::Tuesday),
+            "wed" => Some(Weekday::Wednesday),
+            "thu" => Some(Weekday::Thursday),
+            "fri" => Some(Weekday::Friday),
+            "sat" => Some(Weekday::Saturday),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Weekday::Sunday => "sun",
+            Weekday::Monday => "mon",
+            Weekday::Tuesday => "tue",
+            Weekday::Wednesday => "wed",
+            Weekday::Thursday => "thu",
+            Weekday::Friday => "fri",
+            Weekday::Saturday => "sat",
+        }
+    }
+
+    /// Returns the number of days from Sunday, such as 1 for Monday.
+    pub fn num_days_from_sunday(&self) -> u32 {
+        match self {
+            Weekday::Sunday => 0,
+            Weekday::Monday => 1,
+            Weekday::Tuesday => 2,
+            Weekday::Wednesday => 3,
+            Weekday::Thursday => 4,
+            Weekday::Friday => 5,
+            Weekday::Saturday => 6,
+        }
+    }
+
+    /// Returns the next day of the week.
+    pub fn succ(&self) -> Weekday {
+        match self {
+            Weekday::Sunday => Weekday::Monday,
+            Weekday::Monday => Weekday::Tuesday,
+            Weekday::Tuesday => Weekday::Wednesday,
+            Weekday::Wednesday => Weekday::Thursday,
+            Weekday::Thursday => Weekday::Friday,
+            Weekday::Friday => Weekday::Saturday,
+            Weekday::Saturday => Weekday::Sunday,
+        }
+    }
+
+    /// Returns the day of the week of a `chrono` weekday.
+    pub fn from_chrono(day: chrono::Weekday) -> Self {
+        match day {
+            chrono::Weekday::Sun => Weekday::Sunday,
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+        }
+    }
+}
+
+impl Display for Weekday {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Measurement system, as specified by the `-u-ms-` extension.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MeasurementSystem {
+    /// The metric system, as in `metric`.
+    Metric,
+    /// The United States customary units, as in `ussystem`.
+    UsSystem,
+    /// The mix of metric and imperial units used in the United Kingdom, as in `uksystem`.
+    UkSystem,
+}
+
+impl MeasurementSystem {
+    /// Parses a `-u-ms-` value, such as `"metric"`. The deprecated `"imperial"`
+    /// is read as `"uksystem"`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.to_lowercase().as_ref() {
+            "metric" => Some(MeasurementSystem::Metric),
+            "ussystem" => Some(MeasurementSystem::UsSystem),
+            "uksystem" | "imperial" => Some(MeasurementSystem::UkSystem),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            MeasurementSystem::Metric => "metric",
+            MeasurementSystem::UsSystem => "ussystem",
+            MeasurementSystem::UkSystem => "uksystem",
+        }
+    }
+
+    /// Returns the measurement system of a region, from CLDR, given as
+    /// an ISO 3166-1 alpha-2 code, such as `UsSystem` for `"US"`.
+    pub fn of_region(region: &str) -> Self {
+        match region.to_uppercase().as_ref() {
+            "US" | "LR" | "MM" => MeasurementSystem::UsSystem,
+            "GB" => MeasurementSystem::UkSystem,
+            _ => MeasurementSystem::Metric,
+        }
+    }
+}
+
+impl Display for MeasurementSystem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
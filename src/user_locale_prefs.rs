@@ -0,0 +1,103 @@
+use super::{Country, HourCycle, Locale, MeasurementSystem, NumberingSystem, WeekInfo, Weekday};
+
+/// Locale preferences of a user, combining the language of a locale with the formats
+/// of another region and individual overrides, such as English with German number
+/// and date formats. The preferences are kept as `-u-` extension keywords of a locale,
+/// so that every formatter accepts them through `locale()`.
+///
+/// ```
+/// use recoyx_localization::{parse_country, parse_locale, HourCycle, NumberFormatter, NumberFormatOptions, UserLocalePrefs};
+///
+/// let prefs = UserLocalePrefs::new(&parse_locale("en-US").unwrap())
+///     .with_region(&parse_country("DE").unwrap())
+///     .with_hour_cycle(HourCycle::H23);
+/// assert_eq!(prefs.language(), "en");
+/// assert_eq!(prefs.locale().standard_tag().to_string(), "en-US-u-rg-dezzzz-hc-h23");
+/// let formatter = NumberFormatter::new(&prefs.locale(), &NumberFormatOptions::new());
+/// assert_eq!(formatter.format(1234.5), "1.234,5");
+/// ```
+#[derive(Clone)]
+pub struct UserLocalePrefs {
+    _locale: Locale,
+}
+
+impl UserLocalePrefs {
+    /// Constructs preferences from a locale, including the preferences requested
+    /// through its `-u-` extension, such as `-u-hc-h23`.
+    pub fn new(locale: &Locale) -> Self {
+        Self { _locale: locale.clone() }
+    }
+
+    /// Returns the locale carrying the preferences, to be given to formatters.
+    pub fn locale(&self) -> Locale {
+        self._locale.clone()
+    }
+
+    /// Returns the language, such as `"en"`.
+    pub fn language(&self) -> &str {
+        self._locale.language()
+    }
+
+    /// Returns the region whose formats and conventions are used, which is the one
+    /// given to `with_region()`, if any; otherwise the locale's region or its likely region.
+    pub fn region(&self) -> Option<Country> {
+        self._locale.conventions_country()
+    }
+
+    /// Returns the numbering system of the digits, which is the Latin one unless requested.
+    pub fn numbering_system(&self) -> NumberingSystem {
+        self._locale.numbering_system().unwrap_or_else(NumberingSystem::latin)
+    }
+
+    /// Returns the measurement system; see `Locale::preferred_measurement_system()`.
+    pub fn measurement_system(&self) -> MeasurementSystem {
+        self._locale.preferred_measurement_system()
+    }
+
+    /// Returns the hour cycle of times; see `Locale::preferred_hour_cycle()`.
+    pub fn hour_cycle(&self) -> HourCycle {
+        self._locale.preferred_hour_cycle()
+    }
+
+    /// Returns the first day of the week; see `Locale::week_info()`.
+    pub fn first_day(&self) -> Weekday {
+        self.week_info().first_day
+    }
+
+    pub fn week_info(&self) -> WeekInfo {
+        self._locale.week_info()
+    }
+
+    /// Returns a copy of the preferences with the number and date formats and
+    /// conventions of a region, such as Germany, through the `-u-rg-` extension.
+    pub fn with_region(&self, country: &Country) -> Self {
+        self.with_keyword("rg", format!("{}zzzz", country.standard_code().alpha2()))
+    }
+
+    pub fn with_numbering_system(&self, value: NumberingSystem) -> Self {
+        self.with_keyword("nu", value.code())
+    }
+
+    pub fn with_measurement_system(&self, value: MeasurementSystem) -> Self {
+        self.with_keyword("ms", value.code())
+    }
+
+    pub fn with_hour_cycle(&self, value: HourCycle) -> Self {
+        self.with_keyword("hc", value.code())
+    }
+
+    pub fn with_first_day(&self, value: Weekday) -> Self {
+        self.with_keyword("fw", value.code())
+    }
+
+    fn with_keyword<V: ToString>(&self, key: &str, value: V) -> Self {
+        // The keywords come from valid codes, so the locale remains valid.
+        Self { _locale: self._locale.with_unicode_extension_keyword(key, value).unwrap() }
+    }
+}
+
+impl From<&Locale> for UserLocalePrefs {
+    fn from(locale: &Locale) -> Self {
+        Self::new(locale)
+    }
+}
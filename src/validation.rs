@@ -0,0 +1,202 @@
+use std::{collections::{BTreeMap, BTreeSet}, fmt::{Display, Formatter}};
+use super::{segmenter::grapheme_indices, tokenize, MessagePart, Locale, LocaleMapConfig, MessageMetadata, MessageTree};
+
+/// Translation coverage and validation report, obtained from `LocaleMap::validate()`.
+#[derive(Clone)]
+pub struct ValidationReport {
+    /// The report of every supported locale, ordered by locale.
+    pub locales: Vec<LocaleValidation>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no locale has any issue.
+    pub fn is_ok(&self) -> bool {
+        self.locales.iter().all(|l| l.is_ok())
+    }
+
+    /// Returns the report of the given locale.
+    pub fn get(&self, locale: &Locale) -> Option<&LocaleValidation> {
+        self.locales.iter().find(|l| l.locale == *locale)
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for l in self.locales.iter() {
+            write!(f, "{}", l)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validation report of a locale, compared against the default locale.
+#[derive(Clone)]
+pub struct LocaleValidation {
+    pub locale: Locale,
+    /// Whether the asset files of the locale could be loaded. If not, every
+    /// message of the default locale is reported as missing.
+    pub loaded: bool,
+    /// Messages present in the default locale but not in this locale.
+    pub missing_keys: Vec<String>,
+    /// Messages present in this locale but not in the default locale.
+    pub orphaned_keys: Vec<String>,
+    /// Messages whose `$` variables differ from the default locale's.
+    pub placeholder_mismatches: Vec<PlaceholderMismatch>,
+    /// Messages that are empty strings.
+    pub empty_messages: Vec<String>,
+    /// Messages with more grapheme clusters than the `max_length` specified in their
+    /// metadata or in the metadata of the default locale's message.
+    pub too_long_messages: Vec<String>,
+    /// Messages wider than the `max_width` specified in their metadata or in the metadata
+    /// of the default locale's message, as estimated by a `TextWidthTable`.
+    pub too_wide_messages: Vec<String>,
+    /// Messages with a variant for only one gender, such as `_male` without `_female`,
+    /// identified without the suffix, if `LocaleMapOptions::audit_variants()` was specified.
+    pub incomplete_gender_variants: Vec<String>,
+    /// Messages with quantity variants lacking any of `_empty`, `_one` and `_multiple`,
+    /// identified without the suffix, if `LocaleMapOptions::audit_variants()` was specified.
+    pub incomplete_plural_variants: Vec<String>,
+}
+
+impl LocaleValidation {
+    /// Returns `true` if the locale has no issue.
+    pub fn is_ok(&self) -> bool {
+        self.loaded && self.missing_keys.is_empty() && self.orphaned_keys.is_empty()
+            && self.placeholder_mismatches.is_empty() && self.empty_messages.is_empty()
+            && self.too_long_messages.is_empty() && self.too_wide_messages.is_empty()
+            && self.incomplete_gender_variants.is_empty() && self.incomplete_plural_variants.is_empty()
+    }
+}
+
+impl Display for LocaleValidation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}: {}", self.locale.standard_tag(), if self.is_ok() { "ok" } else if self.loaded { "issues found" } else { "failed to load" })?;
+        for id in self.missing_keys.iter() {
+            writeln!(f, "  missing: {}", id)?;
+        }
+        for id in self.orphaned_keys.iter() {
+            writeln!(f, "  orphaned: {}", id)?;
+        }
+        for mismatch in self.placeholder_mismatches.iter() {
+            writeln!(f, "  {}", mismatch)?;
+        }
+        for id in self.empty_messages.iter() {
+            writeln!(f, "  empty: {}", id)?;
+        }
+        for id in self.too_long_messages.iter() {
+            writeln!(f, "  too long: {}", id)?;
+        }
+        for id in self.too_wide_messages.iter() {
+            writeln!(f, "  too wide: {}", id)?;
+        }
+        for id in self.incomplete_gender_variants.iter() {
+            writeln!(f, "  incomplete gender variants: {}", id)?;
+        }
+        for id in self.incomplete_plural_variants.iter() {
+            writeln!(f, "  incomplete plural variants: {}", id)?;
+        }
+        Ok(())
+    }
+}
+
+/// A message whose `$` variables differ from the default locale's.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PlaceholderMismatch {
+    pub id: String,
+    /// Variables of the default locale's message that this message does not use.
+    pub missing: Vec<String>,
+    /// Variables of this message that the default locale's message does not use.
+    pub unexpected: Vec<String>,
+}
+
+impl Display for PlaceholderMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "placeholders of {}:", self.id)?;
+        for name in self.missing.iter() {
+            write!(f, " -${}", name)?;
+        }
+        for name in self.unexpected.iter() {
+            write!(f, " +${}", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the names of the `$` variables of a message, such as `price` for both
+/// `$price` and `${price|currency:EUR}`, excluding escapes such as `$$`.
+pub(crate) fn placeholders(message: &str) -> BTreeSet<String> {
+    tokenize(message).into_iter().filter_map(|token| match token {
+        MessagePart::Variable(variable) => Some(variable.name),
+        MessagePart::Text(_) => None,
+    }).collect()
+}
+
+/// Compares the placeholders of a message against the reference message.
+pub(crate) fn placeholder_mismatch(id: &str, reference: &str, message: &str) -> Option<PlaceholderMismatch> {
+    let expected = placeholders(reference);
+    let actual = placeholders(message);
+    if expected == actual {
+        return None;
+    }
+    Some(PlaceholderMismatch {
+        id: id.to_string(),
+        missing: expected.difference(&actual).cloned().collect(),
+        unexpected: actual.difference(&expected).cloned().collect(),
+    })
+}
+
+/// Lists the messages of an asset tree along with their metadata.
+fn messages(tree: Option<&MessageTree>) -> BTreeMap<String, (String, MessageMetadata)> {
+    let mut r = BTreeMap::new();
+    for (id, message) in tree.into_iter().flat_map(|tree| tree.iter()) {
+        let metadata = tree.and_then(|tree| tree.metadata(id)).cloned().unwrap_or_default();
+        r.insert(id.to_string(), (message.to_string(), metadata));
+    }
+    r
+}
+
+/// Lists the messages, identified without the suffix, that have some of the given
+/// variant suffixes but not all of them.
+fn incomplete_variants(messages: &BTreeMap<String, (String, MessageMetadata)>, variant_suffixes: &[&str], required_suffixes: &[&str]) -> Vec<String> {
+    let bases: BTreeSet<&str> = messages.keys().filter_map(|id| variant_suffixes.iter().find_map(|s| id.strip_suffix(*s))).collect();
+    bases.into_iter()
+        .filter(|base| required_suffixes.iter().any(|s| !messages.contains_key(&format!("{}{}", base, s))))
+        .map(|base| base.to_string())
+        .collect()
+}
+
+pub(crate) fn validate_locale(locale: &Locale, reference: Option<&MessageTree>, root: Option<&MessageTree>, config: &LocaleMapConfig) -> LocaleValidation {
+    let reference = messages(reference);
+    let messages = messages(root);
+    let mut r = LocaleValidation {
+        locale: locale.clone(),
+        loaded: root.is_some(),
+        missing_keys: reference.keys().filter(|id| !messages.contains_key(*id)).cloned().collect(),
+        orphaned_keys: messages.keys().filter(|id| !reference.contains_key(*id)).cloned().collect(),
+        placeholder_mismatches: vec![],
+        empty_messages: messages.iter().filter(|(_, (m, _))| m.is_empty()).map(|(id, _)| id.clone()).collect(),
+        too_long_messages: vec![],
+        too_wide_messages: vec![],
+        incomplete_gender_variants: vec![],
+        incomplete_plural_variants: vec![],
+    };
+    if config._audit_variants {
+        r.incomplete_gender_variants = incomplete_variants(&messages, &["_male", "_female"], &["_male", "_female"]);
+        r.incomplete_plural_variants = incomplete_variants(&messages, &["_empty", "_one", "_multiple", "_zero", "_negative"], &["_empty", "_one", "_multiple"]);
+    }
+    for (id, (message, metadata)) in messages.iter() {
+        if let Some(mismatch) = reference.get(id).and_then(|(reference, _)| placeholder_mismatch(id, reference, message)) {
+            r.placeholder_mismatches.push(mismatch);
+        }
+        let reference_metadata = reference.get(id).map(|(_, m)| m);
+        let max_length = metadata.max_length.or_else(|| reference_metadata.and_then(|m| m.max_length));
+        if max_length.map(|l| grapheme_indices(message).count() > l).unwrap_or(false) {
+            r.too_long_messages.push(id.clone());
+        }
+        let max_width = metadata.max_width.or_else(|| reference_metadata.and_then(|m| m.max_width));
+        if max_width.map(|w| config._text_widths.width_of(message) > w as f32).unwrap_or(false) {
+            r.too_wide_messages.push(id.clone());
+        }
+    }
+    r
+}
@@ -0,0 +1,159 @@
+//! Per-request localization for axum and other tower-based servers,
+//! available through the `axum` feature.
+//!
+//! A `LocalizationLayer` negotiates the locale of each request from a query
+//! parameter, a cookie or the `Accept-Language` header, and adds a `Localization`
+//! to the request, which handlers receive as an extractor:
+//!
+//! ```no_run
+//! use axum::{routing::get, Router};
+//! use recoyx_localization::{web::{Localization, LocalizationLayer}, *};
+//!
+//! # async fn run() {
+//! let layer = LocalizationLayer::load(|| LocaleMap::new(
+//!     LocaleMapOptions::new()
+//!         .supported_locales(vec!["en-US", "pt-BR"])
+//!         .default_locale("en-US")
+//!         .assets(LocaleMapAssetOptions::new()
+//!             .src("res/lang")
+//!             .base_file_names(vec!["common"])
+//!             .loader_type(LocaleMapLoaderType::FileSystem))
+//! )).await.unwrap().cookie("lang").query_parameter("lang");
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|t: Localization| async move { t.get("common.greeting") }))
+//!     .layer(layer);
+//! # }
+//! ```
+//!
+//! The layer loads every supported locale once into a `SharedCatalog`, and runs
+//! the handler with the `Localization` as the current one of its task, so that
+//! `SharedCatalog::get()` retrieves messages in the language of the request.
+
+use std::task::{Context, Poll};
+use ::axum::{async_trait, extract::FromRequestParts, http::{header, request::Parts, HeaderMap, Request, StatusCode, Uri}};
+use tokio::task::futures::TaskLocalFuture;
+use super::{parse_locale, Locale, LocaleMap, SharedCatalog};
+pub use super::Localization;
+
+/// A tower `Layer` that adds a `Localization` for the negotiated locale to every request.
+#[derive(Clone)]
+pub struct LocalizationLayer {
+    _catalog: SharedCatalog,
+    _cookie_name: Option<String>,
+    _query_parameter: Option<String>,
+}
+
+impl LocalizationLayer {
+    /// Loads every supported locale of the `LocaleMap` returned by a function;
+    /// see `SharedCatalog::load()`.
+    pub async fn load<F: Fn() -> LocaleMap + Send + Sync + 'static>(make_locale_map: F) -> Result<Self, String> {
+        Ok(Self::new(SharedCatalog::load(make_locale_map).await?))
+    }
+
+    /// Constructs a `LocalizationLayer` for the messages of a `SharedCatalog`.
+    pub fn new(catalog: SharedCatalog) -> Self {
+        Self {
+            _catalog: catalog,
+            _cookie_name: None,
+            _query_parameter: None,
+        }
+    }
+
+    /// Specifies a cookie whose value, such as `pt-BR`, takes precedence
+    /// over the `Accept-Language` header.
+    pub fn cookie<S: ToString>(mut self, name: S) -> Self {
+        self._cookie_name = Some(name.to_string());
+        self
+    }
+
+    /// Specifies a query parameter whose value, such as `pt-BR` in `?lang=pt-BR`,
+    /// takes precedence over the cookie and the `Accept-Language` header.
+    pub fn query_parameter<S: ToString>(mut self, name: S) -> Self {
+        self._query_parameter = Some(name.to_string());
+        self
+    }
+
+    /// Returns the localization for the supported locale that best matches a request,
+    /// or the default locale.
+    fn negotiate(&self, headers: &HeaderMap, uri: &Uri) -> Localization {
+        let mut requested = vec![];
+        if let Some(name) = self._query_parameter.as_ref() {
+            requested.extend(uri.query().into_iter()
+                .flat_map(|query| query.split('&'))
+                .filter_map(|pair| pair.split_once('='))
+                .filter(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string()));
+        }
+        if let Some(name) = self._cookie_name.as_ref() {
+            requested.extend(headers.get_all(header::COOKIE).iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(';'))
+                .filter_map(|pair| pair.trim().split_once('='))
+                .filter(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string()));
+        }
+        if let Some(value) = headers.get(header::ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()) {
+            requested.extend(parse_accept_language(value));
+        }
+        let requested: Vec<Locale> = requested.iter().filter_map(|tag| parse_locale(tag).ok()).collect();
+        self._catalog.negotiate(&requested)
+    }
+}
+
+impl<S> tower_layer::Layer<S> for LocalizationLayer {
+    type Service = LocalizationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LocalizationService { _inner: inner, _layer: self.clone() }
+    }
+}
+
+/// The service produced by `LocalizationLayer`.
+#[derive(Clone)]
+pub struct LocalizationService<S> {
+    _inner: S,
+    _layer: LocalizationLayer,
+}
+
+impl<S, B> tower_service::Service<Request<B>> for LocalizationService<S> where S: tower_service::Service<Request<B>> {
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<Localization, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self._inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let localization = self._layer.negotiate(request.headers(), request.uri());
+        request.extensions_mut().insert(localization.clone());
+        localization.scope(self._inner.call(request))
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Localization {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Localization>().cloned()
+            .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "LocalizationLayer is missing"))
+    }
+}
+
+/// Parses the value of an `Accept-Language` header, such as `pt-BR,pt;q=0.9,en;q=0.8`,
+/// into language tags ordered by preference. The `*` range and tags with
+/// a quality of 0 are excluded.
+pub fn parse_accept_language(value: &str) -> Vec<String> {
+    let mut ranges: Vec<(String, f32)> = value.split(',').filter_map(|range| {
+        let mut params = range.split(';');
+        let tag = params.next()?.trim();
+        let quality = params.filter_map(|p| p.trim().strip_prefix("q=")).next()
+            .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+            .unwrap_or(1.0);
+        if tag.is_empty() || tag == "*" || quality <= 0.0 { None } else { Some((tag.to_string(), quality)) }
+    }).collect();
+    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    ranges.into_iter().map(|(tag, _)| tag).collect()
+}
@@ -0,0 +1,61 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use super::Weekday;
+
+/// Week conventions of a region, from CLDR, which decide the week numbers
+/// of dates, such as in calendars and scheduling views. Weeks start on the first day,
+/// and the first week of a year is the first that has at least the minimal days in it.
+///
+/// ```
+/// use recoyx_localization::{parse_locale, WeekInfo, Weekday};
+/// use chrono::NaiveDate;
+///
+/// let date = NaiveDate::from_ymd_opt(2021, 1, 3).unwrap();
+/// assert_eq!(WeekInfo::iso().week_of_year(date), (2020, 53));
+/// assert_eq!(parse_locale("en-US").unwrap().week_info().week_of_year(date), (2021, 2));
+/// assert_eq!(parse_locale("de-DE").unwrap().week_info().first_day, Weekday::Monday);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct WeekInfo {
+    /// The first day of the week, such as Sunday in the United States.
+    pub first_day: Weekday,
+    /// The minimal number of days of the first week of a year in the year,
+    /// such as 4 for ISO 8601 weeks and 1 in the United States.
+    pub minimal_days: u32,
+}
+
+impl WeekInfo {
+    /// Returns the ISO 8601 week conventions, where weeks start on Monday
+    /// and the first week of a year is the one with its first Thursday.
+    pub fn iso() -> Self {
+        Self { first_day: Weekday::Monday, minimal_days: 4 }
+    }
+
+    /// Returns the year of the week of a date, which differs from the year of the date
+    /// around new year, and the number of the week in that year, from 1.
+    pub fn week_of_year(&self, date: NaiveDate) -> (i32, u32) {
+        let year = date.year();
+        let start = if date < self.first_week_start(year) {
+            year - 1
+        } else if date >= self.first_week_start(year + 1) {
+            year + 1
+        } else {
+            year
+        };
+        let week = (date - self.first_week_start(start)).num_days() / 7 + 1;
+        (start, week as u32)
+    }
+
+    /// Returns the first day of the first week of a year.
+    fn first_week_start(&self, year: i32) -> NaiveDate {
+        let january_first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let offset = (january_first.weekday().num_days_from_sunday() as i64 - self.first_day.num_days_from_sunday() as i64).rem_euclid(7);
+        let start = january_first - Duration::days(offset);
+        if 7 - offset >= self.minimal_days as i64 { start } else { start + Duration::days(7) }
+    }
+}
+
+impl Default for WeekInfo {
+    fn default() -> Self {
+        Self { first_day: Weekday::Monday, minimal_days: 1 }
+    }
+}
@@ -0,0 +1,17 @@
+mod localization {
+    include!(env!("RECOYX_LOCALIZATION_GENERATED"));
+}
+
+use recoyx_localization::*;
+
+fn main() {
+    let options = LocaleMapOptions::new();
+    options.supported_locales(vec!["en-US"]).default_locale("en-US");
+    let locale_map = LocaleMap::from_embedded(&options, localization::ASSETS);
+    assert_eq!(localization::messages::common::self_(&locale_map), "Myself");
+    assert_eq!(localization::messages::common::r#type(&locale_map, "me"), "Type of me");
+    assert_eq!(localization::messages::common::super_::crate_(&locale_map, "map"), "Crate map");
+    assert_eq!(localization::messages::common::super_::files(&locale_map, "me", 2), "2 files by me");
+    assert_eq!(localization::messages::common::std::r#fn(&locale_map, "she", "Fn"), "Fn of she");
+    assert_eq!(localization::messages::common::std::r#match(&locale_map, "it", Gender::Female), "She matches it");
+}
@@ -1,29 +1,3009 @@
-use recoyx_localization::*;
-use futures_await_test::async_test;
-
-#[test]
-fn locale_country() {
-    let some_lang = parse_locale(&"pt-BR").unwrap();
-    let some_country = some_lang.country();
-    assert_eq!(some_lang.to_string(), String::from("Português (Brazil)"));
-    assert_eq!(some_lang.standard_tag().to_string(), String::from("pt-BR"));
-    assert!(some_country.is_some());
-    assert_eq!(some_country.unwrap().standard_code().alpha3(), "BRA");
-}
-
-#[async_test]
-async fn locale_map() {
-    let mut locale_map = LocaleMap::new(
-        LocaleMapOptions::new()
-            .supported_locales(vec!["en-US"])
-            .default_locale("en-US")
-            .assets(LocaleMapAssetOptions::new()
-                .src("tests/res")
-                .base_file_names(vec!["common"])
-                .auto_clean(true)
-                .loader_type(LocaleMapLoaderType::FileSystem))
-    ); // locale_map
-    locale_map.load(None).await;
-    assert!(locale_map.supports_locale(&parse_locale("en-US").unwrap()));
-    assert_eq!(locale_map.format_relative_time(std::time::Duration::from_secs(10 * 60 * 60 * 24)), "1 week ago");
-}
\ No newline at end of file
+use recoyx_localization::*;
+use futures_await_test::async_test;
+
+#[test]
+fn locale_country() {
+    let some_lang = parse_locale("pt-BR").unwrap();
+    let some_country = some_lang.country();
+    assert_eq!(some_lang.to_string(), String::from("Português (Brazil)"));
+    assert_eq!(some_lang.standard_tag().to_string(), String::from("pt-BR"));
+    assert!(some_country.is_some());
+    assert_eq!(some_country.unwrap().standard_code().alpha3(), "BRA");
+}
+
+#[test]
+fn bundled_languages() {
+    // English is bundled even when RECOYX_LOCALIZATION_LOCALES keeps only some languages.
+    assert!(is_language_bundled("en"));
+    assert_eq!(is_language_bundled("PT"), parse_locale("pt-BR").is_ok());
+    assert!(!is_language_bundled("xx"));
+}
+
+#[test]
+fn cldr_version() {
+    let major = CLDR_VERSION.split('.').next().unwrap();
+    assert!(major.parse::<u32>().unwrap() >= 41);
+}
+
+#[async_test]
+async fn locale_map() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .auto_clean(true)
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    assert!(locale_map.supports_locale(&parse_locale("en-US").unwrap()));
+    assert_eq!(locale_map.format_relative_time(std::time::Duration::from_secs(10 * 60 * 60 * 24)), "1 week ago");
+    assert!(locale_map.load(Some(parse_locale("en-Latn-US").unwrap())).await);
+    assert!(locale_map.current_locale() == Some(parse_locale("en-US").unwrap()));
+}
+
+#[async_test]
+async fn shared_config() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<LocaleMapConfig>();
+
+    let config = std::sync::Arc::new(LocaleMapConfig::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ));
+    let config = std::thread::spawn(move || config).join().unwrap();
+    assert!(config.default_locale() == &parse_locale("en-US").unwrap());
+    let mut locale_map = LocaleMap::with_config(config.clone());
+    assert!(locale_map.load(None).await);
+    assert!(locale_map.session().loaded_locales() == vec![parse_locale("en-US").unwrap()]);
+    let other = locale_map.new_session();
+    assert!(std::sync::Arc::ptr_eq(other.config(), &config));
+    assert!(other.session().current_locale().is_none());
+    assert!(locale_map.session().current_locale() == Some(&parse_locale("en-US").unwrap()));
+}
+
+#[test]
+fn fallback_validation() {
+    let error = |fallbacks: std::collections::HashMap<&str, Vec<&str>>| LocaleMap::try_new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en", "pt-BR", "pt-PT"])
+            .default_locale("en")
+            .fallbacks(fallbacks)
+    ).err();
+    assert_eq!(error(maplit::hashmap! { "pt-BR" => vec!["en"], "en" => vec!["pt-BR"] }).unwrap(), "fallbacks: cycle en -> pt-BR -> en");
+    assert_eq!(error(maplit::hashmap! { "pt-BR" => vec!["pt-PT"], "pt-PT" => vec!["en", "pt-BR"] }).unwrap(), "fallbacks: cycle pt-BR -> pt-PT -> pt-BR");
+    assert_eq!(error(maplit::hashmap! { "pt-BR" => vec!["pt-BR"] }).unwrap(), "fallbacks.pt-BR: \"pt-BR\" falls back to itself");
+    assert_eq!(error(maplit::hashmap! { "pt-BR" => vec!["es"] }).unwrap(), "fallbacks.pt-BR: \"es\" is not among the supported locales");
+    assert!(error(maplit::hashmap! { "pt-BR" => vec!["pt-PT", "en"], "pt-PT" => vec!["en"] }).is_none());
+    assert_eq!(LocaleMapOptions::from_json(r#"{"supported_locales": ["en", "pt-BR"], "default_locale": "en", "fallbacks": {"en": ["pt-BR"], "pt-BR": ["en"]}}"#).err().unwrap(), "fallbacks: cycle en -> pt-BR -> en");
+}
+
+#[async_test]
+async fn numbering_system() {
+    let deva = parse_numbering_system("deva").unwrap();
+    assert_eq!(deva.transliterate(1024), "१०२४");
+    assert_eq!(parse_locale("ar-EG-u-nu-arab").unwrap().numbering_system().unwrap().code(), "arab");
+    assert!(parse_numbering_system("xyz").is_err());
+    // An unknown numbering system given to a formatter falls back to the locale's one.
+    let ar = parse_locale("ar-EG-u-nu-arab").unwrap();
+    assert_eq!(NumberFormatter::new(&ar, NumberFormatOptions::new().numbering_system("xyz")).format(25.0), "٢٥");
+    assert_eq!(NumberFormatter::new(&parse_locale("en").unwrap(), NumberFormatOptions::new().numbering_system("xyz")).format(25.0), "25");
+    let date = chrono::DateTime::parse_from_rfc3339("2025-03-04T15:05:00+00:00").unwrap();
+    assert_eq!(DateTimeFormatter::new(&ar, DateTimeFormatOptions::new().numbering_system("xyz")).format_pattern(&date, "HH:mm"), "١٥:٠٥");
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .numbering_system("arab")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    assert_eq!(locale_map.get_formatted("common.qty", vec![ &25 ]), "Multiple (٢٥)");
+}
+
+#[test]
+fn locale_components() {
+    let locale = Locale::builder().language("zh").script("Hant").region("TW").build().unwrap();
+    assert_eq!(locale.language(), "zh");
+    assert_eq!(locale.script(), Some("Hant"));
+    assert_eq!(locale.region(), Some("TW"));
+    assert_eq!(locale.with_region("HK").unwrap().standard_tag().to_string(), "zh-Hant-HK");
+    assert_eq!(parse_locale("ar-EG-u-nu-arab").unwrap().extensions(), vec!["u-nu-arab".to_string()]);
+    assert!(Locale::builder().language("zh").region("T").build().is_err());
+}
+
+#[async_test]
+async fn translation_editor() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    std::fs::create_dir_all(dir.join("en-US")).unwrap();
+    std::fs::copy("tests/res/en-US/common.json", dir.join("en-US/common.json")).unwrap();
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    let en_us = parse_locale("en-US").unwrap();
+
+    let mut editor = locale_map.translation_editor(&en_us);
+    editor.set("common.message_id", "Edited message").unwrap();
+    assert!(editor.delete("common.parameterized").unwrap());
+    assert!(!editor.update("common.nonexistent", "x").unwrap());
+    assert_eq!(editor.set("other.message_id", "x"), Err(TranslationEditError::UnknownKey("other.message_id".to_string())));
+    editor.save().unwrap();
+    assert_eq!(locale_map.get("common.message_id"), "Edited message");
+    let saved: serde_json::Value = serde_json::from_slice(&std::fs::read(dir.join("en-US/common.json")).unwrap()).unwrap();
+    assert_eq!(saved["message_id"], "Edited message");
+    assert!(saved.get("parameterized").is_none());
+
+    std::fs::write(dir.join("en-US/common.json"), r#"{"message_id": "Changed elsewhere"}"#).unwrap();
+    let mut editor = locale_map.translation_editor(&en_us);
+    editor.set("common.message_id", "Conflicting message").unwrap();
+    assert!(matches!(editor.save(), Err(TranslationEditError::Conflict(_))));
+}
+
+#[cfg(feature = "editor-protocol")]
+#[async_test]
+async fn editor_protocol() {
+    use std::io::{Read, Write};
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    locale_map.start_capture();
+    locale_map.get("common.message_id");
+
+    let mut server = EditorServer::bind("127.0.0.1:0").unwrap().allow_origin("http://localhost:3000");
+    let addr = server.local_addr().unwrap();
+    let token = server.token().to_string();
+    let mut send = |server: &mut EditorServer, headers: String| {
+        let body = r#"{"type": "set_message", "id": "common.message_id", "message": "Hot message"}"#;
+        let mut client = std::net::TcpStream::connect(addr).unwrap();
+        write!(client, "POST / HTTP/1.1\r\n{}Content-Length: {}\r\n\r\n{}", headers, body.len(), body).unwrap();
+        while server.poll(&mut locale_map).unwrap() == 0 {}
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    };
+
+    // A client that never completes its request does not hold up the others.
+    let mut stalled = std::net::TcpStream::connect(addr).unwrap();
+    write!(stalled, "POST / HTTP/1.1\r\n").unwrap();
+
+    let response = send(&mut server, String::new());
+    assert!(response.starts_with("HTTP/1.1 401"));
+    let response = send(&mut server, format!("Origin: http://evil.example\r\nAuthorization: Bearer {}\r\n", token));
+    assert!(response.starts_with("HTTP/1.1 403"));
+    assert!(!response.contains("Access-Control-Allow-Origin"));
+    let response = send(&mut server, format!("Origin: http://localhost:3000\r\nAuthorization: Bearer {}\r\n", token));
+    assert!(response.contains("Access-Control-Allow-Origin: http://localhost:3000\r\n"));
+    assert!(response.ends_with(r#"{"type":"ok"}"#));
+    assert_eq!(locale_map.get("common.message_id"), "Hot message");
+
+    // A Content-Length beyond the maximum size is rejected without waiting for the body.
+    for length in ["2000000", "18446744073709551615"] {
+        let mut client = std::net::TcpStream::connect(addr).unwrap();
+        write!(client, "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{{", length).unwrap();
+        while server.poll(&mut locale_map).unwrap() == 0 {}
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 413"));
+    }
+
+    assert_eq!(server.handle(&mut locale_map, EditorRequest::ListKeys), EditorResponse::Keys {
+        locale: Some("en-US".to_string()),
+        keys: vec![EditorKey { id: "common.message_id".to_string(), message: Some("Hot message".to_string()) }],
+    });
+}
+
+#[test]
+fn likely_subtags() {
+    assert_eq!(parse_locale("zh").unwrap().maximize().standard_tag().to_string(), "zh-Hans-CN");
+    assert_eq!(parse_locale("zh-TW").unwrap().maximize().standard_tag().to_string(), "zh-Hant-TW");
+    assert_eq!(parse_locale("en-Latn-US").unwrap().minimize().standard_tag().to_string(), "en");
+    assert_eq!(parse_locale("ar-u-nu-arab").unwrap().maximize().standard_tag().to_string(), "ar-Arab-EG-u-nu-arab");
+}
+
+#[async_test]
+async fn accessibility_labels() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    assert_eq!(locale_map.get("common.save"), "Save");
+    assert_eq!(locale_map.get_accessibility("common.save"), "Save the document");
+    assert_eq!(locale_map.get_accessibility("common.message_id"), "Some message");
+    assert_eq!(locale_map.get_accessibility_formatted("common.qty", vec![ &1 ]), "One (1)");
+}
+
+#[test]
+fn uppercase_emphasis() {
+    assert!(parse_locale("en-US").unwrap().supports_uppercase_emphasis());
+    assert!(!parse_locale("ja").unwrap().supports_uppercase_emphasis());
+    assert!(!parse_locale("de").unwrap().supports_uppercase_emphasis());
+    assert_eq!(parse_locale("de").unwrap().uppercase_for_emphasis("Straße"), "Straße");
+    assert_eq!(parse_locale("tr").unwrap().uppercase_for_emphasis("istanbul"), "İSTANBUL");
+    assert_eq!(parse_locale("el").unwrap().uppercase_for_emphasis("καλημέρα"), "ΚΑΛΗΜΕΡΑ");
+}
+
+#[test]
+fn unicode_extension_keywords() {
+    let locale = parse_locale("th-TH-u-ca-buddhist-hc-h23-fw-mon").unwrap();
+    assert_eq!(locale.calendar(), Some("buddhist".to_string()));
+    assert_eq!(locale.hour_cycle(), Some(HourCycle::H23));
+    assert_eq!(locale.first_day(), Some(Weekday::Monday));
+    assert_eq!(locale.collation(), None);
+
+    let locale = Locale::builder().language("de").region("DE")
+        .collation("phonebk")
+        .numbering_system(parse_numbering_system("latn").unwrap())
+        .hour_cycle(HourCycle::H12)
+        .build().unwrap();
+    assert_eq!(locale.standard_tag().to_string(), "de-DE-u-co-phonebk-nu-latn-hc-h12");
+    assert_eq!(locale.collation(), Some("phonebk".to_string()));
+}
+
+#[async_test]
+async fn fallback_trace() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("pt-BR")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert_eq!(locale_map.resolve_with_trace("common.save").to_string(), "common.save: no current locale");
+    locale_map.load(None).await;
+
+    let trace = locale_map.resolve_with_trace("common.save");
+    assert!(trace.locale() == Some(&parse_locale("en-US").unwrap()));
+    assert_eq!(trace.message.as_deref(), Some("Save"));
+    assert_eq!(trace.attempts.iter().map(|a| a.outcome).collect::<Vec<_>>(), vec![FallbackOutcome::Missing, FallbackOutcome::Found]);
+    assert_eq!(trace.to_string(), "common.save: pt-BR missing, en-US found");
+
+    assert_eq!(locale_map.resolve_with_trace("common.message_id").to_string(), "common.message_id: pt-BR found");
+    let trace = locale_map.resolve_with_trace("common.nothing");
+    assert!(trace.locale().is_none() && trace.message.is_none());
+    assert_eq!(trace.attempts.len(), 2);
+}
+
+#[async_test]
+async fn key_page() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("pt-BR")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    let pt_br = parse_locale("pt-BR").unwrap();
+
+    let page = locale_map.key_page("common.", None, 2);
+    assert_eq!(page.entries.iter().map(|e| e.id.as_ref()).collect::<Vec<&str>>(), vec!["common.contextual_female", "common.contextual_male"]);
+    assert!(!page.entries[0].is_present_in(&pt_br));
+    let page = locale_map.key_page("common.", page.next_cursor.as_deref(), 2);
+    assert_eq!(page.entries[0].id, "common.message_id");
+    assert!(page.entries[0].is_present_in(&pt_br));
+
+    let page = locale_map.key_page("common.qty", None, 10);
+    assert_eq!(page.entries.len(), 3);
+    assert!(page.next_cursor.is_none());
+}
+
+#[test]
+fn formatter_support() {
+    let locale_map = LocaleMap::new(LocaleMapOptions::new().on_unsupported_formatter(|_, _| {}));
+    assert!(locale_map.formatter_support(&parse_locale("en-US").unwrap()).is_complete());
+    let support = locale_map.formatter_support(&parse_locale("ab").unwrap());
+    assert!(!support.relative_time);
+    assert!(!support.is_complete());
+}
+
+#[test]
+fn locale_canonicalization() {
+    let a = parse_locale("de-CH-1901-B-Foo-A-Bar").unwrap();
+    let b = parse_locale("de-ch-1901-a-bar-b-foo").unwrap();
+    assert!(a == b);
+    assert_eq!(a.canonicalize().standard_tag().to_string(), "de-CH-1901-a-bar-b-foo");
+
+    let mut locales: Vec<Locale> = vec!["pt-BR", "en-US", "en"].into_iter().map(|s| parse_locale(s).unwrap()).collect();
+    locales.sort();
+    assert_eq!(locales.iter().map(|l| l.standard_tag().to_string()).collect::<Vec<String>>(), vec!["en", "en-US", "pt-BR"]);
+}
+
+#[test]
+fn posix_locale_parsing() {
+    let tag = |s: &str| parse_locale(s).unwrap().standard_tag().to_string();
+    for code in ["en_us", "EN-US", "en_US.UTF-8", " en-us ", "en_US.utf8@euro"] {
+        assert!(parse_locale(code).unwrap() == parse_locale("en-US").unwrap());
+        assert_eq!(tag(code), "en-US");
+    }
+    assert_eq!(tag("pt_br"), "pt-BR");
+    assert_eq!(tag("zh_Hans_CN"), "zh-Hans-CN");
+    assert_eq!(tag("ZH_HANS_cn"), "zh-Hans-CN");
+    assert_eq!(tag("es_419"), "es-419");
+    assert_eq!(tag("sr_RS@latin"), "sr-Latn-RS");
+    assert_eq!(tag("sr@cyrillic"), "sr-Cyrl");
+    assert_eq!(tag("de_DE.ISO-8859-1"), "de-DE");
+    assert!(parse_locale("pt_BR.UTF-8").unwrap().region() == Some("BR"));
+}
+
+#[test]
+fn legacy_locale_tags() {
+    let tag = |s: &str| parse_locale(s).unwrap().standard_tag().to_string();
+    assert_eq!(tag("i-navajo"), "nv");
+    assert_eq!(tag("I-LUX"), "lb");
+    assert_eq!(tag("no-bok"), "nb");
+    assert_eq!(tag("no_nyn"), "nn");
+    assert_eq!(tag("zh-guoyu"), "zh");
+    assert_eq!(tag("zh-cmn-Hans-CN"), "zh-Hans-CN");
+    assert_eq!(tag("ar-arb-EG"), "ar-EG");
+    assert_eq!(tag("iw-IL"), "he-IL");
+    assert!(parse_locale("zh-cmn-Hans").unwrap() == parse_locale("zh-Hans").unwrap());
+    assert_eq!(canonicalize_language_tag("i-klingon").unwrap(), "tlh");
+    assert_eq!(canonicalize_language_tag("zh-yue-HK").unwrap(), "yue-HK");
+    assert_eq!(canonicalize_language_tag("en-GB-oed").unwrap(), "en-GB-oxendict");
+}
+
+#[test]
+fn country_metadata() {
+    let brazil = parse_country("BR").unwrap();
+    assert_eq!(brazil.calling_code(), Some("55"));
+    assert_eq!(brazil.tld(), Some(".br"));
+    assert_eq!(brazil.currencies(), vec!["BRL"]);
+    assert_eq!(brazil.continent(), Continent::SouthAmerica);
+    assert_eq!(brazil.emoji_flag(), "🇧🇷");
+    assert_eq!(parse_country("GBR").unwrap().tld(), Some(".uk"));
+    assert_eq!(brazil.phone_prefix().as_deref(), Some("+55"));
+    assert_eq!(parse_country("JM").unwrap().phone_prefix().as_deref(), Some("+1"));
+    let phone_format = brazil.phone_format().unwrap();
+    assert_eq!(phone_format.national, "(##) #####-####");
+    assert_eq!(phone_format.national_example(), "(11) 91234-5678");
+    assert_eq!(phone_format.international_example(), "+55 11 91234-5678");
+    assert!(parse_country("JM").unwrap().phone_format().is_none());
+    for code in isocountry::CountryCode::iter() {
+        parse_country(code.alpha2()).unwrap().continent();
+    }
+}
+
+#[test]
+fn country_subdivisions() {
+    let brazil = parse_country("BR").unwrap();
+    let states = brazil.subdivisions();
+    assert_eq!(states.len(), 27);
+    assert_eq!(states[0].code(), "BR-AC");
+    assert!(states.iter().all(|s| s.country() == brazil));
+
+    let bavaria = parse_subdivision("de-by").unwrap();
+    assert_eq!(bavaria.universal_name(), "Bavaria");
+    assert_eq!(bavaria.iso_name(), "Bayern");
+    assert_eq!(bavaria.name(&parse_locale("de-AT").unwrap()), "Bayern");
+    assert_eq!(bavaria.name(&parse_locale("ja").unwrap()), "Bavaria");
+    assert_eq!(bavaria.category(), "state");
+    assert_eq!(parse_subdivision("CA_QC").unwrap().name(&parse_locale("fr-CA").unwrap()), "Québec");
+    assert_eq!(parse_subdivision("US-DC").unwrap().category(), "district");
+    assert_eq!(parse_country("US").unwrap().subdivisions().len(), 57);
+    assert_eq!(parse_country("JP").unwrap().subdivisions().len(), 47);
+    let ile_de_france = parse_subdivision("FR-IDF").unwrap();
+    assert_eq!(ile_de_france.name(&parse_locale("fr").unwrap()), "Île-de-France");
+    assert_eq!(ile_de_france.category(), "metropolitan region");
+    // Without an English name from CLDR, the ISO 3166-2 name stands in.
+    assert_eq!(ile_de_france.universal_name(), "Île-de-France");
+    assert_eq!(ile_de_france.name(&parse_locale("en").unwrap()), "Île-de-France");
+    assert!(parse_country("VA").unwrap().subdivisions().is_empty());
+    assert!(parse_subdivision("BR-XX").is_err());
+}
+
+#[async_test]
+async fn bilingual_messages() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("pt-BR")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    let en_us = parse_locale("en-US").unwrap();
+    assert!(locale_map.load_secondary_locale(&en_us).await);
+    assert_eq!(locale_map.get_bilingual("common.message_id", &en_us).to_string(), "Alguma mensagem / Some message");
+    let message = locale_map.get_bilingual_formatted("common.parameterized", &en_us, vec![ &localization_vars!{ "x" => "y" } ]);
+    assert_eq!(message.primary, "Aqui: y");
+    assert_eq!(message.secondary, "Here: y");
+}
+
+#[test]
+fn locale_script_data() {
+    assert!(parse_locale("az-Arab").unwrap().script_direction() == Direction::RightToLeft);
+    assert!(parse_locale("az").unwrap().script_direction() == Direction::LeftToRight);
+    assert!(parse_locale("pa-Arab").unwrap().direction() == Direction::RightToLeft);
+    assert!(parse_locale("pa-PK").unwrap().direction() == Direction::RightToLeft);
+    assert!(parse_locale("pa").unwrap().direction() == Direction::LeftToRight);
+    assert!(parse_locale("sr-Latn").unwrap().direction() == Direction::LeftToRight);
+    assert!(parse_locale("ar").unwrap().direction() == Direction::RightToLeft);
+    assert!(parse_locale("ar-Latn").unwrap().direction() == Direction::LeftToRight);
+    assert!(parse_locale("ar-Latn").unwrap().language_direction() == Direction::RightToLeft);
+    assert!(Direction::of_script("hebr") == Direction::RightToLeft);
+    assert_eq!(parse_locale("sr").unwrap().default_script(), Some("Cyrl".to_string()));
+    assert_eq!(parse_locale("zh-TW").unwrap().default_script(), Some("Hant".to_string()));
+    assert!(parse_locale("de").unwrap().exemplar_characters().unwrap().contains(&'ß'));
+    assert!(parse_locale("sr-Latn").unwrap().exemplar_characters().unwrap().contains(&'đ'));
+    assert!(parse_locale("sr").unwrap().exemplar_characters().unwrap().contains(&'ђ'));
+    assert!(parse_locale("zh").unwrap().exemplar_characters().is_none());
+}
+
+#[test]
+fn locale_matcher() {
+    let matcher = LocaleMatcher::new(vec!["en-US", "en-GB", "pt-BR", "zh-Hant", "nb"].into_iter().map(|s| parse_locale(s).unwrap()).collect());
+    let best = |desired: Vec<&str>| matcher.best_match(&desired.into_iter().map(|s| parse_locale(s).unwrap()).collect::<Vec<_>>()).map(|l| l.standard_tag().to_string());
+    assert_eq!(best(vec!["en-AU"]), Some("en-GB".to_string()));
+    assert_eq!(best(vec!["en-PR"]), Some("en-US".to_string()));
+    assert_eq!(best(vec!["zh-TW"]), Some("zh-Hant".to_string()));
+    assert_eq!(best(vec!["no"]), Some("nb".to_string()));
+    assert_eq!(best(vec!["pt-PT"]), Some("pt-BR".to_string()));
+    assert_eq!(best(vec!["ja", "en-IE"]), Some("en-GB".to_string()));
+    assert_eq!(best(vec!["ja"]), None);
+    assert_eq!(LocaleMatcher::distance(&parse_locale("sr-Cyrl").unwrap(), &parse_locale("sr-Latn").unwrap()), 50);
+    assert_eq!(matcher.lookup(&[parse_locale("zh-Hant-TW").unwrap()]).map(|l| l.standard_tag().to_string()), Some("zh-Hant".to_string()));
+    assert!(matcher.lookup(&[parse_locale("en").unwrap()]).is_none());
+}
+
+#[test]
+fn supported_locales() {
+    let locales = |list: Vec<&str>| list.into_iter().map(|s| parse_locale(s).unwrap()).collect::<Vec<_>>();
+    let tags = |list: Vec<Locale>| list.into_iter().map(|l| l.standard_tag().to_string()).collect::<Vec<_>>();
+    let available = locales(vec!["en", "pt-BR", "zh-Hant"]);
+    let requested = locales(vec!["en-GB-u-nu-latn", "pt-PT", "zh-Hant-TW", "EN-gb-u-nu-latn", "ja"]);
+    assert_eq!(
+        tags(supported_locales_of(&available, &requested, SupportedLocalesOptions::new().locale_matcher(LocaleMatcherAlgorithm::Lookup))),
+        vec!["en-GB-u-nu-latn", "zh-Hant-TW"]);
+    assert_eq!(
+        tags(supported_locales_of(&available, &requested, &SupportedLocalesOptions::new())),
+        vec!["en-GB-u-nu-latn", "pt-PT", "zh-Hant-TW"]);
+}
+
+#[test]
+fn locale_resolution() {
+    let locales = |list: Vec<&str>| list.into_iter().map(|s| parse_locale(s).unwrap()).collect::<Vec<_>>();
+    let tags = |list: Vec<Locale>| list.into_iter().map(|l| l.standard_tag().to_string()).collect::<Vec<_>>();
+    let preferred = locales(vec!["pt-BR", "es", "en"]);
+
+    let matcher = LocaleMatcher::new(locales(vec!["en-US", "es", "pt-PT", "ja"]));
+    assert_eq!(tags(matcher.resolve(&preferred).unwrap().chain()), vec!["pt-PT", "es", "en-US"]);
+    assert!(matcher.resolve(&locales(vec!["ko"])).is_none());
+
+    let context = IntlContext::new("ja", vec!["en-US", "es", "ja"]).unwrap();
+    assert_eq!(tags(context.resolve(&preferred).chain()), vec!["es", "en-US", "ja"]);
+    assert_eq!(tags(context.resolve(&locales(vec!["ko"])).chain()), vec!["ja"]);
+
+    let locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "es", "pt-PT", "pt-BR", "gl"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["pt-PT"], "gl" => vec!["es", "pt-PT"] })
+    );
+    let resolution = locale_map.negotiate(&locales(vec!["gl-ES", "fr"]));
+    assert_eq!(tags(resolution.chain()), vec!["gl", "es", "pt-PT", "en-US"]);
+    assert_eq!(tags(locale_map.negotiate(&preferred).chain()), vec!["pt-BR", "pt-PT", "es", "en-US"]);
+    assert_eq!(tags(locale_map.negotiate(&locales(vec!["ko"])).chain()), vec!["en-US"]);
+}
+
+#[test]
+fn intl_context() {
+    let context = std::sync::Arc::new(IntlContext::new("en-US", vec!["en-US", "en-GB", "fr"]).unwrap());
+    let handles: Vec<_> = vec!["fr-CA", "en-AU", "ja"].into_iter().map(|requested| {
+        let context = context.clone();
+        std::thread::spawn(move || context.negotiate(&[parse_locale(requested).unwrap()]).standard_tag().to_string())
+    }).collect();
+    let negotiated: Vec<String> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    assert_eq!(negotiated, vec!["fr", "en-GB", "en-US"]);
+
+    context.set_default_locale("fr").unwrap();
+    assert_eq!(context.negotiate(&[parse_locale("ja").unwrap()]).standard_tag().to_string(), "fr");
+    assert!(context.set_default_locale("--").is_err());
+}
+
+#[async_test]
+async fn platform_strings() {
+    let android = parse_android_strings(r#"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+    <string name="app_name">My   App</string>
+    <string name="welcome">Don\'t forget, %1$s: <b>"  $5  "</b> off</string>
+    <plurals name="songs">
+        <item quantity="one">%d song by %2$s</item>
+        <item quantity="other">%d songs by %2$s</item>
+    </plurals>
+    <string-array name="planets">
+        <item>Mercury</item>
+        <item>Venus</item>
+    </string-array>
+</resources>"#).unwrap();
+    assert_eq!(android["app_name"], "My App");
+    assert_eq!(android["welcome"], "Don't forget, $1:   $$5   off");
+    assert_eq!(android["songs_empty"], "$number songs by $2");
+    assert_eq!(android["songs_one"], "$number song by $2");
+    assert_eq!(android["planets"]["1"], "Venus");
+    assert!(parse_android_strings("<resources><string>x</string></resources>").is_err());
+
+    let apple = parse_apple_strings("\"a\" = \"100%% \\\"done\\\"\";\n// comment\nb = \"%2$@ and %1$@\";").unwrap();
+    assert_eq!(apple["a"], "100% \"done\"");
+    assert_eq!(apple["b"], "$2 and $1");
+    assert!(parse_apple_strings("\"a\" = \"b\"").is_err());
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res-apple")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem)
+                .format(LocaleMapAssetFormat::AppleStrings))
+    ); // locale_map
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.get_formatted("common.greeting", vec![ &maplit::hashmap!{"1".to_string() => "Ana".to_string()} ]), "Hello, Ana!");
+    assert_eq!(locale_map.get("common.nav.back"), "Back");
+    assert_eq!(locale_map.get_formatted("common.files", vec![ &0 ]), "No files");
+    assert_eq!(locale_map.get_formatted("common.files", vec![ &3 ]), "3 files");
+}
+
+#[async_test]
+async fn csv_round_trip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    for locale in ["en-US", "pt-BR"].iter() {
+        std::fs::create_dir_all(dir.join(locale)).unwrap();
+        std::fs::copy(format!("tests/res/{}/common.json", locale), dir.join(locale).join("common.json")).unwrap();
+    }
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    let pt_br = parse_locale("pt-BR").unwrap();
+    locale_map.load(Some(pt_br.clone())).await;
+
+    let csv = locale_map.export_csv(&pt_br);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "key,source,translation,comment");
+    assert!(lines.contains(&"common.message_id,Some message,Alguma mensagem,"));
+    assert!(lines.contains(&"common.save,Save,,"));
+    assert!(locale_map.export_csv_wide().starts_with("key,en-US,pt-BR\r\n"));
+
+    let edited = csv.replace("common.save,Save,,", "common.save,Save,\"Salvar, \"\"agora\"\"\",");
+    assert_eq!(locale_map.import_csv(&pt_br, &edited).unwrap(), 1);
+    assert_eq!(locale_map.get("common.save"), "Salvar, \"agora\"");
+    let saved: serde_json::Value = serde_json::from_slice(&std::fs::read(dir.join("pt-BR/common.json")).unwrap()).unwrap();
+    assert_eq!(saved["save"], "Salvar, \"agora\"");
+
+    let wide = "key,pt-BR\ncommon.message_id,Outra mensagem\n";
+    assert_eq!(locale_map.import_csv(&pt_br, wide).unwrap(), 1);
+    assert_eq!(locale_map.get("common.message_id"), "Outra mensagem");
+    assert!(matches!(locale_map.import_csv(&pt_br, "id,text\n"), Err(TranslationEditError::InvalidContent(_))));
+    assert!(matches!(locale_map.import_csv(&pt_br, "key,translation\n\"x"), Err(TranslationEditError::InvalidContent(_))));
+}
+
+#[async_test]
+async fn validation_report() {
+    let locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR", "ja"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res-validation")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    let report = locale_map.validate().await;
+    assert!(!report.is_ok());
+    assert!(report.get(&parse_locale("en-US").unwrap()).unwrap().is_ok());
+
+    let pt_br = report.get(&parse_locale("pt-BR").unwrap()).unwrap();
+    assert!(pt_br.loaded);
+    assert!(pt_br.missing_keys.is_empty());
+    assert_eq!(pt_br.orphaned_keys, vec!["common.legacy"]);
+    assert_eq!(pt_br.empty_messages, vec!["common.save"]);
+    assert_eq!(pt_br.placeholder_mismatches, vec![PlaceholderMismatch {
+        id: "common.greeting".to_string(),
+        missing: vec!["name".to_string()],
+        unexpected: vec!["nam".to_string()],
+    }]);
+
+    let ja = report.get(&parse_locale("ja").unwrap()).unwrap();
+    assert!(!ja.loaded);
+    assert_eq!(ja.missing_keys.len(), 3);
+    assert!(report.to_string().contains("  placeholders of common.greeting: -$name +$nam\n"));
+}
+
+#[async_test]
+async fn placeholder_check() {
+    let diagnostics = std::rc::Rc::new(std::cell::RefCell::new(Vec::<Diagnostic>::new()));
+    let options = LocaleMapOptions::new();
+    options
+        .supported_locales(vec!["en-US", "pt-BR"])
+        .default_locale("en-US")
+        .assets(LocaleMapAssetOptions::new()
+            .src("tests/res-validation")
+            .base_file_names(vec!["common"])
+            .loader_type(LocaleMapLoaderType::FileSystem))
+        .check_placeholders(DiagnosticSeverity::Warning);
+    let sink = diagnostics.clone();
+    options.on_diagnostic(move |d| sink.borrow_mut().push(d.clone()));
+
+    let mut locale_map = LocaleMap::new(&options);
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(diagnostics.borrow().len(), 1);
+    assert_eq!(diagnostics.borrow()[0].to_string(), "warning in pt-BR: placeholders of common.greeting: -$name +$nam");
+
+    options.check_placeholders(DiagnosticSeverity::Error);
+    let mut locale_map = LocaleMap::new(&options);
+    assert!(locale_map.load(None).await);
+    assert!(!locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert!(locale_map.current_locale().unwrap() == parse_locale("en-US").unwrap());
+    assert_eq!(diagnostics.borrow()[1].severity, DiagnosticSeverity::Error);
+}
+
+#[async_test]
+async fn message_metadata() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    for locale in ["en-US", "pt-BR"].iter() {
+        std::fs::create_dir_all(dir.join(locale)).unwrap();
+        std::fs::copy(format!("tests/res-metadata/{}/common.json", locale), dir.join(locale).join("common.json")).unwrap();
+    }
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    let en_us = parse_locale("en-US").unwrap();
+    let pt_br = parse_locale("pt-BR").unwrap();
+    locale_map.load(Some(pt_br.clone())).await;
+    assert_eq!(locale_map.get("common.save"), "Salvar documento");
+    // An object with a `message` field and no metadata is an object of nested messages.
+    assert_eq!(locale_map.get("common.errors.message"), "Algo deu errado");
+    assert_eq!(locale_map.message_metadata("common.save"), Some(MessageMetadata {
+        description: Some("Toolbar button that saves the document".to_string()),
+        max_length: Some(8),
+        max_width: None,
+    }));
+    assert_eq!(locale_map.message_metadata("common.errors.message"), Some(MessageMetadata::default()));
+    assert_eq!(locale_map.message_metadata("common.nonexistent"), None);
+    assert!(locale_map.export_csv(&pt_br).contains("common.save,Save,Salvar documento,Toolbar button that saves the document\r\n"));
+
+    let report = locale_map.validate().await;
+    assert_eq!(report.get(&pt_br).unwrap().too_long_messages, vec!["common.save"]);
+    assert!(report.get(&en_us).unwrap().is_ok());
+
+    let mut editor = locale_map.translation_editor(&en_us);
+    editor.set("common.save", "Store").unwrap();
+    editor.save().unwrap();
+    let saved: serde_json::Value = serde_json::from_slice(&std::fs::read(dir.join("en-US/common.json")).unwrap()).unwrap();
+    assert_eq!(saved["save"]["message"], "Store");
+    assert_eq!(saved["save"]["max_length"], 8);
+}
+
+#[async_test]
+async fn length_budgets() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    for (locale, content) in [
+        ("en-US", r#"{"save": {"message": "Save", "max_length": 6, "max_width": 60}, "cafe": {"message": "Cafe", "max_length": 4}}"#),
+        // "Café" has a combining acute accent, counting as one grapheme cluster.
+        ("de-DE", "{\"save\": \"Speichern\", \"cafe\": \"Cafe\u{301}\"}"),
+        ("ja-JP", r#"{"save": "保存", "cafe": "カフェ"}"#),
+    ].iter() {
+        std::fs::create_dir_all(dir.join(locale)).unwrap();
+        std::fs::write(dir.join(locale).join("common.json"), content).unwrap();
+    }
+    let mut widths = TextWidthTable::sans_serif(16.0);
+    widths.set('S', 10.0);
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "de-DE", "ja-JP"])
+            .default_locale("en-US")
+            .text_widths(widths)
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    let report = locale_map.validate().await;
+    let de_de = report.get(&parse_locale("de-DE").unwrap()).unwrap();
+    assert_eq!(de_de.too_long_messages, vec!["common.save"]);
+    assert_eq!(de_de.too_wide_messages, vec!["common.save"]);
+    assert!(de_de.to_string().contains("too wide: common.save"));
+    assert!(report.get(&parse_locale("ja-JP").unwrap()).unwrap().is_ok());
+    assert!(report.get(&parse_locale("en-US").unwrap()).unwrap().is_ok());
+}
+
+#[async_test]
+async fn variant_audit() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    for (locale, content) in [
+        ("en-US", r#"{"invited_male": "He", "invited_female": "She", "qty_empty": "None", "qty_one": "One", "qty_multiple": "Many", "qty_negative": "Debt"}"#),
+        ("pt-BR", r#"{"invited_male": "Ele", "qty_one": "Um", "qty_multiple": "Muitos"}"#),
+    ].iter() {
+        std::fs::create_dir_all(dir.join(locale)).unwrap();
+        std::fs::write(dir.join(locale).join("common.json"), content).unwrap();
+    }
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .audit_variants(true)
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    let report = locale_map.validate().await;
+    assert!(report.get(&parse_locale("en-US").unwrap()).unwrap().is_ok());
+    let pt_br = report.get(&parse_locale("pt-BR").unwrap()).unwrap();
+    assert_eq!(pt_br.incomplete_gender_variants, vec!["common.invited"]);
+    assert_eq!(pt_br.incomplete_plural_variants, vec!["common.qty"]);
+    assert!(pt_br.to_string().contains("incomplete gender variants: common.invited"));
+}
+
+#[cfg(feature = "normalization")]
+#[async_test]
+async fn unicode_normalization() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    std::fs::create_dir_all(dir.join("pt-BR")).unwrap();
+    // Decomposed, as written by some macOS tools.
+    std::fs::write(dir.join("pt-BR/common.json"), "{\"cafe\u{301}\": \"Cafe\u{301} com $item\"}").unwrap();
+    let locale_map = |normalization: Option<NormalizationForm>| LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["pt-BR"])
+            .default_locale("pt-BR")
+            .normalization(normalization)
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    );
+    let mut normalized = locale_map(Some(NormalizationForm::Nfc));
+    assert!(normalized.load(None).await);
+    assert_eq!(normalized.get_formatted("common.café", vec![ &localization_vars!{ "item" => "pa\u{303}o" } ]), "Café com pão");
+    assert_eq!(normalized.get("common.cafe\u{301}"), "Café com undefined");
+    let mut unchanged = locale_map(None);
+    assert!(unchanged.load(None).await);
+    assert_eq!(unchanged.get("common.café"), "common.café");
+    assert_eq!(unchanged.get("common.cafe\u{301}"), "Cafe\u{301} com undefined");
+    // Embedded assets are normalized as well.
+    let options = LocaleMapOptions::new();
+    options.supported_locales(vec!["pt-BR"]).default_locale("pt-BR");
+    let embedded = LocaleMap::from_embedded(&options, &[("pt-BR", "{\"cafe\u{301}\": {\"message\": \"Cafe\u{301}\", \"description\": \"Cafe\u{301}\"}}")]);
+    assert_eq!(embedded.get("café"), "Café");
+    assert_eq!(embedded.messages_for(&parse_locale("pt-BR").unwrap()).unwrap().metadata("café").unwrap().description.as_deref(), Some("Café"));
+
+    assert_eq!(normalize("Å", NormalizationForm::Nfd), "A\u{30a}");
+    assert!(is_normalized("Å", NormalizationForm::Nfc));
+    assert!(LocaleMapOptions::from_json(r#"{"normalization": "nfx"}"#).is_err());
+    assert!(LocaleMapOptions::from_json(r#"{"normalization": "none"}"#).is_ok());
+}
+
+#[async_test]
+async fn layered_assets() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    for locale in ["en", "en-US"].iter() {
+        std::fs::create_dir_all(dir.join(locale)).unwrap();
+        std::fs::copy(format!("tests/res-layered/{}/common.json", locale), dir.join(locale).join("common.json")).unwrap();
+    }
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "en-GB"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem)
+                .layered(true))
+    ); // locale_map
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.get("common.color"), "Color");
+    assert_eq!(locale_map.get("common.greeting"), "Hello");
+    assert_eq!(locale_map.get("common.units.distance"), "Miles");
+    assert_eq!(locale_map.get("common.units.weight"), "Kilograms");
+
+    let en_us = parse_locale("en-US").unwrap();
+    let mut editor = locale_map.translation_editor(&en_us);
+    editor.set("common.greeting", "Hi").unwrap();
+    editor.save().unwrap();
+    let saved: serde_json::Value = serde_json::from_slice(&std::fs::read(dir.join("en-US/common.json")).unwrap()).unwrap();
+    assert_eq!(saved, serde_json::json!({ "color": "Color", "greeting": "Hi", "units": { "distance": "Miles" } }));
+
+    assert!(locale_map.load(Some(parse_locale("en-GB").unwrap())).await);
+    assert_eq!(locale_map.get("common.color"), "Colour");
+    assert_eq!(locale_map.get("common.units.distance"), "Kilometres");
+}
+
+#[async_test]
+async fn custom_formatters() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    locale_map.register_formatter("upper", |s, _, _| s.to_uppercase());
+    locale_map.register_formatter("currency", |s, locale, code| format!("{} {} ({})", code.unwrap_or("USD"), s, locale.standard_tag()));
+    let format = |message: &str, value: &str| {
+        let mut locale_map = locale_map.clone();
+        let en_us = parse_locale("en-US").unwrap();
+        locale_map.translation_editor(&en_us).set("common.custom", message).unwrap();
+        locale_map.get_formatted("common.custom", vec![ &maplit::hashmap!{"x".to_string() => value.to_string()} ])
+    };
+    assert_eq!(format("Hi ${x|upper}!", "ana"), "Hi ANA!");
+    // Formatters only apply within braces.
+    assert_eq!(format("Hi $x|upper!", "ana"), "Hi ana|upper!");
+    assert_eq!(format("Total: ${x|currency:EUR}", "10"), "Total: EUR 10 (en-US)");
+    assert_eq!(format("${x | currency | upper}", "10"), "USD 10 (EN-US)");
+    assert_eq!(format("$x|unknown|upper", "a"), "a|unknown|upper");
+    assert_eq!(format("${x|unknown|upper}", "a"), "A");
+    assert_eq!(format("$$x|upper", "a"), "$x|upper");
+}
+
+#[async_test]
+async fn format_values() {
+    use chrono::{FixedOffset, TimeZone};
+    let number_formatter = |tag: &str| NumberFormatter::new(&parse_locale(tag).unwrap(), &NumberFormatOptions::new());
+    assert_eq!(number_formatter("en-US").format(1234567.891), "1,234,567.891");
+    assert_eq!(number_formatter("de").format(-1234.5), "-1.234,5");
+    assert_eq!(number_formatter("es").format_integer(1234), "1234");
+    assert_eq!(number_formatter("es").format_integer(12345), "12.345");
+    assert_eq!(number_formatter("hi").format_integer(1234567), "12,34,567");
+    assert_eq!(number_formatter("ar-u-nu-arab").format_integer(1000), "١٬٠٠٠");
+    assert_eq!(NumberFormatter::new(&parse_locale("en").unwrap(), NumberFormatOptions::new().minimum_fraction_digits(2)).format(3.0), "3.00");
+
+    let date = FixedOffset::west_opt(3 * 3600).unwrap().with_ymd_and_hms(2025, 3, 4, 15, 5, 9).unwrap();
+    let date_time_formatter = |tag: &str, date_length, time_length| DateTimeFormatter::new(&parse_locale(tag).unwrap(), DateTimeFormatOptions::new().date_length(date_length).time_length(time_length));
+    assert_eq!(date_time_formatter("en-US", Some(DateTimeLength::Medium), Some(DateTimeLength::Short)).format(&date), "Mar 4, 2025, 3:05 PM");
+    assert_eq!(date_time_formatter("en-US", Some(DateTimeLength::Full), None).format(&date), "Tuesday, March 4, 2025");
+    assert_eq!(date_time_formatter("en-US", None, Some(DateTimeLength::Full)).format(&date), "3:05:09 PM GMT-03:00");
+    assert_eq!(date_time_formatter("pt-BR", Some(DateTimeLength::Long), None).format(&date), "4 de março de 2025");
+    assert_eq!(date_time_formatter("de", Some(DateTimeLength::Short), Some(DateTimeLength::Short)).format(&date), "04.03.25, 15:05");
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(Some(parse_locale("pt-BR").unwrap())).await;
+    let pt_br = parse_locale("pt-BR").unwrap();
+    locale_map.translation_editor(&pt_br).set("common.custom", "$n em $d ($b, $s)").unwrap();
+    let vars: std::collections::HashMap<String, FormatValue> = maplit::hashmap!{
+        "n".to_string() => FormatValue::from(1234.5),
+        "d".to_string() => FormatValue::from(date),
+        "b".to_string() => FormatValue::from(true),
+        "s".to_string() => FormatValue::from("x"),
+    };
+    assert_eq!(locale_map.get_formatted("common.custom", vec![&vars]), "1.234,5 em 4 de mar. de 2025 15:05 (true, x)");
+}
+
+#[async_test]
+async fn message_escapes() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    locale_map.register_formatter("upper", |s, _, _| s.to_uppercase());
+    let format = |message: &str| {
+        let mut locale_map = locale_map.clone();
+        let en_us = parse_locale("en-US").unwrap();
+        locale_map.translation_editor(&en_us).set("common.custom", message).unwrap();
+        locale_map.get_formatted("common.custom", vec![ &localization_vars!{"x" => "a"} ])
+    };
+    assert_eq!(format(r"Costs \$5 or $$5"), "Costs $5 or $5");
+    assert_eq!(format(r"Template: \${x} and $${x}"), "Template: ${x} and ${x}");
+    assert_eq!(format(r"$x\|upper and \\$x"), r"a|upper and \a");
+    assert_eq!(format(r"C:\Users\$x"), r"C:\Users$x");
+    assert_eq!(format("First line, \\\n    same line: $x"), "First line, same line: a");
+    assert_eq!(format("Price: $ 5, ${x"), "Price: $ 5, ${x");
+    assert_eq!(format(r"${x|upper}\}"), "A}");
+}
+
+#[test]
+fn message_linting() {
+    let ast = parse_message("Hi $name|upper, total: ${price | currency:EUR}").unwrap();
+    assert_eq!(ast.parts.len(), 4);
+    match &ast.parts[3] {
+        MessagePart::Variable(v) => {
+            assert_eq!(v.name, "price");
+            assert_eq!(v.span, 23..46);
+            assert_eq!(v.formatters[0].name, "currency");
+            assert_eq!(v.formatters[0].argument.as_deref(), Some("EUR"));
+        },
+        _ => panic!(),
+    }
+    let error = |message: &str| parse_message(message).unwrap_err();
+    assert_eq!(error("Total: ${price"), SyntaxError { kind: SyntaxErrorKind::UnclosedPlaceholder, offset: 7 });
+    assert_eq!(error("Costs $ 5"), SyntaxError { kind: SyntaxErrorKind::ExpectedVariableName, offset: 6 });
+    assert_eq!(error("${first name}").kind, SyntaxErrorKind::InvalidVariableName("first name".to_string()));
+    assert_eq!(error("${x|}"), SyntaxError { kind: SyntaxErrorKind::InvalidFormatterName("".to_string()), offset: 3 });
+    assert_eq!(error("a } b"), SyntaxError { kind: SyntaxErrorKind::UnbalancedBrace, offset: 2 });
+    assert_eq!(error("ü { b"), SyntaxError { kind: SyntaxErrorKind::UnbalancedBrace, offset: 3 });
+    assert!(parse_message(r"Costs \$5, {ok} and \}").is_ok());
+    assert_eq!(error("Total: ${price").to_string(), "Unclosed placeholder at byte 7");
+}
+
+#[async_test]
+async fn format_overrides() {
+    use chrono::{FixedOffset, TimeZone};
+    let date = FixedOffset::east_opt(0).unwrap().with_ymd_and_hms(2025, 3, 4, 15, 5, 0).unwrap();
+    let iso = FormatOverrides::new();
+    iso.date_order(DateOrder::YearMonthDay).hour_cycle(HourCycle::H23);
+    let short = DateTimeFormatOptions::new();
+    short.date_length(Some(DateTimeLength::Short)).overrides(&iso);
+    assert_eq!(DateTimeFormatter::new(&parse_locale("en-US").unwrap(), &short).format(&date), "2025-03-04, 15:05");
+    short.date_length(Some(DateTimeLength::Medium));
+    assert_eq!(DateTimeFormatter::new(&parse_locale("en-US").unwrap(), &short).format(&date), "Mar 4, 2025, 15:05");
+    let twelve_hour = FormatOverrides::new();
+    twelve_hour.hour_cycle(HourCycle::H12);
+    assert_eq!(DateTimeFormatter::new(&parse_locale("de").unwrap(), DateTimeFormatOptions::new().date_length(None).overrides(&twelve_hour)).format(&date), "3:05 PM");
+
+    let locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .format_overrides("*", FormatOverrides::new().date_order(DateOrder::YearMonthDay))
+            .format_overrides("en", FormatOverrides::new().decimal_separator(",").group_separator(" "))
+            .format_overrides("en-US", FormatOverrides::new().group_separator("'"))
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert_eq!(locale_map.number_formatter().format(1234.5), "1'234,5");
+    // Only numeric dates are reordered.
+    assert_eq!(locale_map.date_time_formatter().format(&date), "Mar 4, 2025, 3:05 PM");
+}
+
+#[async_test]
+async fn hour_cycle() {
+    use chrono::{FixedOffset, TimeZone};
+    assert_eq!(parse_locale("en-US").unwrap().preferred_hour_cycle(), HourCycle::H12);
+    assert_eq!(parse_locale("de-DE").unwrap().preferred_hour_cycle(), HourCycle::H23);
+    assert_eq!(parse_locale("de-DE-u-hc-h12").unwrap().preferred_hour_cycle(), HourCycle::H12);
+
+    let date = FixedOffset::east_opt(0).unwrap().with_ymd_and_hms(2025, 3, 4, 0, 5, 0).unwrap();
+    let time = |tag: &str, options: &DateTimeFormatOptions| DateTimeFormatter::new(&parse_locale(tag).unwrap(), options.date_length(None)).format(&date);
+    assert_eq!(time("en-US", &DateTimeFormatOptions::new()), "12:05 AM");
+    assert_eq!(time("de-DE", &DateTimeFormatOptions::new()), "00:05");
+    assert_eq!(time("en-US-u-hc-h23", &DateTimeFormatOptions::new()), "00:05");
+    assert_eq!(time("en-US-u-hc-h11", &DateTimeFormatOptions::new()), "0:05 AM");
+    assert_eq!(time("de-DE", DateTimeFormatOptions::new().hour_cycle(HourCycle::H24)), "24:05");
+    assert_eq!(DateTimeFormatter::new(&parse_locale("ko").unwrap(), DateTimeFormatOptions::new().hour_cycle(HourCycle::H23)).hour_cycle(), HourCycle::H23);
+
+    let locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .hour_cycle(HourCycle::H23)
+    ); // locale_map
+    assert_eq!(locale_map.hour_cycle(), HourCycle::H23);
+    assert_eq!(locale_map.date_time_formatter().format_pattern(&date, "H:mm"), "0:05");
+    assert_eq!(locale_map.date_time_formatter().hour_cycle(), HourCycle::H23);
+}
+
+#[async_test]
+async fn relative_time_from() {
+    use std::time::{Duration, SystemTime};
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    let now = chrono::DateTime::parse_from_rfc3339("2025-03-04T12:00:00+00:00").unwrap();
+    let in_brasilia = chrono::DateTime::parse_from_rfc3339("2025-03-04T14:00:00-03:00").unwrap();
+    assert_eq!(locale_map.format_relative_time_between(in_brasilia, now), "in 5 hours");
+    let earlier = chrono::DateTime::parse_from_rfc3339("2025-03-04T11:55:00+00:00").unwrap();
+    assert_eq!(locale_map.format_relative_time_between(earlier, now), "5 minutes ago");
+    assert_eq!(locale_map.format_relative_time_between(now, now), "now");
+    assert_eq!(locale_map.format_relative_time_from(SystemTime::now() + Duration::from_secs(3 * 24 * 60 * 60 + 60)), "in 3 days");
+    assert_eq!(locale_map.format_relative_time_from(SystemTime::now() - Duration::from_secs(90)), "1 minute ago");
+
+    locale_map.load(Some(parse_locale("pt-BR").unwrap())).await;
+    assert_eq!(locale_map.format_relative_time_between(in_brasilia, now), "em 5 horas");
+}
+
+#[test]
+fn date_ranges() {
+    let date = |s: &str| chrono::DateTime::parse_from_rfc3339(&format!("{}T12:00:00+00:00", s)).unwrap();
+    let range = |tag: &str, length, start: &str, end: &str| {
+        DateTimeFormatter::new(&parse_locale(tag).unwrap(), DateTimeFormatOptions::new().date_length(Some(length)))
+            .format_range(&date(start), &date(end))
+    };
+    assert_eq!(range("en-US", DateTimeLength::Medium, "2025-01-05", "2025-01-09"), "Jan 5 – 9, 2025");
+    assert_eq!(range("en-US", DateTimeLength::Medium, "2025-01-05", "2025-02-09"), "Jan 5 – Feb 9, 2025");
+    assert_eq!(range("en-US", DateTimeLength::Medium, "2024-12-30", "2025-01-02"), "Dec 30, 2024 – Jan 2, 2025");
+    assert_eq!(range("en-US", DateTimeLength::Medium, "2025-01-05", "2025-01-05"), "Jan 5, 2025");
+    assert_eq!(range("en-US", DateTimeLength::Full, "2025-01-05", "2025-01-09"), "Sunday, January 5 – Thursday, January 9, 2025");
+    assert_eq!(range("en-US", DateTimeLength::Short, "2025-01-05", "2025-01-09"), "1/5/25 – 1/9/25");
+    assert_eq!(range("de", DateTimeLength::Long, "2025-01-05", "2025-01-09"), "5. – 9. Januar 2025");
+    assert_eq!(range("pt-BR", DateTimeLength::Long, "2025-01-05", "2025-02-09"), "5 de janeiro – 9 de fevereiro de 2025");
+    assert_eq!(range("ja", DateTimeLength::Long, "2025-01-05", "2025-01-09"), "2025年1月5日～9日");
+
+    let locale_map = LocaleMap::new(LocaleMapOptions::new().supported_locales(vec!["en-GB"]).default_locale("en-GB"));
+    assert_eq!(locale_map.format_date_range(&date("2025-01-05"), &date("2025-01-09"), DateTimeLength::Long), "5 – 9 January 2025");
+}
+
+#[async_test]
+async fn month_and_weekday_names() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    assert_eq!(locale_map.month_names(NameWidth::Wide, NameContext::Format)[0], "January");
+    assert_eq!(locale_map.month_names(NameWidth::Narrow, NameContext::Standalone).concat(), "JFMAMJJASOND");
+    assert_eq!(locale_map.weekday_names(NameWidth::Abbreviated, NameContext::Format)[1], "Mon");
+    locale_map.load(Some(parse_locale("pt-BR").unwrap())).await;
+    assert_eq!(locale_map.weekday_names(NameWidth::Wide, NameContext::Format)[1], "segunda-feira");
+
+    let ru = DateTimeFormatter::new(&parse_locale("ru").unwrap(), &DateTimeFormatOptions::new());
+    assert_eq!(ru.month_names(NameWidth::Wide, NameContext::Format)[2], "марта");
+    assert_eq!(ru.month_names(NameWidth::Wide, NameContext::Standalone)[2], "март");
+    let date = chrono::DateTime::parse_from_rfc3339("2025-03-04T12:00:00+00:00").unwrap();
+    assert_eq!(ru.format_pattern(&date, "d MMMM, LLLL, LLLLL, EEEEE"), "4 марта, март, М, В");
+}
+
+#[test]
+fn quarters_and_eras() {
+    use chrono::TimeZone;
+    let formatter = |tag: &str| DateTimeFormatter::new(&parse_locale(tag).unwrap(), &DateTimeFormatOptions::new());
+    let date = chrono::DateTime::parse_from_rfc3339("2025-08-04T12:00:00+00:00").unwrap();
+    assert_eq!(formatter("en-US").format_pattern(&date, "QQQ y, QQQQ, Q"), "Q3 2025, 3rd quarter, 3");
+    assert_eq!(formatter("de").format_pattern(&date, "QQQQ y"), "3. Quartal 2025");
+    assert_eq!(formatter("en-US").format_pattern(&date, "y G, GGGG, GGGGG"), "2025 AD, Anno Domini, A");
+    let ides = chrono::FixedOffset::east_opt(0).unwrap().from_utc_datetime(&chrono::NaiveDate::from_ymd_opt(-43, 3, 15).unwrap().and_hms_opt(12, 0, 0).unwrap());
+    assert_eq!(formatter("en-US").format_pattern(&ides, "MMMM d, y G"), "March 15, 44 BC");
+    assert_eq!(formatter("pt-BR").format_pattern(&ides, "y G"), "44 a.C.");
+}
+
+#[cfg(feature = "segmentation")]
+#[test]
+fn text_segmentation() {
+    let locale = |tag: &str| parse_locale(tag).unwrap();
+    assert_eq!(Segmenter::words("我爱北京", &locale("zh")).collect::<Vec<_>>(), vec!["我", "爱", "北京"]);
+    assert_eq!(Segmenter::words("東京に行く", &locale("ja")).collect::<Vec<_>>(), vec!["東京", "に", "行く"]);
+    assert_eq!(Segmenter::words("สวัสดีครับ ขอบคุณ", &locale("th")).collect::<Vec<_>>(), vec!["สวัสดี", "ครับ", "ขอบคุณ"]);
+    assert_eq!(Segmenter::word_count("can't stop, won't stop", &locale("en")), 4);
+    assert_eq!(Segmenter::word_boundaries("Olá, mundo!", &locale("pt")).collect::<String>(), "Olá, mundo!");
+    assert_eq!(Segmenter::graphemes("👨‍👩‍👧 ok", &locale("en")).count(), 4);
+    assert_eq!(
+        Segmenter::sentences("Das ist z.B. ein Satz. Noch einer?", &locale("de")).collect::<Vec<_>>(),
+        vec!["Das ist z.B. ein Satz. ", "Noch einer?"]);
+    assert_eq!(Segmenter::sentences("Dr. Who. Yes.", &locale("ja")).count(), 3);
+}
+
+#[async_test]
+async fn plural_ranges() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en", "ru", "da"])
+            .default_locale("en")
+    );
+    assert!(locale_map.select_plural_range(1, 2).is_err());
+    assert!(locale_map.load(Some(parse_locale("ru").unwrap())).await);
+    assert_eq!(locale_map.select_plural_range(1, 2), Ok(PluralCategory::FEW));
+    assert_eq!(locale_map.select_plural_range(3, 5), Ok(PluralCategory::MANY));
+    assert_eq!(locale_map.select_plural_range("1.5", "2.5"), Ok(PluralCategory::OTHER));
+
+    let ru = parse_locale("ru").unwrap();
+    locale_map.insert_message(&ru, "common.days_range_few", "$start–$end дня");
+    locale_map.insert_message(&ru, "common.days_range_many", "$start–$end дней");
+    assert_eq!(locale_map.get_formatted("common.days", vec![ &PluralRange::new(1, 2) ]), "1–2 дня");
+    assert_eq!(locale_map.get_formatted("common.days", vec![ &PluralRange::new(3, 5) ]), "3–5 дней");
+
+    assert!(locale_map.load(Some(parse_locale("da").unwrap())).await);
+    assert_eq!(locale_map.select_plural_range(0, 1), Ok(PluralCategory::ONE));
+    assert!(locale_map.load(Some(parse_locale("en").unwrap())).await);
+    assert_eq!(locale_map.select_plural_range(0, 1), Ok(PluralCategory::OTHER));
+    assert_eq!(locale_map.select_plural_range(1, 2), Ok(PluralCategory::OTHER));
+}
+
+#[cfg(feature = "normalization")]
+#[test]
+fn search_collation() {
+    let collator = |tag: &str| SearchCollator::new(&parse_locale(tag).unwrap(), &SearchCollatorOptions::new());
+    assert!(collator("en").contains("Šimon", "simon"));
+    assert!(collator("en").starts_with("Ｆｉｌｅ ﬁnder", "file fi"));
+    assert!(collator("de").equals("STRASSE", "Straße"));
+    assert!(collator("pt").contains("São Paulo", "sao paulo"));
+    assert!(collator("pt").contains("Sa\u{303}o", "são"));
+    assert!(collator("sv").contains("Malmö", "malmö"));
+    assert!(!collator("sv").contains("Malmö", "malmo"));
+    assert!(collator("en").contains("Malmö", "malmo"));
+    assert!(!collator("tr").equals("IRMAK", "irmak"));
+    assert!(collator("tr").equals("IRMAK", "ırmak"));
+    assert!(collator("en").equals("IRMAK", "irmak"));
+    assert!(!collator("ja").equals("が", "か"));
+    assert_eq!(collator("de").find("Die Straße", "SS"), Some(8..10));
+    assert_eq!(collator("en").find("abc", "x"), None);
+
+    let exact = SearchCollator::new(&parse_locale("en").unwrap(), SearchCollatorOptions::new().ignore_case(false).ignore_diacritics(false));
+    assert!(!exact.contains("Šimon", "simon"));
+    assert!(exact.contains("Šimon", "Šim"));
+}
+
+#[cfg(feature = "segmentation")]
+#[test]
+fn line_breaking() {
+    let breaker = |tag: &str| LineBreaker::new(&parse_locale(tag).unwrap());
+    let indices = |tag: &str, text: &str| breaker(tag).break_opportunities(text).iter().map(|o| o.index).collect::<Vec<_>>();
+    let chars = |s: &str| s.chars().count() as f64;
+    let lines = |tag: &str, text: &str, width: f64| breaker(tag).wrap(text, width, chars).into_iter().map(|l| l.text.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(indices("en", "a (b) c-d"), vec![2, 6, 8, 9]);
+    assert_eq!(indices("en", "$10.50, ok"), vec![8, 10]);
+    let mandatory: Vec<bool> = breaker("en").break_opportunities("one
+two
+").iter().map(|o| o.mandatory).collect();
+    assert_eq!(mandatory, vec![true, true]);
+    assert_eq!(lines("ja", "今日は「晴れ」です。", 4.0), vec!["今日は", "「晴れ」", "です。"]);
+    assert_eq!(lines("ja", "ちょっと", 1.0), vec!["ちょっ", "と"]);
+    assert_eq!(lines("ko", "대한민국 만세", 3.0), vec!["대한민국", "만세"]);
+    assert_eq!(lines("zh", "대한민국", 2.0), vec!["대한", "민국"]);
+    assert_eq!(lines("th", "สวัสดีครับขอบคุณ", 6.0), vec!["สวัสดี", "ครับ", "ขอบคุณ"]);
+
+    let wrapped = breaker("en").wrap("שלום עולם
+hello", 20.0, chars);
+    assert!(wrapped[0].direction == Direction::RightToLeft);
+    assert!(wrapped[1].direction == Direction::LeftToRight);
+    assert!(breaker("ar").wrap("123", 20.0, chars)[0].direction == Direction::RightToLeft);
+}
+
+#[async_test]
+async fn person_names() {
+    let formatter = |tag: &str| PersonNameFormatter::new(&parse_locale(tag).unwrap(), &PersonNameFormatOptions::new());
+    let smith = PersonName::new("Jane", "Smith").with_honorific("Dr.");
+    assert_eq!(formatter("en-US").format(&smith), "Dr. Jane Smith");
+    assert_eq!(formatter("en-US").format_formal(&smith), "Dr. Smith");
+    assert_eq!(formatter("zh").format(&PersonName::new("小明", "王")), "王小明");
+    assert_eq!(formatter("ja").format_formal(&PersonName::new("太郎", "山田").with_honorific("様")), "山田様");
+    assert_eq!(formatter("ja").format(&PersonName::new("Taro", "Yamada")), "Yamada Taro");
+    assert!(formatter("hu").order() == PersonNameOrder::FamilyFirst);
+    let given_first = PersonNameFormatter::new(&parse_locale("ja").unwrap(), PersonNameFormatOptions::new().order(PersonNameOrder::GivenFirst));
+    assert_eq!(given_first.format(&PersonName::new("Taro", "Yamada")), "Taro Yamada");
+    assert_eq!(formatter("pt-BR").format(&PersonName::new("Ana", "")), "Ana");
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "hu"])
+            .default_locale("en-US")
+    );
+    assert_eq!(locale_map.format_person_name(&PersonName::new("János", "Kovács")), "János Kovács");
+    locale_map.load(Some(parse_locale("hu").unwrap())).await;
+    assert_eq!(locale_map.format_person_name(&PersonName::new("János", "Kovács")), "Kovács János");
+}
+
+#[async_test]
+async fn message_cache() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(locale_map.get_formatted("common.parameterized", vec![ &localization_vars!{"x" => "a"} ]), "Aqui: a");
+    assert_eq!(locale_map.get_formatted("common.parameterized", vec![ &localization_vars!{"x" => "b"} ]), "Aqui: b");
+    assert_eq!(locale_map.get("common.save"), "Save");
+
+    let pt_br = parse_locale("pt-BR").unwrap();
+    locale_map.translation_editor(&pt_br).set("common.save", "Salvar").unwrap();
+    assert_eq!(locale_map.get("common.save"), "Salvar");
+    locale_map.translation_editor(&pt_br).delete("common.parameterized").unwrap();
+    assert_eq!(locale_map.get_formatted("common.parameterized", vec![ &localization_vars!{"x" => "c"} ]), "Here: c");
+
+    assert!(locale_map.load(Some(parse_locale("en-US").unwrap())).await);
+    assert_eq!(locale_map.get("common.message_id"), "Some message");
+}
+
+#[async_test]
+async fn partial_load() {
+    let options = LocaleMapOptions::new();
+    options
+        .supported_locales(vec!["en-US", "pt-BR"])
+        .default_locale("en-US")
+        .fallbacks(maplit::hashmap!{"pt-BR" => vec!["en-US"]})
+        .assets(LocaleMapAssetOptions::new()
+            .src("tests/res")
+            .base_file_names(vec!["common", "checkout"])
+            .loader_type(LocaleMapLoaderType::FileSystem));
+    let mut locale_map = LocaleMap::new(&options);
+    assert!(!locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert!(locale_map.current_locale().is_none());
+
+    let report = locale_map.load_partial(Some(parse_locale("pt-BR").unwrap())).await;
+    assert!(report.loaded && !report.is_ok());
+    assert_eq!(report.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(), vec![
+        "tests/res/en-US/checkout.json: not found".to_string(),
+        "tests/res/pt-BR/checkout.json: not found".to_string(),
+    ]);
+    assert!(report.errors[1].locale == parse_locale("pt-BR").unwrap());
+    assert!(locale_map.current_locale() == Some(parse_locale("pt-BR").unwrap()));
+    assert_eq!(locale_map.get("common.message_id"), "Alguma mensagem");
+    assert_eq!(locale_map.get("checkout.pay"), "checkout.pay");
+}
+
+#[async_test]
+async fn asset_manifest() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    for locale in ["en-US", "pt-BR"] {
+        std::fs::create_dir_all(dir.join(locale)).unwrap();
+    }
+    std::fs::write(dir.join("en-US/checkout.json"), r#"{ "pay": "Pay" }"#).unwrap();
+    std::fs::write(dir.join("pt-BR/checkout.json"), r#"{ "pay": "Pagar" }"#).unwrap();
+    std::fs::write(dir.join("manifest.json"), r#"{ "locales": ["en-US"], "namespaces": ["checkout"], "versions": { "en-US": "1" } }"#).unwrap();
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "es"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    let changes = locale_map.discover_assets().await.unwrap();
+    assert!(changes.added_locales.is_empty() && changes.updated_locales.is_empty());
+    assert!(changes.removed_locales == vec![parse_locale("es").unwrap()]);
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.get("checkout.pay"), "Pay");
+    assert!(locale_map.discover_assets().await.unwrap().is_empty());
+
+    std::fs::write(dir.join("manifest.json"), r#"{ "locales": ["en-US", "pt-BR"], "namespaces": ["checkout"], "versions": { "en-US": "2", "pt-BR": "1" } }"#).unwrap();
+    let changes = locale_map.discover_assets().await.unwrap();
+    assert!(changes.added_locales == vec![parse_locale("pt-BR").unwrap()]);
+    assert!(changes.updated_locales == vec![parse_locale("en-US").unwrap()]);
+    assert_eq!(locale_map.asset_manifest().unwrap().versions["pt-BR"], "1");
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(locale_map.get("checkout.pay"), "Pagar");
+
+    std::fs::write(dir.join("manifest.json"), r#"{ "locales": ["pt-BR"] }"#).unwrap();
+    assert!(locale_map.discover_assets().await.is_err());
+}
+
+#[async_test]
+async fn catalog_delta() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    std::fs::create_dir_all(dir.join("en-US/delta")).unwrap();
+    std::fs::write(dir.join("manifest.json"), r#"{ "locales": ["en-US"], "namespaces": ["common"], "versions": { "en-US": "12" } }"#).unwrap();
+    std::fs::write(dir.join("en-US/common.json"), r#"{ "save": "Save", "discard": "Discard" }"#).unwrap();
+    std::fs::write(dir.join("en-US/delta/12.json"), r#"{ "version": "13", "changed": { "common.save": "Save changes", "common.close": "Close" }, "removed": ["common.discard"] }"#).unwrap();
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    let en_us = parse_locale("en-US").unwrap();
+    assert!(locale_map.apply_delta(&en_us).await.is_err());
+    locale_map.discover_assets().await.unwrap();
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.catalog_version(&en_us), Some("12".to_string()));
+    assert_eq!(locale_map.get("common.save"), "Save");
+
+    assert_eq!(locale_map.apply_delta(&en_us).await, Ok(3));
+    assert_eq!(locale_map.catalog_version(&en_us), Some("13".to_string()));
+    assert_eq!(locale_map.get("common.save"), "Save changes");
+    assert_eq!(locale_map.get("common.close"), "Close");
+    assert_eq!(locale_map.get("common.discard"), "common.discard");
+    assert!(locale_map.apply_delta(&en_us).await.is_err());
+
+    // A loaded locale no longer listed by the manifest
+    std::fs::create_dir_all(dir.join("pt-BR/delta")).unwrap();
+    std::fs::write(dir.join("manifest.json"), r#"{ "locales": ["en-US", "pt-BR"], "namespaces": ["common"], "versions": { "en-US": "13", "pt-BR": "1" } }"#).unwrap();
+    std::fs::write(dir.join("pt-BR/common.json"), r#"{ "save": "Salvar" }"#).unwrap();
+    std::fs::write(dir.join("pt-BR/delta/1.json"), r#"{ "version": "2", "changed": {}, "removed": [] }"#).unwrap();
+    let pt_br = parse_locale("pt-BR").unwrap();
+    locale_map.discover_assets().await.unwrap();
+    assert!(locale_map.load_secondary_locale(&pt_br).await);
+    std::fs::write(dir.join("manifest.json"), r#"{ "locales": ["en-US"], "namespaces": ["common"], "versions": { "en-US": "13" } }"#).unwrap();
+    locale_map.discover_assets().await.unwrap();
+    assert!(locale_map.apply_delta(&pt_br).await.is_err());
+}
+
+#[test]
+fn embedded_assets() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"common":{"greeting":"Hello"}}"#),
+        ("pt-BR", r#"{"common":{"greeting":"Olá"}}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "pt-BR"]).default_locale("pt-BR"),
+        ASSETS,
+    );
+    assert_eq!(locale_map.get("common.greeting"), "Olá");
+}
+
+#[test]
+fn named_counts() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"cart":{
+            "summary": "${items|plural} in ${files|plural:cart.files}",
+            "summary_items_one": "One item",
+            "summary_items_multiple": "$number items",
+            "files_one": "one file",
+            "files_multiple": "$number files",
+            "total_one": "One total of $items",
+            "total_multiple": "$number totals"
+        }}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"),
+        ASSETS,
+    );
+    assert_eq!(locale_map.get_formatted("cart.summary", vec![&Count::new("items", 3), &Count::new("files", 1)]), "3 items in one file");
+    assert_eq!(locale_map.get_formatted("cart.summary", vec![&Count::new("files", 2), &Count::new("items", 1)]), "One item in 2 files");
+    // The first number selects the quantity suffix regardless of its type.
+    assert_eq!(locale_map.get_formatted("cart.total", vec![&1u64, &5i64]), "One total of undefined");
+    assert_eq!(locale_map.get_formatted("cart.total", vec![&2.5f64, &1i32]), "2.5 totals");
+    assert_eq!(locale_map.get_formatted("cart.total", vec![&5i64, &1u64]), "5 totals");
+    assert_eq!(locale_map.get_formatted("cart.total", vec![&1, &Count::new("items", 4)]), "One total of 4");
+}
+
+#[test]
+fn formatted_parts() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"inbox":{
+            "greeting": "Welcome, $user!",
+            "summary": "$user has ${messages|plural} since $date",
+            "summary_messages_one": "one message",
+            "summary_messages_multiple": "$number messages",
+            "unread_one": "One unread",
+            "unread_multiple": "$number unread"
+        }}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"),
+        ASSETS,
+    );
+    let variable = |name: &str, value: &str| FormattedPart::Variable { name: name.to_string(), value: value.to_string() };
+    let number = |name: &str, value: &str| FormattedPart::Number { name: name.to_string(), value: value.to_string() };
+    let literal = |text: &str| FormattedPart::Literal(text.to_string());
+
+    assert_eq!(locale_map.get_formatted_parts("inbox.greeting", vec![&localization_vars!{"user" => "Ana"}]), vec![literal("Welcome, "), variable("user", "Ana"), literal("!")]);
+    assert_eq!(locale_map.get_formatted_parts("inbox.unread", vec![&1500]), vec![number("number", "1500"), literal(" unread")]);
+    assert_eq!(locale_map.get_formatted_parts("inbox.missing", vec![]), vec![literal("inbox.missing")]);
+
+    let date = chrono::DateTime::parse_from_rfc3339("2024-03-05T10:00:00+00:00").unwrap();
+    let values = maplit::hashmap!{
+        "user".to_string() => FormatValue::from("Ana"),
+        "date".to_string() => FormatValue::DateTime(date),
+    };
+    let parts = locale_map.get_formatted_parts("inbox.summary", vec![&values, &Count::new("messages", 3)]);
+    assert_eq!(parts[..4], [variable("user", "Ana"), literal(" has "), number("number", "3"), literal(" messages since ")]);
+    assert!(matches!(&parts[4], FormattedPart::DateTime { name, .. } if name == "date"));
+    assert_eq!(parts.iter().map(|part| part.value()).collect::<String>(), locale_map.get_formatted("inbox.summary", vec![&values, &Count::new("messages", 3)]));
+}
+
+#[test]
+fn attributed_messages() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"quote":{
+            "saying": "The French say ${phrase|lang:fr}",
+            "untranslated": "Only in English, $user"
+        }}"#),
+        ("pt-BR", r#"{"quote":{"saying": "Os franceses dizem ${phrase|lang:fr}"}}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "pt-BR"]).default_locale("pt-BR")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] }),
+        ASSETS,
+    );
+    let vars = localization_vars!{"phrase" => "c'est la vie", "user" => "Ana"};
+    let segment = |part: FormattedPart, language: &str| AttributedSegment { part, language: language.to_string(), machine_translated: false };
+
+    let attributed = locale_map.get_attributed("quote.saying", vec![&vars]);
+    assert_eq!(attributed.language, "pt-BR");
+    assert_eq!(attributed.segments, vec![
+        segment(FormattedPart::Literal("Os franceses dizem ".to_string()), "pt-BR"),
+        segment(FormattedPart::Variable { name: "phrase".to_string(), value: "c'est la vie".to_string() }, "fr"),
+    ]);
+    assert_eq!(attributed.text(), locale_map.get_formatted("quote.saying", vec![&vars]));
+
+    // Text from a fallback locale is in the language of that locale.
+    let attributed = locale_map.get_attributed("quote.untranslated", vec![&vars]);
+    assert_eq!(attributed.language, "en-US");
+    assert!(attributed.segments.iter().all(|segment| segment.language == "en-US"));
+    assert_eq!(attributed.to_ssml(), r#"<speak xml:lang="en-US">Only in English, Ana</speak>"#);
+
+    // A registered `lang` formatter applies instead of marking the language.
+    locale_map.register_formatter("lang", |s, _, language| format!("{} ({})", s, language.unwrap_or_default()));
+    let attributed = locale_map.get_attributed("quote.saying", vec![&vars]);
+    assert_eq!(attributed.text(), "Os franceses dizem c'est la vie (fr)");
+    assert!(attributed.segments.iter().all(|segment| segment.language == "pt-BR"));
+}
+
+#[async_test]
+async fn missing_translation_provider() {
+    use std::{future::Future, pin::Pin};
+
+    // Fails to translate "Help" the first time, such as a machine translation API timing out.
+    struct Glossary {
+        help_requests: std::cell::Cell<usize>,
+    }
+
+    impl MissingTranslationProvider for Glossary {
+        fn translate<'a>(&'a self, request: &'a MissingTranslation) -> Pin<Box<dyn Future<Output = Option<ProvidedTranslation>> + 'a>> {
+            Box::pin(async move {
+                match (request.locale.standard_tag().to_string().as_str(), request.source.as_deref()) {
+                    ("pt-BR", Some("Close")) => Some(ProvidedTranslation::new("Fechar", false)),
+                    ("pt-BR", Some("Hello, $name")) => Some(ProvidedTranslation::new("Olá, $name", true)),
+                    ("pt-BR", Some("Help")) => {
+                        self.help_requests.set(self.help_requests.get() + 1);
+                        Some(ProvidedTranslation::new("Ajuda", true)).filter(|_| self.help_requests.get() > 1)
+                    },
+                    _ => None,
+                }
+            })
+        }
+    }
+
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"menu":{"save": "Save", "close": "Close", "greeting": "Hello, $name", "help": "Help"}}"#),
+        ("pt-BR", r#"{"menu":{"save": "Salvar"}}"#),
+    ];
+    let mut locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "pt-BR"]).default_locale("en-US")
+            .missing_translation_provider(Glossary { help_requests: std::cell::Cell::new(0) }),
+        ASSETS,
+    );
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(locale_map.get("menu.close"), "menu.close");
+    assert_eq!(locale_map.get_formatted("menu.greeting", vec![&localization_vars!{"name" => "Ana"}]), "menu.greeting");
+    assert_eq!(locale_map.get("menu.help"), "menu.help");
+    assert_eq!(locale_map.provide_missing_translations().await, 2);
+
+    assert_eq!(locale_map.get("menu.save"), "Salvar");
+    assert_eq!(locale_map.get("menu.close"), "Fechar");
+    assert!(locale_map.has_key("menu.close"));
+    assert!(!locale_map.is_machine_translated("menu.close"));
+    assert!(locale_map.is_machine_translated("menu.greeting"));
+    let attributed = locale_map.get_attributed("menu.greeting", vec![&localization_vars!{"name" => "Ana"}]);
+    assert_eq!(attributed.text(), "Olá, Ana");
+    assert!(attributed.segments[0].machine_translated);
+    assert!(!attributed.segments[1].machine_translated);
+
+    // Messages without a translation are requested again once looked up again.
+    assert_eq!(locale_map.provide_missing_translations().await, 0);
+    assert_eq!(locale_map.get("menu.help"), "menu.help");
+    assert_eq!(locale_map.provide_missing_translations().await, 1);
+    assert_eq!(locale_map.get("menu.help"), "Ajuda");
+}
+
+#[test]
+fn key_reveal() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"common":{"qty_one": "One item", "qty_multiple": "$number items", "greeting": "Hello, $name"}}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"),
+        ASSETS,
+    );
+    assert_eq!(locale_map.key_reveal(), None);
+    locale_map.set_key_reveal(Some(KeyReveal::Prefix));
+    assert_eq!(locale_map.get_formatted("common.qty", vec![&3]), "[common.qty] 3 items");
+    assert_eq!(locale_map.get_formatted_parts("common.greeting", vec![&localization_vars!{"name" => "Ana"}]), vec![
+        FormattedPart::Literal("[common.greeting] Hello, ".to_string()),
+        FormattedPart::Variable { name: "name".to_string(), value: "Ana".to_string() },
+    ]);
+    assert_eq!(locale_map.get("common.missing"), "common.missing");
+
+    locale_map.set_key_reveal(Some(KeyReveal::Replace));
+    assert_eq!(locale_map.get_formatted("common.qty", vec![&1]), "common.qty");
+    locale_map.set_key_reveal(None);
+    assert_eq!(locale_map.get_formatted("common.qty", vec![&1]), "One item");
+}
+
+#[test]
+fn lookup_recording() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"cart":{"qty_one": "One item", "qty_multiple": "$number items", "title": "Cart"}}"#),
+        ("pt-BR", r#"{"cart":{"title": "Carrinho"}}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "pt-BR"]).default_locale("pt-BR")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] }),
+        ASSETS,
+    );
+    locale_map.get("cart.title");
+    locale_map.start_lookup_recording();
+    locale_map.get("cart.title");
+    locale_map.get("cart.title");
+    locale_map.get_formatted("cart.qty", vec![&2]);
+    locale_map.get_formatted_parts("cart.qty", vec![&1]);
+    locale_map.get("cart.missing");
+    let record = |id: &str, locale: Option<&str>, text: &str| LookupRecord { id: id.to_string(), locale: locale.map(|l| l.to_string()), text: text.to_string() };
+    assert_eq!(locale_map.recorded_lookups(), vec![
+        record("cart.title", Some("pt-BR"), "Carrinho"),
+        record("cart.qty_multiple", Some("en-US"), "2 items"),
+        record("cart.qty_one", Some("en-US"), "One item"),
+        record("cart.missing", None, "cart.missing"),
+    ]);
+    let records = locale_map.stop_lookup_recording();
+    assert!(lookup_records_to_json(&records).starts_with(r#"[{"id":"cart.title","locale":"pt-BR","text":"Carrinho"},"#));
+    locale_map.get("cart.title");
+    assert!(locale_map.recorded_lookups().is_empty());
+}
+
+#[test]
+fn zero_and_negative_counts() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"account":{
+            "balance_zero": "$number dollars",
+            "balance_one": "$number dollar",
+            "balance_multiple": "$number dollars",
+            "balance_negative": "Overdrawn by $number dollars",
+            "stock_empty": "Out of stock",
+            "stock_one": "One left",
+            "stock_multiple": "$number left",
+            "summary": "${items|plural}",
+            "summary_items_negative": "$number returned",
+            "summary_items_multiple": "$number items"
+        }}"#),
+        ("lv", r#"{"account":{
+            "days_zero": "$number dienu",
+            "days_one": "$number diena",
+            "days_multiple": "$number dienas"
+        }}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "lv"]).default_locale("en-US"),
+        ASSETS,
+    );
+    assert_eq!(locale_map.get_formatted("account.balance", vec![&0]), "0 dollars");
+    assert_eq!(locale_map.get_formatted("account.balance", vec![&-5]), "Overdrawn by -5 dollars");
+    assert_eq!(locale_map.get_formatted("account.balance", vec![&-0.0001f64]), "-0.0001 dollars");
+    // Without the variants, zero selects `_empty` and negative numbers select `_multiple`.
+    assert_eq!(locale_map.get_formatted("account.stock", vec![&0]), "Out of stock");
+    assert_eq!(locale_map.get_formatted("account.stock", vec![&-1]), "-1 left");
+    assert_eq!(locale_map.get_formatted("account.summary", vec![&Count::new("items", -2)]), "-2 returned");
+    assert_eq!(locale_map.quantity_variant("account.balance", -1), "account.balance_negative");
+    assert_eq!(locale_map.quantity_variant("account.stock", 0), "account.stock_empty");
+
+    // Latvian has numbers such as 10 in the `zero` plural category.
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "lv"]).default_locale("lv"),
+        ASSETS,
+    );
+    assert_eq!(locale_map.get_formatted("account.days", vec![&10]), "10 dienu");
+    assert_eq!(locale_map.get_formatted("account.days", vec![&0]), "0 dienu");
+    assert_eq!(locale_map.get_formatted("account.days", vec![&1]), "1 diena");
+    assert_eq!(locale_map.get_formatted("account.days", vec![&3]), "3 dienas");
+}
+
+#[test]
+fn plural_operands_of_formatted_numbers() {
+    let en = parse_locale("en").unwrap();
+    let rounded = NumberFormatter::new(&en, NumberFormatOptions::new().maximum_fraction_digits(2));
+    let padded = NumberFormatter::new(&en, NumberFormatOptions::new().minimum_fraction_digits(1));
+    let operands = padded.plural_operands(1.0);
+    assert_eq!((operands.i, operands.v, operands.f), (1, 1, 0));
+    let operands = rounded.plural_operands(2.499);
+    assert_eq!((operands.i, operands.v, operands.f), (2, 1, 5));
+
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"rating":{"stars_empty": "No stars", "stars_one": "One star", "stars_multiple": "$number stars"}}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"),
+        ASSETS,
+    );
+    assert_eq!(locale_map.select_plural_rule(PluralRuleType::CARDINAL, padded.plural_operands(1.0)), Ok(PluralCategory::OTHER));
+    assert_eq!(locale_map.select_plural_rule(PluralRuleType::CARDINAL, rounded.plural_operands(1.001)), Ok(PluralCategory::ONE));
+    // Float counts select the quantity suffix of the number as formatted by the locale map.
+    assert_eq!(locale_map.get_formatted("rating.stars", vec![&1.0f64]), "One star");
+    assert_eq!(locale_map.get_formatted("rating.stars", vec![&0.0001f64]), "No stars");
+    assert_eq!(locale_map.get_formatted("rating.stars", vec![&4.5f64]), "4.5 stars");
+}
+
+#[test]
+fn gender_of_arguments() {
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"gift":{
+            "sent": "${sender|gender} sent ${recipient|gender:gift.object} a gift",
+            "sent_sender_male": "$sender",
+            "sent_sender_female": "She",
+            "object_male": "him",
+            "object_female": "her",
+            "object_other": "them"
+        }}"#),
+    ];
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"),
+        ASSETS,
+    );
+    assert_eq!(locale_map.get_formatted("gift.sent", vec![&GenderOf("sender", Gender::Female), &GenderOf("recipient", Gender::Male)]), "She sent him a gift");
+    assert_eq!(locale_map.get_formatted("gift.sent", vec![&GenderOf("sender", Gender::Male), &GenderOf("recipient", Gender::Other), &localization_vars!{"sender" => "Bob"}]), "Bob sent them a gift");
+    // Without a message for the gender, the variable is replaced by its value, if any.
+    assert_eq!(locale_map.get_formatted("gift.sent", vec![&GenderOf("sender", Gender::Other), &GenderOf("recipient", Gender::Female)]), "undefined sent her a gift");
+    assert_eq!(locale_map.get_formatted("gift.sent", vec![&GenderOf("sender", Gender::Other), &GenderOf("recipient", Gender::Female), &localization_vars!{"sender" => "Sam"}]), "Sam sent her a gift");
+}
+
+#[async_test]
+async fn options_from_config() {
+    let options = LocaleMapOptions::from_json(r#"{
+        "supported_locales": ["en-US", "pt-BR"],
+        "fallbacks": { "pt-BR": ["en-US"] },
+        "assets": { "src": "tests/res", "base_file_names": ["common"], "loader_type": "file_system" }
+    }"#).unwrap();
+    let mut locale_map = LocaleMap::new(&options);
+    assert!(locale_map.load(parse_locale("pt-BR").ok()).await);
+    assert_eq!(locale_map.get("common.message_id"), "Alguma mensagem");
+    assert_eq!(locale_map.get("common.save"), "Save");
+
+    assert_eq!(LocaleMapOptions::from_json(r#"{"supported_locales": ["en-US"], "default_locale": "pt-BR"}"#).err(), Some("default_locale: \"pt-BR\" is not among the supported locales".to_string()));
+    assert_eq!(LocaleMapOptions::from_json(r#"{"assets": {"loader_type": "ftp"}}"#).err(), Some("assets.loader_type: expected \"http\" or \"file_system\", found \"ftp\"".to_string()));
+    assert!(LocaleMapOptions::from_json(r#"{"supported_locale": ["en-US"]}"#).err().unwrap().starts_with("unknown field `supported_locale`"));
+
+    std::env::set_var("OPTIONS_FROM_CONFIG_SUPPORTED_LOCALES", "en-US, pt-BR");
+    std::env::set_var("OPTIONS_FROM_CONFIG_DEFAULT_LOCALE", "pt-BR");
+    std::env::set_var("OPTIONS_FROM_CONFIG_ASSETS_SRC", "tests/res");
+    std::env::set_var("OPTIONS_FROM_CONFIG_ASSETS_BASE_FILE_NAMES", "common");
+    std::env::set_var("OPTIONS_FROM_CONFIG_ASSETS_LOADER_TYPE", "file_system");
+    let mut locale_map = LocaleMap::new(&LocaleMapOptions::from_env("OPTIONS_FROM_CONFIG").unwrap());
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.get("common.message_id"), "Alguma mensagem");
+    std::env::set_var("OPTIONS_FROM_CONFIG_FALLBACKS", "pt-BR=es");
+    assert_eq!(LocaleMapOptions::from_env("OPTIONS_FROM_CONFIG").err(), Some("OPTIONS_FROM_CONFIG_FALLBACKS: \"es\" is not among the supported locales".to_string()));
+
+    #[cfg(feature = "toml")]
+    {
+        let options = LocaleMapOptions::from_toml(r#"
+            supported_locales = ["en-US", "pt-BR"]
+            default_locale = "pt-BR"
+            [assets]
+            src = "tests/res"
+            base_file_names = ["common"]
+            loader_type = "file_system"
+        "#).unwrap();
+        let mut locale_map = LocaleMap::new(&options);
+        assert!(locale_map.load(None).await);
+        assert_eq!(locale_map.get("common.message_id"), "Alguma mensagem");
+        assert!(LocaleMapOptions::from_toml("supported_locales = \"en-US\"").is_err());
+    }
+}
+
+#[async_test]
+async fn lookup_metrics() {
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Default)]
+    struct Counters {
+        lookups: usize,
+        cache_hits: usize,
+        fallbacks: Vec<(String, usize)>,
+        missing: Vec<String>,
+    }
+
+    struct Sink(Rc<RefCell<Counters>>);
+    impl MetricsSink for Sink {
+        fn lookup(&self, _locale: &Locale, _id: &str) { self.0.borrow_mut().lookups += 1; }
+        fn cache_hit(&self, _locale: &Locale, _id: &str) { self.0.borrow_mut().cache_hits += 1; }
+        fn fallback(&self, _locale: &Locale, id: &str, depth: usize) { self.0.borrow_mut().fallbacks.push((id.to_string(), depth)); }
+        fn missing(&self, locale: &Locale, id: &str) { self.0.borrow_mut().missing.push(format!("{} {}", locale.standard_tag(), id)); }
+    }
+
+    let counters = Rc::new(RefCell::new(Counters::default()));
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap!{"pt-BR" => vec!["en-US"]})
+            .metrics(Sink(counters.clone()))
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    locale_map.get("common.message_id");
+    locale_map.get("common.message_id");
+    locale_map.get("common.contextual_male");
+    locale_map.get("common.nope");
+    assert!(!locale_map.has_key("common.nope"));
+
+    {
+        let counters = counters.borrow();
+        assert_eq!(counters.lookups, 4);
+        assert_eq!(counters.cache_hits, 1);
+        assert_eq!(counters.fallbacks, vec![("common.contextual_male".to_string(), 1)]);
+        assert_eq!(counters.missing, vec!["pt-BR common.nope".to_string()]);
+    }
+
+    // Missing messages are evicted from the cache beyond a limit, unlike the others.
+    let cache_hits = || counters.borrow().cache_hits;
+    locale_map.get("common.nope");
+    assert_eq!(cache_hits(), 2);
+    for i in 0..1024 {
+        locale_map.get(format!("common.nope_{}", i));
+    }
+    locale_map.get("common.nope");
+    assert_eq!(cache_hits(), 2);
+    locale_map.get("common.message_id");
+    assert_eq!(cache_hits(), 3);
+}
+
+#[async_test]
+async fn runtime_messages() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    let pt_br = parse_locale("pt-BR").unwrap();
+    assert_eq!(locale_map.get("common.save"), "Save");
+    locale_map.insert_message(&pt_br, "common.save", "Salvar");
+    locale_map.insert_message(&pt_br, "common.message_id", "Outra mensagem");
+    assert_eq!(locale_map.get("common.save"), "Salvar");
+    assert_eq!(locale_map.get("common.message_id"), "Outra mensagem");
+
+    assert!(locale_map.remove_message(&pt_br, "common.save"));
+    assert!(!locale_map.remove_message(&pt_br, "common.save"));
+    assert_eq!(locale_map.get("common.save"), "Save");
+
+    assert!(locale_map.load(Some(pt_br.clone())).await);
+    assert_eq!(locale_map.get("common.message_id"), "Alguma mensagem");
+}
+
+#[async_test]
+async fn experiment_variants() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    let (en_us, pt_br) = (parse_locale("en-US").unwrap(), parse_locale("pt-BR").unwrap());
+    locale_map.insert_variant(&en_us, "common.save", "B", "Keep");
+    locale_map.insert_variant(&en_us, "common.message_id", "B", "Another message");
+    locale_map.insert_variant(&pt_br, "common.parameterized", "B", "Aqui (B): $x");
+    locale_map.insert_variant(&en_us, "common.qty_one", "B", "Just one");
+    locale_map.set_variant_selector(|id| if id.starts_with("common.") { Some("B".to_string()) } else { None });
+
+    assert_eq!(locale_map.get("common.save"), "Keep");
+    assert_eq!(locale_map.get("common.message_id"), "Alguma mensagem");
+    assert_eq!(locale_map.get_formatted("common.parameterized", vec![ &localization_vars!{"x" => "a"} ]), "Aqui (B): a");
+    assert_eq!(locale_map.get_formatted("common.qty", vec![ &1 ]), "Just one");
+    assert_eq!(locale_map.get_formatted("common.qty", vec![ &2 ]), "Multiple (2)");
+
+    locale_map.clear_variant_selector();
+    assert_eq!(locale_map.get("common.save"), "Save");
+}
+
+#[async_test]
+async fn overlays() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .overlays(vec!["tenant-a", "tenant-b"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(locale_map.get("common.save"), "Save");
+
+    let tenant_a = locale_map.with_overlay("tenant-a");
+    assert_eq!(tenant_a.overlay(), Some("tenant-a".to_string()));
+    assert_eq!(tenant_a.get("common.save"), "Store");
+    assert_eq!(tenant_a.get("common.message_id"), "Alguma mensagem");
+    assert_eq!(tenant_a.get_formatted("common.parameterized", vec![ &localization_vars!{"x" => "a"} ]), "Aqui, cliente: a");
+    assert_eq!(locale_map.get_formatted("common.parameterized", vec![ &localization_vars!{"x" => "a"} ]), "Aqui: a");
+    assert_eq!(locale_map.with_overlay("tenant-b").get("common.save"), "Save");
+}
+
+#[cfg(feature = "gui")]
+#[async_test]
+async fn gui_handle() {
+    use recoyx_localization::{tr, gui::LocalizationHandle};
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(None).await);
+    assert_eq!(tr!("common.message_id"), "common.message_id");
+
+    let handle = LocalizationHandle::new(locale_map);
+    handle.install();
+    let changes = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let changes_clone = changes.clone();
+    handle.on_change(move |locale| changes_clone.borrow_mut().push(locale.standard_tag().to_string()));
+    assert_eq!(tr!("common.message_id"), "Some message");
+    assert_eq!(tr!("common.parameterized", localization_vars!{"x" => "a"}), "Here: a");
+
+    assert!(handle.update_locale(parse_locale("pt-BR").unwrap()).await);
+    assert_eq!(handle.generation(), 1);
+    assert_eq!(*changes.borrow(), vec!["pt-BR".to_string()]);
+    assert_eq!(tr!("common.message_id"), "Alguma mensagem");
+    assert_eq!(LocalizationHandle::current().unwrap().with(|m| m.len_for(&parse_locale("pt-BR").unwrap())), 2);
+}
+
+#[async_test]
+async fn current_localization() {
+    let catalog = SharedCatalog::load(|| LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    )).await.unwrap();
+    let pt = parse_locale("pt-BR").unwrap();
+    assert!(Localization::current().is_none());
+    assert_eq!(catalog.get("common.message_id"), "Some message");
+    assert_eq!(catalog.with_locale(&pt, || catalog.get("common.message_id")), "Alguma mensagem");
+    let nested = catalog.with_locale(&pt, || catalog.with_locale(&parse_locale("en-US").unwrap(), || catalog.get("common.message_id")));
+    assert_eq!(nested, "Some message");
+    assert!(Localization::current().is_none());
+
+    let localization = catalog.negotiate(&[pt]);
+    let task_catalog = catalog.clone();
+    let r = localization.scope(async move {
+        std::future::ready(()).await;
+        task_catalog.get("common.message_id")
+    }).await;
+    assert_eq!(r, "Alguma mensagem");
+    assert_eq!(catalog.get("common.message_id"), "Some message");
+
+    // The LocaleMap of the thread is reused, and can be used within itself.
+    let localization = catalog.negotiate(&[parse_locale("pt-BR").unwrap()]);
+    let first = localization.with(|locale_map| locale_map as *const LocaleMap);
+    assert!(localization.with(|locale_map| locale_map as *const LocaleMap) == first);
+    assert_eq!(localization.with(|locale_map| localization.get("common.save") + &locale_map.get("common.message_id")), "SaveAlguma mensagem");
+}
+
+#[cfg(feature = "axum")]
+#[async_test]
+async fn axum_layer() {
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use recoyx_localization::web::{parse_accept_language, Localization, LocalizationLayer};
+    use tower::ServiceExt;
+
+    assert_eq!(parse_accept_language("en;q=0.5, pt-BR, *;q=0.1, fr;q=0"), vec!["pt-BR", "en"]);
+
+    let layer = LocalizationLayer::load(|| LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    )).await.unwrap().cookie("lang").query_parameter("lang");
+    let app = Router::new()
+        .route("/", get(|t: Localization| async move {
+            format!("{} {} {}", t.locale().standard_tag(), t.get("common.message_id"), t.get("common.save"))
+        }))
+        .layer(layer);
+
+    let body = |request: Request<Body>| {
+        let app = app.clone();
+        async move {
+            let response = app.oneshot(request).await.unwrap();
+            String::from_utf8(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+        }
+    };
+    assert_eq!(body(Request::get("/").body(Body::empty()).unwrap()).await, "en-US Some message Save");
+    assert_eq!(body(Request::get("/").header("accept-language", "fr, pt;q=0.8").body(Body::empty()).unwrap()).await, "pt-BR Alguma mensagem Save");
+    assert_eq!(body(Request::get("/").header("accept-language", "pt-BR").header("cookie", "a=b; lang=en-US").body(Body::empty()).unwrap()).await, "en-US Some message Save");
+    assert_eq!(body(Request::get("/?x=1&lang=pt-BR").header("cookie", "lang=en-US").body(Body::empty()).unwrap()).await, "pt-BR Alguma mensagem Save");
+}
+
+#[cfg(any(feature = "tera", feature = "handlebars", feature = "askama"))]
+async fn test_catalog() -> SharedCatalog {
+    SharedCatalog::load(|| LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    )).await.unwrap()
+}
+
+#[cfg(feature = "tera")]
+#[async_test]
+async fn tera_function() {
+    let mut tera = tera::Tera::default();
+    tera.register_function("t", recoyx_localization::templates::tera_function(test_catalog().await));
+    tera.add_raw_template("page.html", r#"{{ t(key="common.parameterized", lang=lang, x="<b>") }} {{ t(key="common.qty", count=2) }}"#).unwrap();
+    let mut context = tera::Context::new();
+    context.insert("lang", "pt-BR");
+    assert_eq!(tera.render("page.html", &context).unwrap(), "Aqui: &lt;b&gt; Multiple (2)");
+}
+
+#[cfg(feature = "handlebars")]
+#[async_test]
+async fn handlebars_helper() {
+    let mut handlebars = handlebars::Handlebars::new();
+    handlebars.register_helper("t", Box::new(recoyx_localization::templates::HandlebarsHelper::new(test_catalog().await)));
+    handlebars.register_template_string("page", r#"{{t "common.parameterized" x="<b>"}} {{t "common.message_id" lang="en-US"}}"#).unwrap();
+    assert_eq!(handlebars.render("page", &serde_json::json!({ "lang": "pt-BR" })).unwrap(), "Aqui: &lt;b&gt; Some message");
+}
+
+#[cfg(feature = "askama")]
+#[async_test]
+async fn askama_filter() {
+    use askama::Template;
+
+    mod filters {
+        pub use recoyx_localization::templates::{t, t_args};
+    }
+
+    #[derive(Template)]
+    #[template(source = r#"{{ "common.message_id"|t(loc) }} {{ "common.parameterized"|t_args(loc, (("x", x.as_str()),)) }} {{ "common.qty"|t_args(loc, (("count", count),)) }} {{ "common.parameterized"|t_args(loc, vars) }}"#, ext = "txt")]
+    struct Page {
+        loc: Localization,
+        x: String,
+        count: i64,
+        vars: std::collections::HashMap<String, String>,
+    }
+
+    let loc = test_catalog().await.negotiate(&[parse_locale("pt").unwrap()]);
+    let vars = maplit::hashmap! { "x".to_string() => "y".to_string() };
+    assert_eq!(Page { loc, x: "b".into(), count: 2, vars }.render().unwrap(), "Alguma mensagem Aqui: b Multiple (2) Aqui: y");
+}
+
+/// Serves files over HTTP from a thread, returning the base URL.
+#[cfg(any(feature = "integrity", feature = "live-updates"))]
+fn serve_files(files: Vec<(&'static str, Vec<u8>)>) -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request_line = String::new();
+            BufReader::new(&stream).read_line(&mut request_line).unwrap();
+            let path = request_line.split(' ').nth(1).unwrap_or("").trim_start_matches('/').to_string();
+            match files.iter().find(|(p, _)| *p == path) {
+                Some((_, body)) => {
+                    write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len()).unwrap();
+                    stream.write_all(body).unwrap();
+                },
+                None => write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap(),
+            }
+        }
+    });
+    url
+}
+
+#[cfg(feature = "integrity")]
+#[tokio::test]
+async fn asset_integrity() {
+    use ed25519_dalek::{Signer, SigningKey};
+    use sha2::{Digest, Sha256};
+
+    let common = br#"{ "greeting": "Hello" }"#.to_vec();
+    let checksum: String = Sha256::digest(&common).iter().map(|b| format!("{:02x}", b)).collect();
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+    let signature = signing_key.sign(&AssetIntegrity::signed_message("en-US/common.json", None, &common)).to_bytes().to_vec();
+    let url = serve_files(vec![
+        ("en-US/common.json", common.clone()),
+        ("en-US/common.json.sig", signature.clone()),
+        // A file signed for another path.
+        ("pt-BR/common.json", common.clone()),
+        ("pt-BR/common.json.sig", signature),
+    ]);
+    let load_locale = |integrity: AssetIntegrity, tag: &'static str| {
+        let locale_map = LocaleMap::new(
+            LocaleMapOptions::new()
+                .supported_locales(vec!["en-US", "pt-BR"])
+                .default_locale(tag)
+                .assets(LocaleMapAssetOptions::new()
+                    .src(&url)
+                    .base_file_names(vec!["common"])
+                    .integrity(integrity)
+                    .loader_type(LocaleMapLoaderType::Http))
+        ); // locale_map
+        async move {
+            let mut locale_map = locale_map;
+            let report = locale_map.load_partial(None).await;
+            (locale_map.get("common.greeting"), report.errors.iter().map(|e| e.kind.to_string()).collect::<Vec<_>>())
+        }
+    };
+    let load = |integrity: AssetIntegrity| load_locale(integrity, "en-US");
+
+    let manifest = format!(r#"{{ "en-US/common.json": "{}" }}"#, checksum.to_uppercase());
+    assert_eq!(load(AssetIntegrity::sha256_manifest(&manifest).unwrap()).await, ("Hello".to_string(), vec![]));
+    let manifest = format!(r#"{{ "en-US/common.json": "{}" }}"#, "0".repeat(64));
+    assert_eq!(load(AssetIntegrity::sha256_manifest(&manifest).unwrap()).await, ("common.greeting".to_string(), vec!["untrusted: checksum mismatch".to_string()]));
+    assert_eq!(load(AssetIntegrity::sha256_manifest("{}").unwrap()).await.1, vec!["untrusted: no checksum in the manifest".to_string()]);
+
+    assert_eq!(load(AssetIntegrity::Ed25519(signing_key.verifying_key().to_bytes())).await, ("Hello".to_string(), vec![]));
+    let other_key = SigningKey::from_bytes(&[8; 32]).verifying_key().to_bytes();
+    assert_eq!(load(AssetIntegrity::Ed25519(other_key)).await.1, vec!["untrusted: invalid signature".to_string()]);
+    assert_eq!(load_locale(AssetIntegrity::Ed25519(signing_key.verifying_key().to_bytes()), "pt-BR").await.1, vec!["untrusted: invalid signature".to_string()]);
+}
+
+#[cfg(feature = "live-updates")]
+#[tokio::test]
+async fn live_updates() {
+    use recoyx_localization::live_updates::{LiveUpdateClient, LiveUpdateTransport, MessageUpdate};
+
+    let url = serve_files(vec![
+        ("updates", br#"{"cursor": "1", "updates": [{"locale": "pt-BR", "id": "menu.save", "message": "Gravar"}, {"locale": "en-US", "id": "menu.save", "message": "Save now"}]}"#.to_vec()),
+        ("updates?cursor=1", br#"{"cursor": "2", "updates": [{"locale": "pt-BR", "id": "menu.save", "message": null}]}"#.to_vec()),
+        ("events", b": connected\n\nid: 7\ndata: {\"locale\": \"pt-BR\", \"id\": \"menu.open\",\ndata: \"message\": \"Abrir\"}\n\n".to_vec()),
+        ("large-event", [b"data: ".to_vec(), vec![b'x'; 2 << 20]].concat()),
+    ]);
+    const ASSETS: &[(&str, &str)] = &[
+        ("en-US", r#"{"menu":{"save": "Save", "open": "Open"}}"#),
+        ("pt-BR", r#"{"menu":{"save": "Salvar"}}"#),
+    ];
+    let mut locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "pt-BR"]).default_locale("pt-BR")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] }),
+        ASSETS,
+    );
+    let applied = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let mut client = LiveUpdateClient::new(format!("{}/updates", url), LiveUpdateTransport::LongPolling);
+    let listener_applied = applied.clone();
+    client.on_update(move |update| listener_applied.borrow_mut().push(update.id.clone()));
+    assert_eq!(client.poll(&mut locale_map).await, Ok(2));
+    assert_eq!(client.cursor(), Some("1"));
+    assert_eq!(locale_map.get("menu.save"), "Gravar");
+    assert_eq!(client.poll(&mut locale_map).await, Ok(1));
+    assert_eq!(locale_map.get("menu.save"), "Save now");
+    assert_eq!(*applied.borrow(), vec!["menu.save"; 3]);
+
+    // Updates are reapplied after reloading, which discards them.
+    locale_map.load(Some(parse_locale("pt-BR").unwrap())).await;
+    assert_eq!(locale_map.get("menu.save"), "Salvar");
+    client.reapply(&mut locale_map);
+    assert_eq!(locale_map.get("menu.save"), "Save now");
+
+    let mut client = LiveUpdateClient::new(format!("{}/events", url), LiveUpdateTransport::ServerSentEvents);
+    assert_eq!(client.next_updates().await, Ok(vec![MessageUpdate { locale: "pt-BR".to_string(), id: "menu.open".to_string(), message: Some("Abrir".to_string()) }]));
+    assert_eq!(client.cursor(), Some("7"));
+    assert_eq!(client.next_updates().await, Ok(vec![]));
+
+    let mut client = LiveUpdateClient::new(format!("{}/large-event", url), LiveUpdateTransport::ServerSentEvents);
+    assert_eq!(client.next_updates().await, Err("live updates: event exceeds 1048576 bytes".to_string()));
+
+    // An event is decoded once complete, even if a chunk ends within a character.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let chunked_url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        use std::io::{BufRead, BufReader, Write};
+
+        let event = "id: 8\r\ndata: {\"locale\": \"pt-BR\", \"id\": \"menu.open\", \"message\": \"Não\"}\r\n\r\n".as_bytes();
+        let split = event.iter().position(|b| *b == 0xC3).unwrap() + 1;
+        let mut stream = listener.incoming().next().unwrap().unwrap();
+        BufReader::new(&stream).read_line(&mut String::new()).unwrap();
+        write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", event.len()).unwrap();
+        stream.write_all(&event[..split]).unwrap();
+        stream.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        stream.write_all(&event[split..]).unwrap();
+    });
+    let mut client = LiveUpdateClient::new(chunked_url, LiveUpdateTransport::ServerSentEvents);
+    assert_eq!(client.next_updates().await, Ok(vec![MessageUpdate { locale: "pt-BR".to_string(), id: "menu.open".to_string(), message: Some("Não".to_string()) }]));
+    assert_eq!(client.cursor(), Some("8"));
+}
+
+#[cfg(feature = "compression")]
+#[async_test]
+async fn compressed_assets() {
+    let options = LocaleMapOptions::new();
+    options
+        .supported_locales(vec!["en-US", "pt-BR"])
+        .default_locale("en-US")
+        .fallbacks(maplit::hashmap!{"pt-BR" => vec!["en-US"]})
+        .assets(LocaleMapAssetOptions::new()
+            .src("tests/res-compressed")
+            .base_file_names(vec!["common"])
+            .compress_in_memory(true)
+            .loader_type(LocaleMapLoaderType::FileSystem));
+    let mut locale_map = LocaleMap::new(&options);
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(locale_map.get("common.greeting"), "Olá");
+    assert_eq!(locale_map.get("common.farewell"), "Goodbye");
+
+    let en_us = parse_locale("en-US").unwrap();
+    assert_eq!(locale_map.len_for(&en_us), 2);
+    locale_map.insert_message(&en_us, "common.greeting", "Hi");
+    assert_eq!(locale_map.messages_for(&en_us).unwrap().iter().collect::<Vec<_>>(), vec![("common.farewell", "Goodbye"), ("common.greeting", "Hi")]);
+}
+
+#[cfg(feature = "build")]
+#[test]
+fn build_generate() {
+    let options = LocaleMapOptions::new();
+    options
+        .supported_locales(vec!["en-US", "pt-BR"])
+        .default_locale("en-US")
+        .assets(LocaleMapAssetOptions::new()
+            .src("tests/res")
+            .base_file_names(vec!["common"]));
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output = temp_dir.path().join("generate.rs");
+    build::generate(&options, &output).unwrap();
+    let code = std::fs::read_to_string(&output).unwrap();
+    assert!(code.contains("    (\"pt-BR\", \"{\\\"common\\\":{"));
+    assert!(code.contains("            Key::CommonMessageId => \"common.message_id\",\n"));
+    assert!(code.contains("            Key::CommonQty => \"common.qty\",\n"));
+    assert!(code.contains("            Key::CommonQtyOne => \"common.qty_one\",\n"));
+    assert!(code.contains("        pub fn parameterized(__locale_map: &::recoyx_localization::LocaleMap, x: &str) -> ::std::string::String {\n"));
+    assert!(code.contains("        pub fn qty(__locale_map: &::recoyx_localization::LocaleMap, __count: i64) -> ::std::string::String {\n"));
+    assert!(code.contains("        pub fn contextual(__locale_map: &::recoyx_localization::LocaleMap, __gender: ::recoyx_localization::Gender) -> ::std::string::String {\n"));
+
+    options.assets(LocaleMapAssetOptions::new()
+        .src("tests/res-validation")
+        .base_file_names(vec!["common"]));
+    assert_eq!(build::generate(&options, &output), Err("placeholders of common.greeting: -$name +$nam in pt-BR".to_string()));
+}
+
+#[cfg(feature = "build")]
+#[test]
+fn build_generate_identifiers() {
+    let options = LocaleMapOptions::new();
+    options
+        .supported_locales(vec!["en-US"])
+        .default_locale("en-US")
+        .assets(LocaleMapAssetOptions::new()
+            .src("tests/res-build")
+            .base_file_names(vec!["common"]));
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output = temp_dir.path().join("generate.rs");
+    build::generate(&options, &output).unwrap();
+    std::env::set_var("RECOYX_LOCALIZATION_GENERATED", &output);
+    trybuild::TestCases::new().pass("tests/build/generated.rs");
+
+    options.assets(LocaleMapAssetOptions::new()
+        .src("tests/res-build-collision")
+        .base_file_names(vec!["modules"]));
+    assert_eq!(build::generate(&options, &output), Err("Messages modules.a-b and modules.a_b are both in the module messages::modules::a_b".to_string()));
+    options.assets(LocaleMapAssetOptions::new()
+        .src("tests/res-build-collision")
+        .base_file_names(vec!["variables"]));
+    assert_eq!(build::generate(&options, &output), Err("Variables $userName and $user_name of variables.greeting are both named user_name".to_string()));
+}
+
+#[cfg(feature = "tracing")]
+#[async_test]
+async fn tracing_events() {
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let output = Arc::new(Mutex::new(Vec::<u8>::new()));
+    let writer = output.clone();
+    let _guard = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || Writer(writer.clone()))
+        .finish()
+        .set_default();
+
+    struct Writer(Arc<Mutex<Vec<u8>>>);
+    impl std::io::Write for Writer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> { self.0.lock().unwrap().write(buf) }
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("en-US")
+            .fallbacks(maplit::hashmap!{"pt-BR" => vec!["en-US"]})
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    locale_map.get("common.contextual_male");
+    locale_map.get("common.nope");
+    locale_map.get("common.nope");
+
+    let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+    assert!(output.contains("fetch_asset{path=\"tests/res/pt-BR/common.json\"}"));
+    assert!(output.contains("fetched asset elapsed_ms="));
+    assert!(output.contains("loaded locale locale=pt-BR locales=2"));
+    assert!(output.contains("message resolved from fallback id=\"common.contextual_male\" locale=pt-BR fallback=en-US"));
+    assert_eq!(output.matches("missing message id=\"common.nope\"").count(), 1);
+}
+
+#[cfg(feature = "test-support")]
+#[async_test]
+async fn in_memory_locale_map() {
+    use recoyx_localization::test_support::*;
+
+    let mut locale_map = LocaleMap::from_in_memory(
+        LocaleMapOptions::new().supported_locales(vec!["en-US", "pt-BR"]).default_locale("en-US"),
+        maplit::hashmap!{
+            "en-US" => serde_json::json!({ "common": { "greeting": "Hello, $name!", "qty_one": "One item" } }),
+            "pt-BR" => serde_json::json!({ "common": { "greeting": "Olá, $nome!" } }),
+        },
+    );
+    assert!(locale_map.current_locale() == Some(parse_locale("en-US").unwrap()));
+    assert_eq!(locale_map.get_formatted("common.greeting", vec![ &localization_vars!{"name" => "Ana"} ]), "Hello, Ana!");
+    assert_message_exists(&locale_map, "common.greeting");
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| assert_message_exists(&locale_map, "common.missing"))).is_err());
+    let mismatch = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| assert_all_placeholders_match(&locale_map))).unwrap_err();
+    assert!(mismatch.downcast_ref::<String>().unwrap().contains("pt-BR: placeholders of common.greeting: -$name +$nome"));
+
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(locale_map.get_formatted("common.greeting", vec![ &localization_vars!{"nome" => "Ana"} ]), "Olá, Ana!");
+}
+
+#[test]
+fn currency_long_names() {
+    let options = LocaleMapOptions::new();
+    options.supported_locales(vec!["en-US", "es", "ru", "zh", "pt-PT", "hi"]);
+    let locale_map = |default_locale: &str| LocaleMap::from_embedded(options.default_locale(default_locale), &[(default_locale, "{}")]);
+    let en_us = locale_map("en-US");
+    assert_eq!(en_us.format_currency_long(1.0, "USD"), "1 US dollar");
+    assert_eq!(en_us.format_currency_long(2.0, "usd"), "2 US dollars");
+    assert_eq!(en_us.format_currency_long(1234.567, "EUR"), "1,234.57 euros");
+    assert_eq!(en_us.format_currency_long(1234.4, "JPY"), "1,234 Japanese yen");
+    assert_eq!(en_us.format_currency_long(3.0, "XXY"), "3 XXY");
+    assert_eq!(en_us.format_currency_long(3.0, "ATS"), "3 Austrian schillings");
+    assert_eq!(locale_map("es").format_currency_long(1.0, "USD"), "1 dólar estadounidense");
+    assert_eq!(locale_map("pt-PT").format_currency_long(2.0, "USD"), "2 dólares dos Estados Unidos");
+    // Names missing in a language use the code rather than the English name.
+    assert_eq!(locale_map("hi").format_currency_long(3.0, "ATS"), "3 ATS");
+    let ru = locale_map("ru");
+    assert_eq!(ru.format_currency_long(2.0, "USD"), "2 доллара США");
+    assert_eq!(ru.format_currency_long(5.0, "USD"), "5 долларов США");
+    assert_eq!(ru.format_currency_long(1.5, "USD"), "1,5 доллара США");
+    assert_eq!(locale_map("zh").format_currency_long(10.0, "CNY"), "10人民币");
+}
+
+#[test]
+fn currency_accounting_and_ranges() {
+    let en = parse_locale("en").unwrap();
+    let de = parse_locale("de").unwrap();
+    let fr = parse_locale("fr").unwrap();
+    let standard = CurrencyFormatOptions::new();
+    let accounting = CurrencyFormatOptions::new();
+    accounting.style(CurrencyStyle::Accounting);
+    assert_eq!(CurrencyFormatter::new(&en, "USD", &standard).format(-5.0), "-$5.00");
+    assert_eq!(CurrencyFormatter::new(&en, "USD", &accounting).format(-5.0), "($5.00)");
+    assert_eq!(CurrencyFormatter::new(&en, "USD", &accounting).format(-0.001), "$0.00");
+    assert_eq!(CurrencyFormatter::new(&en, "CHF", &standard).format(5.0), "CHF\u{A0}5.00");
+    assert_eq!(CurrencyFormatter::new(&en, "JPY", &standard).format(1234.4), "¥1,234");
+    // German has no parentheses for negative amounts, while French does.
+    assert_eq!(CurrencyFormatter::new(&de, "EUR", &accounting).format(-5.0), "-5,00\u{A0}€");
+    assert_eq!(CurrencyFormatter::new(&fr, "EUR", &accounting).format(-5.0), "(5,00\u{A0}€)");
+    // The minus sign follows the numbering system.
+    assert_eq!(CurrencyFormatter::new(&parse_locale("ar-u-nu-arab").unwrap(), "USD", &standard).format(-5.0), "\u{61C}-$٥٫٠٠");
+
+    let whole = CurrencyFormatOptions::new();
+    whole.minimum_fraction_digits(0);
+    assert_eq!(CurrencyFormatter::new(&en, "USD", &whole).format_range(10.0, 20.0), "$10–$20");
+    assert_eq!(CurrencyFormatter::new(&de, "EUR", &whole).format_range(10.0, 20.0), "10–20\u{A0}€");
+    assert_eq!(CurrencyFormatter::new(&en, "USD", &standard).format_range(-5.0, 5.0), "-$5.00–$5.00");
+
+    let locale_map = LocaleMap::from_embedded(LocaleMapOptions::new().supported_locales(vec!["de-DE"]).default_locale("de-DE"), &[("de-DE", "{}")]);
+    assert_eq!(locale_map.currency_formatter("eur", &standard).format(1234.5), "1.234,50\u{A0}€");
+}
+
+#[test]
+fn scientific_notation() {
+    let formatter = |tag: &str, notation: Notation| {
+        NumberFormatter::new(&parse_locale(tag).unwrap(), NumberFormatOptions::new().notation(notation).maximum_fraction_digits(5))
+    };
+    assert_eq!(formatter("de", Notation::Scientific).format(123456.0), "1,23456E5");
+    assert_eq!(formatter("en", Notation::Scientific).format(-0.000123), "-1.23E-4");
+    assert_eq!(formatter("en", Notation::Scientific).format(999999.9), "1E6");
+    assert_eq!(formatter("en", Notation::Scientific).format(0.0), "0E0");
+    assert_eq!(formatter("en", Notation::Scientific).format(-0.0), "0E0");
+    assert_eq!(formatter("en", Notation::Engineering).format(0.0), "0E0");
+    assert_eq!(formatter("sv", Notation::Scientific).format(1500.0), "1,5×10^3");
+    assert_eq!(formatter("en", Notation::Engineering).format(123456.0), "123.456E3");
+    assert_eq!(formatter("en", Notation::Engineering).format(1234567.0), "1.23457E6");
+    assert_eq!(formatter("ar-u-nu-arab", Notation::Scientific).format(1500.0), "١٫٥أس٣");
+    assert_eq!(formatter("ar-u-nu-arab", Notation::Scientific).format(-0.0015), "\u{61C}-١٫٥أس\u{61C}-٣");
+    assert_eq!(formatter("en", Notation::Scientific).format(1e-310), "1E-310");
+    assert_eq!(formatter("en", Notation::Scientific).format(-2.5e-310), "-2.5E-310");
+    assert_eq!(formatter("en", Notation::Scientific).format(f64::MAX), "1.79769E308");
+    assert_eq!(NumberParser::new(&parse_locale("ar-u-nu-arab").unwrap()).parse("\u{61C}-١٫٥أس\u{61C}-٣"), Ok(-0.0015));
+}
+
+#[test]
+fn number_parsing() {
+    let parser = |tag: &str| NumberParser::new(&parse_locale(tag).unwrap());
+    let en = parser("en");
+    assert_eq!(en.parse("1,234.56"), Ok(1234.56));
+    assert_eq!(en.parse(" -1,234 "), Ok(-1234.0));
+    assert_eq!(en.parse(".5"), Ok(0.5));
+    assert_eq!(en.parse("50%"), Ok(0.5));
+    assert_eq!(en.parse("$1,234.50"), Ok(1234.5));
+    assert_eq!(en.parse("($5.00)"), Ok(-5.0));
+    assert_eq!(en.parse("CHF\u{A0}5.00"), Ok(5.0));
+    assert_eq!(en.parse("USD 12"), Ok(12.0));
+    assert_eq!(en.parse("1.5E3"), Ok(1500.0));
+    assert!(en.parse("1.2.3").is_err());
+    assert!(en.parse("").is_err());
+    assert!(en.parse("abc").is_err());
+    assert!(en.parse("--5").is_err());
+    assert_eq!(parser("de").parse("1.234,56"), Ok(1234.56));
+    assert_eq!(parser("fr").parse("1\u{202F}234,5\u{A0}€"), Ok(1234.5));
+    assert_eq!(parser("pt").parse("R$\u{A0}10,00"), Ok(10.0));
+    assert_eq!(parser("sv").parse("1,5×10^3"), Ok(1500.0));
+    assert_eq!(parser("hi").parse("१,२३४.५"), Ok(1234.5));
+    assert_eq!(en.parse("1,234,567"), Ok(1234567.0));
+    assert!(en.parse("1,5").is_err());
+    assert!(en.parse("12,34").is_err());
+    assert!(en.parse("1234,567").is_err());
+    assert!(en.parse(",123").is_err());
+    assert!(parser("de").parse("1.5").is_err());
+    assert!(parser("fr").parse("1\u{202F}5").is_err());
+    assert_eq!(parser("hi").parse("12,34,567"), Ok(1234567.0));
+    assert!(parser("hi").parse("1,234,567").is_err());
+
+    let formatter = NumberFormatter::new(&parse_locale("de").unwrap(), &NumberFormatOptions::new());
+    assert_eq!(parser("de").parse(&formatter.format(-9876543.21)), Ok(-9876543.21));
+
+    let overrides = FormatOverrides::new();
+    overrides.decimal_separator(".").group_separator(" ");
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["de-DE"]).default_locale("de-DE").format_overrides("de-DE", &overrides),
+        &[("de-DE", "{}")],
+    );
+    assert_eq!(locale_map.number_parser().parse("1 234.5"), Ok(1234.5));
+}
+
+#[test]
+fn date_parsing() {
+    let date = |y, m, d| Ok(chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap());
+    let parser = |tag: &str| DateParser::new(&parse_locale(tag).unwrap());
+    let en_us = parser("en-US");
+    assert_eq!(en_us.parse("3/4/2025"), date(2025, 3, 4));
+    assert_eq!(en_us.parse("3/4/25"), date(2025, 3, 4));
+    assert_eq!(en_us.parse("Mar 4, 2025"), date(2025, 3, 4));
+    assert_eq!(en_us.parse("Tuesday, March 4, 2025"), date(2025, 3, 4));
+    assert_eq!(en_us.parse("4 sept 2025"), date(2025, 9, 4));
+    assert_eq!(en_us.parse("2025-03-04"), date(2025, 3, 4));
+    assert!(en_us.parse("Ju 4, 2025").is_err());
+    assert!(en_us.parse("13/4/2025").is_err());
+    assert!(en_us.parse("tomorrow").is_err());
+    assert_eq!(parser("en-GB").parse("3/4/2025"), date(2025, 4, 3));
+    assert_eq!(parser("de").parse("4. März 2025"), date(2025, 3, 4));
+    assert_eq!(parser("de").parse("04.03.25"), date(2025, 3, 4));
+    assert_eq!(parser("fr").parse("mar. 4 mars 2025"), date(2025, 3, 4));
+    assert_eq!(parser("ru").parse("4 марта 2025 г."), date(2025, 3, 4));
+    assert_eq!(parser("ja").parse("2025年3月4日"), date(2025, 3, 4));
+    assert_eq!(parser("hi").parse("४/३/२०२५"), date(2025, 3, 4));
+
+    let formatter = DateTimeFormatter::new(&parse_locale("es").unwrap(), DateTimeFormatOptions::new().date_length(Some(DateTimeLength::Long)).time_length(None));
+    let formatted = formatter.format(&chrono::DateTime::parse_from_rfc3339("2025-03-04T00:00:00Z").unwrap());
+    assert_eq!(parser("es").parse(&formatted), date(2025, 3, 4));
+
+    let overrides = FormatOverrides::new();
+    overrides.date_order(DateOrder::DayMonthYear);
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US").format_overrides("en-US", &overrides),
+        &[("en-US", "{}")],
+    );
+    assert_eq!(locale_map.date_parser().parse("3/4/2025"), date(2025, 4, 3));
+}
+
+#[test]
+fn week_numbers() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let us = parse_locale("en-US").unwrap().week_info();
+    assert_eq!(us, WeekInfo { first_day: Weekday::Sunday, minimal_days: 1 });
+    assert_eq!(us.week_of_year(date(2020, 12, 26)), (2020, 52));
+    assert_eq!(us.week_of_year(date(2020, 12, 31)), (2021, 1));
+    assert_eq!(us.week_of_year(date(2021, 1, 2)), (2021, 1));
+    assert_eq!(us.week_of_year(date(2021, 1, 3)), (2021, 2));
+    assert_eq!(us.week_of_year(date(2021, 12, 26)), (2022, 1));
+
+    let de = parse_locale("de-DE").unwrap().week_info();
+    assert_eq!(de, WeekInfo::iso());
+    assert_eq!(de.week_of_year(date(2021, 1, 3)), (2020, 53));
+    assert_eq!(de.week_of_year(date(2021, 1, 4)), (2021, 1));
+    assert_eq!(de.week_of_year(date(2024, 12, 30)), (2025, 1));
+
+    assert_eq!(parse_locale("pt").unwrap().week_info().first_day, Weekday::Sunday);
+    assert_eq!(parse_locale("ar-EG").unwrap().week_info().first_day, Weekday::Saturday);
+    assert_eq!(parse_locale("de-DE-u-fw-sun").unwrap().week_info(), WeekInfo { first_day: Weekday::Sunday, minimal_days: 4 });
+
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"),
+        &[("en-US", "{}")],
+    );
+    assert_eq!(locale_map.week_info().first_day, Weekday::Sunday);
+}
+
+#[test]
+fn region_calendar() {
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let country = |code| parse_country(code).unwrap();
+    assert_eq!(country("US").weekend(), vec![Weekday::Saturday, Weekday::Sunday]);
+    assert_eq!(country("EG").weekend(), vec![Weekday::Friday, Weekday::Saturday]);
+    assert_eq!(country("IN").weekend(), vec![Weekday::Sunday]);
+    assert_eq!(country("AF").weekend(), vec![Weekday::Thursday, Weekday::Friday]);
+
+    let mut calendar = RegionCalendar::new();
+    calendar.add_holiday_provider(|date: chrono::NaiveDate, country: &Country| {
+        if country.standard_code().alpha2() == "US" && date == chrono::NaiveDate::from_ymd_opt(2025, 7, 4).unwrap() { Some("Independence Day".to_string()) } else { None }
+    });
+    // 2025-03-07 is a Friday.
+    assert!(calendar.is_business_day(date(2025, 3, 7), &country("US")));
+    assert!(!calendar.is_business_day(date(2025, 3, 7), &country("AE")));
+    assert!(calendar.is_business_day(date(2025, 3, 9), &country("AE")));
+    assert!(calendar.is_business_day(date(2025, 3, 8), &country("IN")));
+    assert_eq!(calendar.holiday(date(2025, 7, 4), &country("US")), Some("Independence Day".to_string()));
+    assert!(calendar.is_business_day(date(2025, 7, 4), &country("CA")));
+    assert_eq!(calendar.next_business_day(date(2025, 7, 3), &country("US")), Some(date(2025, 7, 7)));
+    assert_eq!(calendar.business_days_between(date(2025, 6, 30), date(2025, 7, 7), &country("US")), 4);
+
+    calendar.set_weekend(&country("US"), &[Weekday::Sunday]);
+    assert!(calendar.is_business_day(date(2025, 3, 8), &country("US")));
+    assert!(calendar.is_weekend(date(2025, 3, 9), &country("US")));
+}
+
+#[test]
+fn language_menu() {
+    let locale_map = LocaleMap::from_embedded(
+        LocaleMapOptions::new().supported_locales(vec!["pt-BR", "en-US", "ar", "ja", "de", "es"]).default_locale("en-US"),
+        &[("pt-BR", "{}"), ("en-US", "{}"), ("ar", "{}"), ("ja", "{}"), ("de", "{}"), ("es", "{}")],
+    );
+    let menu = locale_map.language_menu();
+    let names: Vec<&str> = menu.iter().map(|item| item.native_name.as_ref()).collect();
+    assert_eq!(names, vec!["Deutsch", "English", "Español", "Português", "العربية", "日本語"]);
+    assert_eq!(menu[3].locale.standard_tag().to_string(), "pt-BR");
+    assert_eq!(menu[3].universal_name, "Portuguese");
+    assert_eq!(menu[3].flag.as_deref(), Some("🇧🇷"));
+    assert_eq!(menu[1].flag.as_deref(), Some("🇺🇸"));
+    assert_eq!(menu[4].direction, Direction::RightToLeft);
+    assert_eq!(menu[0].direction, Direction::LeftToRight);
+
+    #[cfg(feature = "normalization")]
+    {
+        let sv = SearchCollator::new(&parse_locale("sv").unwrap(), &SearchCollatorOptions::new());
+        assert_eq!(sv.compare("Örebro", "Zürich"), std::cmp::Ordering::Greater);
+        let de = SearchCollator::new(&parse_locale("de").unwrap(), &SearchCollatorOptions::new());
+        assert_eq!(de.compare("Österreich", "Zürich"), std::cmp::Ordering::Less);
+    }
+}
+
+#[async_test]
+async fn remembered_locale() {
+    let store = MemoryLocalePreferenceStore::new();
+    let options = LocaleMapOptions::new();
+    options.supported_locales(vec!["en", "pt-BR"]).default_locale("en").remember_selection(store.clone());
+    let assets = [("en", r#"{"hello": "Hello"}"#), ("pt-BR", r#"{"hello": "Olá"}"#)];
+
+    let mut locale_map = LocaleMap::from_embedded(&options, &assets);
+    assert_eq!(locale_map.get("hello"), "Hello");
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(store.load(), Ok(Some("pt-BR".to_string())));
+
+    // A restart loads the selected locale.
+    let mut locale_map = LocaleMap::from_embedded(&options, &assets);
+    assert_eq!(locale_map.get("hello"), "Olá");
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.get("hello"), "Olá");
+
+    locale_map.forget_selection();
+    assert_eq!(store.load(), Ok(None));
+    assert_eq!(LocaleMap::from_embedded(&options, &assets).get("hello"), "Hello");
+
+    // Stored locales that are no longer supported are ignored.
+    store.save("ja").unwrap();
+    assert_eq!(LocaleMap::from_embedded(&options, &assets).get("hello"), "Hello");
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("settings").join("locale");
+    let file_store = FileLocalePreferenceStore::new(&path);
+    assert_eq!(file_store.load(), Ok(None));
+    file_store.save("pt-BR").unwrap();
+    assert_eq!(FileLocalePreferenceStore::new(&path).load(), Ok(Some("pt-BR".to_string())));
+    file_store.clear().unwrap();
+    assert_eq!(file_store.load(), Ok(None));
+}
+
+#[test]
+fn user_locale_prefs() {
+    let en_us = parse_locale("en-US").unwrap();
+    let prefs = UserLocalePrefs::new(&en_us);
+    assert_eq!(prefs.region().map(|c| c.standard_code().alpha2().to_string()), Some("US".to_string()));
+    assert_eq!(prefs.measurement_system(), MeasurementSystem::UsSystem);
+    assert_eq!(prefs.hour_cycle(), HourCycle::H12);
+    assert_eq!(prefs.first_day(), Weekday::Sunday);
+
+    // English with German formats.
+    let prefs = prefs.with_region(&parse_country("DE").unwrap());
+    let locale = prefs.locale();
+    assert_eq!(locale.region_override(), Some("DE".to_string()));
+    assert_eq!(prefs.measurement_system(), MeasurementSystem::Metric);
+    assert_eq!(prefs.hour_cycle(), HourCycle::H23);
+    assert_eq!(prefs.week_info(), WeekInfo::iso());
+    assert_eq!(NumberFormatter::new(&locale, &NumberFormatOptions::new()).format(1234.5), "1.234,5");
+    assert_eq!(NumberParser::new(&locale).parse("1.234,5"), Ok(1234.5));
+    let date = chrono::DateTime::parse_from_rfc3339("2025-03-04T15:30:00Z").unwrap();
+    let formatter = DateTimeFormatter::new(&locale, DateTimeFormatOptions::new().date_length(Some(DateTimeLength::Short)).time_length(None));
+    assert_eq!(formatter.format(&date), "04.03.25");
+    let formatter = DateTimeFormatter::new(&locale, DateTimeFormatOptions::new().date_length(Some(DateTimeLength::Long)).time_length(None));
+    assert!(formatter.format(&date).contains("March"));
+    assert_eq!(CurrencyFormatter::new(&locale, "EUR", &CurrencyFormatOptions::new()).format(5.0), "5,00\u{A0}€");
+
+    let prefs = prefs
+        .with_numbering_system(parse_numbering_system("arab").unwrap())
+        .with_measurement_system(MeasurementSystem::UkSystem)
+        .with_hour_cycle(HourCycle::H12)
+        .with_first_day(Weekday::Saturday);
+    assert_eq!(prefs.language(), "en");
+    assert_eq!(prefs.numbering_system().code(), "arab");
+    assert_eq!(prefs.measurement_system(), MeasurementSystem::UkSystem);
+    assert_eq!(prefs.hour_cycle(), HourCycle::H12);
+    assert_eq!(prefs.first_day(), Weekday::Saturday);
+    assert_eq!(prefs.locale().standard_tag().to_string(), "en-US-u-rg-dezzzz-nu-arab-ms-uksystem-hc-h12-fw-sat");
+    assert_eq!(UserLocalePrefs::from(&prefs.locale()).measurement_system(), MeasurementSystem::UkSystem);
+}
+
+#[async_test]
+async fn formatting_locale() {
+    let options = LocaleMapOptions::new();
+    options.supported_locales(vec!["en-US", "pt-BR"]).default_locale("en-US").formatting_locale("de-DE");
+    let assets = [("en-US", r#"{"hello": "Hello"}"#), ("pt-BR", r#"{"hello": "Olá"}"#)];
+    let mut locale_map = LocaleMap::from_embedded(&options, &assets);
+    assert_eq!(locale_map.get("hello"), "Hello");
+    assert_eq!(locale_map.formatting_locale().standard_tag().to_string(), "de-DE");
+    assert_eq!(locale_map.number_formatter().format(1234.5), "1.234,5");
+    assert_eq!(locale_map.hour_cycle(), HourCycle::H23);
+    assert_eq!(locale_map.format_currency_long(2.5, "EUR"), "2,5 euros");
+    // Dates follow the patterns of the formatting locale with names in the language of the messages.
+    assert_eq!(locale_map.month_names(NameWidth::Wide, NameContext::Format)[0], "January");
+    let date = chrono::DateTime::parse_from_rfc3339("2025-03-04T15:30:00Z").unwrap();
+    let en_de = parse_locale("en-US-u-rg-dezzzz").unwrap();
+    assert_eq!(locale_map.date_time_formatter().format(&date), DateTimeFormatter::new(&en_de, &DateTimeFormatOptions::new()).format(&date));
+    locale_map.register_formatter("tag", |_, locale, _| locale.standard_tag().to_string());
+    locale_map.insert_message(&parse_locale("en-US").unwrap(), "tag", "${x|tag}");
+    assert_eq!(locale_map.get_formatted("tag", vec![&localization_vars!{"x" => "a"}]), "de-DE");
+
+    // The formatting locale is kept when the message locale changes.
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    assert_eq!(locale_map.get("hello"), "Olá");
+    assert_eq!(locale_map.number_formatter().format(1234.5), "1.234,5");
+
+    locale_map.set_formatting_locale(Some(parse_locale("ja").unwrap()));
+    let pt_jp = parse_locale("pt-BR-u-rg-jpzzzz").unwrap();
+    assert_eq!(locale_map.date_time_formatter().format(&date), DateTimeFormatter::new(&pt_jp, &DateTimeFormatOptions::new()).format(&date));
+    assert_eq!(locale_map.weekday_names(NameWidth::Wide, NameContext::Standalone)[0], "domingo");
+
+    let mut locale_map = LocaleMap::from_embedded(LocaleMapOptions::new().supported_locales(vec!["en-US", "pt-BR"]).default_locale("en-US"), &assets);
+    assert_eq!(locale_map.number_formatter().format(1234.5), "1,234.5");
+    locale_map.set_formatting_locale(Some(parse_locale("de").unwrap()));
+    assert_eq!(locale_map.number_formatter().format(1234.5), "1.234,5");
+    locale_map.set_formatting_locale(None);
+    assert_eq!(locale_map.number_formatter().format(1234.5), "1,234.5");
+
+    assert!(LocaleMapOptions::from_json(r#"{"supported_locales": ["en"], "formatting_locale": "fr-CA"}"#).is_ok());
+    assert_eq!(LocaleMapOptions::from_json(r#"{"supported_locales": ["en"], "formatting_locale": "x"}"#).err(), Some("formatting_locale: invalid locale \"x\"".to_string()));
+}
+
+#[async_test]
+async fn localization_context() {
+    let context = LocalizationContext::new(&parse_locale("pt-PT").unwrap());
+    assert_eq!(context.plural_rules, "pt-PT");
+    assert_eq!(context.decimal_separator, ",");
+    assert_eq!(LocalizationContext::new(&parse_locale("he").unwrap()).direction, "rtl");
+    assert_eq!(LocalizationContext::new(&parse_locale("en-US").unwrap()).plural_rules, "en");
+
+    let options = LocaleMapOptions::new();
+    options.supported_locales(vec!["en-US", "pt-BR"]).default_locale("en-US").formatting_locale("de-DE");
+    let assets = [("en-US", r#"{"hello": "Hello"}"#), ("pt-BR", r#"{"hello": "Olá"}"#)];
+    let mut locale_map = LocaleMap::from_embedded(&options, &assets);
+    assert!(locale_map.load(Some(parse_locale("pt-BR").unwrap())).await);
+    let context = locale_map.localization_context();
+    assert_eq!(context.locale, "pt-BR");
+    assert_eq!(context.plural_rules, "pt");
+    assert_eq!(context.decimal_separator, ",");
+    assert_eq!(context.group_separator, ".");
+    let json = serde_json::to_value(&context).unwrap();
+    assert_eq!(json["direction"], "ltr");
+    assert_eq!(serde_json::from_value::<LocalizationContext>(json).unwrap(), context);
+}
+
+#[test]
+fn usage_recording() {
+    let assets = [("en-US", r#"{"hello": "Hello", "qty_one": "One", "qty_multiple": "Many", "menu": {"open": "Open", "stale": "Stale"}}"#)];
+    let locale_map = LocaleMap::from_embedded(LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"), &assets);
+    locale_map.get("hello");
+    assert!(locale_map.usage_counts().is_empty());
+
+    locale_map.start_usage_recording();
+    locale_map.get("hello");
+    locale_map.get("hello");
+    locale_map.get("menu.open");
+    locale_map.get_formatted("qty", vec![&1]);
+    locale_map.get("missing");
+    let counts = locale_map.usage_counts();
+    assert_eq!(counts.get("hello"), Some(&2));
+    assert_eq!(counts.get("qty_one"), Some(&1));
+    assert_eq!(counts.get("missing"), Some(&1));
+    assert_eq!(locale_map.unused_keys(), vec!["menu.stale", "qty_multiple"]);
+
+    assert_eq!(locale_map.stop_usage_recording().get("menu.open"), Some(&1));
+    assert!(locale_map.usage_counts().is_empty());
+}
+
+#[async_test]
+async fn catalog_diff() {
+    let options = LocaleMapOptions::new();
+    options.supported_locales(vec!["en-US", "pt-BR"]).default_locale("en-US");
+    let old = LocaleMap::from_embedded(&options, &[("en-US", r#"{"menu": {"open": "Open", "quit": "Quit"}, "title": "App"}"#)]);
+    let mut new = LocaleMap::from_embedded(&options, &[
+        ("en-US", r#"{"menu": {"open": "Open…", "save": "Save"}, "title": "App"}"#),
+        ("pt-BR", r#"{"title": "Aplicativo"}"#),
+    ]);
+    assert!(diff_catalogs(&old, &old.clone()).await.is_empty());
+
+    let diffs = diff_catalogs(&old, &new).await;
+    assert_eq!(diffs.len(), 2);
+    assert_eq!(diffs[0].locale, "en-US");
+    assert_eq!(diffs[0].added, vec!["menu.save"]);
+    assert_eq!(diffs[0].removed, vec!["menu.quit"]);
+    assert_eq!(diffs[0].changed, vec![ChangedMessage { id: "menu.open".to_string(), old: "Open".to_string(), new: "Open…".to_string() }]);
+    // Locales that are not loaded are read from the assets, and a locale
+    // without assets in one of the catalogs has all of its messages added or removed.
+    assert_eq!(diffs[1].locale, "pt-BR");
+    assert_eq!(diffs[1].added, vec!["title"]);
+    assert_eq!(serde_json::to_value(&diffs[1]).unwrap()["removed"], serde_json::json!([]));
+
+    // Loading another locale, which discards the messages of en-US, does not change the differences.
+    assert!(new.load(Some(parse_locale("pt-BR").unwrap())).await);
+    let locale = parse_locale("pt-BR").unwrap();
+    assert_eq!(diff_catalogs(&old, &new).await, diffs);
+
+    assert!(diff_messages(&locale, new.messages_for(&locale).unwrap(), new.messages_for(&locale).unwrap()).is_empty());
+}
+
+#[test]
+fn nested_message_namespace() {
+    let assets = [("en-US", r#"{"errors": {"message": "Something went wrong"}, "save": {"message": "Save", "description": "Toolbar button"}}"#)];
+    let locale_map = LocaleMap::from_embedded(LocaleMapOptions::new().supported_locales(vec!["en-US"]).default_locale("en-US"), &assets);
+    assert_eq!(locale_map.get("errors.message"), "Something went wrong");
+    assert_eq!(locale_map.get("save"), "Save");
+}
+
+#[test]
+fn lenient_options() {
+    let options = LocaleMapOptions::new();
+    options
+        .supported_locales(vec!["en", "pt-BR", "not a locale"])
+        .default_locale("en")
+        .fallbacks(maplit::hashmap! { "en" => vec!["pt-BR"], "pt-BR" => vec!["en", "es"] })
+        .numbering_system("xyz");
+    assert!(LocaleMap::try_new(&options).is_err());
+    // LocaleMap::new() drops the invalid entries instead.
+    let locale_map = LocaleMap::new(&options);
+    let en = parse_locale("en").unwrap();
+    let pt_br = parse_locale("pt-BR").unwrap();
+    assert!(locale_map.supported_locales() == vec![en.clone(), pt_br.clone()].into_iter().collect());
+    assert!(locale_map.config().fallbacks(&en) == [pt_br.clone()]);
+    assert!(locale_map.config().fallbacks(&pt_br).is_empty());
+    assert_eq!(locale_map.numbering_system().code(), "latn");
+}
+
+#[async_test]
+async fn invalid_overlay_asset() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    std::fs::create_dir_all(dir.join("en-US")).unwrap();
+    std::fs::create_dir_all(dir.join("tenant-a/en-US")).unwrap();
+    std::fs::write(dir.join("en-US/common.json"), r#"{"save": "Save"}"#).unwrap();
+    std::fs::write(dir.join("tenant-a/en-US/common.json"), r#"{"save": "#).unwrap();
+    let diagnostics = std::rc::Rc::new(std::cell::RefCell::new(Vec::<Diagnostic>::new()));
+    let options = LocaleMapOptions::new();
+    options
+        .supported_locales(vec!["en-US"])
+        .default_locale("en-US")
+        .assets(LocaleMapAssetOptions::new()
+            .src(dir.to_str().unwrap())
+            .base_file_names(vec!["common"])
+            .overlays(vec!["tenant-a"])
+            .loader_type(LocaleMapLoaderType::FileSystem));
+    let sink = diagnostics.clone();
+    options.on_diagnostic(move |d| sink.borrow_mut().push(d.clone()));
+
+    // The base messages load despite the overlay.
+    let mut locale_map = LocaleMap::new(&options);
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.with_overlay("tenant-a").get("common.save"), "Save");
+    assert_eq!(diagnostics.borrow().len(), 1);
+    assert_eq!(diagnostics.borrow()[0].severity, DiagnosticSeverity::Warning);
+    assert!(matches!(&diagnostics.borrow()[0].kind, DiagnosticKind::OverlayAssetFailed { path, .. } if path.ends_with("tenant-a/en-US/common.json")));
+
+    let mut locale_map = LocaleMap::new(&options);
+    let report = locale_map.load_partial(None).await;
+    assert!(report.loaded);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(diagnostics.borrow().len(), 1);
+}
+
+#[async_test]
+async fn message_tree() {
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US", "pt-BR"])
+            .default_locale("pt-BR")
+            .fallbacks(maplit::hashmap! { "pt-BR" => vec!["en-US"] })
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    locale_map.load(None).await;
+    let pt_br = parse_locale("pt-BR").unwrap();
+
+    let messages = locale_map.messages_for(&pt_br).unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages.get("common.message_id"), Some("Alguma mensagem"));
+    assert!(!messages.contains("common.save"));
+    assert_eq!(messages.iter().collect::<Vec<(&str, &str)>>(), vec![("common.message_id", "Alguma mensagem"), ("common.parameterized", "Aqui: $x")]);
+    assert!(locale_map.messages_for(&parse_locale("en-GB").unwrap()).is_none());
+
+    let keys = locale_map.keys();
+    assert_eq!(keys.len(), 9);
+    assert_eq!(keys[..3], ["common.contextual_female", "common.contextual_male", "common.message_id"]);
+    assert!(locale_map.has_key("common.save"));
+    assert!(!locale_map.has_key("common.missing"));
+    assert_eq!(locale_map.len_for(&pt_br), 2);
+    assert_eq!(locale_map.len_for(&parse_locale("en-US").unwrap()), 9);
+    assert_eq!(locale_map.len_for(&parse_locale("en-GB").unwrap()), 0);
+}
+
+#[async_test]
+async fn translation_editor_round_trip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path().to_path_buf();
+    std::fs::create_dir_all(dir.join("en-US")).unwrap();
+    let original = serde_json::json!({
+        "save": { "message": "Save", "description": "Toolbar button", "max_length": 12 },
+        "quit": "Quit",
+        "version": 3,
+        "beta": true,
+        "tags": ["menu", "file"],
+        "nothing": null,
+        "menu": { "open": "Open", "order": 1.5 },
+    });
+    std::fs::write(dir.join("en-US/common.json"), serde_json::to_vec(&original).unwrap()).unwrap();
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src(dir.to_str().unwrap())
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    assert!(locale_map.load(None).await);
+    let en_us = parse_locale("en-US").unwrap();
+
+    // Only the edited messages change; other values and the metadata are kept.
+    let mut editor = locale_map.translation_editor(&en_us);
+    editor.set("common.save", "Save all").unwrap();
+    editor.set("common.quit", "Exit").unwrap();
+    editor.delete("common.menu.open").unwrap();
+    editor.set("common.help.about", "About").unwrap();
+    editor.save().unwrap();
+    assert!(!editor.has_changes());
+    let saved: serde_json::Value = serde_json::from_slice(&std::fs::read(dir.join("en-US/common.json")).unwrap()).unwrap();
+    let mut expected = original.clone();
+    expected["save"]["message"] = serde_json::json!("Save all");
+    expected["quit"] = serde_json::json!("Exit");
+    expected["menu"] = serde_json::json!({ "order": 1.5 });
+    expected["help"] = serde_json::json!({ "about": "About" });
+    assert_eq!(saved, expected);
+
+    assert!(locale_map.load(None).await);
+    assert_eq!(locale_map.get("common.save"), "Save all");
+    assert_eq!(locale_map.messages_for(&en_us).unwrap().metadata("common.save").unwrap().max_length, Some(12));
+}
+
+#[cfg(feature = "compression")]
+#[async_test]
+async fn compressed_asset_limits() {
+    // Decompresses to a valid asset file larger than the limit.
+    let mut locale_map = LocaleMap::new(
+        LocaleMapOptions::new()
+            .supported_locales(vec!["en-US"])
+            .default_locale("en-US")
+            .assets(LocaleMapAssetOptions::new()
+                .src("tests/res-compressed-large")
+                .base_file_names(vec!["common"])
+                .loader_type(LocaleMapLoaderType::FileSystem))
+    ); // locale_map
+    let report = locale_map.load_partial(None).await;
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.errors[0].path.ends_with("en-US/common.json.gz"));
+    assert!(report.errors[0].kind.to_string().starts_with("decompressed content exceeds"));
+}
+
+#[cfg(all(feature = "compression", feature = "integrity"))]
+#[tokio::test]
+async fn compressed_asset_integrity() {
+    use sha2::{Digest, Sha256};
+
+    // The checksum is of the file as fetched, before it is decompressed.
+    let compressed = std::fs::read("tests/res-compressed/en-US/common.json.gz").unwrap();
+    let checksum: String = Sha256::digest(&compressed).iter().map(|b| format!("{:02x}", b)).collect();
+    let url = serve_files(vec![("en-US/common.json.gz", compressed)]);
+    let load = |manifest: String| {
+        let locale_map = LocaleMap::new(
+            LocaleMapOptions::new()
+                .supported_locales(vec!["en-US"])
+                .default_locale("en-US")
+                .assets(LocaleMapAssetOptions::new()
+                    .src(&url)
+                    .base_file_names(vec!["common"])
+                    .integrity(AssetIntegrity::sha256_manifest(&manifest).unwrap())
+                    .loader_type(LocaleMapLoaderType::Http))
+        ); // locale_map
+        async move {
+            let mut locale_map = locale_map;
+            let report = locale_map.load_partial(None).await;
+            (locale_map.get("common.greeting"), report.errors.iter().map(|e| e.kind.to_string()).collect::<Vec<_>>())
+        }
+    };
+    assert_eq!(load(format!(r#"{{ "en-US/common.json.gz": "{}" }}"#, checksum)).await, ("Hello".to_string(), vec![]));
+    assert_eq!(load(format!(r#"{{ "en-US/common.json": "{}" }}"#, checksum)).await.1, vec!["untrusted: no checksum in the manifest".to_string()]);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_commands() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = |name: &str, src: &str| {
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(src);
+        std::fs::write(temp_dir.path().join(name), serde_json::json!({
+            "src": src,
+            "supported_locales": ["en-US", "pt-BR"],
+            "default_locale": "en-US",
+            "fallbacks": { "pt-BR": ["en-US"] },
+            "base_file_names": ["common"],
+        }).to_string()).unwrap();
+    };
+    config("localization.json", "tests/res");
+    config("validation.json", "tests/res-validation");
+    let run = |args: &[&str]| {
+        let output = assert_cmd::Command::cargo_bin("recoyx-localization").unwrap().current_dir(temp_dir.path()).args(args).output().unwrap();
+        (output.status.code(), String::from_utf8(output.stdout).unwrap())
+    };
+
+    let (code, keys) = run(&["keys", "common.qty"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(keys, "common.qty_empty  (missing in pt-BR)\ncommon.qty_multiple  (missing in pt-BR)\ncommon.qty_one  (missing in pt-BR)\n");
+    assert_eq!(run(&["--config", "missing.json", "keys"]).0, Some(2));
+
+    assert_eq!(run(&["get", "pt-BR", "common.parameterized", "x=1"]), (Some(0), "Aqui: 1\n".to_string()));
+    assert_eq!(run(&["get", "en-US", "common.qty", "count=1"]), (Some(0), "One (1)\n".to_string()));
+    assert_eq!(run(&["get", "pt-BR", "common.unknown"]).0, Some(1));
+    assert_eq!(run(&["get", "pt-BR", "common.parameterized", "x"]).0, Some(2));
+
+    let (code, diff) = run(&["diff", "en-US", "pt-BR"]);
+    assert_eq!(code, Some(0));
+    assert!(diff.lines().any(|line| line == "- common.save") && !diff.contains("message_id"));
+    assert_eq!(run(&["diff", "en-US"]).0, Some(2));
+
+    assert_eq!(run(&["--config", "validation.json", "missing", "en-US"]).0, Some(0));
+    let (code, report) = run(&["--config", "validation.json", "missing"]);
+    assert_eq!(code, Some(1));
+    assert!(report.contains("placeholders of common.greeting: -$name +$nam"));
+    assert_eq!(run(&["unknown"]).0, Some(2));
+}
@@ -0,0 +1,453 @@
+//! Regenerates the files of `locale-data` from an unpacked release of the
+//! [cldr-json](https://github.com/unicode-org/cldr-json) packages `cldr-core`,
+//! `cldr-localenames-full`, `cldr-misc-full`, `cldr-numbers-full`, `cldr-dates-full`
+//! and `cldr-units-full`.
+//!
+//! The locales and countries already present in each file are refreshed, keeping
+//! the values that CLDR does not provide, such as top-level domains, so that the
+//! bundled data only grows when a locale is added to a file by hand. The plural
+//! ranges are generated for every language.
+
+use std::{fs, path::Path};
+use serde_json::{json, Map, Value};
+
+pub fn generate(src: &Path, output: &Path) -> Result<(), String> {
+    let version = read(&src.join("cldr-core/package.json"))?;
+    let version = get(&version, &["version"])?.as_str().ok_or("cldr-core/package.json: version is not a string")?.to_string();
+
+    let basic_data = basic_data(src, &read(&output.join("basic_data.json"))?)?;
+    let exemplar_characters = exemplar_characters(src, &read(&output.join("exemplar_characters.json"))?)?;
+    let country_data = country_data(src, &read(&output.join("country_data.json"))?)?;
+    let subdivision_data = subdivision_data(src, &read(&output.join("subdivision_data.json"))?)?;
+    let formatting_data = formatting_data(src, &read(&output.join("formatting_data.json"))?)?;
+    let currency_data = currency_data(src, &read(&output.join("currency_data.json"))?)?;
+    let plural_ranges = plural_ranges(src)?;
+
+    write(&output.join("basic_data.json"), &basic_data)?;
+    write(&output.join("exemplar_characters.json"), &exemplar_characters)?;
+    write(&output.join("country_data.json"), &country_data)?;
+    write(&output.join("subdivision_data.json"), &subdivision_data)?;
+    write(&output.join("formatting_data.json"), &formatting_data)?;
+    write(&output.join("currency_data.json"), &currency_data)?;
+    write(&output.join("plural_ranges.json"), &plural_ranges)?;
+    fs::write(output.join("cldr_version.txt"), &version).map_err(|e| e.to_string())?;
+    println!("Regenerated locale-data from CLDR {}", version);
+    Ok(())
+}
+
+pub(crate) fn read(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn read_optional(path: &Path) -> Result<Option<Value>, String> {
+    if path.exists() { read(path).map(Some) } else { Ok(None) }
+}
+
+pub(crate) fn write(path: &Path, value: &Value) -> Result<(), String> {
+    let mut content = vec![];
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, serde_json::ser::PrettyFormatter::with_indent(b"    "));
+    serde::Serialize::serialize(value, &mut serializer).map_err(|e| e.to_string())?;
+    content.push(b'\n');
+    fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn get<'a>(value: &'a Value, path: &[&str]) -> Result<&'a Value, String> {
+    path.iter().try_fold(value, |value, key| value.get(key).ok_or_else(|| format!("missing {}", path.join("."))))
+}
+
+fn get_str(value: &Value, path: &[&str]) -> Result<String, String> {
+    get(value, path)?.as_str().map(|s| s.to_string()).ok_or_else(|| format!("{} is not a string", path.join(".")))
+}
+
+/// Reads the locale-specific file of a package, such as `cldr-numbers-full/main/de/numbers.json`,
+/// returning the object under `main.<locale>`.
+fn main_file(src: &Path, package: &str, locale: &str, file: &str) -> Result<Option<Value>, String> {
+    let path = src.join(package).join("main").join(locale).join(file);
+    match read_optional(&path)? {
+        Some(value) => get(&value, &["main", locale]).cloned().map(Some).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => Ok(None),
+    }
+}
+
+fn capitalize_first(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+fn basic_data(src: &Path, previous: &Value) -> Result<Value, String> {
+    let en = main_file(src, "cldr-localenames-full", "en", "languages.json")?.ok_or("cldr-localenames-full: missing en")?;
+    let en = get(&en, &["localeDisplayNames", "languages"])?;
+    let mut r = Map::new();
+    for (code, previous) in previous.as_object().ok_or("basic_data.json is not an object")? {
+        let mut entry = previous.clone();
+        if let Some(name) = en.get(code).and_then(|v| v.as_str()) {
+            entry["universal_name"] = json!(name);
+        }
+        if let Some(names) = main_file(src, "cldr-localenames-full", code, "languages.json")? {
+            if let Some(name) = get(&names, &["localeDisplayNames", "languages", code]).ok().and_then(|v| v.as_str()) {
+                entry["native_name"] = json!(capitalize_first(name));
+            }
+        }
+        if let Some(layout) = main_file(src, "cldr-misc-full", code, "layout.json")? {
+            let order = get_str(&layout, &["layout", "orientation", "characterOrder"])?;
+            entry["direction"] = json!(if order == "right-to-left" { 0 } else { 1 });
+        }
+        r.insert(code.clone(), entry);
+    }
+    Ok(Value::Object(r))
+}
+
+fn exemplar_characters(src: &Path, previous: &Value) -> Result<Value, String> {
+    let mut r = Map::new();
+    for (code, previous) in previous.as_object().ok_or("exemplar_characters.json is not an object")? {
+        let characters = match main_file(src, "cldr-misc-full", code, "characters.json")? {
+            Some(characters) => json!(parse_unicode_set(&get_str(&characters, &["characters", "exemplarCharacters"])?)),
+            None => previous.clone(),
+        };
+        r.insert(code.clone(), characters);
+    }
+    Ok(Value::Object(r))
+}
+
+/// Lists the single characters of a CLDR exemplar set, such as `[a b c {ch} á-ã]`,
+/// skipping sequences of several characters.
+fn parse_unicode_set(set: &str) -> String {
+    let set = set.trim().trim_start_matches('[').trim_end_matches(']');
+    let mut r = String::new();
+    for item in set.split_whitespace().filter(|item| !item.starts_with('{')) {
+        let chars: Vec<char> = item.replace('\\', "").chars().collect();
+        let mut push = |c: char| if !r.contains(c) { r.push(c) };
+        if chars.len() == 3 && chars[1] == '-' {
+            (chars[0]..=chars[2]).for_each(&mut push);
+        } else {
+            chars.into_iter().for_each(&mut push);
+        }
+    }
+    r
+}
+
+fn country_data(src: &Path, previous: &Value) -> Result<Value, String> {
+    let supplemental = |file: &str| read(&src.join("cldr-core/supplemental").join(file));
+    let telephone = supplemental("telephoneCodeData.json")?;
+    let telephone = get(&telephone, &["supplemental", "telephoneCodeData"])?;
+    let currencies = supplemental("currencyData.json")?;
+    let currencies = get(&currencies, &["supplemental", "currencyData", "region"])?;
+    let containment = supplemental("territoryContainment.json")?;
+    let containment = get(&containment, &["supplemental", "territoryContainment"])?.as_object().ok_or("territoryContainment is not an object")?;
+    let week_data = supplemental("weekData.json")?;
+    let first_days = get(&week_data, &["supplemental", "weekData", "firstDay"])?;
+    let min_days = get(&week_data, &["supplemental", "weekData", "minDays"])?;
+    let weekend_starts = get(&week_data, &["supplemental", "weekData", "weekendStart"])?;
+    let weekend_ends = get(&week_data, &["supplemental", "weekData", "weekendEnd"])?;
+
+    let mut r = Map::new();
+    for (code, previous) in previous.as_object().ok_or("country_data.json is not an object")? {
+        let mut entry = previous.clone();
+        if let Some(codes) = telephone.get(code).and_then(|v| v.as_array()) {
+            entry["calling_codes"] = json!(codes.iter().filter_map(|c| c.get("telephoneCountryCode")?.as_str()).collect::<Vec<_>>());
+        }
+        if let Some(list) = currencies.get(code).and_then(|v| v.as_array()) {
+            // Currencies in use have no end date and are legal tender.
+            let current: Vec<&String> = list.iter()
+                .filter_map(|c| c.as_object())
+                .flat_map(|c| c.iter())
+                .filter(|(_, period)| period.get("_to").is_none() && period.get("_tender").and_then(|t| t.as_str()) != Some("false"))
+                .map(|(currency, _)| currency)
+                .collect();
+            entry["currencies"] = json!(current);
+        }
+        if let Some(continent) = continent(code, containment) {
+            entry["continent"] = json!(continent);
+        }
+        // The week fields are omitted where they match the world defaults.
+        let entry_map = entry.as_object_mut().ok_or("country entry is not an object")?;
+        entry_map.remove("first_day");
+        entry_map.remove("min_days");
+        entry_map.remove("weekend_start");
+        entry_map.remove("weekend_end");
+        if let Some(day) = first_days.get(code).and_then(|v| v.as_str()).filter(|d| Some(*d) != first_days["001"].as_str()) {
+            entry_map.insert("first_day".into(), json!(day));
+        }
+        if let Some(days) = min_days.get(code).and_then(|v| v.as_str()).filter(|d| Some(*d) != min_days["001"].as_str()) {
+            entry_map.insert("min_days".into(), json!(days.parse::<u32>().map_err(|e| e.to_string())?));
+        }
+        let weekend = |days: &Value| days.get(code).or_else(|| days.get("001")).and_then(|v| v.as_str()).map(String::from);
+        let (start, end) = (weekend(weekend_starts), weekend(weekend_ends));
+        if (start.as_deref(), end.as_deref()) != (weekend_starts["001"].as_str(), weekend_ends["001"].as_str()) {
+            entry_map.insert("weekend_start".into(), json!(start));
+            entry_map.insert("weekend_end".into(), json!(end));
+        }
+        r.insert(code.clone(), entry);
+    }
+    Ok(Value::Object(r))
+}
+
+/// Refreshes the names of the subdivisions in English and in each language already listed
+/// for them, from `subdivisions.json`, which identifies `BR-SP` as `brsp`. The subdivisions
+/// themselves are listed by the `subdivisions` task.
+fn subdivision_data(src: &Path, previous: &Value) -> Result<Value, String> {
+    let mut names_by_language = std::collections::HashMap::<String, Option<Value>>::new();
+    let mut r = Map::new();
+    for (code, previous) in previous.as_object().ok_or("subdivision_data.json is not an object")? {
+        let mut entry = previous.clone();
+        let mut languages: Vec<String> = get(previous, &["names"])?.as_object().ok_or("names is not an object")?.keys().cloned().collect();
+        if !languages.iter().any(|l| l == "en") {
+            languages.push("en".to_string());
+        }
+        for language in languages {
+            if !names_by_language.contains_key(&language) {
+                let names = main_file(src, "cldr-localenames-full", &language, "subdivisions.json")?
+                    .and_then(|names| get(&names, &["localeDisplayNames", "subdivisions", "subdivision"]).ok().cloned());
+                names_by_language.insert(language.clone(), names);
+            }
+            let cldr_code = code.replace('-', "").to_lowercase();
+            if let Some(name) = names_by_language[&language].as_ref().and_then(|names| names.get(&cldr_code)).and_then(|v| v.as_str()) {
+                entry["names"][&language] = json!(name);
+            }
+        }
+        r.insert(code.clone(), entry);
+    }
+    Ok(Value::Object(r))
+}
+
+/// Finds the continent code of a region from the UN M49 groupings that contain it.
+fn continent(region: &str, containment: &Map<String, Value>) -> Option<&'static str> {
+    if region == "AQ" {
+        return Some("AN");
+    }
+    let mut ancestors: Vec<String> = vec![region.to_string()];
+    let mut i = 0;
+    while i < ancestors.len() {
+        for (group, members) in containment.iter().filter(|(group, _)| !group.contains('-')) {
+            let contains = members.get("_contains").and_then(|m| m.as_array()).map(|m| m.iter().any(|m| m.as_str() == Some(ancestors[i].as_str()))).unwrap_or(false);
+            if contains && !ancestors.contains(group) {
+                ancestors.push(group.clone());
+            }
+        }
+        i += 1;
+    }
+    let has = |group: &str| ancestors.iter().any(|a| a == group);
+    [("005", "SA"), ("019", "NA"), ("002", "AF"), ("142", "AS"), ("150", "EU"), ("009", "OC")]
+        .iter()
+        .find(|(group, _)| has(group))
+        .map(|(_, continent)| *continent)
+}
+
+fn formatting_data(src: &Path, previous: &Value) -> Result<Value, String> {
+    let locales: Vec<&String> = previous.as_object().ok_or("formatting_data.json is not an object")?.keys().collect();
+    let mut full = Map::new();
+    for locale in locales.iter() {
+        full.insert(locale.to_string(), Value::Object(formatting_entry(src, locale).map_err(|e| format!("formatting data of {}: {}", locale, e))?));
+    }
+
+    // English is complete, while languages only keep the fields that differ from English
+    // and regions the fields that differ from their language, as resolved by the crate.
+    let mut r = Map::new();
+    let mut order: Vec<&String> = locales.clone();
+    order.sort_by_key(|l| (l.as_str() != "en", l.contains('-')));
+    for locale in order {
+        let mut chain: Vec<&Map<String, Value>> = vec![];
+        let language = locale.split('-').next().unwrap();
+        if locale.contains('-') {
+            chain.extend(r.get(language).and_then(|e: &Value| e.as_object()));
+        }
+        if locale != "en" {
+            chain.extend(r.get("en").and_then(|e: &Value| e.as_object()));
+        }
+        let entry = full[locale.as_str()].as_object().unwrap();
+        let mut partial = Map::new();
+        for (field, value) in entry.iter().filter(|(field, _)| !field.ends_with("_standalone")) {
+            if chain.iter().find_map(|e| e.get(field)) != Some(value) {
+                partial.insert(field.clone(), value.clone());
+            }
+        }
+        for (field, fallback) in [("months_standalone", "months"), ("weekdays_standalone", "weekdays")].iter() {
+            let resolved = std::iter::once(&partial).chain(chain.iter().copied()).find_map(|e| e.get(*field).or_else(|| e.get(*fallback)));
+            if resolved != entry.get(*field) {
+                partial.insert(field.to_string(), entry[*field].clone());
+            }
+        }
+        r.insert(locale.to_string(), Value::Object(partial));
+    }
+    Ok(Value::Object(r))
+}
+
+fn formatting_entry(src: &Path, locale: &str) -> Result<Map<String, Value>, String> {
+    let numbers = main_file(src, "cldr-numbers-full", locale, "numbers.json")?.ok_or("missing numbers.json")?;
+    let gregorian = main_file(src, "cldr-dates-full", locale, "ca-gregorian.json")?.ok_or("missing ca-gregorian.json")?;
+    let gregorian = get(&gregorian, &["dates", "calendars", "gregorian"])?;
+    let fields = main_file(src, "cldr-dates-full", locale, "dateFields.json")?.ok_or("missing dateFields.json")?;
+    let units = main_file(src, "cldr-units-full", locale, "units.json")?.ok_or("missing units.json")?;
+
+    let mut r = Map::new();
+    r.insert("decimal".into(), json!(get_str(&numbers, &["numbers", "symbols-numberSystem-latn", "decimal"])?));
+    r.insert("group".into(), json!(get_str(&numbers, &["numbers", "symbols-numberSystem-latn", "group"])?));
+    r.insert("exponential".into(), json!(get_str(&numbers, &["numbers", "symbols-numberSystem-latn", "exponential"])?));
+    let pattern = get_str(&numbers, &["numbers", "decimalFormats-numberSystem-latn", "standard"])?;
+    if let Some(grouping) = grouping(&pattern) {
+        r.insert("grouping".into(), json!(grouping));
+    }
+    let minimum_grouping_digits = get_str(&numbers, &["numbers", "minimumGroupingDigits"])?;
+    r.insert("minimum_grouping_digits".into(), json!(minimum_grouping_digits.parse::<usize>().map_err(|e| e.to_string())?));
+
+    let names = |path: &[&str], keys: &[&str]| -> Result<Value, String> {
+        let mut widths = Map::new();
+        for width in ["wide", "abbreviated", "narrow"].iter() {
+            let mut p = path.to_vec();
+            p.push(width);
+            let names = get(gregorian, &p)?;
+            widths.insert(width.to_string(), json!(keys.iter().map(|k| get_str(names, &[k])).collect::<Result<Vec<_>, _>>()?));
+        }
+        Ok(Value::Object(widths))
+    };
+    let months = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12"];
+    let days = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+    r.insert("months".into(), names(&["months", "format"], &months)?);
+    r.insert("months_standalone".into(), names(&["months", "stand-alone"], &months)?);
+    r.insert("weekdays".into(), names(&["days", "format"], &days)?);
+    r.insert("weekdays_standalone".into(), names(&["days", "stand-alone"], &days)?);
+    r.insert("quarters".into(), names(&["quarters", "format"], &["1", "2", "3", "4"])?);
+    r.insert("eras".into(), json!({
+        "wide": [get_str(gregorian, &["eras", "eraNames", "0"])?, get_str(gregorian, &["eras", "eraNames", "1"])?],
+        "abbreviated": [get_str(gregorian, &["eras", "eraAbbr", "0"])?, get_str(gregorian, &["eras", "eraAbbr", "1"])?],
+        "narrow": [get_str(gregorian, &["eras", "eraNarrow", "0"])?, get_str(gregorian, &["eras", "eraNarrow", "1"])?],
+    }));
+    r.insert("day_periods".into(), json!([
+        get_str(gregorian, &["dayPeriods", "format", "abbreviated", "am"])?,
+        get_str(gregorian, &["dayPeriods", "format", "abbreviated", "pm"])?,
+    ]));
+    let lengths = |key: &str| -> Result<Value, String> {
+        Ok(json!({
+            "full": get_str(gregorian, &[key, "full"])?,
+            "long": get_str(gregorian, &[key, "long"])?,
+            "medium": get_str(gregorian, &[key, "medium"])?,
+            "short": get_str(gregorian, &[key, "short"])?,
+        }))
+    };
+    r.insert("date_formats".into(), lengths("dateFormats")?);
+    r.insert("time_formats".into(), lengths("timeFormats")?);
+    r.insert("date_time_format".into(), json!(get_str(gregorian, &["dateTimeFormats", "medium"])?));
+
+    // The future of a duration, such as "in {0}", is the future of hours, such as
+    // "in {0} hours", without the unit pattern, such as "{0} hours".
+    let future = get_str(&fields, &["dates", "fields", "hour", "relativeTime-type-future", "relativeTimePattern-count-other"])?;
+    let hours = get_str(&units, &["units", "long", "duration-hour", "unitPattern-count-other"])?;
+    if !future.contains(&hours) {
+        return Err(format!("cannot derive the relative time future from {:?} and {:?}", future, hours));
+    }
+    r.insert("relative_time_future".into(), json!(future.replace(&hours, "{0}")));
+
+    let fallback = get_str(gregorian, &["dateTimeFormats", "intervalFormats", "intervalFormatFallback"])?;
+    r.insert("interval_separator".into(), json!(fallback.trim_start_matches("{0}").trim_end_matches("{1}")));
+    Ok(r)
+}
+
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// Refreshes the currency patterns of each locale and its symbols for the currencies already
+/// listed for it, and generates its display names, by plural category, for every currency.
+/// Only the patterns already present are refreshed, and regional entries only contain the
+/// names that differ from their language, since they are resolved from it.
+fn currency_data(src: &Path, previous: &Value) -> Result<Value, String> {
+    let mut r = Map::new();
+    for (locale, previous) in previous.as_object().ok_or("currency_data.json is not an object")? {
+        let mut entry = previous.clone();
+        let has = |field: &str| previous.get(field).is_some();
+        if let Some(numbers) = main_file(src, "cldr-numbers-full", locale, "numbers.json")? {
+            let formats = get(&numbers, &["numbers", "currencyFormats-numberSystem-latn"])?;
+            if has("standard_pattern") {
+                entry["standard_pattern"] = json!(get_str(formats, &["standard"])?);
+            }
+            if has("accounting_pattern") {
+                entry["accounting_pattern"] = json!(get_str(formats, &["accounting"])?);
+            }
+            if has("range_pattern") {
+                entry["range_pattern"] = json!(get_str(&numbers, &["numbers", "miscPatterns-numberSystem-latn", "range"])?);
+            }
+            if has("unit_pattern") {
+                entry["unit_pattern"] = plural_forms(formats, "unitPattern-count-");
+            }
+        }
+        if let Some(currencies) = main_file(src, "cldr-numbers-full", locale, "currencies.json")? {
+            let currencies = get(&currencies, &["numbers", "currencies"])?;
+            let codes = |field: &str| -> Vec<String> {
+                previous.get(field).and_then(|v| v.as_object()).map(|v| v.keys().cloned().collect()).unwrap_or_default()
+            };
+            for code in codes("symbols") {
+                if let Some(symbol) = currencies.get(&code).and_then(|c| c.get("symbol")) {
+                    entry["symbols"][&code] = symbol.clone();
+                }
+            }
+            let language_currencies = match locale.split_once('-') {
+                Some((language, _)) => main_file(src, "cldr-numbers-full", language, "currencies.json")?
+                    .and_then(|currencies| get(&currencies, &["numbers", "currencies"]).ok().cloned()),
+                None => None,
+            };
+            let mut names = Map::new();
+            for (code, currency) in currencies.as_object().ok_or("currencies is not an object")? {
+                let forms = plural_forms(currency, "displayName-count-");
+                let inherited = language_currencies.as_ref().and_then(|c| c.get(code)).map(|c| plural_forms(c, "displayName-count-"));
+                if forms.as_object().is_some_and(|f| !f.is_empty()) && inherited.as_ref() != Some(&forms) {
+                    names.insert(code.clone(), forms);
+                }
+            }
+            let entry_map = entry.as_object_mut().ok_or("currency entry is not an object")?;
+            entry_map.remove("names");
+            if !names.is_empty() {
+                entry_map.insert("names".into(), Value::Object(names));
+            }
+        }
+        r.insert(locale.clone(), entry);
+    }
+    Ok(Value::Object(r))
+}
+
+/// Lists the plural ranges of each language whose category is not the category of
+/// the end of the range, keyed by the start and end categories, such as `other-one`,
+/// from `pluralRanges.json`, which identifies them as `pluralRange-start-other-end-one`.
+fn plural_ranges(src: &Path) -> Result<Value, String> {
+    let ranges = read(&src.join("cldr-core/supplemental/pluralRanges.json"))?;
+    let mut r = Map::new();
+    for (language, rules) in get(&ranges, &["supplemental", "plurals"])?.as_object().ok_or("plurals is not an object")? {
+        let mut exceptions = Map::new();
+        for (key, result) in rules.as_object().ok_or("plural ranges are not an object")? {
+            let (start, end) = key.strip_prefix("pluralRange-start-").and_then(|k| k.split_once("-end-"))
+                .ok_or_else(|| format!("unknown plural range {}", key))?;
+            if result.as_str() != Some(end) {
+                exceptions.insert(format!("{}-{}", start, end), result.clone());
+            }
+        }
+        if !exceptions.is_empty() {
+            r.insert(language.clone(), Value::Object(exceptions));
+        }
+    }
+    Ok(Value::Object(r))
+}
+
+/// Collects the fields of an object suffixed by a plural category, such as
+/// `displayName-count-one`, into an object keyed by the category.
+fn plural_forms(value: &Value, prefix: &str) -> Value {
+    let mut r = Map::new();
+    for category in PLURAL_CATEGORIES.iter() {
+        if let Some(form) = value.get(format!("{}{}", prefix, category)).and_then(|v| v.as_str()) {
+            r.insert(category.to_string(), json!(form));
+        }
+    }
+    Value::Object(r)
+}
+
+/// Reads the sizes of the digit groups of a decimal pattern, such as `[3, 2]` for `#,##,##0.###`.
+fn grouping(pattern: &str) -> Option<Vec<usize>> {
+    let integer = pattern.split(';').next()?.split('.').next()?;
+    let groups: Vec<&str> = integer.split(',').collect();
+    let primary = groups.last()?.chars().filter(|c| *c == '#' || c.is_ascii_digit()).count();
+    match groups.len() {
+        0 | 1 => None,
+        2 => Some(vec![primary]),
+        n => {
+            let secondary = groups[n - 2].len();
+            Some(if secondary == primary { vec![primary] } else { vec![primary, secondary] })
+        },
+    }
+}
@@ -0,0 +1,39 @@
+//! Regenerates the subdivisions of `locale-data/subdivision_data.json` from the
+//! [iso-codes](https://salsa.debian.org/iso-codes-team/iso-codes) JSON files, such as
+//! those installed in `/usr/share/iso-codes/json`, listing the first-level ISO 3166-2
+//! subdivisions of every country.
+//!
+//! Every subdivision has its ISO 3166-2 name as `iso_name`, which is usually romanized from
+//! a language of the country rather than English, such as `Île-de-France`. The categories and
+//! names of the subdivisions already present are kept, since the names are localized from CLDR
+//! by the `cldr` task. New subdivisions have no names, and are categorized with their
+//! lowercase ISO 3166-2 type, such as `province`.
+
+use std::path::Path;
+use serde_json::{json, Map, Value};
+use super::cldr::{read, write};
+
+pub fn generate(src: &Path, output: &Path) -> Result<(), String> {
+    let iso = read(&src.join("iso_3166-2.json"))?;
+    let previous = read(&output.join("subdivision_data.json"))?;
+    let previous = previous.as_object().ok_or("subdivision_data.json is not an object")?;
+    let mut r = Map::new();
+    for subdivision in iso.get("3166-2").and_then(|v| v.as_array()).ok_or("iso_3166-2.json: missing 3166-2")? {
+        // Subdivisions of other subdivisions, such as the departments of French regions, are left out.
+        if subdivision.get("parent").is_some() {
+            continue;
+        }
+        let field = |name: &str| subdivision.get(name).and_then(|v| v.as_str()).ok_or_else(|| format!("iso_3166-2.json: subdivision without {}", name));
+        let code = field("code")?;
+        let mut entry = match previous.get(code) {
+            Some(entry) => entry.clone(),
+            None => json!({ "category": field("type")?.to_lowercase(), "names": {} }),
+        };
+        entry["iso_name"] = json!(field("name")?);
+        r.insert(code.to_string(), entry);
+    }
+    let mut countries: Vec<&str> = r.keys().map(|code| &code[..2]).collect();
+    countries.dedup();
+    println!("Regenerated the subdivisions of {} countries", countries.len());
+    write(&output.join("subdivision_data.json"), &Value::Object(r))
+}
@@ -0,0 +1,25 @@
+//! Maintenance tasks of the repository, run as `cargo xtask <task>`:
+//!
+//! ```text
+//! cargo xtask cldr <cldr-json>               Regenerates the files of locale-data from a CLDR release
+//! cargo xtask subdivisions <iso-codes-json>   Regenerates the list of subdivisions from iso-codes
+//! ```
+
+mod cldr;
+mod iso_codes;
+
+use std::{path::Path, process::exit};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let locale_data = Path::new(env!("CARGO_MANIFEST_DIR")).join("../locale-data");
+    let result = match args.iter().map(|a| a.as_str()).collect::<Vec<_>>().as_slice() {
+        ["cldr", src] => cldr::generate(Path::new(src), &locale_data),
+        ["subdivisions", src] => iso_codes::generate(Path::new(src), &locale_data),
+        _ => Err("usage: cargo xtask cldr <path to an unpacked cldr-json release>\n       cargo xtask subdivisions <path to the iso-codes JSON files>".to_string()),
+    };
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        exit(1);
+    }
+}